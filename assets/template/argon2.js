@@ -0,0 +1,346 @@
+
+    // Argon2id（版本 0x13，單一 lane）與 BLAKE2b 的純 JS 實作，僅用於檢查密碼驗證值
+    // 64 位元字以兩個 32 位元整數表示：索引 2k 為低位、2k+1 為高位
+    const BLAKE2B_IV = new Uint32Array([
+        0xf3bcc908, 0x6a09e667, 0x84caa73b, 0xbb67ae85, 0xfe94f82b, 0x3c6ef372, 0x5f1d36f1, 0xa54ff53a,
+        0xade682d1, 0x510e527f, 0x2b3e6c1f, 0x9b05688c, 0xfb41bd6b, 0x1f83d9ab, 0x137e2179, 0x5be0cd19,
+    ]);
+    const BLAKE2B_SIGMA = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+        [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+        [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+        [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+        [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+        [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+        [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+        [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+        [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    ];
+    const TWO_POW_32 = 4294967296;
+
+    // v[a] += v[b] + (m 的第 i 個字，未指定 m 時不加)
+    function add64(v, a, b, m, i) {
+        let lo = v[a] + v[b];
+        let hi = v[a + 1] + v[b + 1];
+        if (m) {
+            lo += m[i];
+            hi += m[i + 1];
+        }
+        v[a + 1] = (hi + Math.floor(lo / TWO_POW_32)) >>> 0;
+        v[a] = lo >>> 0;
+    }
+
+    // v[d] = rotr64(v[d] ^ v[a], n)
+    function xorRotr64(v, d, a, n) {
+        let lo = v[d] ^ v[a];
+        let hi = v[d + 1] ^ v[a + 1];
+        if (n >= 32) {
+            const t = lo;
+            lo = hi;
+            hi = t;
+            n -= 32;
+        }
+        if (n > 0) {
+            const nlo = (lo >>> n) | (hi << (32 - n));
+            hi = (hi >>> n) | (lo << (32 - n));
+            lo = nlo;
+        }
+        v[d] = lo >>> 0;
+        v[d + 1] = hi >>> 0;
+    }
+
+    function blake2bMix(v, a, b, c, d, m, x, y) {
+        add64(v, a, b, m, x);
+        xorRotr64(v, d, a, 32);
+        add64(v, c, d);
+        xorRotr64(v, b, c, 24);
+        add64(v, a, b, m, y);
+        xorRotr64(v, d, a, 16);
+        add64(v, c, d);
+        xorRotr64(v, b, c, 63);
+    }
+
+    function blake2bCompress(h, block, counter, last) {
+        const v = new Uint32Array(32);
+        const m = new Uint32Array(32);
+        const view = new DataView(block.buffer, block.byteOffset, 128);
+        for (let i = 0; i < 32; i++) {
+            m[i] = view.getUint32(i * 4, true);
+        }
+        v.set(h);
+        v.set(BLAKE2B_IV, 16);
+        v[24] ^= counter >>> 0;
+        v[25] ^= Math.floor(counter / TWO_POW_32);
+        if (last) {
+            v[28] = ~v[28];
+            v[29] = ~v[29];
+        }
+        for (let r = 0; r < 12; r++) {
+            const s = BLAKE2B_SIGMA[r % 10];
+            blake2bMix(v, 0, 8, 16, 24, m, s[0] * 2, s[1] * 2);
+            blake2bMix(v, 2, 10, 18, 26, m, s[2] * 2, s[3] * 2);
+            blake2bMix(v, 4, 12, 20, 28, m, s[4] * 2, s[5] * 2);
+            blake2bMix(v, 6, 14, 22, 30, m, s[6] * 2, s[7] * 2);
+            blake2bMix(v, 0, 10, 20, 30, m, s[8] * 2, s[9] * 2);
+            blake2bMix(v, 2, 12, 22, 24, m, s[10] * 2, s[11] * 2);
+            blake2bMix(v, 4, 14, 16, 26, m, s[12] * 2, s[13] * 2);
+            blake2bMix(v, 6, 8, 18, 28, m, s[14] * 2, s[15] * 2);
+        }
+        for (let i = 0; i < 16; i++) {
+            h[i] ^= v[i] ^ v[i + 16];
+        }
+    }
+
+    // 不帶金鑰的 BLAKE2b，輸出長度 1–64 位元組
+    function blake2b(input, outLen) {
+        const h = new Uint32Array(BLAKE2B_IV);
+        h[0] ^= 0x01010000 ^ outLen;
+        const block = new Uint8Array(128);
+        let offset = 0;
+        while (input.length - offset > 128) {
+            block.set(input.subarray(offset, offset + 128));
+            offset += 128;
+            blake2bCompress(h, block, offset, false);
+        }
+        block.fill(0);
+        block.set(input.subarray(offset));
+        blake2bCompress(h, block, input.length, true);
+        const out = new Uint8Array(64);
+        const view = new DataView(out.buffer);
+        for (let i = 0; i < 16; i++) {
+            view.setUint32(i * 4, h[i], true);
+        }
+        return out.slice(0, outLen);
+    }
+
+    function le32(value) {
+        const out = new Uint8Array(4);
+        new DataView(out.buffer).setUint32(0, value, true);
+        return out;
+    }
+
+    function concatAll(arrays) {
+        let total = 0;
+        arrays.forEach(function (a) { total += a.length; });
+        const out = new Uint8Array(total);
+        let offset = 0;
+        arrays.forEach(function (a) {
+            out.set(a, offset);
+            offset += a.length;
+        });
+        return out;
+    }
+
+    // Argon2 的可變長度雜湊 H'
+    function argon2Hash(input, outLen) {
+        const data = concatAll([le32(outLen), input]);
+        if (outLen <= 64) {
+            return blake2b(data, outLen);
+        }
+        const out = new Uint8Array(outLen);
+        let v = blake2b(data, 64);
+        out.set(v.subarray(0, 32));
+        let offset = 32;
+        while (outLen - offset > 64) {
+            v = blake2b(v, 64);
+            out.set(v.subarray(0, 32), offset);
+            offset += 32;
+        }
+        out.set(blake2b(v, outLen - offset), offset);
+        return out;
+    }
+
+    // Argon2 的 BlaMka：v[a] = v[a] + v[b] + 2 * lo32(v[a]) * lo32(v[b])
+    function blaMka(v, a, b) {
+        const x = v[a];
+        const y = v[b];
+        const x0 = x & 0xffff;
+        const x1 = x >>> 16;
+        const y0 = y & 0xffff;
+        const y1 = y >>> 16;
+        const mid = x0 * y1 + x1 * y0;
+        const low = x0 * y0 + (mid % 65536) * 65536;
+        let plo = low >>> 0;
+        let phi = x1 * y1 + Math.floor(mid / 65536) + Math.floor(low / TWO_POW_32);
+        phi = ((phi << 1) | (plo >>> 31)) >>> 0;
+        plo = (plo << 1) >>> 0;
+        const lo = x + y + plo;
+        v[a + 1] = (v[a + 1] + v[b + 1] + phi + Math.floor(lo / TWO_POW_32)) >>> 0;
+        v[a] = lo >>> 0;
+    }
+
+    function argon2Mix(v, a, b, c, d) {
+        blaMka(v, a, b);
+        xorRotr64(v, d, a, 32);
+        blaMka(v, c, d);
+        xorRotr64(v, b, c, 24);
+        blaMka(v, a, b);
+        xorRotr64(v, d, a, 16);
+        blaMka(v, c, d);
+        xorRotr64(v, b, c, 63);
+    }
+
+    // 對 16 個 64 位元字套用 Argon2 的置換 P，words 為各字在區塊中的字索引
+    const permuteBuffer = new Uint32Array(32);
+    function argon2Permute(block, words) {
+        const v = permuteBuffer;
+        for (let i = 0; i < 16; i++) {
+            v[i * 2] = block[words[i] * 2];
+            v[i * 2 + 1] = block[words[i] * 2 + 1];
+        }
+        argon2Mix(v, 0, 8, 16, 24);
+        argon2Mix(v, 2, 10, 18, 26);
+        argon2Mix(v, 4, 12, 20, 28);
+        argon2Mix(v, 6, 14, 22, 30);
+        argon2Mix(v, 0, 10, 20, 30);
+        argon2Mix(v, 2, 12, 22, 24);
+        argon2Mix(v, 4, 14, 16, 26);
+        argon2Mix(v, 6, 8, 18, 28);
+        for (let i = 0; i < 16; i++) {
+            block[words[i] * 2] = v[i * 2];
+            block[words[i] * 2 + 1] = v[i * 2 + 1];
+        }
+    }
+
+    const ARGON2_ROWS = [];
+    const ARGON2_COLUMNS = [];
+    for (let i = 0; i < 8; i++) {
+        const row = [];
+        const column = [];
+        for (let j = 0; j < 16; j++) {
+            row.push(i * 16 + j);
+            column.push(Math.floor(j / 2) * 16 + i * 2 + (j % 2));
+        }
+        ARGON2_ROWS.push(row);
+        ARGON2_COLUMNS.push(column);
+    }
+
+    // 壓縮函式 G：out = P(x ^ y) ^ (x ^ y)，withXor 時再與 out 原內容 XOR
+    const compressR = new Uint32Array(256);
+    const compressQ = new Uint32Array(256);
+    function argon2Compress(out, x, y, withXor) {
+        for (let i = 0; i < 256; i++) {
+            compressR[i] = x[i] ^ y[i];
+        }
+        compressQ.set(compressR);
+        ARGON2_ROWS.forEach(function (row) { argon2Permute(compressQ, row); });
+        ARGON2_COLUMNS.forEach(function (column) { argon2Permute(compressQ, column); });
+        for (let i = 0; i < 256; i++) {
+            out[i] = (withXor ? out[i] : 0) ^ compressQ[i] ^ compressR[i];
+        }
+    }
+
+    function bytesToBlock(bytes) {
+        const block = new Uint32Array(256);
+        const view = new DataView(bytes.buffer, bytes.byteOffset, 1024);
+        for (let i = 0; i < 256; i++) {
+            block[i] = view.getUint32(i * 4, true);
+        }
+        return block;
+    }
+
+    // 計算 Argon2id 雜湊（lanes = 1），每處理完一個 segment 讓出主執行緒
+    async function argon2id(password, salt, timeCost, memoryCost, outLen) {
+        const h0 = blake2b(concatAll([
+            le32(1), le32(outLen), le32(memoryCost), le32(timeCost), le32(0x13), le32(2),
+            le32(password.length), password, le32(salt.length), salt, le32(0), le32(0),
+        ]), 64);
+        const segmentLength = Math.floor(memoryCost / 4);
+        const laneLength = segmentLength * 4;
+        const memory = [];
+        for (let i = 0; i < 2; i++) {
+            memory.push(bytesToBlock(argon2Hash(concatAll([h0, le32(i), le32(0)]), 1024)));
+        }
+        for (let i = 2; i < laneLength; i++) {
+            memory.push(new Uint32Array(256));
+        }
+        const zero = new Uint32Array(256);
+        const input = new Uint32Array(256);
+        const address = new Uint32Array(256);
+        for (let pass = 0; pass < timeCost; pass++) {
+            for (let slice = 0; slice < 4; slice++) {
+                const independent = pass === 0 && slice < 2;
+                const start = pass === 0 && slice === 0 ? 2 : 0;
+                if (independent) {
+                    input.fill(0);
+                    input[0] = pass;
+                    input[2] = 0;
+                    input[4] = slice;
+                    input[6] = laneLength;
+                    input[8] = timeCost;
+                    input[10] = 2;
+                }
+                const nextAddresses = function () {
+                    input[12] += 1;
+                    argon2Compress(address, zero, input, false);
+                    argon2Compress(address, zero, address, false);
+                };
+                if (independent && start === 2) {
+                    nextAddresses();
+                }
+                for (let index = start; index < segmentLength; index++) {
+                    const current = slice * segmentLength + index;
+                    const previous = current === 0 ? laneLength - 1 : current - 1;
+                    let j1;
+                    if (independent) {
+                        if (index % 128 === 0) {
+                            nextAddresses();
+                        }
+                        j1 = address[(index % 128) * 2];
+                    } else {
+                        j1 = memory[previous][0];
+                    }
+                    let area;
+                    if (pass === 0) {
+                        area = slice * segmentLength + index - 1;
+                    } else {
+                        area = laneLength - segmentLength + index - 1;
+                    }
+                    let relative = BigInt(j1);
+                    relative = (relative * relative) >> 32n;
+                    relative = BigInt(area - 1) - ((BigInt(area) * relative) >> 32n);
+                    const startPosition = pass === 0 || slice === 3 ? 0 : (slice + 1) * segmentLength;
+                    const reference = (startPosition + Number(relative)) % laneLength;
+                    argon2Compress(memory[current], memory[previous], memory[reference], pass > 0);
+                }
+                await new Promise(function (resolve) { setTimeout(resolve, 0); });
+            }
+        }
+        const last = memory[laneLength - 1];
+        const bytes = new Uint8Array(1024);
+        const view = new DataView(bytes.buffer);
+        for (let i = 0; i < 256; i++) {
+            view.setUint32(i * 4, last[i], true);
+        }
+        return argon2Hash(bytes, outLen);
+    }
+
+    // PHC 字串使用不含補位的標準 Base64
+    function phcBase64ToBytes(text) {
+        return base64ToBytes(text + '='.repeat((4 - text.length % 4) % 4));
+    }
+
+    // 以頁面嵌入的 Argon2id 驗證值檢查密碼，回傳 true/false，參數不支援時回傳 null
+    async function checkPasswordVerifier(password) {
+        const fields = PASSWORD_VERIFIER.split('$');
+        if (fields.length !== 6 || fields[1] !== 'argon2id' || fields[2] !== 'v=19') {
+            return null;
+        }
+        const params = {};
+        fields[3].split(',').forEach(function (pair) {
+            const kv = pair.split('=');
+            params[kv[0]] = parseInt(kv[1], 10);
+        });
+        if (params.p !== 1 || !(params.m >= 8) || !(params.t >= 1)) {
+            return null;
+        }
+        const expected = phcBase64ToBytes(fields[5]);
+        const actual = await argon2id(
+            new TextEncoder().encode(password), phcBase64ToBytes(fields[4]), params.t, params.m, expected.length);
+        let diff = 0;
+        for (let i = 0; i < expected.length; i++) {
+            diff |= expected[i] ^ actual[i];
+        }
+        return diff === 0;
+    }
@@ -0,0 +1,30 @@
+    // 解碼 Base64，忽略經聊天軟體或郵件轉貼時加入的換行與空白
+    function base64ToBytes(base64Data) {
+        const binary = atob(base64Data.replace(/\s+/g, ''));
+        const array = new Uint8Array(binary.length);
+        for (let i = 0; i < binary.length; i++) {
+            array[i] = binary.charCodeAt(i);
+        }
+        return array;
+    }
+
+    // 解碼 Base85（Ascii85 字元集，頁面中的 `v` 代表 `<`），不足 5 字元的最後一組以 `u` 補齊
+    function base85ToBytes(text) {
+        text = text.replace(/\s+/g, '');
+        const full = Math.floor(text.length / 5);
+        const rest = text.length % 5;
+        const array = new Uint8Array(full * 4 + (rest ? rest - 1 : 0));
+        let offset = 0;
+        for (let i = 0; i < text.length; i += 5) {
+            let value = 0;
+            for (let j = 0; j < 5; j++) {
+                const code = i + j < text.length ? text.charCodeAt(i + j) : 117;
+                value = value * 85 + ((code === 118 ? 60 : code) - 33);
+            }
+            const count = Math.min(4, text.length - i - 1);
+            for (let k = 0; k < count; k++) {
+                array[offset++] = (value >>> (24 - k * 8)) & 0xff;
+            }
+        }
+        return array;
+    }
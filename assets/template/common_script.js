@@ -0,0 +1,391 @@
+    const PAYLOAD_SHA256 = '{{PAYLOAD_SHA256}}';
+    const DOWNLOAD_ZIP_NAME = {{DOWNLOAD_ZIP_NAME_JS}};
+    const PAYLOAD_ENCODING = '{{PAYLOAD_ENCODING}}';
+    const PAYLOAD_SUFFIX = '{{PAYLOAD_SUFFIX}}';
+    const PAYLOAD_COMPRESSION = '{{PAYLOAD_COMPRESSION}}';
+    const MESSAGES = {{MESSAGES_JSON}};
+    const EXPIRES_AT = {{EXPIRES_AT}};
+    const ARCHIVE_LAYER = '{{LAYER}}';
+    const ARCHIVE_FORMAT = '{{ARCHIVE_FORMAT}}';
+    const DOWNLOAD_MIME = '{{DOWNLOAD_MIME}}';
+    const ZIP_ENCRYPTED = {{ZIP_ENCRYPTED}};
+    const KDF_ITERATIONS = {{KDF_ITERATIONS}};
+    const STREAM_CHUNK_SIZE = {{STREAM_CHUNK_SIZE}};
+    const PASSWORD_VERIFIER = '{{PASSWORD_VERIFIER}}';
+    const PAYLOAD_FILE = {{PAYLOAD_FILE_JS}};
+    const PAYLOAD_LINE_WIDTH = {{PAYLOAD_LINE_WIDTH}};
+
+{{CODEC_SCRIPT}}
+    // 讀取頁面中以多個 script 標籤嵌入的編碼分塊，移除轉貼或編輯時混入的換行與空白
+    function readPayloadChunks() {
+        return Array.from(document.querySelectorAll('#payload script.payload-chunk'), function (el) {
+            return el.textContent.replace(/\s+/g, '');
+        });
+    }
+
+    function decodedLength(chunk) {
+        if (PAYLOAD_ENCODING === 'base85') {
+            return Math.floor(chunk.length / 5) * 4 + (chunk.length % 5 ? chunk.length % 5 - 1 : 0);
+        }
+        const padding = chunk.endsWith('==') ? 2 : (chunk.endsWith('=') ? 1 : 0);
+        return chunk.length / 4 * 3 - padding;
+    }
+
+    function decodeChunk(chunk) {
+        return PAYLOAD_ENCODING === 'base85' ? base85ToBytes(chunk) : base64ToBytes(chunk);
+    }
+
+    // 逐塊解碼，每塊之間讓出主執行緒並回報進度，避免大型檔案凍結頁面
+    async function decodeChunks(chunks, onProgress) {
+        let total = 0;
+        chunks.forEach(function (chunk) { total += decodedLength(chunk); });
+        const bytes = new Uint8Array(total);
+        let offset = 0;
+        for (let i = 0; i < chunks.length; i++) {
+            const part = decodeChunk(chunks[i]);
+            bytes.set(part, offset);
+            offset += part.length;
+            if (onProgress) {
+                onProgress(i + 1, chunks.length);
+            }
+            await new Promise(function (resolve) { setTimeout(resolve, 0); });
+        }
+        return bytes;
+    }
+
+    function showDecodeProgress(done, total) {
+        const box = document.getElementById('decode-status');
+        if (!box) {
+            return;
+        }
+        document.getElementById('decode-progress').value = done / total;
+        document.getElementById('decode-text').textContent = MESSAGES.decoding + ' ' + Math.floor(done / total * 100) + '%';
+        box.style.display = done < total ? 'block' : 'none';
+    }
+
+    // 資料整體壓縮但頁面無法還原時改為直接下載壓縮檔，回傳附加的副檔名：
+    // gzip 在瀏覽器不支援 DecompressionStream 時為 .gz，zstd 在頁面未附解碼器（樹狀檢視以外）時為 .zst
+    function compressedSuffix() {
+        if (PAYLOAD_COMPRESSION === 'gzip' && typeof DecompressionStream === 'undefined') {
+            return '.gz';
+        }
+        if (PAYLOAD_COMPRESSION === 'zstd' && typeof zstdDecompress === 'undefined') {
+            return '.zst';
+        }
+        return '';
+    }
+
+    async function decompressPayload(bytes) {
+        if (PAYLOAD_COMPRESSION === 'none' || compressedSuffix()) {
+            return bytes;
+        }
+        if (PAYLOAD_COMPRESSION === 'zstd') {
+            return zstdDecompress(bytes);
+        }
+        const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream('gzip'));
+        return new Uint8Array(await new Response(stream).arrayBuffer());
+    }
+
+    function payloadFileName(fileName) {
+        return fileName + compressedSuffix();
+    }
+
+    let externalPromise = null;
+
+    // 取得外部資料檔：先以 fetch 讀取同目錄的檔案，以 file:// 開啟等無法讀取時改由使用者選擇檔案
+    function externalPayloadBlob() {
+        if (!externalPromise) {
+            externalPromise = fetch(encodeURIComponent(PAYLOAD_FILE)).then(function (response) {
+                if (!response.ok) {
+                    throw new Error(response.status + ' ' + response.statusText);
+                }
+                return response.blob();
+            }).catch(pickPayloadFile);
+        }
+        return externalPromise;
+    }
+
+    // 在完整性提示下方顯示檔案選擇欄位，使用者選擇後回傳該檔案
+    function pickPayloadFile() {
+        return new Promise(function (resolve) {
+            const box = document.createElement('p');
+            const label = document.createElement('label');
+            const input = document.createElement('input');
+            label.textContent = MESSAGES.selectPayload.replace('{name}', PAYLOAD_FILE) + ' ';
+            input.type = 'file';
+            input.addEventListener('change', function () {
+                if (input.files.length) {
+                    box.remove();
+                    resolve(input.files[0]);
+                }
+            });
+            label.appendChild(input);
+            box.appendChild(label);
+            const anchor = document.getElementById('integrity');
+            anchor.parentNode.insertBefore(box, anchor.nextSibling);
+        });
+    }
+
+    let payloadPromise = null;
+
+    // 取得頁面嵌入或外部資料檔的完整資料，僅解碼一次
+    function loadPayload() {
+        if (!payloadPromise) {
+            const raw = PAYLOAD_FILE
+                ? externalPayloadBlob().then(function (blob) { return blob.arrayBuffer(); }).then(function (buffer) {
+                    return new Uint8Array(buffer);
+                })
+                : decodeChunks(readPayloadChunks(), showDecodeProgress);
+            payloadPromise = raw.then(decompressPayload);
+        }
+        return payloadPromise;
+    }
+
+    function saveBytes(bytes, fileName, type) {
+        const blob = new Blob([bytes], { type: type });
+        const url = URL.createObjectURL(blob);
+        const link = document.createElement('a');
+        link.href = url;
+        link.download = fileName;
+        document.body.appendChild(link);
+        link.click();
+        document.body.removeChild(link);
+        URL.revokeObjectURL(url);
+    }
+
+    // 依頁面語系格式化檔案大小，千分位與小數點與頁面其他數字一致
+    function formatSize(size) {
+        const options = { minimumFractionDigits: 2, maximumFractionDigits: 2 };
+        const lang = document.documentElement.lang;
+        if (size < 1024 * 1024) {
+            return (size / 1024).toLocaleString(lang, options) + ' KB';
+        }
+        return (size / (1024 * 1024)).toLocaleString(lang, options) + ' MB';
+    }
+
+    function showCopyStatus(text) {
+        document.getElementById('copy-status').textContent = text;
+    }
+
+    // 取得可供其他工具解碼的完整文字，Base85 換回標準 Ascii85 的 `<`；指定行長時依行長換行
+    function payloadText() {
+        const text = readPayloadChunks().join('');
+        if (PAYLOAD_ENCODING === 'base85') {
+            return text.replace(/v/g, '<');
+        }
+        return PAYLOAD_LINE_WIDTH > 0 ? text.match(new RegExp('.{1,' + PAYLOAD_LINE_WIDTH + '}', 'g')).join('\n') : text;
+    }
+
+    // 將完整的編碼資料複製到剪貼簿，不支援 Clipboard API 時改用選取複製
+    async function copyPayload() {
+        const text = payloadText();
+        const done = MESSAGES.copied.replace('{size}', formatSize(text.length));
+        try {
+            await navigator.clipboard.writeText(text);
+            showCopyStatus(done);
+            return;
+        } catch (e) {
+            // 改用下方的選取複製
+        }
+        const area = document.createElement('textarea');
+        area.value = text;
+        area.setAttribute('readonly', '');
+        area.style.position = 'fixed';
+        area.style.opacity = '0';
+        document.body.appendChild(area);
+        area.select();
+        let ok = false;
+        try {
+            ok = document.execCommand('copy');
+        } catch (e) {
+            ok = false;
+        }
+        document.body.removeChild(area);
+        showCopyStatus(ok ? done : MESSAGES.copyFailed);
+    }
+
+    // 將編碼資料另存為文字檔；.b64 可用 `base64 -d` 或 `certutil -decode` 還原，.b85 可用 Python 的 `base64.a85decode` 還原
+    function savePayloadText(fileName) {
+        const name = fileName + ({ gzip: '.gz', zstd: '.zst' }[PAYLOAD_COMPRESSION] || '');
+        saveBytes(payloadText(), name + '.' + PAYLOAD_SUFFIX, 'text/plain');
+    }
+
+    // 依 User-Agent 判斷收件者的平台，無法辨識時回傳 null
+    function detectPlatform() {
+        const ua = navigator.userAgent || '';
+        const platform = (navigator.userAgentData && navigator.userAgentData.platform) || navigator.platform || '';
+        if (/android|iphone|ipad|ipod/i.test(ua)) {
+            return 'Mobile';
+        }
+        if (/win/i.test(platform) || /windows/i.test(ua)) {
+            return 'Windows';
+        }
+        if (/mac/i.test(platform) || /mac os x/i.test(ua)) {
+            return 'Mac';
+        }
+        if (/linux|bsd|x11/i.test(platform + ' ' + ua)) {
+            return 'Linux';
+        }
+        return null;
+    }
+
+    function shellQuote(text) {
+        return "'" + text.replace(/'/g, "'\\''") + "'";
+    }
+
+    // 依收件者的平台與 ZIP 是否以 AES 加密，在通用說明下方補充對應的解壓方式
+    function showPlatformTips(fileName) {
+        const box = document.getElementById('platform-tips');
+        const platform = detectPlatform();
+        if (!box || !platform || ARCHIVE_LAYER === 'none') {
+            return;
+        }
+        const add = function (tag, text) {
+            const el = document.createElement(tag);
+            el.textContent = text;
+            box.appendChild(el);
+        };
+        if (ARCHIVE_FORMAT === '7z') {
+            add('p', MESSAGES.tips7z);
+            add('code', '7z x ' + shellQuote(fileName));
+            box.style.display = 'block';
+            return;
+        }
+        if (ARCHIVE_FORMAT !== 'zip') {
+            add('p', MESSAGES.tipsTar);
+            add('code', 'tar -xf ' + shellQuote(fileName));
+            box.style.display = 'block';
+            return;
+        }
+        add('p', MESSAGES['tips' + platform + (ZIP_ENCRYPTED ? 'Aes' : '')]);
+        if (platform === 'Linux' || platform === 'Mac') {
+            add('code', (ZIP_ENCRYPTED ? '7z x ' : 'unzip ') + shellQuote(fileName));
+        }
+        if (ARCHIVE_LAYER === 'double') {
+            add('p', MESSAGES.tipsDouble);
+        }
+        box.style.display = 'block';
+    }
+
+{{CRYPTO_SCRIPT}}
+    // 逐一解碼頁面中的分塊並回報進度，不將完整資料保留在記憶體中；外部資料檔則逐段讀取檔案
+    async function* payloadPieces() {
+        if (PAYLOAD_FILE) {
+            const reader = (await externalPayloadBlob()).stream().getReader();
+            for (;;) {
+                const result = await reader.read();
+                if (result.done) {
+                    return;
+                }
+                yield result.value;
+            }
+        }
+        const chunks = readPayloadChunks();
+        for (let i = 0; i < chunks.length; i++) {
+            yield decodeChunk(chunks[i]);
+            showDecodeProgress(i + 1, chunks.length);
+            await new Promise(function (resolve) { setTimeout(resolve, 0); });
+        }
+    }
+
+    // 開啟下載目的地：支援 File System Access API 時直接寫入使用者選擇的檔案，否則累積為 Blob 片段後下載
+    async function openDownloadSink(fileName, type) {
+        if (window.showSaveFilePicker) {
+            try {
+                const handle = await window.showSaveFilePicker({ suggestedName: fileName });
+                const writable = await handle.createWritable();
+                return {
+                    write: function (bytes) { return writable.write(bytes); },
+                    close: function () { return writable.close(); },
+                    abort: function () { return writable.abort(); },
+                };
+            } catch (e) {
+                if (e.name === 'AbortError') {
+                    throw e;
+                }
+            }
+        }
+        const parts = [];
+        return {
+            write: function (bytes) { parts.push(bytes); },
+            close: function () { saveBytes(new Blob(parts, { type: type }), fileName, type); },
+            abort: function () { parts.length = 0; },
+        };
+    }
+
+    // 以嵌入的 Argon2id 驗證值預先檢查密碼，錯誤時顯示提示並回傳 true；未嵌入驗證值或參數不支援時交由解密判斷
+    async function passwordRejected(password, errorEl) {
+        if (!PASSWORD_VERIFIER || (await checkPasswordVerifier(password)) !== false) {
+            return false;
+        }
+        errorEl.textContent = MESSAGES.wrongPassword;
+        markPasswordInvalid();
+        return true;
+    }
+
+    // 密碼錯誤或解密失敗時標示密碼欄並將焦點移回，鍵盤與螢幕閱讀器使用者可直接重新輸入；再次輸入時清除標示
+    function markPasswordInvalid() {
+        const input = document.getElementById('password');
+        if (!input) {
+            return;
+        }
+        input.setAttribute('aria-invalid', 'true');
+        input.addEventListener('input', function () { input.removeAttribute('aria-invalid'); }, { once: true });
+        input.focus();
+        input.select();
+    }
+
+    async function sha256Hex(bytes) {
+        const digest = new Uint8Array(await crypto.subtle.digest('SHA-256', bytes));
+        return Array.from(digest, function (b) { return b.toString(16).padStart(2, '0'); }).join('');
+    }
+
+    // 驗證解碼後的資料與產生時的 SHA-256 是否一致，回傳 true/false，無法驗證時回傳 null
+    async function verifyIntegrity(bytes) {
+        const el = document.getElementById('integrity');
+        if (compressedSuffix()) {
+            el.textContent = PAYLOAD_COMPRESSION === 'zstd' ? MESSAGES.zstdDownload : MESSAGES.gzipUnsupported;
+            return null;
+        }
+        if (!window.crypto || !crypto.subtle) {
+            el.textContent = MESSAGES.integrityUnavailable;
+            return null;
+        }
+        const ok = (await sha256Hex(bytes)) === PAYLOAD_SHA256;
+        el.textContent = ok
+            ? MESSAGES.integrityOk.replace('{hash}', PAYLOAD_SHA256.slice(0, 16))
+            : MESSAGES.integrityBad;
+        el.className = 'integrity ' + (ok ? 'integrity-ok' : 'integrity-bad');
+        return ok;
+    }
+
+    // 顯示下載期限；超過期限時隱藏下載相關控制項並改為過期提示，頁面開啟期間到期也會自動切換
+    function applyExpiry() {
+        const el = document.getElementById('expiry');
+        if (EXPIRES_AT === null || !el) {
+            return;
+        }
+        const date = new Date(EXPIRES_AT).toLocaleString(document.documentElement.lang);
+        const remaining = EXPIRES_AT - Date.now();
+        el.style.display = 'block';
+        if (remaining > 0) {
+            el.textContent = MESSAGES.expiresOn.replace('{date}', date);
+            if (remaining < 2147483647) {
+                setTimeout(applyExpiry, remaining);
+            }
+            return;
+        }
+        el.textContent = MESSAGES.expired.replace('{date}', date);
+        el.className = 'expiry expiry-expired';
+        document.body.classList.add('expired');
+    }
+
+    applyExpiry();
+
+    // 外部資料檔模式沒有嵌入的編碼文字，隱藏複製與另存按鈕，並提早讀取資料檔以便需要時讓使用者選擇
+    if (PAYLOAD_FILE) {
+        document.querySelectorAll('.payload-actions').forEach(function (el) { el.style.display = 'none'; });
+        externalPayloadBlob();
+    }
+{{ARGON2_SCRIPT}}
+{{ZSTD_SCRIPT}}
@@ -0,0 +1,72 @@
+    // 瀏覽器端 AES-GCM 解密，使用者需先定義 KDF_ITERATIONS 與 STREAM_CHUNK_SIZE（0 表示整體加密）
+    const SALT_LEN = 16;
+    const IV_LEN = 12;
+    const TAG_LEN = 16;
+    const NONCE_PREFIX_LEN = 8;
+
+    async function deriveAesKey(password, salt) {
+        const baseKey = await crypto.subtle.importKey(
+            'raw', new TextEncoder().encode(password), 'PBKDF2', false, ['deriveKey']);
+        return crypto.subtle.deriveKey(
+            { name: 'PBKDF2', salt: salt, iterations: KDF_ITERATIONS, hash: 'SHA-256' },
+            baseKey, { name: 'AES-GCM', length: 256 }, false, ['decrypt']);
+    }
+
+    async function decryptPayload(bytes, password) {
+        if (STREAM_CHUNK_SIZE) {
+            const parts = [];
+            await decryptChunked([bytes], password, function (part) { parts.push(part); });
+            return new Uint8Array(await new Blob(parts).arrayBuffer());
+        }
+        const salt = bytes.slice(0, SALT_LEN);
+        const iv = bytes.slice(SALT_LEN, SALT_LEN + IV_LEN);
+        const ciphertext = bytes.slice(SALT_LEN + IV_LEN);
+        const key = await deriveAesKey(password, salt);
+        return new Uint8Array(await crypto.subtle.decrypt({ name: 'AES-GCM', iv: iv }, key, ciphertext));
+    }
+
+    function concatBytes(a, b) {
+        const out = new Uint8Array(a.length + b.length);
+        out.set(a);
+        out.set(b, a.length);
+        return out;
+    }
+
+    // 分塊 IV：固定前綴加上 4 位元組大端序塊序號；最後一塊的附加資料為 1，其餘為 0，可偵測截斷
+    async function decryptSealedChunk(key, prefix, index, sealed, last) {
+        const iv = new Uint8Array(IV_LEN);
+        iv.set(prefix);
+        new DataView(iv.buffer).setUint32(NONCE_PREFIX_LEN, index);
+        const params = { name: 'AES-GCM', iv: iv, additionalData: new Uint8Array([last ? 1 : 0]) };
+        return new Uint8Array(await crypto.subtle.decrypt(params, key, sealed));
+    }
+
+    // 逐塊解密分塊 AES-GCM 資料：格式為 鹽值(16) || IV 前綴(8) || 各塊密文+標籤
+    // source 為依序產生位元組片段的（非同步）可迭代物件，每解密一塊即交給 onPlain，不需保留完整資料
+    async function decryptChunked(source, password, onPlain) {
+        const sealedSize = STREAM_CHUNK_SIZE + TAG_LEN;
+        let buffer = new Uint8Array(0);
+        let key = null;
+        let prefix = null;
+        let index = 0;
+        for await (const piece of source) {
+            buffer = concatBytes(buffer, piece);
+            if (!key) {
+                if (buffer.length < SALT_LEN + NONCE_PREFIX_LEN) {
+                    continue;
+                }
+                key = await deriveAesKey(password, buffer.slice(0, SALT_LEN));
+                prefix = buffer.slice(SALT_LEN, SALT_LEN + NONCE_PREFIX_LEN);
+                buffer = buffer.slice(SALT_LEN + NONCE_PREFIX_LEN);
+            }
+            // 保留最後一塊，待所有資料讀完後以最後一塊的附加資料解密
+            while (buffer.length > sealedSize) {
+                await onPlain(await decryptSealedChunk(key, prefix, index++, buffer.subarray(0, sealedSize), false));
+                buffer = buffer.slice(sealedSize);
+            }
+        }
+        if (!key) {
+            throw new Error('truncated');
+        }
+        await onPlain(await decryptSealedChunk(key, prefix, index, buffer, true));
+    }
@@ -0,0 +1,529 @@
+    // Zstandard（RFC 8878）解碼器的純 JS 實作，用於還原以 --payload-compression zstd 壓縮的頁面資料，由樹狀檢視頁面與解碼工具頁共用；不支援字典，略過內容檢查碼
+    const ZSTD_MAGIC = 0xfd2fb528;
+    const ZSTD_LL_DEFAULT = [4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1, -1, -1, -1, -1];
+    const ZSTD_ML_DEFAULT = [
+        1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1,
+    ];
+    const ZSTD_OF_DEFAULT = [1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1];
+    const ZSTD_LL_BASE = [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32, 40,
+        48, 64, 128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768, 65536,
+    ];
+    const ZSTD_LL_BITS = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+    const ZSTD_ML_BASE = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34,
+        35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027, 2051, 4099, 8195, 16387, 32771, 65539,
+    ];
+    const ZSTD_ML_BITS = [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    ];
+
+    function zstdError(reason) {
+        return new Error('Zstandard: ' + reason);
+    }
+
+    function zstdHighBit(value) {
+        return 31 - Math.clz32(value);
+    }
+
+    // 自 p 起讀取 n 個位元（n 不超過 32，低位在前），p 為負數時不足的低位補 0
+    function zstdBitsAt(src, p, n) {
+        if (n <= 0) {
+            return 0;
+        }
+        if (p < 0) {
+            return n + p > 0 ? zstdBitsAt(src, 0, n + p) * Math.pow(2, -p) : 0;
+        }
+        if (n > 24) {
+            return zstdBitsAt(src, p, 16) + zstdBitsAt(src, p + 16, n - 16) * 65536;
+        }
+        const i = p >> 3;
+        const word = (src[i] | (src[i + 1] << 8) | (src[i + 2] << 16) | (src[i + 3] << 24)) >>> 0;
+        return (word >>> (p & 7)) & ((1 << n) - 1);
+    }
+
+    // 由結尾往前讀取的位元流，最後一個位元組的最高位 1 為起始標記
+    function zstdBackwardReader(src, start, end) {
+        const last = src[end - 1];
+        if (end <= start || !last) {
+            throw zstdError('invalid bitstream');
+        }
+        const bytes = src.subarray(start, end);
+        const reader = {
+            pos: (end - start - 1) * 8 + zstdHighBit(last),
+            read: function (n) {
+                reader.pos -= n;
+                return zstdBitsAt(bytes, reader.pos, n);
+            },
+            peek: function (n) {
+                return zstdBitsAt(bytes, reader.pos - n, n);
+            },
+        };
+        return reader;
+    }
+
+    // 依正規化機率建立 FSE 解碼表
+    function zstdBuildFseTable(probs, accuracyLog) {
+        const size = 1 << accuracyLog;
+        const symbol = new Uint8Array(size);
+        const bits = new Uint8Array(size);
+        const base = new Uint16Array(size);
+        const next = new Uint16Array(probs.length);
+        let high = size - 1;
+        for (let s = 0; s < probs.length; s++) {
+            if (probs[s] === -1) {
+                symbol[high--] = s;
+                next[s] = 1;
+            }
+        }
+        const step = (size >> 1) + (size >> 3) + 3;
+        let pos = 0;
+        for (let s = 0; s < probs.length; s++) {
+            if (probs[s] <= 0) {
+                continue;
+            }
+            next[s] = probs[s];
+            for (let i = 0; i < probs[s]; i++) {
+                symbol[pos] = s;
+                do {
+                    pos = (pos + step) & (size - 1);
+                } while (pos > high);
+            }
+        }
+        if (pos !== 0) {
+            throw zstdError('invalid FSE distribution');
+        }
+        for (let u = 0; u < size; u++) {
+            const state = next[symbol[u]]++;
+            bits[u] = accuracyLog - zstdHighBit(state);
+            base[u] = (state << bits[u]) - size;
+        }
+        return { accuracyLog: accuracyLog, symbol: symbol, bits: bits, base: base };
+    }
+
+    // 只有單一符號的 RLE 表
+    function zstdRleTable(symbol) {
+        return { accuracyLog: 0, symbol: new Uint8Array([symbol]), bits: new Uint8Array(1), base: new Uint16Array(1) };
+    }
+
+    // 讀取 FSE 表的描述，回傳解碼表與描述所佔的位元組數
+    function zstdReadFseTable(src, offset, maxSymbol, maxAccuracyLog) {
+        const bytes = src.subarray(offset);
+        const accuracyLog = (bytes[0] & 15) + 5;
+        if (accuracyLog > maxAccuracyLog) {
+            throw zstdError('FSE accuracy log too large');
+        }
+        let bitPos = 4;
+        let remaining = (1 << accuracyLog) + 1;
+        let threshold = 1 << accuracyLog;
+        let nbBits = accuracyLog + 1;
+        const probs = [];
+        while (remaining > 1) {
+            if (probs.length > maxSymbol) {
+                throw zstdError('too many FSE symbols');
+            }
+            const max = 2 * threshold - 1 - remaining;
+            let value;
+            if ((zstdBitsAt(bytes, bitPos, nbBits) & (threshold - 1)) < max) {
+                value = zstdBitsAt(bytes, bitPos, nbBits - 1);
+                bitPos += nbBits - 1;
+            } else {
+                value = zstdBitsAt(bytes, bitPos, nbBits);
+                if (value >= threshold) {
+                    value -= max;
+                }
+                bitPos += nbBits;
+            }
+            const prob = value - 1;
+            probs.push(prob);
+            remaining -= prob < 0 ? -prob : prob;
+            if (prob === 0) {
+                let repeat;
+                do {
+                    repeat = zstdBitsAt(bytes, bitPos, 2);
+                    bitPos += 2;
+                    for (let i = 0; i < repeat; i++) {
+                        probs.push(0);
+                    }
+                } while (repeat === 3);
+            }
+            while (remaining < threshold) {
+                nbBits--;
+                threshold >>= 1;
+            }
+        }
+        if (remaining !== 1 || probs.length > maxSymbol + 1) {
+            throw zstdError('invalid FSE table description');
+        }
+        return { table: zstdBuildFseTable(probs, accuracyLog), size: (bitPos + 7) >> 3 };
+    }
+
+    // 讀取 Huffman 樹的描述，回傳解碼表與描述所佔的位元組數
+    function zstdReadHuffmanTable(src, offset) {
+        const header = src[offset];
+        const weights = [];
+        let size;
+        if (header < 128) {
+            size = 1 + header;
+            const fse = zstdReadFseTable(src, offset + 1, 255, 6);
+            const table = fse.table;
+            const reader = zstdBackwardReader(src, offset + 1 + fse.size, offset + size);
+            let state1 = reader.read(table.accuracyLog);
+            let state2 = reader.read(table.accuracyLog);
+            for (;;) {
+                weights.push(table.symbol[state1]);
+                state1 = table.base[state1] + reader.read(table.bits[state1]);
+                if (reader.pos < 0) {
+                    weights.push(table.symbol[state2]);
+                    break;
+                }
+                weights.push(table.symbol[state2]);
+                state2 = table.base[state2] + reader.read(table.bits[state2]);
+                if (reader.pos < 0) {
+                    weights.push(table.symbol[state1]);
+                    break;
+                }
+                if (weights.length > 255) {
+                    throw zstdError('too many Huffman weights');
+                }
+            }
+        } else {
+            const count = header - 127;
+            size = 1 + ((count + 1) >> 1);
+            for (let i = 0; i < count; i++) {
+                const byte = src[offset + 1 + (i >> 1)];
+                weights.push(i & 1 ? byte & 15 : byte >> 4);
+            }
+        }
+        let total = 0;
+        for (const w of weights) {
+            if (w > 0) {
+                total += 1 << (w - 1);
+            }
+        }
+        if (total === 0) {
+            throw zstdError('invalid Huffman weights');
+        }
+        const maxBits = zstdHighBit(total) + 1;
+        const rest = (1 << maxBits) - total;
+        if (rest & (rest - 1)) {
+            throw zstdError('invalid Huffman weights');
+        }
+        weights.push(zstdHighBit(rest) + 1);
+        const rankStart = new Uint32Array(maxBits + 2);
+        for (const w of weights) {
+            if (w > 0) {
+                rankStart[w] += 1;
+            }
+        }
+        let next = 0;
+        for (let w = 1; w <= maxBits; w++) {
+            const count = rankStart[w];
+            rankStart[w] = next;
+            next += count << (w - 1);
+        }
+        const symbol = new Uint8Array(1 << maxBits);
+        const bits = new Uint8Array(1 << maxBits);
+        weights.forEach(function (w, s) {
+            if (w === 0) {
+                return;
+            }
+            const length = 1 << (w - 1);
+            symbol.fill(s, rankStart[w], rankStart[w] + length);
+            bits.fill(maxBits + 1 - w, rankStart[w], rankStart[w] + length);
+            rankStart[w] += length;
+        });
+        return { table: { maxBits: maxBits, symbol: symbol, bits: bits }, size: size };
+    }
+
+    function zstdDecodeHuffmanStream(src, start, end, table, out, outStart, count) {
+        const reader = zstdBackwardReader(src, start, end);
+        for (let i = 0; i < count; i++) {
+            const index = reader.peek(table.maxBits);
+            out[outStart + i] = table.symbol[index];
+            reader.pos -= table.bits[index];
+        }
+        if (reader.pos !== 0) {
+            throw zstdError('corrupted Huffman stream');
+        }
+    }
+
+    // 解碼區塊的字面值區段，回傳字面值與區段所佔的位元組數
+    function zstdReadLiterals(src, offset, frame) {
+        const b0 = src[offset];
+        const type = b0 & 3;
+        const format = (b0 >> 2) & 3;
+        if (type < 2) {
+            let regenerated;
+            let header;
+            if (format === 0 || format === 2) {
+                regenerated = b0 >> 3;
+                header = 1;
+            } else if (format === 1) {
+                regenerated = (b0 >> 4) + (src[offset + 1] << 4);
+                header = 2;
+            } else {
+                regenerated = (b0 >> 4) + (src[offset + 1] << 4) + (src[offset + 2] << 12);
+                header = 3;
+            }
+            const start = offset + header;
+            if (type === 0) {
+                return { literals: src.subarray(start, start + regenerated), size: header + regenerated };
+            }
+            return { literals: new Uint8Array(regenerated).fill(src[start]), size: header + 1 };
+        }
+        let regenerated;
+        let compressed;
+        let header;
+        const b1 = src[offset + 1];
+        const b2 = src[offset + 2];
+        if (format < 2) {
+            regenerated = (b0 >> 4) | ((b1 & 63) << 4);
+            compressed = (b1 >> 6) | (b2 << 2);
+            header = 3;
+        } else if (format === 2) {
+            regenerated = (b0 >> 4) | (b1 << 4) | ((b2 & 3) << 12);
+            compressed = (b2 >> 2) | (src[offset + 3] << 6);
+            header = 4;
+        } else {
+            regenerated = (b0 >> 4) | (b1 << 4) | ((b2 & 63) << 12);
+            compressed = (b2 >> 6) | (src[offset + 3] << 2) | (src[offset + 4] << 10);
+            header = 5;
+        }
+        let start = offset + header;
+        const end = start + compressed;
+        if (type === 2) {
+            const huffman = zstdReadHuffmanTable(src, start);
+            frame.huffman = huffman.table;
+            start += huffman.size;
+        } else if (!frame.huffman) {
+            throw zstdError('missing Huffman table');
+        }
+        const literals = new Uint8Array(regenerated);
+        if (format === 0) {
+            zstdDecodeHuffmanStream(src, start, end, frame.huffman, literals, 0, regenerated);
+        } else {
+            const size1 = src[start] | (src[start + 1] << 8);
+            const size2 = src[start + 2] | (src[start + 3] << 8);
+            const size3 = src[start + 4] | (src[start + 5] << 8);
+            const part = (regenerated + 3) >> 2;
+            let streamStart = start + 6;
+            const streamEnds = [streamStart + size1, streamStart + size1 + size2, streamStart + size1 + size2 + size3, end];
+            for (let i = 0; i < 4; i++) {
+                const count = i < 3 ? part : regenerated - 3 * part;
+                zstdDecodeHuffmanStream(src, streamStart, streamEnds[i], frame.huffman, literals, i * part, count);
+                streamStart = streamEnds[i];
+            }
+        }
+        return { literals: literals, size: header + compressed };
+    }
+
+    // 依壓縮模式取得序列的解碼表：預設分布、RLE、FSE 描述或沿用前一個區塊
+    function zstdSequenceTable(mode, src, offset, kind, frame) {
+        const limits = { ll: [35, 9, ZSTD_LL_DEFAULT, 6], ml: [52, 9, ZSTD_ML_DEFAULT, 6], of: [31, 8, ZSTD_OF_DEFAULT, 5] }[kind];
+        if (mode === 0) {
+            frame[kind] = zstdBuildFseTable(limits[2], limits[3]);
+            return 0;
+        }
+        if (mode === 1) {
+            frame[kind] = zstdRleTable(src[offset]);
+            return 1;
+        }
+        if (mode === 2) {
+            const fse = zstdReadFseTable(src, offset, limits[0], limits[1]);
+            frame[kind] = fse.table;
+            return fse.size;
+        }
+        if (!frame[kind]) {
+            throw zstdError('missing sequence table');
+        }
+        return 0;
+    }
+
+    function zstdDecodeBlock(src, start, end, frame, out) {
+        const lit = zstdReadLiterals(src, start, frame);
+        const literals = lit.literals;
+        let offset = start + lit.size;
+        const b0 = src[offset];
+        let count;
+        if (b0 < 128) {
+            count = b0;
+            offset += 1;
+        } else if (b0 < 255) {
+            count = ((b0 - 128) << 8) + src[offset + 1];
+            offset += 2;
+        } else {
+            count = src[offset + 1] + (src[offset + 2] << 8) + 0x7f00;
+            offset += 3;
+        }
+        let litPos = 0;
+        if (count > 0) {
+            const modes = src[offset++];
+            offset += zstdSequenceTable(modes >> 6, src, offset, 'll', frame);
+            offset += zstdSequenceTable((modes >> 4) & 3, src, offset, 'of', frame);
+            offset += zstdSequenceTable((modes >> 2) & 3, src, offset, 'ml', frame);
+            const ll = frame.ll;
+            const of = frame.of;
+            const ml = frame.ml;
+            const rep = frame.rep;
+            const reader = zstdBackwardReader(src, offset, end);
+            let llState = reader.read(ll.accuracyLog);
+            let ofState = reader.read(of.accuracyLog);
+            let mlState = reader.read(ml.accuracyLog);
+            for (let i = 0; i < count; i++) {
+                const ofCode = of.symbol[ofState];
+                const mlCode = ml.symbol[mlState];
+                const llCode = ll.symbol[llState];
+                const offsetValue = Math.pow(2, ofCode) + reader.read(ofCode);
+                const matchLength = ZSTD_ML_BASE[mlCode] + reader.read(ZSTD_ML_BITS[mlCode]);
+                const literalLength = ZSTD_LL_BASE[llCode] + reader.read(ZSTD_LL_BITS[llCode]);
+                let distance;
+                if (offsetValue > 3) {
+                    distance = offsetValue - 3;
+                    rep[2] = rep[1];
+                    rep[1] = rep[0];
+                    rep[0] = distance;
+                } else {
+                    const k = offsetValue - 1 + (literalLength === 0 ? 1 : 0);
+                    if (k === 0) {
+                        distance = rep[0];
+                    } else {
+                        distance = k === 3 ? rep[0] - 1 : rep[k];
+                        if (k !== 1) {
+                            rep[2] = rep[1];
+                        }
+                        rep[1] = rep[0];
+                        rep[0] = distance;
+                    }
+                }
+                if (i < count - 1) {
+                    llState = ll.base[llState] + reader.read(ll.bits[llState]);
+                    mlState = ml.base[mlState] + reader.read(ml.bits[mlState]);
+                    ofState = of.base[ofState] + reader.read(of.bits[ofState]);
+                }
+                if (litPos + literalLength > literals.length || distance > out.pos + literalLength || distance === 0) {
+                    throw zstdError('corrupted sequence');
+                }
+                out.write(literals.subarray(litPos, litPos + literalLength));
+                litPos += literalLength;
+                out.copyMatch(distance, matchLength);
+            }
+            if (reader.pos !== 0) {
+                throw zstdError('corrupted sequence stream');
+            }
+        }
+        out.write(literals.subarray(litPos));
+    }
+
+    // 可擴充的輸出緩衝區
+    function zstdOutput(capacity) {
+        const out = {
+            buf: new Uint8Array(Math.max(capacity, 1024)),
+            pos: 0,
+            reserve: function (n) {
+                if (out.pos + n > out.buf.length) {
+                    const grown = new Uint8Array(Math.max(out.buf.length * 2, out.pos + n));
+                    grown.set(out.buf.subarray(0, out.pos));
+                    out.buf = grown;
+                }
+            },
+            write: function (bytes) {
+                out.reserve(bytes.length);
+                out.buf.set(bytes, out.pos);
+                out.pos += bytes.length;
+            },
+            copyMatch: function (distance, length) {
+                out.reserve(length);
+                const buf = out.buf;
+                let from = out.pos - distance;
+                if (distance >= length) {
+                    buf.copyWithin(out.pos, from, from + length);
+                    out.pos += length;
+                    return;
+                }
+                for (let i = 0; i < length; i++) {
+                    buf[out.pos++] = buf[from++];
+                }
+            },
+        };
+        return out;
+    }
+
+    // 還原 Zstandard 資料，可包含多個框架與可略過的框架
+    function zstdDecompress(src) {
+        const view = new DataView(src.buffer, src.byteOffset, src.byteLength);
+        let offset = 0;
+        let out = null;
+        while (offset < src.length) {
+            const magic = view.getUint32(offset, true);
+            if ((magic & 0xfffffff0) === 0x184d2a50) {
+                offset += 8 + view.getUint32(offset + 4, true);
+                continue;
+            }
+            if (magic !== ZSTD_MAGIC) {
+                throw zstdError('unknown frame');
+            }
+            offset += 4;
+            const descriptor = src[offset++];
+            const sizeFlag = descriptor >> 6;
+            const singleSegment = (descriptor >> 5) & 1;
+            const hasChecksum = (descriptor >> 2) & 1;
+            if (descriptor & 3) {
+                throw zstdError('dictionaries are not supported');
+            }
+            if (!singleSegment) {
+                offset += 1;
+            }
+            let contentSize = 0;
+            if (sizeFlag === 0 && singleSegment) {
+                contentSize = src[offset];
+                offset += 1;
+            } else if (sizeFlag === 1) {
+                contentSize = view.getUint16(offset, true) + 256;
+                offset += 2;
+            } else if (sizeFlag === 2) {
+                contentSize = view.getUint32(offset, true);
+                offset += 4;
+            } else if (sizeFlag === 3) {
+                contentSize = view.getUint32(offset, true) + view.getUint32(offset + 4, true) * 4294967296;
+                offset += 8;
+            }
+            if (!out) {
+                out = zstdOutput(contentSize || src.length * 4);
+            } else {
+                out.reserve(contentSize);
+            }
+            const frame = { rep: [1, 4, 8], huffman: null, ll: null, of: null, ml: null };
+            let last = 0;
+            while (!last) {
+                const header = src[offset] | (src[offset + 1] << 8) | (src[offset + 2] << 16);
+                offset += 3;
+                last = header & 1;
+                const type = (header >> 1) & 3;
+                const size = header >>> 3;
+                if (type === 0) {
+                    out.write(src.subarray(offset, offset + size));
+                    offset += size;
+                } else if (type === 1) {
+                    out.reserve(size);
+                    out.buf.fill(src[offset], out.pos, out.pos + size);
+                    out.pos += size;
+                    offset += 1;
+                } else if (type === 2) {
+                    zstdDecodeBlock(src, offset, offset + size, frame, out);
+                    offset += size;
+                } else {
+                    throw zstdError('reserved block type');
+                }
+                if (offset > src.length) {
+                    throw zstdError('truncated data');
+                }
+            }
+            if (hasChecksum) {
+                offset += 4;
+            }
+        }
+        return out ? out.buf.slice(0, out.pos) : new Uint8Array(0);
+    }
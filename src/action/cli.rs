@@ -1,45 +1,245 @@
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use clap::Parser;
-use crate::config::config::{Cli, Mode, PasswordMode, validate_input_path, validate_file_patterns};
-use crate::utils::utils::setup_logging;
+use crate::config::config::{
+    App, Cli, Command, SUBCOMMAND_NAMES, Mode, PasswordMode, validate_input_path, validate_file_patterns, validate_name_pattern,
+    validate_output_extension, validate_split_size, validate_expires, DEFAULT_INDEX_NAME, DEFAULT_KEY_NAME,
+    DEFAULT_OUTPUT_EXTENSION, DEFAULT_PAYLOAD_ENCODING, DEFAULT_COMPRESSION_METHOD, DEFAULT_COMPRESSION_LEVEL,
+    DEFAULT_PAYLOAD_COMPRESSION, DEFAULT_MAX_HTML_SIZE, DEFAULT_OVERSIZE_POLICY, DEFAULT_PAYLOAD_MODE, validate_max_html_size,
+    FORMAT_VERSION, validate_format_version, validate_minify, DEFAULT_JOBS, validate_jobs,
+    validate_slices, validate_password_hint, parse_byte_size, validate_tags, validate_rules, parse_file_mode, DEFAULT_KEY_FILE_MODE,
+    DEFAULT_DATA_URI_THRESHOLD, parse_data_uri_threshold,
+};
+use crate::action::watch::{run_watch, WatchOptions};
+use crate::utils::log_sampler::DEFAULT_LOG_SAMPLE;
+use crate::utils::utils::{open_in_browser, setup_logging};
 use crate::config::ports::{AppConfig, ConfigPort};
+use crate::service::crypto::is_browser_encryption;
+use crate::service::clipboard::validate_clipboard;
+use crate::service::zip::{is_limited_compression_method, validate_archive_comment, validate_archive_root};
+use crate::service::gzip::validate_payload_compression_level;
 use crate::facade::conversion_facade::ConversionFacade;
 use crate::facade::traits::i_conversion::ConversionFacadeTrait;
-use crate::models::conversion::ConversionInput;
-use crate::service::config_service::{DefaultConfigAdapter};
+use crate::models::conversion::{ConversionInput, ConversionOutput};
+use crate::service::config_service::{validate_app_config, validate_double_layer, validate_group_by, validate_low_memory, validate_volume_size, ConfigWatcher, DefaultConfigAdapter, FileConfigAdapter, StaticConfigAdapter};
+use crate::service::job_file::JobFile;
 use crate::service::file::FileService;
 use crate::service::html::HtmlService;
+use crate::service::i18n::DEFAULT_LANG;
+use crate::service::prompt::{prompter_for, TerminalPrompter};
+use crate::service::traits::i_prompt::Prompter;
 use crate::service::zip::ZipService;
 
-pub fn process_args(args: Vec<String>) -> io::Result<String> {
+/// 子命令的執行結果，決定結束時印出的訊息與結束碼
+pub enum CommandOutcome {
+    // 產生輸出檔案的子命令，附上輸出目錄
+    Output(String),
+    // 自行印出結果、不產生轉換輸出的子命令，false 表示有項目未通過
+    Finished(bool),
+}
+
+pub fn process_args(args: Vec<String>) -> io::Result<CommandOutcome> {
     if args.len() == 1 {
-        crate::action::interactive::process_interactive_mode()
-    } else {
-        process_cli_mode()
+        return crate::action::interactive::process_interactive_mode(Arc::new(TerminalPrompter)).map(CommandOutcome::Output);
+    }
+    let app = App::parse_from(with_default_subcommand(args));
+    let global = &app.global;
+    let prompter = prompter_for(global.non_interactive);
+    match app.command {
+        Command::Convert(cli) => process_cli_mode(*cli, prompter).map(CommandOutcome::Output),
+        Command::Extract(cli) => crate::action::page::process_extract_mode(&cli, global).map(|()| CommandOutcome::Finished(true)),
+        Command::Inspect(cli) => crate::action::page::process_inspect_mode(&cli, global).map(CommandOutcome::Finished),
+        Command::Verify(cli) => crate::action::page::process_verify_mode(&cli, global).map(CommandOutcome::Finished),
+        Command::Serve(cli) => crate::action::server::process_server_mode(&cli, global, prompter).map(CommandOutcome::Output),
+        Command::Keys(cli) => crate::action::keys::process_keys_mode(&cli, global).map(|()| CommandOutcome::Finished(true)),
+        Command::Doctor(cli) => crate::action::doctor::process_doctor_mode(&cli).map(CommandOutcome::Finished),
+        Command::Decoder(cli) => crate::action::decoder::process_decoder_mode(&cli, global).map(CommandOutcome::Output),
+        Command::Stats(cli) => crate::action::stats::process_stats_mode(&cli).map(|()| CommandOutcome::Finished(true)),
+    }
+}
+
+// 第一個非全域選項的參數不是子命令或說明選項時，於前面補上 `convert`，
+// 讓 `file_to_html <input>` 維持原本的轉換用法；輸入路徑與子命令同名時需明確寫出 `convert`
+fn with_default_subcommand(mut args: Vec<String>) -> Vec<String> {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--log-level" | "--log-sample" => {
+                rest.next();
+            }
+            _ if arg.starts_with("--log-level=") || arg.starts_with("--log-sample=") => {}
+            "--yes" | "--non-interactive" => {}
+            "-h" | "--help" => return args,
+            _ if SUBCOMMAND_NAMES.contains(&arg.as_str()) => return args,
+            _ => break,
+        }
     }
+    args.insert(1, "convert".to_string());
+    args
 }
 
-pub fn process_cli_mode() -> io::Result<String> {
-    let cli = Cli::parse();
-    setup_logging(&cli.log_level.clone().unwrap_or("info".to_string()))?;
+pub fn process_cli_mode(cli: Cli, prompter: Arc<dyn Prompter>) -> io::Result<String> {
+    setup_logging(&cli.global.log_level.clone().unwrap_or("info".to_string()), cli.global.log_sample)?;
+    crate::utils::random::set_seed(cli.seed);
+    if let Some(seed) = cli.seed {
+        log::warn!("已指定亂數種子 {}，隨機密碼與加密鹽值可由種子重現，僅適用於測試與可重現建置", seed);
+    }
+    #[cfg(feature = "dynamic-plugins")]
+    for plugin_path in &cli.plugin {
+        crate::service::plugin::load_plugin(std::path::Path::new(plugin_path))?;
+    }
 
     let is_default_config = cli.mode.is_none()
         && cli.include.is_none()
         && cli.exclude.is_none()
         && cli.compress.is_none()
         && cli.password_mode.is_none()
+        && cli.inner_password_mode.is_none()
+        && cli.outer_password_mode.is_none()
         && cli.display_password.is_none()
+        && !cli.password_qr
         && cli.layer.is_none()
         && cli.encryption_method.is_none()
         && cli.no_progress.is_none()
         && cli.max_size.is_none()
-        && cli.log_level.is_none();
+        && cli.split_size.is_none()
+        && cli.global.log_level.is_none()
+        && cli.global.log_sample == DEFAULT_LOG_SAMPLE
+        && cli.view.is_none()
+        && !cli.index
+        && cli.format.is_none()
+        && cli.lang.is_none()
+        && cli.extra_css.is_none()
+        && cli.extra_js.is_none()
+        && cli.key_name.is_none()
+        && cli.index_name.is_none()
+        && cli.title.is_none()
+        && cli.logo.is_none()
+        && cli.footer.is_none()
+        && cli.output_extension.is_none()
+        && cli.expires.is_none()
+        && !cli.restore
+        && cli.payload_encoding.is_none()
+        && cli.compression_method.is_none()
+        && !cli.force_method
+        && cli.payload_compression.is_none()
+        && cli.max_html_size.is_none()
+        && cli.oversize_policy.is_none()
+        && !cli.password_verifier
+        && cli.payload_mode.is_none()
+        && cli.format_version.is_none()
+        && !cli.minify
+        && cli.jobs.is_none()
+        && cli.group_by.is_none()
+        && !cli.low_memory
+        && cli.slices.is_empty()
+        && !cli.key_sheet
+        && cli.password_hint.is_none()
+        && cli.transcode_text.is_none()
+        && !cli.keep_original_text
+        && cli.target_size.is_none()
+        && cli.line_endings.is_none()
+        && cli.tags.is_empty()
+        && cli.tag_rules.is_empty()
+        && cli.classification.is_none()
+        && !cli.auto_downgrade
+        && !cli.verify_output
+        && !cli.ascii_names
+        && cli.report.is_none()
+        && !cli.pwa
+        && !cli.a11y_strict
+        && cli.key_file_mode.is_none()
+        && cli.archive_format.is_none()
+        && !cli.ignore_case
+        && cli.match_on.is_none()
+        && cli.rules.is_empty()
+        && cli.payload_compression_level.is_none()
+        && cli.compression_level.is_none()
+        && !cli.allow_weak_password
+        && cli.export_job.is_none()
+        && cli.import_job.is_none()
+        && cli.on_change.is_none()
+        && cli.on_missing.is_none()
+        && !cli.no_preserve_metadata
+        && cli.message.is_none()
+        && cli.symlinks.is_none()
+        && !cli.viewer
+        && !cli.spill_to_disk
+        && !cli.no_js
+        && cli.data_uri_threshold.is_none()
+        && !cli.manifest
+        && !cli.wrap_base64
+        && cli.archive_root.is_none()
+        && !cli.strip_prefix
+        && cli.copy_to_clipboard.is_none()
+        && cli.volume_size.is_none()
+        && cli.stats_file.is_none()
+        && cli.comment.is_none()
+        && !cli.no_passthrough
+        && cli.config.is_none()
+        && !cli.watch
+        && cli.metrics_listen.is_none()
+        && cli.pid_file.is_none()
+        && cli.seed.is_none();
+
+    if cli.config.is_some() && cli.import_job.is_some() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "--import-job 不可與 --config 同時使用"));
+    }
+
+    // 使用設定檔時以設定檔內容為準，命令列的輸入/輸出路徑優先
+    if let Some(config_path) = &cli.config {
+        let adapter = FileConfigAdapter::new(
+            PathBuf::from(config_path),
+            Some(cli.input.clone()),
+            Some(cli.output.clone()),
+        );
+        let watcher = ConfigWatcher::new(adapter)?;
+        if cli.watch {
+            return run_watch(Some(watcher), AppConfig::default(), watch_options(&cli, prompter));
+        }
+        let config = watcher.current().clone();
+        return convert_with_config(&cli, config, prompter);
+    }
+
+    // 匯入工作檔時以工作檔的配置為準，命令列的輸入/輸出路徑優先，轉換前確認輸入檔案與記錄一致
+    if let Some(job_path) = &cli.import_job {
+        let job = JobFile::load(Path::new(job_path))?;
+        if cli.seed.is_none() {
+            if let Some(seed) = job.seed {
+                crate::utils::random::set_seed(Some(seed));
+                log::warn!("沿用工作檔的亂數種子 {}，隨機密碼與加密鹽值可由種子重現", seed);
+            }
+        }
+        let config = AppConfig {
+            input: cli.input.clone(),
+            output: cli.output.clone(),
+            export_job: cli.export_job.clone(),
+            job_files: Some(job.files),
+            ..job.config
+        };
+        validate_app_config(&config)?;
+        return convert_with_config(&cli, config, prompter);
+    }
 
     let config_port: Box<dyn ConfigPort> = if is_default_config {
         log::info!("未提供選項參數，使用預設配置：壓縮模式，單層壓縮，隨機密碼");
         Box::new(DefaultConfigAdapter::new(cli.input.clone(), cli.output.clone()))
     } else {
-        Box::new(CliConfigAdapter::new(cli.clone()))
+        Box::new(CliConfigAdapter::new(cli.clone(), Arc::clone(&prompter)))
+    };
+
+    if cli.watch {
+        let config = config_port.get_config()?;
+        return run_watch(None, config, watch_options(&cli, prompter));
+    }
+
+    // 依目錄分組時每個群組各自取得配置，先驗證並取得一次（手動密碼只詢問一次）供所有群組共用
+    let config_port: Box<dyn ConfigPort> = if cli.group_by.is_some() {
+        Box::new(StaticConfigAdapter::new(config_port.get_config()?))
+    } else {
+        config_port
     };
 
     let facade: Box<dyn ConversionFacadeTrait> = Box::new(ConversionFacade::new(
@@ -47,7 +247,7 @@ pub fn process_cli_mode() -> io::Result<String> {
         Box::new(FileService::new()),
         Box::new(ZipService::new()),
         Box::new(HtmlService::new()),
-    ));
+    ).with_prompter(prompter));
 
     let conversion_input = ConversionInput {
         input_path: std::path::Path::new(&cli.input).to_path_buf(),
@@ -63,14 +263,94 @@ pub fn process_cli_mode() -> io::Result<String> {
             Some("none") => crate::config::config::PasswordMode::None,
             _ => crate::config::config::PasswordMode::Random,
         },
+        inner_password_mode: cli.inner_password_mode.as_deref().map(password_mode_of),
+        outer_password_mode: cli.outer_password_mode.as_deref().map(password_mode_of),
         display_password: cli.display_password.unwrap_or(cli.password_mode.as_deref() == Some("random")),
-        layer: cli.layer.clone().unwrap_or("double".to_string()),
+        password_qr: cli.password_qr,
+        layer: cli.layer.clone().unwrap_or("single".to_string()),
         encryption_method: cli.encryption_method.clone().unwrap_or("aes256".to_string()),
         no_progress: cli.no_progress.unwrap_or(false),
         max_size: cli.max_size,
+        split_size: cli.split_size,
+        view: cli.view.clone().unwrap_or("archive".to_string()),
+        index: cli.index,
+        format: cli.format.clone().unwrap_or("html".to_string()),
+        lang: cli.lang.clone().unwrap_or(DEFAULT_LANG.to_string()),
+        extra_css: cli.extra_css.clone(),
+        extra_js: cli.extra_js.clone(),
+        key_name: cli.key_name.clone().unwrap_or(DEFAULT_KEY_NAME.to_string()),
+        index_name: cli.index_name.clone().unwrap_or(DEFAULT_INDEX_NAME.to_string()),
+        title: cli.title.clone(),
+        logo: cli.logo.clone(),
+        footer: cli.footer.clone(),
+        message: cli.message.clone(),
+        output_extension: cli.output_extension.clone().unwrap_or(DEFAULT_OUTPUT_EXTENSION.to_string()),
+        expires: cli.expires.clone(),
+        restore: cli.restore,
+        payload_encoding: cli.payload_encoding.clone().unwrap_or(DEFAULT_PAYLOAD_ENCODING.to_string()),
+        compression_method: cli.compression_method.clone().unwrap_or(DEFAULT_COMPRESSION_METHOD.to_string()),
+        force_method: cli.force_method,
+        compression_level: cli.compression_level.unwrap_or(DEFAULT_COMPRESSION_LEVEL),
+        payload_compression: cli.payload_compression.clone().unwrap_or(DEFAULT_PAYLOAD_COMPRESSION.to_string()),
+        payload_compression_level: cli.payload_compression_level,
+        max_html_size: cli.max_html_size.unwrap_or(DEFAULT_MAX_HTML_SIZE),
+        oversize_policy: cli.oversize_policy.clone().unwrap_or(DEFAULT_OVERSIZE_POLICY.to_string()),
+        password_verifier: cli.password_verifier,
+        payload_mode: cli.payload_mode.clone().unwrap_or(DEFAULT_PAYLOAD_MODE.to_string()),
+        format_version: cli.format_version.unwrap_or(FORMAT_VERSION),
+        minify: cli.minify,
+        jobs: cli.jobs.unwrap_or(DEFAULT_JOBS),
+        group_by: cli.group_by.clone(),
+        low_memory: cli.low_memory,
+        slices: cli.slices.clone(),
+        key_sheet: cli.key_sheet,
+        password_hint: cli.password_hint.clone(),
+        allow_weak_password: cli.allow_weak_password,
+        transcode_text: cli.transcode_text.clone(),
+        keep_original_text: cli.keep_original_text,
+        target_size: cli.target_size.clone(),
+        line_endings: cli.line_endings.clone(),
+        tags: cli.tags.clone(),
+        tag_rules: cli.tag_rules.clone(),
+        classification: cli.classification.clone(),
+        auto_downgrade: cli.auto_downgrade,
+        verify_output: cli.verify_output,
+        ascii_names: cli.ascii_names,
+        report: cli.report.clone(),
+        export_job: cli.export_job.clone(),
+        job_files: None,
+        preset_password: None,
+        pwa: cli.pwa,
+        viewer: cli.viewer,
+        spill_to_disk: cli.spill_to_disk,
+        no_js: cli.no_js,
+        data_uri_threshold: cli.data_uri_threshold.clone().unwrap_or(DEFAULT_DATA_URI_THRESHOLD.to_string()),
+        manifest: cli.manifest,
+        wrap_base64: cli.wrap_base64,
+        archive_root: cli.archive_root.clone(),
+        strip_prefix: cli.strip_prefix,
+        copy_to_clipboard: cli.copy_to_clipboard.clone(),
+        volume_size: cli.volume_size.clone(),
+        stats_file: cli.stats_file.clone(),
+        comment: cli.comment.clone(),
+        passthrough: !cli.no_passthrough,
+        a11y_strict: cli.a11y_strict,
+        key_file_mode: cli.key_file_mode.clone().unwrap_or(DEFAULT_KEY_FILE_MODE.to_string()),
+        archive_format: cli.archive_format.clone().unwrap_or("zip".to_string()),
+        ignore_case: cli.ignore_case,
+        match_on: cli.match_on.clone().unwrap_or("path".to_string()),
+        rules: cli.rules.clone(),
+        on_change: cli.on_change.clone().unwrap_or("warn".to_string()),
+        on_missing: cli.on_missing.clone().unwrap_or("skip".to_string()),
+        symlinks: cli.symlinks.clone().unwrap_or("skip".to_string()),
+        preserve_metadata: !cli.no_preserve_metadata,
     };
 
     let output = facade.execute_conversion(conversion_input)?;
+    print_downgrades(&output);
+    print_unmatched_patterns(&output);
+    print_missing_files(&output);
+    open_output(&cli, &output);
     if cli.show_config {
         println!("實際使用的配置：{:#?}", output);
     }
@@ -78,14 +358,98 @@ pub fn process_cli_mode() -> io::Result<String> {
     Ok(output.output_path)
 }
 
+// 以已載入的配置執行一次轉換，用於設定檔與工作檔
+fn convert_with_config(cli: &Cli, config: AppConfig, prompter: Arc<dyn Prompter>) -> io::Result<String> {
+    let facade = ConversionFacade::new(
+        Box::new(StaticConfigAdapter::new(config.clone())),
+        Box::new(FileService::new()),
+        Box::new(ZipService::new()),
+        Box::new(HtmlService::new()),
+    )
+    .with_prompter(prompter);
+    let output = facade.execute_conversion(ConversionInput::from(&config))?;
+    print_downgrades(&output);
+    print_unmatched_patterns(&output);
+    print_missing_files(&output);
+    open_output(cli, &output);
+    if cli.show_config {
+        println!("實際使用的配置：{:#?}", output);
+    }
+    Ok(output.output_path)
+}
+
+// 將命令列的密碼模式轉為 PasswordMode，值已由 clap 限制
+fn password_mode_of(mode: &str) -> PasswordMode {
+    match mode {
+        "manual" => PasswordMode::Manual,
+        "timestamp" => PasswordMode::Timestamp,
+        "none" => PasswordMode::None,
+        _ => PasswordMode::Random,
+    }
+}
+
+// 列出 --auto-downgrade 為了完成轉換而調整的設定，方便之後直接以這些參數執行
+fn print_downgrades(output: &ConversionOutput) {
+    if output.downgrades.is_empty() {
+        return;
+    }
+    println!("已自動調整以下設定後完成轉換：");
+    for change in &output.downgrades {
+        println!("  - {}", change);
+    }
+}
+
+/// 列出沒有符合任何檔案的 include 模式及不分大小寫時的相近結果，避免打錯大小寫的模式被忽略
+pub fn print_unmatched_patterns(output: &ConversionOutput) {
+    for pattern in &output.unmatched_patterns {
+        println!("注意：{}", pattern.describe());
+    }
+}
+
+// 列出蒐集後已被刪除而略過的檔案
+pub fn print_missing_files(output: &ConversionOutput) {
+    if output.missing_files.is_empty() {
+        return;
+    }
+    println!("注意：{} 個檔案在蒐集後已被刪除，未包含在產出中：", output.missing_files.len());
+    for file in &output.missing_files {
+        println!("  {}", file.display());
+    }
+}
+
+// 指定 --open 且只產生一個頁面時，以預設瀏覽器開啟該頁面；開啟失敗不影響轉換結果
+fn open_output(cli: &Cli, output: &ConversionOutput) {
+    if !cli.open {
+        return;
+    }
+    match &output.single_page {
+        Some(page) => {
+            if let Err(e) = open_in_browser(Path::new(page)) {
+                log::warn!("{}", e);
+            }
+        }
+        None => log::warn!("--open 僅適用於只產生一個 HTML 頁面的轉換（不含分段、索引頁與還原頁），已略過"),
+    }
+}
+
+fn watch_options(cli: &Cli, prompter: Arc<dyn Prompter>) -> WatchOptions {
+    WatchOptions {
+        interval: Duration::from_secs(cli.watch_interval),
+        metrics_listen: cli.metrics_listen.clone(),
+        pid_file: cli.pid_file.clone(),
+        prompter,
+    }
+}
+
 // CLI 配置適配器
 pub struct CliConfigAdapter {
     cli: Cli,
+    prompter: Arc<dyn Prompter>,
 }
 
 impl CliConfigAdapter {
-    pub fn new(cli: Cli) -> Self {
-        CliConfigAdapter { cli }
+    pub fn new(cli: Cli, prompter: Arc<dyn Prompter>) -> Self {
+        CliConfigAdapter { cli, prompter }
     }
 }
 
@@ -103,6 +467,261 @@ impl ConfigPort for CliConfigAdapter {
             ));
         }
 
+        // 雙層壓縮的外層 ZIP 包住完整的內層 ZIP，樹狀檢視與低記憶體模式無法讀取或串流內層項目
+        validate_double_layer(
+            self.cli.layer.as_deref().unwrap_or("single"),
+            self.cli.inner_password_mode.is_some() || self.cli.outer_password_mode.is_some(),
+            &[
+                (self.cli.archive_format.as_deref().is_some_and(|format| format != "zip"), "--archive-format"),
+                (self.cli.view.as_deref() == Some("tree"), "--view tree"),
+                (self.cli.low_memory, "--low-memory"),
+                (self.cli.key_sheet, "--key-sheet"),
+            ],
+        )?;
+
+        validate_low_memory(
+            self.cli.low_memory,
+            self.cli.archive_format.as_deref() == Some("none") || self.cli.layer.as_deref() == Some("none"),
+        )?;
+
+        // 依目錄分組時每個子目錄各產生一個壓縮頁面，還原頁與工作檔只對應單一轉換；手動密碼只在開始前詢問一次，無法分別詢問雙層壓縮的內外層密碼
+        validate_group_by(
+            self.cli.group_by.as_deref(),
+            &[
+                (self.cli.mode == Some(Mode::Individual), "--mode individual"),
+                (
+                    self.cli.layer.as_deref() == Some("double")
+                        && [&self.cli.password_mode, &self.cli.inner_password_mode, &self.cli.outer_password_mode]
+                            .iter()
+                            .any(|mode| mode.as_deref() == Some("manual")),
+                    "--layer double 的手動密碼",
+                ),
+                (self.cli.restore, "--restore"),
+                (self.cli.export_job.is_some(), "--export-job"),
+            ],
+        )?;
+
+        // 樹狀檢視需在瀏覽器端讀取 ZIP 項目，無法處理 ZIP 的 AES 加密
+        if self.cli.view.as_deref() == Some("tree")
+            && self.cli.password_mode.as_deref() != Some("none")
+            && !self.cli.encryption_method.as_deref().is_some_and(is_browser_encryption)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "樹狀檢視需搭配 '--password-mode none' 或 '--encryption-method webcrypto'"
+            ));
+        }
+
+        // tar 沒有加密機制，設定密碼時需改由瀏覽器端解密保護；7z 只以 AES-256 加密
+        if self.cli.archive_format.as_deref().is_some_and(|format| format.starts_with("tar"))
+            && self.cli.password_mode.as_deref() != Some("none")
+            && !self.cli.encryption_method.as_deref().is_some_and(is_browser_encryption)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "tar 封存格式不支援 ZIP 的 AES 加密，請搭配 '--password-mode none' 或 '--encryption-method webcrypto'"
+            ));
+        }
+        // 不封存時頁面資料即為來源檔案本身，只能逐檔轉換，也沒有 ZIP 可加密
+        if self.cli.archive_format.as_deref() == Some("none") && self.cli.mode == Some(Mode::Compressed) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "不封存（--archive-format none）時每個檔案各自產生頁面，無法搭配壓縮模式，請改用 '--mode individual'"
+            ));
+        }
+        if (self.cli.archive_format.as_deref() == Some("none") || self.cli.layer.as_deref() == Some("none"))
+            && self.cli.password_mode.as_deref() != Some("none")
+            && !self.cli.encryption_method.as_deref().is_some_and(is_browser_encryption)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "不封存時頁面資料即為來源檔案，無法以 ZIP 的 AES 加密，請搭配 '--password-mode none' 或 '--encryption-method webcrypto'"
+            ));
+        }
+        if self.cli.archive_format.as_deref() == Some("7z")
+            && self.cli.password_mode.as_deref() != Some("none")
+            && matches!(self.cli.encryption_method.as_deref(), Some("aes128" | "aes192"))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "7z 封存格式僅支援 AES-256 加密，請改用 '--encryption-method aes256' 或 'webcrypto'"
+            ));
+        }
+        // tar.gz 與 7z 整體壓縮，樹狀檢視無法以位移讀取項目
+        if let Some(format) = self.cli.archive_format.as_deref().filter(|format| matches!(*format, "tar.gz" | "7z")) {
+            if self.cli.view.as_deref() == Some("tree") {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("樹狀檢視無法讀取 {} 中的項目，請改用 '--archive-format zip'、'tar' 或 '--view archive'", format)
+                ));
+            }
+        }
+
+        // 項目清單寫在 ZIP 的第一個項目，不分層時沒有封存檔可放
+        if self.cli.manifest {
+            if let Some(format) = self.cli.archive_format.as_deref().filter(|format| *format != "zip") {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("項目清單（--manifest）僅適用於 ZIP 封存格式，無法搭配 '--archive-format {}'", format)
+                ));
+            }
+            if self.cli.layer.as_deref() == Some("none") {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "項目清單（--manifest）需寫入封存檔，無法搭配 '--layer none'"
+                ));
+            }
+        }
+
+        // 封存檔註解寫在 ZIP 的結尾記錄，其他封存格式與不分層時沒有可寫入的位置
+        if let Some(comment) = &self.cli.comment {
+            if let Some(format) = self.cli.archive_format.as_deref().filter(|format| *format != "zip") {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("封存檔註解（--comment）僅適用於 ZIP 封存格式，無法搭配 '--archive-format {}'", format)
+                ));
+            }
+            if self.cli.layer.as_deref() == Some("none") {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "封存檔註解（--comment）需寫入封存檔，無法搭配 '--layer none'"
+                ));
+            }
+            validate_archive_comment(comment)?;
+        }
+
+        // 封存根目錄取代輸入目錄的名稱，去除前綴則不保留頂層資料夾，兩者互斥
+        if let Some(archive_root) = &self.cli.archive_root {
+            if self.cli.strip_prefix {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--archive-root 與 --strip-prefix 無法同時使用"
+                ));
+            }
+            validate_archive_root(archive_root)?;
+        }
+
+        // 樹狀檢視僅能以 DecompressionStream 解開 deflate 項目
+        if self.cli.view.as_deref() == Some("tree")
+            && self.cli.force_method
+            && self.cli.compression_method.as_deref().is_some_and(is_limited_compression_method)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "樹狀檢視僅能在瀏覽器中解壓 deflate 項目，無法搭配 '--force-method'"
+            ));
+        }
+
+        // MHTML 以附件提供 ZIP，多數檢視器不執行頁面腳本，無法在瀏覽器中解密或瀏覽項目
+        if self.cli.format.as_deref() == Some("mhtml")
+            && (self.cli.view.as_deref() == Some("tree")
+                || self.cli.encryption_method.as_deref().is_some_and(is_browser_encryption))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "MHTML 格式不支援樹狀檢視與瀏覽器端解密（webcrypto），請改用 ZIP 的 AES 加密"
+            ));
+        }
+
+        // JSON 輸出的資料需能直接還原為 ZIP，不接受只有頁面腳本能解開的 AES-GCM 加密
+        if self.cli.format.as_deref() == Some("json")
+            && self.cli.encryption_method.as_deref().is_some_and(is_browser_encryption)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "JSON 格式不支援瀏覽器端解密（webcrypto），請改用 ZIP 的 AES 加密"
+            ));
+        }
+
+        // 離線安裝需要頁面連結 Manifest 並註冊 Service Worker，其他格式無法使用
+        if let Some(format) = self.cli.format.as_deref().filter(|format| self.cli.pwa && *format != "html") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("離線安裝（--pwa）僅適用於 HTML 格式，無法搭配 '--format {}'", format)
+            ));
+        }
+
+        // 離線檢視頁只能開啟 HTML 頁面
+        if let Some(format) = self.cli.format.as_deref().filter(|format| self.cli.viewer && *format != "html") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("離線檢視頁（--viewer）僅適用於 HTML 格式，無法搭配 '--format {}'", format)
+            ));
+        }
+
+        // 不含腳本的頁面只能以資料連結或 Base64 文字提供資料，需要頁面腳本的功能皆無法使用
+        if self.cli.no_js {
+            let conflicts = [
+                (self.cli.format.as_deref().is_some_and(|format| format != "html"), "--format"),
+                (
+                    self.cli.password_mode.as_deref() != Some("none")
+                        && self.cli.encryption_method.as_deref().is_some_and(is_browser_encryption),
+                    "--encryption-method webcrypto",
+                ),
+                (self.cli.view.as_deref() == Some("tree"), "--view tree"),
+                (self.cli.payload_mode.as_deref() == Some("external"), "--payload external"),
+                (self.cli.payload_compression.as_deref().is_some_and(|compression| compression != "none"), "--payload-compression"),
+                (self.cli.payload_encoding.as_deref().is_some_and(|encoding| encoding != "base64"), "--payload-encoding"),
+                (self.cli.split_size.is_some(), "--split-size"),
+                (self.cli.volume_size.is_some(), "--volume-size"),
+                (self.cli.target_size.is_some(), "--target-size"),
+                (self.cli.extra_js.is_some(), "--extra-js"),
+                (self.cli.expires.is_some(), "--expires"),
+                (self.cli.pwa, "--pwa"),
+            ];
+            if let Some((_, option)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("不含腳本的頁面（--no-js）無法搭配 '{}'", option)
+                ));
+            }
+        }
+
+        validate_split_size(self.cli.split_size)?;
+        if let Some(max_html_size) = self.cli.max_html_size {
+            validate_max_html_size(max_html_size)?;
+        }
+        if let Some(format_version) = self.cli.format_version {
+            validate_format_version(format_version)?;
+        }
+        validate_minify(self.cli.minify)?;
+        validate_clipboard(self.cli.copy_to_clipboard.as_deref())?;
+        validate_slices(&self.cli.slices)?;
+        validate_tags(&self.cli.tags, &self.cli.tag_rules)?;
+        validate_rules(&self.cli.rules)?;
+        validate_payload_compression_level(
+            self.cli.payload_compression.as_deref().unwrap_or(DEFAULT_PAYLOAD_COMPRESSION),
+            self.cli.payload_compression_level,
+        )?;
+        if let Some(hint) = &self.cli.password_hint {
+            validate_password_hint(hint)?;
+        }
+        if let Some(target_size) = &self.cli.target_size {
+            parse_byte_size(target_size)?;
+        }
+        validate_volume_size(self.cli.volume_size.as_deref(), self.cli.split_size)?;
+        if let Some(threshold) = &self.cli.data_uri_threshold {
+            parse_data_uri_threshold(threshold)?;
+        }
+        if let Some(mode) = &self.cli.key_file_mode {
+            parse_file_mode(mode)?;
+        }
+        if let Some(jobs) = self.cli.jobs {
+            validate_jobs(jobs)?;
+        }
+        if let Some(key_name) = &self.cli.key_name {
+            validate_name_pattern("--key-name", key_name, true)?;
+        }
+        if let Some(index_name) = &self.cli.index_name {
+            validate_name_pattern("--index-name", index_name, false)?;
+        }
+        if let Some(extension) = &self.cli.output_extension {
+            validate_output_extension(extension)?;
+        }
+        if let Some(expires) = &self.cli.expires {
+            validate_expires(expires)?;
+        }
+
         let password_mode = match self.cli.password_mode.as_deref() {
             Some("random") => PasswordMode::Random,
             Some("manual") => PasswordMode::Manual,
@@ -111,15 +730,19 @@ impl ConfigPort for CliConfigAdapter {
             _ => PasswordMode::Random, // 預設隨機密碼
         };
 
-        if self.cli.password_mode.as_deref() == Some("manual") {
-            Some(crate::action::interactive::prompt_manual_password()?)
+        // 手動模式在此先詢問一次，轉換時沿用；雙層壓縮的內外層都另有指定模式時不會用到
+        let uses_password_mode = self.cli.layer.as_deref() != Some("double")
+            || self.cli.inner_password_mode.is_none()
+            || self.cli.outer_password_mode.is_none();
+        let preset_password = if password_mode == PasswordMode::Manual && uses_password_mode {
+            Some(crate::utils::utils::prompt_manual_password(self.prompter.as_ref(), self.cli.allow_weak_password)?)
         } else {
             None
         };
 
         // 檢查是否忽略了自訂參數
         if self.cli.mode != Some(Mode::Individual) ||
-            self.cli.layer.as_deref() != Some("double") ||
+            self.cli.layer.as_deref() != Some("single") ||
             self.cli.password_mode.as_deref() != Some("random") ||
             self.cli.compress != Some(true) ||
             self.cli.encryption_method.as_deref() != Some("aes256") ||
@@ -142,11 +765,87 @@ impl ConfigPort for CliConfigAdapter {
             include: self.cli.include.clone().unwrap_or(vec!["*".to_string()]),
             exclude: self.cli.exclude.clone(),
             password_mode,
+            inner_password_mode: self.cli.inner_password_mode.as_deref().map(password_mode_of),
+            outer_password_mode: self.cli.outer_password_mode.as_deref().map(password_mode_of),
             display_password: self.cli.display_password.unwrap_or(self.cli.password_mode.as_deref() == Some("random")),
-            layer: self.cli.layer.clone().unwrap_or("double".to_string()),
+            password_qr: self.cli.password_qr,
+            layer: self.cli.layer.clone().unwrap_or("single".to_string()),
             encryption_method: self.cli.encryption_method.clone().unwrap_or("aes256".to_string()),
             no_progress: self.cli.no_progress.unwrap_or(false),
             max_size: self.cli.max_size,
+            split_size: self.cli.split_size,
+            view: self.cli.view.clone().unwrap_or("archive".to_string()),
+            index: self.cli.index,
+            format: self.cli.format.clone().unwrap_or("html".to_string()),
+            lang: self.cli.lang.clone().unwrap_or(DEFAULT_LANG.to_string()),
+            extra_css: self.cli.extra_css.clone(),
+            extra_js: self.cli.extra_js.clone(),
+            key_name: self.cli.key_name.clone().unwrap_or(DEFAULT_KEY_NAME.to_string()),
+            index_name: self.cli.index_name.clone().unwrap_or(DEFAULT_INDEX_NAME.to_string()),
+            title: self.cli.title.clone(),
+            logo: self.cli.logo.clone(),
+            footer: self.cli.footer.clone(),
+            message: self.cli.message.clone(),
+            output_extension: self.cli.output_extension.clone().unwrap_or(DEFAULT_OUTPUT_EXTENSION.to_string()),
+            expires: self.cli.expires.clone(),
+            restore: self.cli.restore,
+            payload_encoding: self.cli.payload_encoding.clone().unwrap_or(DEFAULT_PAYLOAD_ENCODING.to_string()),
+            compression_method: self.cli.compression_method.clone().unwrap_or(DEFAULT_COMPRESSION_METHOD.to_string()),
+            force_method: self.cli.force_method,
+            compression_level: self.cli.compression_level.unwrap_or(DEFAULT_COMPRESSION_LEVEL),
+            payload_compression: self.cli.payload_compression.clone().unwrap_or(DEFAULT_PAYLOAD_COMPRESSION.to_string()),
+            payload_compression_level: self.cli.payload_compression_level,
+            max_html_size: self.cli.max_html_size.unwrap_or(DEFAULT_MAX_HTML_SIZE),
+            oversize_policy: self.cli.oversize_policy.clone().unwrap_or(DEFAULT_OVERSIZE_POLICY.to_string()),
+            password_verifier: self.cli.password_verifier,
+            payload_mode: self.cli.payload_mode.clone().unwrap_or(DEFAULT_PAYLOAD_MODE.to_string()),
+            format_version: self.cli.format_version.unwrap_or(FORMAT_VERSION),
+            minify: self.cli.minify,
+            jobs: self.cli.jobs.unwrap_or(DEFAULT_JOBS),
+            group_by: self.cli.group_by.clone(),
+            low_memory: self.cli.low_memory,
+            slices: self.cli.slices.clone(),
+            key_sheet: self.cli.key_sheet,
+            password_hint: self.cli.password_hint.clone(),
+            allow_weak_password: self.cli.allow_weak_password,
+            transcode_text: self.cli.transcode_text.clone(),
+            keep_original_text: self.cli.keep_original_text,
+            target_size: self.cli.target_size.clone(),
+            line_endings: self.cli.line_endings.clone(),
+            tags: self.cli.tags.clone(),
+            tag_rules: self.cli.tag_rules.clone(),
+            classification: self.cli.classification.clone(),
+            auto_downgrade: self.cli.auto_downgrade,
+            verify_output: self.cli.verify_output,
+            ascii_names: self.cli.ascii_names,
+            report: self.cli.report.clone(),
+            export_job: self.cli.export_job.clone(),
+            job_files: None,
+            preset_password,
+            pwa: self.cli.pwa,
+            viewer: self.cli.viewer,
+            spill_to_disk: self.cli.spill_to_disk,
+            no_js: self.cli.no_js,
+            data_uri_threshold: self.cli.data_uri_threshold.clone().unwrap_or(DEFAULT_DATA_URI_THRESHOLD.to_string()),
+            manifest: self.cli.manifest,
+            wrap_base64: self.cli.wrap_base64,
+            archive_root: self.cli.archive_root.clone(),
+            strip_prefix: self.cli.strip_prefix,
+            copy_to_clipboard: self.cli.copy_to_clipboard.clone(),
+            volume_size: self.cli.volume_size.clone(),
+            stats_file: self.cli.stats_file.clone(),
+            comment: self.cli.comment.clone(),
+            passthrough: !self.cli.no_passthrough,
+            a11y_strict: self.cli.a11y_strict,
+            key_file_mode: self.cli.key_file_mode.clone().unwrap_or(DEFAULT_KEY_FILE_MODE.to_string()),
+            archive_format: self.cli.archive_format.clone().unwrap_or("zip".to_string()),
+            ignore_case: self.cli.ignore_case,
+            match_on: self.cli.match_on.clone().unwrap_or("path".to_string()),
+            rules: self.cli.rules.clone(),
+            on_change: self.cli.on_change.clone().unwrap_or("warn".to_string()),
+            on_missing: self.cli.on_missing.clone().unwrap_or("skip".to_string()),
+            symlinks: self.cli.symlinks.clone().unwrap_or("skip".to_string()),
+            preserve_metadata: !self.cli.no_preserve_metadata,
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+use std::fs;
+use std::io;
+use crate::config::config::{DecoderCli, GlobalArgs};
+use crate::service::html::HtmlService;
+use crate::service::i18n::DEFAULT_LANG;
+use crate::service::traits::i_service::HtmlServiceTrait;
+use crate::utils::utils::setup_logging;
+
+/// 解碼工具入口：在輸出目錄產生獨立的解碼工具頁
+/// # 參數
+/// - cli: `decoder` 子命令的參數
+/// - global: 全域選項
+/// # 回傳
+/// - 成功時返回輸出目錄，失敗時返回 IO 錯誤
+pub fn process_decoder_mode(cli: &DecoderCli, global: &GlobalArgs) -> io::Result<String> {
+    setup_logging(&global.log_level.clone().unwrap_or("info".to_string()), global.log_sample)?;
+    if cli.name.is_empty() || cli.name.contains(['/', '\\']) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("無效的解碼工具頁檔名 '{}'，不可包含路徑分隔符", cli.name)));
+    }
+    fs::create_dir_all(&cli.output)?;
+    let lang = cli.lang.as_deref().unwrap_or(DEFAULT_LANG);
+    HtmlService::new().generate_decoder(&cli.output, &cli.name, lang)?;
+    Ok(cli.output.clone())
+}
@@ -0,0 +1,295 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use jwalk::WalkDir;
+
+use crate::config::config::DoctorCli;
+use crate::service::i18n::{DEFAULT_LANG, SUPPORTED_LANGS};
+use crate::utils::locale::LocaleFormat;
+
+// 未指定輸入時，輸出目錄與暫存目錄建議保留的最低可用空間
+const MIN_FREE_SPACE: u64 = 100 * 1024 * 1024;
+// Base64 嵌入後的頁面大小約為資料的 4/3，另保留頁面模板與金鑰檔的空間
+const PAGE_SIZE_FACTOR: f64 = 1.4;
+
+// 常見瀏覽器開啟單一大型 HTML 頁面的經驗上限（MB）與說明
+const BROWSER_LIMITS: &[(&str, u64, &str)] = &[
+    ("行動瀏覽器（iOS / Android）", 50, "記憶體較少，超過時常見分頁重新載入或當機"),
+    ("Safari（macOS）", 256, "大型頁面解碼時容易觸發分頁記憶體限制"),
+    ("Chrome / Edge", 512, "V8 單一字串長度上限約 512MB"),
+    ("Firefox", 1024, "單一字串長度上限約 1GB"),
+];
+
+#[derive(PartialEq)]
+enum Status {
+    Ok,
+    Info,
+    Warn,
+    Fail,
+}
+
+// 單項檢查結果與建議的修正方式
+struct Diagnosis {
+    status: Status,
+    title: String,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Diagnosis {
+    fn new(status: Status, title: &str, detail: String, fix: Option<String>) -> Self {
+        Diagnosis { status, title: title.to_string(), detail, fix }
+    }
+}
+
+/// 環境檢查入口：逐項檢查執行環境並印出結果與修正建議
+/// # 參數
+/// - cli: `doctor` 子命令的參數
+/// # 回傳
+/// - 沒有錯誤項目時返回 true，有任一項錯誤時返回 false
+pub fn process_doctor_mode(cli: &DoctorCli) -> io::Result<bool> {
+    let input_size = match cli.input.as_deref() {
+        Some(input) => Some(input_total_size(Path::new(input))?),
+        None => None,
+    };
+    let required = input_size.map(|size| (size as f64 * PAGE_SIZE_FACTOR) as u64);
+
+    let mut results = vec![check_output_dir(Path::new(&cli.output))];
+    results.push(check_disk_space("輸出目錄可用空間", Path::new(&cli.output), required));
+    results.extend(check_temp_dir());
+    results.push(check_console_encoding());
+    results.push(check_locale());
+    results.extend(check_browser_limits(input_size));
+
+    println!("file_to_html 環境檢查");
+    for result in &results {
+        let label = match result.status {
+            Status::Ok => "[正常]",
+            Status::Info => "[提示]",
+            Status::Warn => "[警告]",
+            Status::Fail => "[錯誤]",
+        };
+        println!("{} {}：{}", label, result.title, result.detail);
+        if let Some(fix) = &result.fix {
+            println!("       建議：{}", fix);
+        }
+    }
+    let count = |status: Status| results.iter().filter(|r| r.status == status).count();
+    let failures = count(Status::Fail);
+    println!("檢查完成：{} 項正常，{} 項警告，{} 項錯誤", count(Status::Ok), count(Status::Warn), failures);
+    Ok(failures == 0)
+}
+
+// 計算輸入檔案或目錄的總大小
+fn input_total_size(input: &Path) -> io::Result<u64> {
+    let metadata = fs::metadata(input)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法讀取輸入路徑 {}: {}", input.display(), e)))?;
+    if metadata.is_file() {
+        return Ok(metadata.len());
+    }
+    Ok(WalkDir::new(input)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum())
+}
+
+// 取得路徑本身或最近一層已存在的上層目錄，輸出目錄尚未建立時以此判斷權限與空間
+fn nearest_existing(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir().map(|dir| dir.join(path)).unwrap_or_else(|_| path.to_path_buf())
+    };
+    absolute
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .map(Path::to_path_buf)
+        .unwrap_or(absolute)
+}
+
+// 於目錄中建立並移除測試檔，確認實際可寫入
+fn probe_write(dir: &Path) -> io::Result<()> {
+    let probe = dir.join(format!(".file_to_html_doctor_{}", std::process::id()));
+    fs::write(&probe, b"ok")?;
+    fs::remove_file(&probe)
+}
+
+fn check_output_dir(output: &Path) -> Diagnosis {
+    const TITLE: &str = "輸出目錄";
+    if output.exists() && !output.is_dir() {
+        return Diagnosis::new(
+            Status::Fail,
+            TITLE,
+            format!("{} 已存在但不是目錄", output.display()),
+            Some("以 -o 指定其他輸出目錄，或移除同名檔案".to_string()),
+        );
+    }
+    let dir = nearest_existing(output);
+    match probe_write(&dir) {
+        Ok(()) if output.exists() => Diagnosis::new(Status::Ok, TITLE, format!("{} 可寫入", output.display()), None),
+        Ok(()) => Diagnosis::new(
+            Status::Ok,
+            TITLE,
+            format!("{} 尚未建立，轉換時會在 {} 下建立", output.display(), dir.display()),
+            None,
+        ),
+        Err(e) => Diagnosis::new(
+            Status::Fail,
+            TITLE,
+            format!("無法寫入 {}: {}", dir.display(), e),
+            Some(format!("以 -o 指定可寫入的目錄，或調整 {} 的權限", dir.display())),
+        ),
+    }
+}
+
+fn check_disk_space(title: &str, path: &Path, required: Option<u64>) -> Diagnosis {
+    let dir = nearest_existing(path);
+    let available = match fs4::available_space(&dir) {
+        Ok(available) => available,
+        Err(e) => {
+            return Diagnosis::new(Status::Warn, title, format!("無法取得 {} 的可用空間: {}", dir.display(), e), None);
+        }
+    };
+    let detail = format!("{} 剩餘 {}", dir.display(), LocaleFormat::system().format_size(available as usize));
+    match required {
+        Some(required) if available < required => Diagnosis::new(
+            Status::Fail,
+            title,
+            format!("{}，預估需要 {}", detail, LocaleFormat::system().format_size(required as usize)),
+            Some("清出磁碟空間、以 -o 指定其他磁碟，或以 --include / --exclude 減少輸入檔案".to_string()),
+        ),
+        None if available < MIN_FREE_SPACE => Diagnosis::new(
+            Status::Warn,
+            title,
+            detail,
+            Some("可用空間不足 100MB，轉換大型檔案前請先清出磁碟空間".to_string()),
+        ),
+        _ => Diagnosis::new(Status::Ok, title, detail, None),
+    }
+}
+
+fn check_temp_dir() -> Vec<Diagnosis> {
+    const TITLE: &str = "暫存目錄";
+    let temp = env::temp_dir();
+    let fix = if cfg!(windows) { "將 TEMP 環境變數指向可寫入的目錄" } else { "將 TMPDIR 環境變數指向可寫入的目錄" };
+    if let Err(e) = probe_write(&temp) {
+        return vec![Diagnosis::new(
+            Status::Fail,
+            TITLE,
+            format!("無法寫入 {}: {}", temp.display(), e),
+            Some(fix.to_string()),
+        )];
+    }
+    vec![
+        Diagnosis::new(Status::Ok, TITLE, format!("{} 可寫入", temp.display()), None),
+        check_disk_space("暫存目錄可用空間", &temp, None),
+    ]
+}
+
+// 判斷主控台是否使用 UTF-8，否則中文訊息與檔名可能顯示為亂碼
+fn check_console_encoding() -> Diagnosis {
+    const TITLE: &str = "主控台編碼";
+    if cfg!(windows) {
+        return Diagnosis::new(
+            Status::Info,
+            TITLE,
+            "Windows 無法自動偵測主控台字碼頁".to_string(),
+            Some("若中文訊息顯示為亂碼，請先執行 `chcp 65001` 或改用 Windows Terminal".to_string()),
+        );
+    }
+    let setting = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()).map(|value| (*name, value)));
+    match setting {
+        Some((name, value)) if value.to_lowercase().replace('-', "").contains("utf8") => {
+            Diagnosis::new(Status::Ok, TITLE, format!("{}={}", name, value), None)
+        }
+        Some((name, value)) => Diagnosis::new(
+            Status::Warn,
+            TITLE,
+            format!("{}={} 不是 UTF-8", name, value),
+            Some(format!("執行 `export {}=C.UTF-8`（或其他 UTF-8 語系）後再轉換", name)),
+        ),
+        None => Diagnosis::new(
+            Status::Warn,
+            TITLE,
+            "未設定 LC_ALL、LC_CTYPE 或 LANG".to_string(),
+            Some("執行 `export LANG=C.UTF-8` 後再轉換".to_string()),
+        ),
+    }
+}
+
+// 比對系統語系與頁面支援的語言
+fn check_locale() -> Diagnosis {
+    const TITLE: &str = "系統語系";
+    let Some(locale) = sys_locale::get_locale() else {
+        return Diagnosis::new(
+            Status::Info,
+            TITLE,
+            format!("無法取得系統語系，頁面預設使用 {}", DEFAULT_LANG),
+            Some(format!("以 --lang 指定頁面語言：{}", SUPPORTED_LANGS.join("、"))),
+        );
+    };
+    let lower = locale.to_lowercase();
+    let lang = if lower.starts_with("zh") {
+        if lower.contains("cn") || lower.contains("hans") || lower.contains("sg") { "zh-CN" } else { "zh-TW" }
+    } else {
+        lower.split(['-', '_']).next().unwrap_or_default()
+    };
+    if lang == DEFAULT_LANG {
+        Diagnosis::new(Status::Ok, TITLE, locale, None)
+    } else if SUPPORTED_LANGS.contains(&lang) {
+        Diagnosis::new(
+            Status::Info,
+            TITLE,
+            format!("{}，頁面預設仍使用 {}", locale, DEFAULT_LANG),
+            Some(format!("若收件者使用相同語言，可加上 --lang {}", lang)),
+        )
+    } else {
+        Diagnosis::new(
+            Status::Info,
+            TITLE,
+            format!("{} 沒有對應的頁面語言，頁面預設使用 {}", locale, DEFAULT_LANG),
+            Some(format!("以 --lang 指定頁面語言：{}", SUPPORTED_LANGS.join("、"))),
+        )
+    }
+}
+
+// 依輸入大小估算頁面大小，對照常見瀏覽器可開啟的經驗上限
+fn check_browser_limits(input_size: Option<u64>) -> Vec<Diagnosis> {
+    const TITLE: &str = "瀏覽器大小限制";
+    let Some(input_size) = input_size else {
+        return BROWSER_LIMITS
+            .iter()
+            .map(|(browser, limit, note)| {
+                Diagnosis::new(Status::Info, TITLE, format!("{} 約 {}MB（{}）", browser, limit, note), None)
+            })
+            .chain(std::iter::once(Diagnosis::new(
+                Status::Info,
+                TITLE,
+                "以 --input 指定要轉換的路徑即可估算產出頁面能否開啟".to_string(),
+                None,
+            )))
+            .collect();
+    };
+    let page_size = (input_size as f64 * PAGE_SIZE_FACTOR) as u64;
+    let estimate = format!("輸入 {}，預估頁面最大約 {}", LocaleFormat::system().format_size(input_size as usize), LocaleFormat::system().format_size(page_size as usize));
+    let exceeded: Vec<String> = BROWSER_LIMITS
+        .iter()
+        .filter(|(_, limit, _)| page_size > limit * 1024 * 1024)
+        .map(|(browser, limit, note)| format!("{}（約 {}MB，{}）", browser, limit, note))
+        .collect();
+    if exceeded.is_empty() {
+        return vec![Diagnosis::new(Status::Ok, TITLE, format!("{}，常見瀏覽器皆可開啟", estimate), None)];
+    }
+    vec![Diagnosis::new(
+        Status::Warn,
+        TITLE,
+        format!("{}，可能無法在以下瀏覽器開啟：{}", estimate, exceeded.join("；")),
+        Some("使用 --split-size 或 --oversize-policy split 分段輸出，或以 --encryption-method webcrypto-chunked 逐塊解密".to_string()),
+    )]
+}
@@ -1,107 +1,96 @@
-use dialoguer::{Input, Password, Select, Confirm};
 use std::io;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use crate::config::config::{PasswordMode};
+use crate::utils::log_sampler::DEFAULT_LOG_SAMPLE;
+use crate::action::cli::{print_missing_files, print_unmatched_patterns};
 use crate::utils::utils::setup_logging;
 use crate::config::ports::{AppConfig, ConfigPort};
 use crate::facade::conversion_facade::ConversionFacade;
 use crate::facade::traits::i_conversion::ConversionFacadeTrait;
 use crate::models::conversion::ConversionInput;
 use crate::service::config_service::{DefaultConfigAdapter};
+use crate::service::prompt::TerminalPrompter;
 use crate::service::file::FileService;
 use crate::service::html::HtmlService;
+use crate::service::sink::CollisionSink;
+use crate::service::traits::i_prompt::Prompter;
+use crate::service::traits::i_sink::{CollisionAction, CollisionResolver};
 use crate::service::zip::ZipService;
 
-pub fn process_interactive_mode() -> io::Result<String> {
+/// 互動模式入口：逐一詢問輸入、輸出路徑與轉換選項後執行轉換
+/// # 參數
+/// - prompter: 詢問使用者的實作，一般為 TerminalPrompter
+/// # 回傳
+/// - 成功時返回輸出目錄，詢問或轉換失敗時返回 IO 錯誤
+pub fn process_interactive_mode(prompter: Arc<dyn Prompter>) -> io::Result<String> {
     println!("=== 歡迎使用互動模式 ===");
-    let use_default_config = get_default_config_option()?;
-    let input = get_input_path()?;
-    let output = get_output_path()?;
+    let use_default_config = get_default_config_option(prompter.as_ref())?;
+    let input = get_input_path(prompter.as_ref())?;
+    let output = get_output_path(prompter.as_ref())?;
 
     let config_port: Box<dyn ConfigPort> = if use_default_config {
         println!("使用預設配置：壓縮模式，單層壓縮，隨機密碼，AES256 加密");
         Box::new(DefaultConfigAdapter::new(input.clone(), output.clone()))
     } else {
-        Box::new(InteractiveConfigAdapter::new(input.clone(), output.clone()))
+        Box::new(InteractiveConfigAdapter::new(input.clone(), output.clone(), Arc::clone(&prompter)))
     };
 
     let facade: Box<dyn ConversionFacadeTrait> = Box::new(ConversionFacade::new(
         config_port,
         Box::new(FileService::new()),
         Box::new(ZipService::new()),
-        Box::new(HtmlService::new()),
-    ));
-
-    let conversion_input = ConversionInput {
-        input_path: Path::new(&input).to_path_buf(),
-        output_dir: output.clone(),
-        is_compressed: true,
-        compress: true,
-        include: vec!["*".to_string()],
-        exclude: None,
-        password_mode: crate::config::config::PasswordMode::Random,
-        display_password: true,
-        layer: "single".to_string(),
-        encryption_method: "aes256".to_string(),
-        no_progress: false,
-        max_size: None,
-    };
+        Box::new(HtmlService::with_sink(Box::new(CollisionSink::new(PromptCollisionResolver::new(Arc::clone(&prompter)))))),
+    ).with_prompter(prompter));
+
+    let conversion_input = ConversionInput::from(&AppConfig {
+        input: input.clone(),
+        output: output.clone(),
+        ..AppConfig::default()
+    });
 
     let output = facade.execute_conversion(conversion_input)?;
     println!("實際使用的配置：{:#?}", output);
+    print_unmatched_patterns(&output);
+    print_missing_files(&output);
     Ok(output.output_path)
 }
 
-pub fn get_default_config_option() -> io::Result<bool> {
-    Confirm::new()
-        .with_prompt("是否使用預設配置？（壓縮模式、單層壓縮、隨機密碼等，僅需指定輸入和輸出路徑）")
-        .default(true)
-        .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("預設配置選擇失敗: {}", e)))
+pub fn get_default_config_option(prompter: &dyn Prompter) -> io::Result<bool> {
+    prompter.confirm("是否使用預設配置？（壓縮模式、單層壓縮、隨機密碼等，僅需指定輸入和輸出路徑）", true)
+        .map_err(|e| io::Error::other(format!("預設配置選擇失敗: {}", e)))
 }
 
-pub fn get_input_path() -> io::Result<String> {
-    Input::new()
-        .with_prompt("請輸入檔案或目錄路徑（例如：./myfile.txt 或 ./mydir）")
-        .validate_with(|input: &String| -> Result<(), String> {
-            if Path::new(input).exists() { Ok(()) } else { Err(format!("路徑 '{}' 不存在", input)) }
-        })
-        .interact_text()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+pub fn get_input_path(prompter: &dyn Prompter) -> io::Result<String> {
+    prompter.input("請輸入檔案或目錄路徑（例如：./myfile.txt 或 ./mydir）", None, &|input| {
+        if Path::new(input).exists() { Ok(()) } else { Err(format!("路徑 '{}' 不存在", input)) }
+    })
 }
 
-pub fn get_output_path() -> io::Result<String> {
-    Input::new()
-        .with_prompt("輸入輸出目錄（例如：./output，預設為 output）")
-        .default("output".to_string())
-        .interact_text()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+pub fn get_output_path(prompter: &dyn Prompter) -> io::Result<String> {
+    prompter.input("輸入輸出目錄（例如：./output，預設為 output）", Some("output"), &|_| Ok(()))
 }
 
-pub fn get_conversion_mode() -> io::Result<bool> {
-    let is_compressed = Select::new()
-        .with_prompt("選擇轉換模式（使用方向鍵選擇，按 Enter 確認）")
-        .items(&["個別 - 為每個檔案生成單獨的 HTML", "壓縮 - 壓縮成單個 ZIP 嵌入 HTML"])
-        .default(0)
-        .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("轉換模式選擇失敗: {}", e)))? == 1;
+pub fn get_conversion_mode(prompter: &dyn Prompter) -> io::Result<bool> {
+    let is_compressed = prompter.select(
+        "選擇轉換模式（使用方向鍵選擇，按 Enter 確認）",
+        &["個別 - 為每個檔案生成單獨的 HTML", "壓縮 - 壓縮成單個 ZIP 嵌入 HTML"],
+        0,
+    )
+    .map_err(|e| io::Error::other(format!("轉換模式選擇失敗: {}", e)))? == 1;
     Ok(is_compressed)
 }
 
-pub fn get_zip_layer(is_compressed: bool) -> io::Result<String> {
+pub fn get_zip_layer(prompter: &dyn Prompter, is_compressed: bool) -> io::Result<String> {
     let (items, default) = if is_compressed {
         (vec!["單層 - 僅生成一層 ZIP", "雙層 - 生成外層和內層 ZIP（預設）"], 1)
     } else {
         (vec!["不壓縮", "單層 - 僅生成一層 ZIP", "雙層 - 生成外層和內層 ZIP（預設）"], 0)
     };
 
-    let layer = Select::new()
-        .with_prompt("選擇 ZIP 層數（使用方向鍵選擇，按 Enter 確認）")
-        .items(&items)
-        .default(default)
-        .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("ZIP 層數選擇失敗: {}", e)))?;
+    let layer = prompter.select("選擇 ZIP 層數（使用方向鍵選擇，按 Enter 確認）", &items, default)
+        .map_err(|e| io::Error::other(format!("ZIP 層數選擇失敗: {}", e)))?;
 
     Ok(match (is_compressed, layer) {
         (true, 0) => "single".to_string(),
@@ -113,18 +102,14 @@ pub fn get_zip_layer(is_compressed: bool) -> io::Result<String> {
     })
 }
 
-pub fn get_password_options(layer: &str) -> io::Result<(PasswordMode, bool)> {
+pub fn get_password_options(prompter: &dyn Prompter, layer: &str) -> io::Result<(PasswordMode, bool)> {
     if layer == "none" {
         return Ok((PasswordMode::None, false));
     }
 
     let modes = ["隨機生成（16 位，預設）", "手動輸入", "時間戳（yyyyMMddhhmmss）", "無密碼"];
-    let mode = Select::new()
-        .with_prompt("選擇密碼模式（使用方向鍵選擇，按 Enter 確認）")
-        .items(&modes)
-        .default(0)
-        .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("密碼模式選擇失敗: {}", e)))?;
+    let mode = prompter.select("選擇密碼模式（使用方向鍵選擇，按 Enter 確認）", &modes, 0)
+        .map_err(|e| io::Error::other(format!("密碼模式選擇失敗: {}", e)))?;
 
     let password_mode = match mode {
         0 => PasswordMode::Random,
@@ -135,46 +120,34 @@ pub fn get_password_options(layer: &str) -> io::Result<(PasswordMode, bool)> {
     };
 
     let display_password = match mode {
-        0 => Confirm::new()
-            .with_prompt("是否在 HTML 中顯示隨機生成的密碼？（預設為是）")
-            .default(true)
-            .interact()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("密碼顯示選項輸入失敗: {}", e)))?,
+        0 => prompter.confirm("是否在 HTML 中顯示隨機生成的密碼？（預設為是）", true)
+            .map_err(|e| io::Error::other(format!("密碼顯示選項輸入失敗: {}", e)))?,
         3 => false,
-        _ => Confirm::new()
-            .with_prompt("是否在 HTML 中顯示密碼？（預設為否，將儲存至 .key 檔案）")
-            .default(false)
-            .interact()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("密碼顯示選項輸入失敗: {}", e)))?,
+        _ => prompter.confirm("是否在 HTML 中顯示密碼？（預設為否，將儲存至 .key 檔案）", false)
+            .map_err(|e| io::Error::other(format!("密碼顯示選項輸入失敗: {}", e)))?,
     };
 
     Ok((password_mode, display_password))
 }
 
-pub fn get_conversion_mode_and_password() -> io::Result<(bool, PasswordMode, bool, String, String)> {
-    let is_compressed = get_conversion_mode()?;
-    let layer = get_zip_layer(is_compressed)?;
-    let (password_mode, display_password) = get_password_options(&layer)?;
+pub fn get_conversion_mode_and_password(prompter: &dyn Prompter) -> io::Result<(bool, PasswordMode, bool, String, String)> {
+    let is_compressed = get_conversion_mode(prompter)?;
+    let layer = get_zip_layer(prompter, is_compressed)?;
+    let (password_mode, display_password) = get_password_options(prompter, &layer)?;
     let encryption_method = "aes256".to_string();
     Ok((is_compressed, password_mode, display_password, layer, encryption_method))
 }
 
-pub fn get_file_patterns() -> io::Result<(Vec<String>, Option<Vec<String>>)> {
-    let include = Input::new()
-        .with_prompt("輸入包含模式（例如：.txt,.pdf，預設為 *）")
-        .default("*".to_string())
-        .interact_text()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("包含模式輸入失敗: {}", e)))?
+pub fn get_file_patterns(prompter: &dyn Prompter) -> io::Result<(Vec<String>, Option<Vec<String>>)> {
+    let include = prompter.input("輸入包含模式（例如：.txt,.pdf，預設為 *）", Some("*"), &|_| Ok(()))
+        .map_err(|e| io::Error::other(format!("包含模式輸入失敗: {}", e)))?
         .split(',')
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
         .collect::<Vec<String>>();
 
-    let exclude = Input::new()
-        .with_prompt("輸入排除模式（例如：.jpg,.png，預設為空）")
-        .default("".to_string())
-        .interact_text()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("排除模式輸入失敗: {}", e)))?
+    let exclude = prompter.input("輸入排除模式（例如：.jpg,.png，預設為空）", Some(""), &|_| Ok(()))
+        .map_err(|e| io::Error::other(format!("排除模式輸入失敗: {}", e)))?
         .split(',')
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
@@ -183,13 +156,10 @@ pub fn get_file_patterns() -> io::Result<(Vec<String>, Option<Vec<String>>)> {
     Ok((include, if exclude.is_empty() { None } else { Some(exclude) }))
 }
 
-pub fn get_compression_options(is_compressed: bool) -> io::Result<bool> {
+pub fn get_compression_options(prompter: &dyn Prompter, is_compressed: bool) -> io::Result<bool> {
     let compress = if !is_compressed {
-        Confirm::new()
-            .with_prompt("是否在個別模式下將檔案壓縮為 ZIP？")
-            .default(true)
-            .interact()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("壓縮選項輸入失敗: {}", e)))?
+        prompter.confirm("是否在個別模式下將檔案壓縮為 ZIP？", true)
+            .map_err(|e| io::Error::other(format!("壓縮選項輸入失敗: {}", e)))?
     } else {
         true
     };
@@ -208,43 +178,30 @@ pub fn get_log_level_option() -> io::Result<String> {
     Ok("info".to_string())
 }
 
-pub fn prompt_manual_password() -> io::Result<String> {
-    let pwd = Password::new()
-        .with_prompt("請輸入 ZIP 加密密碼")
-        .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("密碼輸入失敗: {}", e)))?;
-    let confirm_pwd = Password::new()
-        .with_prompt("請再次輸入密碼以確認")
-        .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("密碼確認失敗: {}", e)))?;
-    if pwd != confirm_pwd {
-        return Err(io::Error::new(io::ErrorKind::InvalidInput, "密碼不匹配"));
-    }
-    Ok(pwd)
-}
-
 // 交互配置適配器
 pub struct InteractiveConfigAdapter {
     input: String,
     output: String,
+    prompter: Arc<dyn Prompter>,
 }
 
 impl InteractiveConfigAdapter {
-    pub fn new(input: String, output: String) -> Self {
-        InteractiveConfigAdapter { input, output }
+    pub fn new(input: String, output: String, prompter: Arc<dyn Prompter>) -> Self {
+        InteractiveConfigAdapter { input, output, prompter }
     }
 }
 
 impl ConfigPort for InteractiveConfigAdapter {
     fn get_config(&self) -> io::Result<AppConfig> {
-        let (is_compressed, password_mode, display_password, layer, encryption_method) = get_conversion_mode_and_password()?;
-        let (include, exclude) = get_file_patterns()?;
-        let compress = get_compression_options(is_compressed)?;
+        let prompter = self.prompter.as_ref();
+        let (is_compressed, password_mode, display_password, layer, encryption_method) = get_conversion_mode_and_password(prompter)?;
+        let (include, exclude) = get_file_patterns(prompter)?;
+        let compress = get_compression_options(prompter, is_compressed)?;
         let no_progress = get_no_progress_option()?;
         let max_size = get_max_size_option()?;
         let log_level = get_log_level_option()?;
 
-        setup_logging(&log_level)?;
+        setup_logging(&log_level, DEFAULT_LOG_SAMPLE)?;
 
         Ok(AppConfig {
             input: self.input.clone(),
@@ -259,6 +216,118 @@ impl ConfigPort for InteractiveConfigAdapter {
             encryption_method,
             no_progress,
             max_size,
+            ..AppConfig::default()
         })
     }
-}
\ No newline at end of file
+}
+
+// 互動模式的檔名衝突決策：逐一詢問，選擇「全部」後之後的衝突沿用相同處理方式
+pub struct PromptCollisionResolver {
+    prompter: Arc<dyn Prompter>,
+    remembered: Mutex<Option<CollisionAction>>,
+}
+
+impl PromptCollisionResolver {
+    pub fn new(prompter: Arc<dyn Prompter>) -> Self {
+        PromptCollisionResolver { prompter, remembered: Mutex::new(None) }
+    }
+}
+
+impl Default for PromptCollisionResolver {
+    fn default() -> Self {
+        Self::new(Arc::new(TerminalPrompter))
+    }
+}
+
+impl CollisionResolver for PromptCollisionResolver {
+    fn resolve(&self, path: &Path) -> io::Result<CollisionAction> {
+        let mut remembered = self.remembered.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(action) = *remembered {
+            return Ok(action);
+        }
+        let choice = self.prompter.select(
+            &format!("輸出檔案 {} 已存在，請選擇處理方式", path.display()),
+            &[
+                "覆寫",
+                "重新命名（加上序號另存）",
+                "略過（保留既有檔案）",
+                "全部覆寫",
+                "全部重新命名",
+                "全部略過",
+            ],
+            1,
+        )
+        .map_err(|e| io::Error::other(format!("檔案衝突處理選擇失敗: {}", e)))?;
+        let action = match choice % 3 {
+            0 => CollisionAction::Overwrite,
+            1 => CollisionAction::Rename,
+            _ => CollisionAction::Skip,
+        };
+        if choice >= 3 {
+            *remembered = Some(action);
+        }
+        Ok(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::prompt::ScriptedPrompter;
+
+    // 建立含一個文字檔的輸入目錄，回傳（暫存目錄, 輸入路徑, 輸出路徑）
+    fn sample_input() -> (tempfile::TempDir, String, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input");
+        std::fs::create_dir(&input).unwrap();
+        std::fs::write(input.join("a.txt"), "hello").unwrap();
+        let output = dir.path().join("out").to_string_lossy().into_owned();
+        (dir, input.to_string_lossy().into_owned(), output)
+    }
+
+    fn html_count(output: &str) -> usize {
+        std::fs::read_dir(output)
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path().extension().is_some_and(|ext| ext == "html"))
+            .count()
+    }
+
+    #[test]
+    fn default_config_flow_runs_from_scripted_answers() {
+        let (_dir, input, output) = sample_input();
+        let prompter = Arc::new(ScriptedPrompter::new(["y", input.as_str(), output.as_str()]));
+        let output_path = process_interactive_mode(prompter.clone()).unwrap();
+        assert_eq!(output_path, output);
+        assert!(html_count(&output) > 0);
+        assert_eq!(prompter.remaining(), 0);
+    }
+
+    // 自訂配置會初始化日誌，而日誌在同一行程只能初始化一次，因此自訂配置的流程只在這個測試執行
+    #[test]
+    fn custom_config_flow_asks_every_option() {
+        let (_dir, input, output) = sample_input();
+        let prompter = Arc::new(ScriptedPrompter::new([
+            "n",
+            input.as_str(),
+            output.as_str(),
+            "0",  // 個別模式
+            "1",  // 單層 ZIP
+            "0",  // 隨機密碼
+            "",   // 顯示密碼（預設為是）
+            "*",  // 包含模式
+            "",   // 排除模式
+            "no", // 不壓縮為 ZIP
+        ]));
+        process_interactive_mode(prompter.clone()).unwrap();
+        assert!(html_count(&output) > 0);
+        assert_eq!(prompter.remaining(), 0);
+    }
+
+    #[test]
+    fn interactive_flow_stops_when_answers_run_out() {
+        let (_dir, input, _output) = sample_input();
+        let prompter = Arc::new(ScriptedPrompter::new(["y", input.as_str()]));
+        let error = process_interactive_mode(prompter).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
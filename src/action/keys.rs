@@ -0,0 +1,59 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::config::config::{parse_file_mode, GlobalArgs, KeysCli, DEFAULT_KEY_FILE_MODE};
+use crate::service::html::generate_key_sheet_content;
+use crate::service::i18n::{messages, DEFAULT_LANG};
+use crate::service::sink::{FileSink, OutputBatch};
+use crate::utils::utils::setup_logging;
+
+// 預設密碼檔名稱（`{name}.{ext}.key`）的副檔名，去除後即為對應的頁面檔名
+const KEY_FILE_SUFFIX: &str = ".key";
+
+/// 密碼單模式入口：讀取既有的密碼檔，在同一目錄寫出可列印的密碼單
+/// # 參數
+/// - cli: `keys` 子命令的參數
+/// - global: 全域選項
+/// # 回傳
+/// - 密碼檔無法讀取、頁面檔名無法推斷或寫入失敗時返回 IO 錯誤
+pub fn process_keys_mode(cli: &KeysCli, global: &GlobalArgs) -> io::Result<()> {
+    setup_logging(&global.log_level.clone().unwrap_or("info".to_string()), global.log_sample)?;
+    let mode = parse_file_mode(cli.key_file_mode.as_deref().unwrap_or(DEFAULT_KEY_FILE_MODE))?;
+    let key_path = Path::new(&cli.key_file);
+    let password = fs::read_to_string(key_path)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法讀取密碼檔 {}：{}", cli.key_file, e)))?;
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("密碼檔是空的：{}", cli.key_file)));
+    }
+
+    let key_name = key_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("無效的密碼檔路徑：{}", cli.key_file)))?;
+    let page_name = match &cli.page {
+        Some(page) => page.clone(),
+        None => key_name.strip_suffix(KEY_FILE_SUFFIX).map(str::to_string).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("無法由密碼檔名稱 {} 推斷頁面檔名，請以 --page 指定", key_name),
+            )
+        })?,
+    };
+    // 頁面檔名去除頁面副檔名即為原始檔案名稱
+    let file_name = page_name.rsplit_once('.').map_or(page_name.as_str(), |(stem, _)| stem);
+
+    let msgs = messages(cli.lang.as_deref().unwrap_or(DEFAULT_LANG));
+    let sheet = generate_key_sheet_content(password, file_name, &page_name, msgs)?;
+    let output_dir = match key_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().into_owned(),
+        _ => ".".to_string(),
+    };
+    let mut batch = OutputBatch::new(&output_dir);
+    batch.add_private(&format!("{}.html", key_name), sheet.into_bytes(), mode);
+    for location in batch.commit(&FileSink)? {
+        println!("已產生密碼單：{}", location);
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::io;
+use crate::utils::utils::is_reserved_device_name;
+
+// multipart/form-data 中的單一欄位
+#[derive(Debug, Clone)]
+pub struct MultipartField {
+    pub name: String,
+    pub file_name: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// 從 Content-Type 標頭取出 multipart 邊界字串
+pub fn boundary_from_content_type(content_type: &str) -> Option<String> {
+    let (mime, params) = content_type.split_once(';')?;
+    if !mime.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params.split(';').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// 解析 multipart/form-data 內容
+/// # 參數
+/// - body: 完整的請求內容
+/// - boundary: Content-Type 中的邊界字串
+/// # 回傳
+/// - 成功時返回所有欄位，格式錯誤時返回 IO 錯誤
+pub fn parse_multipart(body: &[u8], boundary: &str) -> io::Result<Vec<MultipartField>> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut fields = Vec::new();
+    let mut rest = match find(body, &delimiter) {
+        Some(start) => &body[start + delimiter.len()..],
+        None => return Err(invalid("找不到 multipart 邊界")),
+    };
+
+    loop {
+        // 結尾邊界為 `--boundary--`
+        if rest.starts_with(b"--") {
+            return Ok(fields);
+        }
+        rest = rest.strip_prefix(b"\r\n").ok_or_else(|| invalid("multipart 邊界後缺少換行"))?;
+        let header_end = find(rest, b"\r\n\r\n").ok_or_else(|| invalid("multipart 欄位缺少標頭結尾"))?;
+        let headers = String::from_utf8_lossy(&rest[..header_end]).to_string();
+        rest = &rest[header_end + 4..];
+
+        let mut next_delimiter = b"\r\n".to_vec();
+        next_delimiter.extend_from_slice(&delimiter);
+        let data_end = find(rest, &next_delimiter).ok_or_else(|| invalid("multipart 欄位缺少結尾邊界"))?;
+        let data = rest[..data_end].to_vec();
+        rest = &rest[data_end + next_delimiter.len()..];
+
+        let disposition = headers
+            .lines()
+            .find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.trim().eq_ignore_ascii_case("content-disposition").then_some(value.to_string())
+            })
+            .ok_or_else(|| invalid("multipart 欄位缺少 Content-Disposition"))?;
+        let name = disposition_param(&disposition, "name").unwrap_or_default();
+        let file_name = disposition_param(&disposition, "filename");
+        fields.push(MultipartField { name, file_name, data });
+    }
+}
+
+/// 整理用戶端提供的檔名，只保留最後一段檔名部分，供伺服器寫入或讀取自己的目錄
+/// # 參數
+/// - name: multipart 欄位的 filename 或網址中的路徑片段
+/// # 回傳
+/// - 可安全使用的檔名；整理後為空、`.`、`..`，包含 NUL 或控制字元，或為 Windows 保留的裝置名稱時返回 None
+pub fn sanitize_file_name(name: &str) -> Option<String> {
+    let name = name.rsplit(['/', '\\']).next()?.trim();
+    if name.is_empty() || name == "." || name == ".." {
+        return None;
+    }
+    if name.chars().any(char::is_control) || is_reserved_device_name(name) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+// 取出 Content-Disposition 中的參數值，例如 name="file"
+fn disposition_param(disposition: &str, key: &str) -> Option<String> {
+    disposition.split(';').skip(1).find_map(|param| {
+        let (k, v) = param.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_keeps_only_the_file_name() {
+        assert_eq!(sanitize_file_name("report.pdf").as_deref(), Some("report.pdf"));
+        assert_eq!(sanitize_file_name("../../etc/passwd").as_deref(), Some("passwd"));
+        assert_eq!(sanitize_file_name("/etc/passwd").as_deref(), Some("passwd"));
+        assert_eq!(sanitize_file_name("C:\\Windows\\win.ini").as_deref(), Some("win.ini"));
+        assert_eq!(sanitize_file_name("..\\..\\boot.ini").as_deref(), Some("boot.ini"));
+    }
+
+    #[test]
+    fn sanitize_rejects_malicious_names() {
+        for name in ["", "  ", ".", "..", "../", "a/..", "dir/", "a\0b.txt", "a\nb.txt", "CON", "nul.txt", "com1.tar.gz", "LPT9"] {
+            assert_eq!(sanitize_file_name(name), None, "{:?} 應被拒絕", name);
+        }
+    }
+
+    #[test]
+    fn parse_keeps_raw_file_name_for_sanitizing() {
+        let body = b"--b\r\nContent-Disposition: form-data; name=\"file\"; filename=\"../x.txt\"\r\n\r\nhi\r\n--b--\r\n";
+        let fields = parse_multipart(body, "b").unwrap();
+        assert_eq!(fields[0].file_name.as_deref(), Some("../x.txt"));
+        assert_eq!(fields[0].data, b"hi");
+        assert_eq!(sanitize_file_name(fields[0].file_name.as_deref().unwrap()).as_deref(), Some("x.txt"));
+    }
+}
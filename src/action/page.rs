@@ -0,0 +1,242 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::config::{ExtractCli, GlobalArgs, InspectCli, VerifyCli};
+use crate::service::schema::{read_document, PageInfo};
+use crate::service::sink::FileSink;
+use crate::service::traits::i_sink::OutputSink;
+use crate::service::verify::{archive_entry_hashes, check_manifest, read_output_payload};
+use crate::service::zip::MANIFEST_NAME;
+use crate::utils::locale::LocaleFormat;
+use crate::utils::utils::setup_logging;
+
+// 指定頁面中的一份產出物：一般頁面各自一份，分段頁面依段次合併為一份
+struct PageGroup {
+    html: PathBuf,
+    // 依段次排列的分段頁面，未分段時為空
+    parts: Vec<PathBuf>,
+    payload_file: Option<PathBuf>,
+    info: PageInfo,
+}
+
+impl PageGroup {
+    // 讀回完整資料，瀏覽器端加密時以密碼解密
+    fn read_payload(&self, password: Option<&str>) -> io::Result<Vec<u8>> {
+        let parts: Vec<&Path> = self.parts.iter().map(PathBuf::as_path).collect();
+        read_output_payload(&self.html, &parts, self.payload_file.as_deref(), password).map(|(_, data)| data)
+    }
+
+    // 頁面資料的封存格式，優先使用轉換資訊，舊版頁面依下載檔名判斷；不分層時資料即為原始檔案，返回 None
+    fn archive_format(&self) -> Option<&str> {
+        let layer = self.info.metadata.as_ref().map(|metadata| metadata.layer.as_str()).or(self.info.layer.as_deref());
+        if layer == Some("none") {
+            return None;
+        }
+        if let Some(metadata) = &self.info.metadata {
+            return Some(metadata.archive_format.as_deref().unwrap_or("zip"));
+        }
+        let name = self.info.download_zip_name.as_deref().unwrap_or_default();
+        Some(if name.ends_with(".tar.gz") {
+            "tar.gz"
+        } else if name.ends_with(".tar") {
+            "tar"
+        } else if name.ends_with(".7z") {
+            "7z"
+        } else {
+            "zip"
+        })
+    }
+}
+
+/// 還原模式入口：取出每份產出物的封存檔並寫入輸出目錄
+/// # 參數
+/// - cli: `extract` 子命令的參數
+/// - global: 全域選項
+/// # 回傳
+/// - 頁面無法讀取、解密失敗或寫入失敗時返回 IO 錯誤
+pub fn process_extract_mode(cli: &ExtractCli, global: &GlobalArgs) -> io::Result<()> {
+    setup_logging(&global.log_level.clone().unwrap_or("info".to_string()), global.log_sample)?;
+    let password = read_password(cli.password.as_deref(), cli.key_file.as_deref())?;
+    let groups = group_pages(&cli.pages)?;
+    fs::create_dir_all(&cli.output)?;
+    for group in &groups {
+        let data = group.read_payload(password.as_deref())?;
+        let location = FileSink.write(&cli.output, &output_name(group), &data)?;
+        println!("已取出 {} → {}（{}）", group.html.display(), location, LocaleFormat::system().format_size(data.len()));
+    }
+    Ok(())
+}
+
+/// 檢查模式入口：列出每個頁面的結構資訊，不需密碼也不解碼資料
+/// # 參數
+/// - cli: `inspect` 子命令的參數
+/// - global: 全域選項
+/// # 回傳
+/// - 所有頁面皆可讀取時返回 true，有任一頁面無法辨識時返回 false
+pub fn process_inspect_mode(cli: &InspectCli, global: &GlobalArgs) -> io::Result<bool> {
+    setup_logging(&global.log_level.clone().unwrap_or("info".to_string()), global.log_sample)?;
+    let mut readable = true;
+    for (i, page) in cli.pages.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        match load_page(Path::new(page)) {
+            Ok(info) => print_page_info(page, &info),
+            Err(e) => {
+                println!("[錯誤] {}：{}", page, e);
+                readable = false;
+            }
+        }
+    }
+    Ok(readable)
+}
+
+/// 驗證模式入口：取出每份產出物的資料並比對 SHA-256，再開啟封存檔讀取每個項目
+/// # 參數
+/// - cli: `verify` 子命令的參數
+/// - global: 全域選項
+/// # 回傳
+/// - 所有產出物皆驗證通過時返回 true，有任一份失敗時返回 false
+pub fn process_verify_mode(cli: &VerifyCli, global: &GlobalArgs) -> io::Result<bool> {
+    setup_logging(&global.log_level.clone().unwrap_or("info".to_string()), global.log_sample)?;
+    let password = read_password(cli.password.as_deref(), cli.key_file.as_deref())?;
+    let groups = group_pages(&cli.pages)?;
+    let mut failures = 0;
+    for group in &groups {
+        let result = group.read_payload(password.as_deref()).and_then(|data| match group.archive_format() {
+            Some(format) => {
+                let mut entries = archive_entry_hashes(&data, format, password.as_deref())?;
+                let manifest = match format {
+                    "zip" => check_manifest(&data, password.as_deref(), &mut entries)?,
+                    _ => None,
+                };
+                let mut detail = format!("SHA-256 相符，{} 封存檔的 {} 個項目皆可讀取", format, entries.len());
+                if let Some(count) = manifest {
+                    detail.push_str(&format!("，與 {} 記錄的 {} 個項目相符", MANIFEST_NAME, count));
+                }
+                Ok(detail)
+            }
+            None => Ok("SHA-256 相符".to_string()),
+        });
+        match result {
+            Ok(detail) => println!("[通過] {}：{}", group.html.display(), detail),
+            Err(e) => {
+                println!("[失敗] {}：{}", group.html.display(), e);
+                failures += 1;
+            }
+        }
+    }
+    println!("驗證完成：{} 份通過，{} 份失敗", groups.len() - failures, failures);
+    Ok(failures == 0)
+}
+
+// 由 --password 或 --key-file 取得密碼，密碼檔結尾的換行不視為密碼的一部分
+fn read_password(password: Option<&str>, key_file: Option<&str>) -> io::Result<Option<String>> {
+    if let Some(key_file) = key_file {
+        let content = fs::read_to_string(key_file)
+            .map_err(|e| io::Error::new(e.kind(), format!("無法讀取密碼檔 {}：{}", key_file, e)))?;
+        return Ok(Some(content.trim_end_matches(['\r', '\n']).to_string()));
+    }
+    Ok(password.map(str::to_string))
+}
+
+fn load_page(path: &Path) -> io::Result<PageInfo> {
+    let html = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法讀取頁面 {}：{}", path.display(), e)))?;
+    read_document(&html)
+}
+
+// 將指定的頁面分組，分段頁面依段次排序並確認段數齊全；外部資料檔以頁面所在目錄解析
+fn group_pages(pages: &[String]) -> io::Result<Vec<PageGroup>> {
+    let mut groups = Vec::new();
+    let mut parts: Vec<(usize, usize, PathBuf, PageInfo)> = Vec::new();
+    for page in pages {
+        let path = PathBuf::from(page);
+        let info = load_page(&path)?;
+        if let Some((index, count)) = info.part {
+            parts.push((index, count, path, info));
+            continue;
+        }
+        let payload_file = info
+            .payload_file
+            .as_deref()
+            .and_then(|name| Path::new(name).file_name())
+            .map(|name| path.with_file_name(name));
+        groups.push(PageGroup { html: path, parts: Vec::new(), payload_file, info });
+    }
+    if parts.is_empty() {
+        return Ok(groups);
+    }
+    parts.sort_by_key(|(index, ..)| *index);
+    let count = parts[0].1;
+    let missing: Vec<String> = (1..=count)
+        .filter(|index| !parts.iter().any(|(i, c, ..)| i == index && *c == count))
+        .map(|index| index.to_string())
+        .collect();
+    if !missing.is_empty() || parts.len() != count {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("分段頁面不完整，共 {} 段，缺少第 {} 段或指定了其他產出物的分段", count, missing.join("、")),
+        ));
+    }
+    let (_, _, html, info) = parts[0].clone();
+    groups.push(PageGroup { html, parts: parts.into_iter().map(|(_, _, path, _)| path).collect(), payload_file: None, info });
+    Ok(groups)
+}
+
+// 取出的封存檔名稱：頁面記錄的下載檔名（只取檔名部分），舊版頁面未記錄時以頁面檔名加上 .bin
+fn output_name(group: &PageGroup) -> String {
+    group
+        .info
+        .download_zip_name
+        .as_deref()
+        .and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| {
+            let stem = group.html.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            format!("{}.bin", stem.unwrap_or_else(|| "payload".to_string()))
+        })
+}
+
+fn print_page_info(page: &str, info: &PageInfo) {
+    let yes_no = |value: bool| if value { "是" } else { "否" };
+    println!("頁面：{}", page);
+    println!("  輸出格式：{}", info.output_format);
+    println!("  輸出格式版本：{}", info.format_version);
+    if let Some(generator) = &info.generator {
+        println!("  產生工具：{}", generator);
+    }
+    if let Some(name) = &info.download_zip_name {
+        println!("  下載檔名：{}", name);
+    }
+    if let Some(layer) = &info.layer {
+        println!("  壓縮層級：{}", layer);
+    }
+    println!("  資料編碼：{}", info.payload_encoding);
+    println!("  整體壓縮：{}", info.payload_compression);
+    println!("  資料 SHA-256：{}", info.payload_sha256.as_deref().unwrap_or("（未記錄）"));
+    if info.stream_chunk_size > 0 {
+        println!("  瀏覽器端加密：是（分塊加密，每塊 {} 位元組）", info.stream_chunk_size);
+    } else {
+        println!("  瀏覽器端加密：{}", yes_no(info.encrypted));
+    }
+    if let Some(payload_file) = &info.payload_file {
+        println!("  外部資料檔：{}", payload_file);
+    }
+    if let Some((index, count)) = info.part {
+        println!("  分段：第 {}/{} 段", index, count);
+    }
+    let Some(metadata) = &info.metadata else {
+        return;
+    };
+    println!("  產生時間：{}", metadata.created_at);
+    println!("  封存格式：{}", metadata.archive_format.as_deref().unwrap_or("zip"));
+    println!("  加密方式：{}", metadata.encryption_method);
+    if let Some(classification) = &metadata.classification {
+        println!("  分級：{}", classification);
+    }
+    if !metadata.tags.is_empty() {
+        println!("  標籤：{}", metadata.tags.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join("、"));
+    }
+}
@@ -0,0 +1,562 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::action::multipart::{boundary_from_content_type, parse_multipart, sanitize_file_name};
+use crate::config::config::{GlobalArgs, PasswordMode, ServerCli};
+use crate::config::ports::AppConfig;
+use crate::facade::conversion_facade::ConversionFacade;
+use crate::facade::job_queue::JobQueue;
+use crate::facade::metrics::{MeteredFacade, Metrics};
+use crate::facade::traits::i_conversion::ConversionFacadeTrait;
+use crate::models::conversion::ConversionInput;
+use crate::models::job::{JobInfo, JobStatus};
+use crate::service::config_service::{validate_app_config, StaticConfigAdapter};
+use crate::service::file::FileService;
+use crate::service::html::{encode_href, sha256_hex, HtmlService};
+use crate::service::traits::i_prompt::Prompter;
+use crate::service::zip::ZipService;
+use crate::utils::daemon::{install_shutdown_handler, PidFile};
+use crate::utils::random::random_id;
+use crate::utils::utils::setup_logging;
+
+// 請求內容上限，避免過大的 JSON 佔用記憶體
+const MAX_BODY_SIZE: u64 = 1024 * 1024;
+// 上傳配額的計算週期
+const QUOTA_WINDOW: Duration = Duration::from_secs(60 * 60);
+// 等待請求時檢查結束訊號的間隔
+const SHUTDOWN_POLL: Duration = Duration::from_millis(200);
+// 保留上傳的識別碼位元組數，以十六進位表示
+const UPLOAD_ID_BYTES: usize = 16;
+// 記錄上傳者的檔案，內容為用戶端識別的 SHA-256，不保存權杖本身
+const UPLOAD_OWNER_FILE: &str = "owner";
+
+type HttpResponse = Response<io::Cursor<Vec<u8>>>;
+
+// `/upload` 的配置可指定的欄位；輸入、輸出與所有讀寫伺服器檔案的欄位（報告檔、統計檔、工作檔、
+// 額外樣式與腳本、標誌、說明檔、剪貼簿）以及密碼檔與索引頁的檔名樣式皆不開放
+const UPLOAD_CONFIG_FIELDS: &[&str] = &[
+    "is_compressed", "compress", "include", "exclude", "password_mode", "inner_password_mode", "outer_password_mode",
+    "display_password", "password_qr", "layer",
+    "encryption_method", "max_size", "split_size", "view", "index", "format", "lang", "title", "footer", "expires",
+    "restore", "payload_encoding", "compression_method", "force_method", "compression_level", "payload_compression",
+    "payload_compression_level", "max_html_size", "oversize_policy", "password_verifier", "format_version", "minify",
+    "slices", "key_sheet", "password_hint", "allow_weak_password", "transcode_text", "keep_original_text",
+    "target_size", "line_endings", "tags", "tag_rules", "classification", "auto_downgrade", "verify_output",
+    "ascii_names", "pwa", "viewer", "no_js", "data_uri_threshold", "manifest", "wrap_base64", "archive_root",
+    "strip_prefix", "volume_size", "comment", "passthrough", "a11y_strict", "archive_format", "ignore_case",
+    "match_on", "rules", "on_change", "on_missing", "preserve_metadata", "output_extension", "low_memory",
+];
+
+// 伺服器狀態，由所有請求執行緒共用
+struct ServerState {
+    facade: Arc<dyn ConversionFacadeTrait>,
+    queue: JobQueue,
+    metrics: Arc<Metrics>,
+    tokens: Vec<String>,
+    max_upload_size: u64,
+    quota: Option<u64>,
+    // 各用戶端於目前週期的起始時間與已上傳位元組數
+    usage: Mutex<HashMap<String, (Instant, u64)>>,
+    upload_dir: PathBuf,
+    // 收到結束訊號後不再接受新工作
+    shutting_down: Arc<AtomicBool>,
+    // 尚未回應的請求數，關閉前需等待歸零
+    active_requests: AtomicUsize,
+}
+
+/// 伺服器模式入口：啟動工作佇列並處理 HTTP 請求
+/// # 參數
+/// - cli: `serve`（或別名 `server`）子命令的參數
+/// - global: 全域選項
+/// - prompter: 手動密碼模式詢問密碼的實作
+pub fn process_server_mode(cli: &ServerCli, global: &GlobalArgs, prompter: Arc<dyn Prompter>) -> io::Result<String> {
+    setup_logging(&global.log_level.clone().unwrap_or("info".to_string()), global.log_sample)?;
+    let _pid_file = cli.pid_file.as_deref().map(PidFile::create).transpose()?;
+
+    let metrics = Arc::new(Metrics::new());
+    let facade: Arc<dyn ConversionFacadeTrait> = Arc::new(MeteredFacade::new(
+        Arc::new(ConversionFacade::new(
+            Box::new(StaticConfigAdapter::new(AppConfig::default())),
+            Box::new(FileService::new()),
+            Box::new(ZipService::new()),
+            Box::new(HtmlService::new()),
+        ).with_prompter(prompter)),
+        Arc::clone(&metrics),
+    ));
+    let state = Arc::new(ServerState {
+        queue: JobQueue::new(Arc::clone(&facade), cli.workers),
+        facade,
+        metrics,
+        tokens: cli.token.clone(),
+        max_upload_size: mb_to_bytes(cli.max_upload_size),
+        quota: cli.quota.map(mb_to_bytes),
+        usage: Mutex::new(HashMap::new()),
+        upload_dir: PathBuf::from(&cli.upload_dir),
+        shutting_down: install_shutdown_handler()?,
+        active_requests: AtomicUsize::new(0),
+    });
+    fs::create_dir_all(&state.upload_dir)?;
+
+    let server = Server::http(&cli.listen).map_err(|e| io::Error::other(format!("無法監聽 {}: {}", cli.listen, e)))?;
+    log::info!("伺服器模式啟動，監聽 {}，背景執行緒 {} 個", cli.listen, cli.workers.max(1));
+    if state.tokens.is_empty() {
+        log::warn!("未設定 --token，所有用戶端皆可提交轉換");
+    }
+    let mut drain_deadline = None;
+    loop {
+        if state.shutting_down.load(Ordering::SeqCst) {
+            let deadline = *drain_deadline.get_or_insert_with(|| Instant::now() + Duration::from_secs(cli.drain_timeout));
+            let pending = state.queue.pending() + state.active_requests.load(Ordering::SeqCst);
+            if pending == 0 {
+                log::info!("所有工作已完成，伺服器結束");
+                break;
+            }
+            if Instant::now() >= deadline {
+                log::warn!("等待逾時，仍有 {} 個工作或請求未完成，伺服器結束", pending);
+                break;
+            }
+        }
+        let Some(request) = server.recv_timeout(SHUTDOWN_POLL)? else {
+            continue;
+        };
+        let state = Arc::clone(&state);
+        state.active_requests.fetch_add(1, Ordering::SeqCst);
+        thread::spawn(move || {
+            handle_request(&state, request);
+            state.active_requests.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+    Ok(cli.listen.clone())
+}
+
+fn handle_request(state: &ServerState, mut request: Request) {
+    let method = request.method().clone();
+    let (url, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    let (url, query) = (url.to_string(), query.to_string());
+    let segments: Vec<&str> = url.trim_matches('/').split('/').collect();
+    log::info!("{} {}", method, url);
+
+    // 健康檢查供容器平台探測，不需驗證
+    let response = match (&method, segments.as_slice()) {
+        (Method::Get, ["healthz"]) => json_response(200, &serde_json::json!({ "status": "ok" })),
+        (Method::Get, ["readyz"]) => readiness(&state.shutting_down),
+        _ => route(state, &mut request, &method, &segments, &query),
+    };
+    if let Err(e) = request.respond(response) {
+        log::warn!("回應請求失敗：{}", e);
+    }
+}
+
+fn route(state: &ServerState, request: &mut Request, method: &Method, segments: &[&str], query: &str) -> HttpResponse {
+    match authorize(state, request) {
+        Err(response) => response,
+        Ok(client) => match (method, segments) {
+            (Method::Get, ["metrics"]) => metrics_response(&state.metrics.render(&state.queue.status_counts())),
+            (Method::Post, ["convert" | "upload"]) if state.shutting_down.load(Ordering::SeqCst) => {
+                error_response(503, "伺服器正在關閉，不再接受新的轉換")
+            }
+            (Method::Post, ["convert"]) => submit_job(state, request, &client),
+            (Method::Post, ["upload"]) => upload(state, request, &client, query_flag(query, "store")),
+            (Method::Get, ["uploads", id, name]) => stored_file(state, &client, id, name),
+            // 工作只對提交者可見，其他用戶端與不存在的工作同樣回傳 404
+            (Method::Get, ["jobs", id]) => match state.queue.get(id, &owner_key(&client)) {
+                Some(job) => json_response(200, &job),
+                None => error_response(404, "找不到指定的工作"),
+            },
+            (Method::Get, ["jobs", id, "result"]) => match state.queue.get(id, &owner_key(&client)) {
+                Some(job) => job_result(&job),
+                None => error_response(404, "找不到指定的工作"),
+            },
+            _ => error_response(404, "找不到資源"),
+        },
+    }
+}
+
+// 收到結束訊號後回傳 503，讓負載平衡器停止導入流量
+fn readiness(shutting_down: &AtomicBool) -> HttpResponse {
+    if shutting_down.load(Ordering::SeqCst) {
+        json_response(503, &serde_json::json!({ "status": "shutting_down" }))
+    } else {
+        json_response(200, &serde_json::json!({ "status": "ready" }))
+    }
+}
+
+// 驗證 `Authorization: Bearer <token>`，回傳用於配額計算的用戶端識別
+fn authorize(state: &ServerState, request: &Request) -> Result<String, HttpResponse> {
+    let remote = request
+        .remote_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    if state.tokens.is_empty() {
+        return Ok(remote);
+    }
+    let token = header_value(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer ").map(|t| t.trim().to_string()));
+    match token {
+        Some(token) if state.tokens.contains(&token) => Ok(format!("token:{}", token)),
+        _ => Err(error_response(401, "缺少或無效的存取權杖")),
+    }
+}
+
+// POST /convert：請求內容為 JSON 配置，欄位與設定檔相同
+fn submit_job(state: &ServerState, request: &mut Request, client: &str) -> HttpResponse {
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().take(MAX_BODY_SIZE).read_to_string(&mut body) {
+        return error_response(400, &format!("無法讀取請求內容: {}", e));
+    }
+    let mut config: AppConfig = match serde_json::from_str(&body) {
+        Ok(config) => config,
+        Err(e) => return error_response(400, &format!("配置格式錯誤: {}", e)),
+    };
+    if let Err(response) = check_server_config(&config) {
+        return response;
+    }
+    // 背景執行無終端機，不顯示進度條
+    config.no_progress = true;
+    match state.queue.submit(ConversionInput::from(&config), &owner_key(client)) {
+        Ok(job) => json_response(202, &job),
+        Err(e) => error_response(503, &e.to_string()),
+    }
+}
+
+// 上傳後儲存的產出物清單
+#[derive(Serialize)]
+struct UploadResult {
+    id: String,
+    processed_files: usize,
+    artifacts: Vec<String>,
+}
+
+// POST /upload：multipart/form-data，`file` 欄位為待轉換檔案，可選的 `config` 欄位為 JSON 配置
+// 單一產出物且未指定 `?store=true` 時直接回傳 HTML，否則保留於伺服器並回傳下載連結
+fn upload(state: &ServerState, request: &mut Request, client: &str, store: bool) -> HttpResponse {
+    let Some(boundary) = header_value(request, "Content-Type").and_then(|v| boundary_from_content_type(&v)) else {
+        return error_response(415, "請使用 multipart/form-data 上傳");
+    };
+    let Some(length) = request.body_length().map(|l| l as u64) else {
+        return error_response(411, "上傳需提供 Content-Length");
+    };
+    if length > state.max_upload_size {
+        return error_response(413, &format!("上傳內容超過上限 {} 位元組", state.max_upload_size));
+    }
+    if let Err(response) = consume_quota(state, client, length) {
+        return response;
+    }
+    state.metrics.record_upload(length);
+
+    let mut body = Vec::with_capacity(length as usize);
+    if let Err(e) = request.as_reader().take(length).read_to_end(&mut body) {
+        return error_response(400, &format!("無法讀取上傳內容: {}", e));
+    }
+    let fields = match parse_multipart(&body, &boundary) {
+        Ok(fields) => fields,
+        Err(e) => return error_response(400, &e.to_string()),
+    };
+
+    let mut config = match fields.iter().find(|f| f.name == "config" && f.file_name.is_none()) {
+        Some(field) => match parse_upload_config(&field.data) {
+            Ok(config) => config,
+            Err(response) => return response,
+        },
+        None => AppConfig::default(),
+    };
+    let files: Vec<_> = fields.iter().filter(|f| f.name == "file" && f.file_name.is_some()).collect();
+    if files.is_empty() {
+        return error_response(400, "缺少 file 欄位");
+    }
+
+    let id = new_upload_id();
+    let job_dir = state.upload_dir.join(&id);
+    let input_dir = job_dir.join("input");
+    let output_dir = job_dir.join("output");
+    let saved = (|| -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(&input_dir)?;
+        fs::write(job_dir.join(UPLOAD_OWNER_FILE), owner_key(client))?;
+        let mut saved = Vec::new();
+        for field in &files {
+            let name = sanitize_file_name(field.file_name.as_deref().unwrap_or_default())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "無效的檔案名稱"))?;
+            let path = input_dir.join(name);
+            fs::write(&path, &field.data)?;
+            saved.push(path);
+        }
+        Ok(saved)
+    })();
+    let saved = match saved {
+        Ok(saved) => saved,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&job_dir);
+            return error_response(400, &e.to_string());
+        }
+    };
+
+    config.input = if saved.len() == 1 { saved[0].display().to_string() } else { input_dir.display().to_string() };
+    config.output = output_dir.display().to_string();
+    config.no_progress = true;
+    if let Err(response) = check_server_config(&config) {
+        let _ = fs::remove_dir_all(&job_dir);
+        return response;
+    }
+
+    log::info!("處理上傳 {}：{} 個檔案，共 {} 位元組", id, saved.len(), length);
+    let output = match state.facade.execute_conversion(ConversionInput::from(&config)) {
+        Ok(output) => output,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&job_dir);
+            return error_response(500, &format!("轉換失敗: {}", e));
+        }
+    };
+    // 原始上傳檔案轉換後即不再需要
+    let _ = fs::remove_dir_all(&input_dir);
+
+    if !store && output.artifacts.len() == 1 {
+        let result = fs::read(&output.artifacts[0].html);
+        let _ = fs::remove_dir_all(&job_dir);
+        return match result {
+            Ok(content) => Response::from_data(content).with_header(content_type("text/html; charset=utf-8")),
+            Err(e) => error_response(500, &format!("無法讀取產出物: {}", e)),
+        };
+    }
+
+    let artifacts = output
+        .artifacts
+        .iter()
+        .filter_map(|a| Path::new(&a.html).file_name())
+        .map(|name| format!("/uploads/{}/{}", id, encode_href(&name.to_string_lossy())))
+        .collect();
+    json_response(201, &UploadResult { id, processed_files: output.processed_files, artifacts })
+}
+
+// GET /uploads/:id/:name：下載已保留的產出物，只有上傳者（相同權杖，未設定權杖時為相同 IP）可以取得
+fn stored_file(state: &ServerState, client: &str, id: &str, name: &str) -> HttpResponse {
+    let name = urlencoding::decode(name).map(|n| n.into_owned()).unwrap_or_default();
+    match read_stored_file(&state.upload_dir, client, id, &name) {
+        Some(content) => Response::from_data(content).with_header(content_type("text/html; charset=utf-8")),
+        None => error_response(404, "找不到指定的檔案"),
+    }
+}
+
+// 讀取保留的產出物；識別碼或檔名無效、檔案不存在或請求者不是上傳者時一律返回 None，不透露上傳是否存在
+fn read_stored_file(upload_dir: &Path, client: &str, id: &str, name: &str) -> Option<Vec<u8>> {
+    let job_dir = upload_dir.join(sanitize_file_name(id)?);
+    let owner = fs::read_to_string(job_dir.join(UPLOAD_OWNER_FILE)).ok()?;
+    if owner != owner_key(client) {
+        log::warn!("拒絕讀取非請求者上傳的產出物：{}", id);
+        return None;
+    }
+    fs::read(job_dir.join("output").join(sanitize_file_name(name)?)).ok()
+}
+
+// 以系統的密碼學安全亂數產生上傳識別碼，不受 --seed 影響，無法由時間或序號推測
+fn new_upload_id() -> String {
+    random_id(UPLOAD_ID_BYTES)
+}
+
+// 上傳與轉換工作的擁有者：用戶端識別的 SHA-256，不保存權杖本身
+fn owner_key(client: &str) -> String {
+    sha256_hex(client.as_bytes())
+}
+
+// 累計用戶端於目前週期的上傳量，超過配額時拒絕
+fn consume_quota(state: &ServerState, client: &str, size: u64) -> Result<(), HttpResponse> {
+    let Some(quota) = state.quota else {
+        return Ok(());
+    };
+    let mut usage = state.usage.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let entry = usage.entry(client.to_string()).or_insert((now, 0));
+    if now.duration_since(entry.0) >= QUOTA_WINDOW {
+        *entry = (now, 0);
+    }
+    if entry.1 + size > quota {
+        log::warn!("用戶端 {} 超過上傳配額", client);
+        return Err(error_response(429, &format!("超過每小時上傳配額 {} 位元組", quota)));
+    }
+    entry.1 += size;
+    Ok(())
+}
+
+// 上傳者只能調整轉換選項，不可指定任何伺服器路徑或讀寫伺服器上的檔案
+fn parse_upload_config(data: &[u8]) -> Result<AppConfig, HttpResponse> {
+    let value: serde_json::Value =
+        serde_json::from_slice(data).map_err(|e| error_response(400, &format!("配置格式錯誤: {}", e)))?;
+    let Some(fields) = value.as_object() else {
+        return Err(error_response(400, "配置格式錯誤: 需為 JSON 物件"));
+    };
+    let rejected: Vec<&str> = fields
+        .keys()
+        .map(String::as_str)
+        .filter(|key| !UPLOAD_CONFIG_FIELDS.contains(key))
+        .collect();
+    if !rejected.is_empty() {
+        return Err(error_response(400, &format!("上傳轉換不支援的配置欄位：{}", rejected.join("、"))));
+    }
+    serde_json::from_value(value).map_err(|e| error_response(400, &format!("配置格式錯誤: {}", e)))
+}
+
+// 伺服器模式無法互動輸入密碼，其餘規則與設定檔相同
+fn check_server_config(config: &AppConfig) -> Result<(), HttpResponse> {
+    if let Err(e) = validate_app_config(config) {
+        return Err(error_response(400, &e.to_string()));
+    }
+    let modes = [Some(&config.password_mode), config.inner_password_mode.as_ref(), config.outer_password_mode.as_ref()];
+    if modes.contains(&Some(&PasswordMode::Manual)) {
+        return Err(error_response(400, "伺服器模式不支援手動輸入密碼"));
+    }
+    // 產出物以單一頁面回傳或提供下載，無法一併提供外部資料檔
+    if config.payload_mode == "external" {
+        return Err(error_response(400, "伺服器模式不支援外部資料檔（payload_mode: external）"));
+    }
+    // 工作檔寫入伺服器本機路徑，應於命令列模式匯出
+    if config.export_job.is_some() {
+        return Err(error_response(400, "伺服器模式不支援匯出工作檔（export_job）"));
+    }
+    // 統計檔同樣寫入伺服器本機路徑，伺服器的使用量請以 /metrics 取得
+    if config.stats_file.is_some() {
+        return Err(error_response(400, "伺服器模式不支援使用統計檔（stats_file）"));
+    }
+    // 報告檔同樣寫入伺服器本機路徑，產出物資訊請以 /jobs/:id 取得
+    if config.report.is_some() {
+        return Err(error_response(400, "伺服器模式不支援報告檔（report）"));
+    }
+    // 剪貼簿屬於伺服器的桌面工作階段，用戶端無法取得
+    if config.copy_to_clipboard.is_some() {
+        return Err(error_response(400, "伺服器模式不支援複製至剪貼簿（copy_to_clipboard）"));
+    }
+    Ok(())
+}
+
+// GET /jobs/:id/result：單一產出物直接回傳內容，多個產出物回傳路徑清單
+fn job_result(job: &JobInfo) -> HttpResponse {
+    match job.status {
+        JobStatus::Succeeded if job.artifacts.len() == 1 => match fs::read(&job.artifacts[0]) {
+            Ok(content) => Response::from_data(content)
+                .with_header(content_type("text/html; charset=utf-8")),
+            Err(e) => error_response(500, &format!("無法讀取產出物: {}", e)),
+        },
+        JobStatus::Succeeded => json_response(200, &job.artifacts),
+        JobStatus::Failed => json_response(500, job),
+        _ => json_response(409, job),
+    }
+}
+
+fn query_flag(query: &str, key: &str) -> bool {
+    query.split('&').any(|pair| pair == key || pair == format!("{}=true", key) || pair == format!("{}=1", key))
+}
+
+fn header_value(request: &Request, name: &'static str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str().to_string())
+}
+
+fn mb_to_bytes(mb: f64) -> u64 {
+    (mb * 1024.0 * 1024.0) as u64
+}
+
+fn json_response<T: Serialize>(status: u16, value: &T) -> HttpResponse {
+    let body = serde_json::to_vec(value).unwrap_or_default();
+    Response::from_data(body)
+        .with_status_code(status)
+        .with_header(content_type("application/json; charset=utf-8"))
+}
+
+fn metrics_response(body: &str) -> HttpResponse {
+    Response::from_data(body.as_bytes().to_vec()).with_header(content_type("text/plain; version=0.0.4; charset=utf-8"))
+}
+
+/// 於背景執行緒提供 `GET /metrics`、`/healthz` 與 `/readyz` 端點，供監看模式使用
+/// # 參數
+/// - listen: 監聽位址
+/// - metrics: 要輸出的統計數據
+/// - shutting_down: 結束訊號旗標，設定後 `/readyz` 回傳 503
+pub fn serve_metrics(listen: &str, metrics: Arc<Metrics>, shutting_down: Arc<AtomicBool>) -> io::Result<()> {
+    let server = Server::http(listen).map_err(|e| io::Error::other(format!("無法監聽 {}: {}", listen, e)))?;
+    log::info!("統計數據端點：http://{}/metrics", listen);
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = match (request.method(), request.url().split('?').next()) {
+                (Method::Get, Some("/metrics")) => metrics_response(&metrics.render(&[])),
+                (Method::Get, Some("/healthz")) => json_response(200, &serde_json::json!({ "status": "ok" })),
+                (Method::Get, Some("/readyz")) => readiness(&shutting_down),
+                _ => error_response(404, "找不到資源"),
+            };
+            if let Err(e) = request.respond(response) {
+                log::warn!("回應請求失敗：{}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+fn error_response(status: u16, message: &str) -> HttpResponse {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", value).expect("固定的標頭內容必定有效")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_ids_are_random_hex() {
+        let (a, b) = (new_upload_id(), new_upload_id());
+        assert_ne!(a, b);
+        assert_eq!(a.len(), UPLOAD_ID_BYTES * 2);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn stored_file_is_scoped_to_uploader() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = new_upload_id();
+        let job_dir = dir.path().join(&id);
+        fs::create_dir_all(job_dir.join("output")).unwrap();
+        fs::write(job_dir.join("output").join("a.html"), b"page").unwrap();
+        fs::write(job_dir.join(UPLOAD_OWNER_FILE), owner_key("token:alice")).unwrap();
+
+        assert_eq!(read_stored_file(dir.path(), "token:alice", &id, "a.html").as_deref(), Some(&b"page"[..]));
+        assert_eq!(read_stored_file(dir.path(), "token:bob", &id, "a.html"), None);
+        assert_eq!(read_stored_file(dir.path(), "127.0.0.1", &id, "a.html"), None);
+        assert_eq!(read_stored_file(dir.path(), "token:alice", &id, "../owner"), None);
+        assert_eq!(read_stored_file(dir.path(), "token:alice", "..", "a.html"), None);
+        assert!(!fs::read_to_string(job_dir.join(UPLOAD_OWNER_FILE)).unwrap().contains("alice"), "不應保存權杖本身");
+    }
+
+    #[test]
+    fn upload_config_accepts_conversion_options() {
+        let config = parse_upload_config(br#"{"password_mode": "none", "lang": "en", "no_js": true}"#)
+            .unwrap_or_else(|_| panic!("轉換選項應可接受"));
+        assert_eq!(config.password_mode, PasswordMode::None);
+        assert_eq!(config.lang, "en");
+        assert!(config.no_js);
+    }
+
+    #[test]
+    fn upload_config_rejects_server_paths() {
+        for field in ["report", "stats_file", "export_job", "output", "input", "extra_css", "key_name", "copy_to_clipboard"] {
+            let body = serde_json::json!({ field: "/tmp/pwned" }).to_string();
+            let response = parse_upload_config(body.as_bytes()).err();
+            assert_eq!(response.map(|r| r.status_code().0), Some(400), "{} 應被拒絕", field);
+        }
+    }
+
+    #[test]
+    fn server_config_rejects_report() {
+        let config = AppConfig { report: Some("/tmp/pwned.json".to_string()), ..AppConfig::default() };
+        assert!(check_server_config(&config).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::io;
+use std::path::Path;
+use chrono::{Duration, Local, NaiveDate};
+
+use crate::config::config::StatsCli;
+use crate::service::stats::{DailyStats, UsageStats};
+use crate::utils::locale::LocaleFormat;
+
+/// 使用統計入口：讀取 `--stats-file` 累積的統計檔，印出合計、最近幾天的每日統計與前後期間的比較
+/// # 參數
+/// - cli: `stats` 子命令的參數
+/// # 回傳
+/// - 成功時返回 Ok(())，統計檔無法讀取或格式錯誤時返回 IO 錯誤
+pub fn process_stats_mode(cli: &StatsCli) -> io::Result<()> {
+    let stats = UsageStats::load(Path::new(&cli.stats_file))?;
+    let locale = LocaleFormat::system();
+
+    println!("file_to_html 使用統計：{}", cli.stats_file);
+    let (Some(first), Some(last)) = (stats.days.keys().next(), stats.days.keys().next_back()) else {
+        println!("尚無任何轉換紀錄");
+        return Ok(());
+    };
+    println!("紀錄期間：{} ～ {}（{} 天有轉換）", first, last, stats.days.len());
+    println!("合計：{}", summary(&stats.total(), &locale));
+
+    let days = i64::from(cli.days);
+    let today = Local::now().date_naive();
+    let from = today - Duration::days(days - 1);
+    let recent = stats.range(from, today);
+    let previous = stats.range(from - Duration::days(days), from - Duration::days(1));
+
+    println!();
+    println!("最近 {} 天：", days);
+    if recent.is_empty() {
+        println!("  沒有轉換紀錄");
+    }
+    for (date, day) in &recent {
+        println!("  {}  {}", date, summary(day, &locale));
+    }
+
+    let current = sum(&recent);
+    let before = sum(&previous);
+    println!();
+    println!("與前 {} 天相比：", days);
+    println!(
+        "  轉換次數 {}，來源資料量 {}，平均耗時 {}",
+        change(current.runs, before.runs, &locale),
+        change(current.source_bytes, before.source_bytes, &locale),
+        change(current.average_duration_ms(), before.average_duration_ms(), &locale)
+    );
+    Ok(())
+}
+
+// 一段期間的統計摘要
+fn summary(stats: &DailyStats, locale: &LocaleFormat) -> String {
+    format!(
+        "轉換 {} 次（失敗 {} 次），檔案 {} 個，來源 {}，產出 {}，平均耗時 {} 秒",
+        locale.format_number(stats.runs),
+        locale.format_number(stats.failures),
+        locale.format_number(stats.files),
+        locale.format_size(stats.source_bytes as usize),
+        locale.format_size(stats.output_bytes as usize),
+        locale.format_decimal(stats.average_duration_ms() as f64 / 1000.0, 2)
+    )
+}
+
+fn sum(days: &[(NaiveDate, DailyStats)]) -> DailyStats {
+    let mut total = DailyStats::default();
+    for (_, day) in days {
+        total.add(day);
+    }
+    total
+}
+
+// 以百分比表示相對於前一期間的增減，前一期間為 0 時無法比較
+fn change(current: u64, previous: u64, locale: &LocaleFormat) -> String {
+    if previous == 0 {
+        return if current == 0 { "持平".to_string() } else { "無前期資料可比較".to_string() };
+    }
+    let percent = (current as f64 - previous as f64) / previous as f64 * 100.0;
+    let sign = if percent < 0.0 { "-" } else { "+" };
+    format!("{}{}%", sign, locale.format_decimal(percent.abs(), 1))
+}
@@ -0,0 +1,114 @@
+use std::io;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use jwalk::WalkDir;
+
+use crate::config::ports::AppConfig;
+use crate::facade::conversion_facade::ConversionFacade;
+use crate::facade::metrics::{MeteredFacade, Metrics};
+use crate::facade::traits::i_conversion::ConversionFacadeTrait;
+use crate::models::conversion::ConversionInput;
+use crate::service::config_service::{ConfigWatcher, StaticConfigAdapter};
+use crate::service::file::FileService;
+use crate::service::html::HtmlService;
+use crate::service::traits::i_prompt::Prompter;
+use crate::service::zip::ZipService;
+use crate::utils::daemon::{install_shutdown_handler, PidFile};
+
+// 輸入路徑的快照：檔案數、總大小與最後修改時間
+type InputFingerprint = (usize, u64, Option<SystemTime>);
+
+// 檢查結束訊號的間隔
+const SHUTDOWN_POLL: Duration = Duration::from_millis(200);
+
+// 監看模式的執行選項
+pub struct WatchOptions {
+    // 輪詢間隔
+    pub interval: Duration,
+    // 提供 /metrics、/healthz、/readyz 的監聽位址
+    pub metrics_listen: Option<String>,
+    // 程序 ID 檔路徑
+    pub pid_file: Option<String>,
+    // 手動密碼模式詢問密碼的實作
+    pub prompter: Arc<dyn Prompter>,
+}
+
+/// 監看模式：輸入路徑或設定檔變更時重新轉換，收到 SIGTERM 或 Ctrl+C 時於目前轉換完成後結束
+/// # 參數
+/// - watcher: 設定檔監看器，未使用設定檔時為 None
+/// - config: 未使用設定檔時的固定配置
+/// - options: 輪詢間隔、統計端點、程序 ID 檔與提示的實作
+pub fn run_watch(mut watcher: Option<ConfigWatcher>, config: AppConfig, options: WatchOptions) -> io::Result<String> {
+    let mut config = watcher.as_ref().map(|w| w.current().clone()).unwrap_or(config);
+    let shutting_down = install_shutdown_handler()?;
+    let _pid_file = options.pid_file.as_deref().map(PidFile::create).transpose()?;
+    let metrics = match &options.metrics_listen {
+        Some(listen) => {
+            let metrics = Arc::new(Metrics::new());
+            crate::action::server::serve_metrics(listen, Arc::clone(&metrics), Arc::clone(&shutting_down))?;
+            Some(metrics)
+        }
+        None => None,
+    };
+    let mut fingerprint = input_fingerprint(Path::new(&config.input));
+    log::info!("進入監看模式，每 {} 秒檢查一次變更，按 Ctrl+C 結束", options.interval.as_secs());
+    run_once(&config, metrics.as_ref(), &options.prompter);
+
+    loop {
+        // 分段等待，收到結束訊號時不必等滿整個間隔
+        let wake_at = Instant::now() + options.interval;
+        while Instant::now() < wake_at && !shutting_down.load(Ordering::SeqCst) {
+            thread::sleep(SHUTDOWN_POLL.min(wake_at.saturating_duration_since(Instant::now())));
+        }
+        if shutting_down.load(Ordering::SeqCst) {
+            log::info!("監看模式結束");
+            return Ok(config.output);
+        }
+        let config_changed = watcher.as_mut().is_some_and(|w| w.reload_if_changed());
+        if let (true, Some(w)) = (config_changed, watcher.as_ref()) {
+            config = w.current().clone();
+        }
+        let current = input_fingerprint(Path::new(&config.input));
+        let input_changed = current != fingerprint;
+        fingerprint = current;
+        if input_changed {
+            log::info!("偵測到輸入變更：{}", config.input);
+        }
+        if config_changed || input_changed {
+            run_once(&config, metrics.as_ref(), &options.prompter);
+        }
+    }
+}
+
+// 執行一次轉換，失敗時僅記錄錯誤，監看繼續進行
+fn run_once(config: &AppConfig, metrics: Option<&Arc<Metrics>>, prompter: &Arc<dyn Prompter>) {
+    let facade: Arc<dyn ConversionFacadeTrait> = Arc::new(ConversionFacade::new(
+        Box::new(StaticConfigAdapter::new(config.clone())),
+        Box::new(FileService::new()),
+        Box::new(ZipService::new()),
+        Box::new(HtmlService::new()),
+    ).with_prompter(Arc::clone(prompter)));
+    let facade = match metrics {
+        Some(metrics) => Arc::new(MeteredFacade::new(facade, Arc::clone(metrics))),
+        None => facade,
+    };
+    match facade.execute_conversion(ConversionInput::from(config)) {
+        Ok(output) => log::info!("轉換完成，處理 {} 個檔案，輸出目錄：{}", output.processed_files, output.output_path),
+        Err(e) => log::error!("轉換失敗：{}", e),
+    }
+}
+
+fn input_fingerprint(path: &Path) -> InputFingerprint {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .fold((0, 0, None), |(count, size, latest), metadata| {
+            let modified = metadata.modified().ok();
+            (count + 1, size + metadata.len(), latest.max(modified))
+        })
+}
@@ -1,12 +1,63 @@
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use crate::models::conversion::TagRule;
+use crate::models::file::FileSlice;
+use crate::utils::locale::LocaleFormat;
+use crate::utils::log_sampler::DEFAULT_LOG_SAMPLE;
+use crate::utils::utils::parse_expires;
 
+// 命令列入口：file_to_html [全域選項] <子命令> ...，未指定子命令時（file_to_html <input>）視為 convert
 #[derive(Parser, Clone)]
 #[command(
     name = "file_to_html",
     about = "將檔案或目錄轉換為嵌入式 HTML 格式",
-    long_about = "一個將檔案或目錄轉換為 HTML 格式的工具，支援單一檔案轉換或壓縮成單一 ZIP 檔案並嵌入 HTML，內嵌單層或雙層 ZIP（可選擇加密）。\nCLI 模式不提供選項時使用預設配置（壓縮模式、單層壓縮、隨機密碼等），僅需指定 input 和 output。使用 --show-config 預覽實際配置。\n使用 `--help` 查看詳細用法。",
+    long_about = "一個將檔案或目錄轉換為 HTML 格式的工具，並提供檢查、驗證與還原已產出頁面的子命令。\n未指定子命令時視為 convert，`file_to_html <input>` 與 `file_to_html convert <input>` 相同。\n使用 `file_to_html <子命令> --help` 查看各子命令的用法。",
+    arg_required_else_help = true
+)]
+pub struct App {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// 所有子命令共用的選項，可寫在子命令之前或之後
+#[derive(Args, Clone, Debug)]
+pub struct GlobalArgs {
+    #[arg(long, global = true, value_parser = ["debug", "info", "warn", "error"])]
+    pub log_level: Option<String>,
+    #[arg(long, global = true, default_value_t = DEFAULT_LOG_SAMPLE)]
+    pub log_sample: u64,
+    // 不顯示任何提示：有預設值的提示採用預設值，需要使用者輸入的提示（如手動密碼）直接返回錯誤
+    #[arg(long = "yes", visible_alias = "non-interactive", global = true, default_value_t = false)]
+    pub non_interactive: bool,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum Command {
+    Convert(Box<Cli>),
+    Extract(ExtractCli),
+    Inspect(InspectCli),
+    Verify(VerifyCli),
+    #[command(alias = "server")]
+    Serve(ServerCli),
+    Keys(KeysCli),
+    Doctor(DoctorCli),
+    Decoder(DecoderCli),
+    Stats(StatsCli),
+}
+
+/// 子命令名稱（含別名），第一個參數不是子命令時於前面補上 `convert`，維持舊版 `file_to_html <input>` 的用法
+pub const SUBCOMMAND_NAMES: [&str; 11] =
+    ["convert", "extract", "inspect", "verify", "serve", "server", "keys", "doctor", "decoder", "stats", "help"];
+
+// 轉換參數：file_to_html [convert] <input> [-o <目錄>]
+#[derive(Parser, Clone)]
+#[command(
+    about = "將檔案或目錄轉換為嵌入式 HTML 格式（預設子命令）",
+    long_about = "將檔案或目錄轉換為 HTML 格式，支援單一檔案轉換或壓縮成單一 ZIP 檔案並嵌入 HTML，內嵌單層或雙層 ZIP（可選擇加密）。\nCLI 模式不提供選項時使用預設配置（壓縮模式、單層壓縮、隨機密碼等），僅需指定 input 和 output。使用 --show-config 預覽實際配置。",
     arg_required_else_help = true
 )]
 pub struct Cli {
@@ -23,22 +74,313 @@ pub struct Cli {
     pub compress: Option<bool>, // 改為 Option
     #[arg(long, value_parser = ["random", "manual", "timestamp", "none"])]
     pub password_mode: Option<String>, // 改為 Option
+    #[arg(long, value_parser = ["random", "manual", "timestamp", "none"])]
+    pub inner_password_mode: Option<String>,
+    #[arg(long, value_parser = ["random", "manual", "timestamp", "none"])]
+    pub outer_password_mode: Option<String>,
     #[arg(long)]
     pub display_password: Option<bool>,
+    #[arg(long, default_value_t = false)]
+    pub password_qr: bool,
     #[arg(long, value_parser = ["none", "single", "double"])]
     pub layer: Option<String>, // 改為 Option
-    #[arg(long, value_parser = ["aes128", "aes192", "aes256"])]
+    #[arg(long, value_parser = ["aes128", "aes192", "aes256", "webcrypto", "webcrypto-chunked"])]
     pub encryption_method: Option<String>, // 改為 Option
     #[arg(long)]
     pub no_progress: Option<bool>, // 改為 Option
     #[arg(long)]
     pub max_size: Option<f64>,
-    #[arg(long, value_parser = ["info", "warn", "error"])]
-    pub log_level: Option<String>, // 改為 Option
+    #[arg(long)]
+    pub split_size: Option<f64>,
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    #[arg(long, value_parser = ["archive", "tree"])]
+    pub view: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub index: bool,
+    #[arg(long)]
+    pub format: Option<String>,
+    #[arg(long, value_parser = ["zh-TW", "zh-CN", "en", "ja"])]
+    pub lang: Option<String>,
+    #[arg(long)]
+    pub extra_css: Option<String>,
+    #[arg(long)]
+    pub extra_js: Option<String>,
+    #[arg(long)]
+    pub key_name: Option<String>,
+    #[arg(long)]
+    pub index_name: Option<String>,
+    #[arg(long)]
+    pub title: Option<String>,
+    #[arg(long)]
+    pub logo: Option<String>,
+    #[arg(long)]
+    pub footer: Option<String>,
+    #[arg(long)]
+    pub output_extension: Option<String>,
+    #[arg(long)]
+    pub expires: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub restore: bool,
+    #[arg(long, value_parser = ["base64", "base85"])]
+    pub payload_encoding: Option<String>,
+    #[arg(long, value_parser = ["deflate", "deflate64", "bzip2", "zstd", "stored"])]
+    pub compression_method: Option<String>,
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=9))]
+    pub compression_level: Option<u8>,
+    #[arg(long, default_value_t = false)]
+    pub force_method: bool,
+    #[arg(long, value_parser = ["none", "gzip", "zstd"])]
+    pub payload_compression: Option<String>,
+    #[arg(long)]
+    pub payload_compression_level: Option<i32>,
+    #[arg(long)]
+    pub max_html_size: Option<f64>,
+    #[arg(long, value_parser = ["warn", "error", "split"])]
+    pub oversize_policy: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub password_verifier: bool,
+    #[arg(long = "payload", value_parser = ["embedded", "external"])]
+    pub payload_mode: Option<String>,
+    #[arg(long)]
+    pub format_version: Option<u32>,
+    #[arg(long, default_value_t = false)]
+    pub minify: bool,
+    #[arg(long)]
+    pub jobs: Option<usize>,
+    #[arg(long, value_parser = ["dir"])]
+    pub group_by: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub low_memory: bool,
+    #[arg(long = "slice")]
+    pub slices: Vec<String>,
+    #[arg(long, default_value_t = false)]
+    pub key_sheet: bool,
+    #[arg(long)]
+    pub password_hint: Option<String>,
+    #[arg(long, value_parser = ["utf8"])]
+    pub transcode_text: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub keep_original_text: bool,
+    #[arg(long)]
+    pub target_size: Option<String>,
+    #[arg(long, value_parser = ["lf", "crlf"])]
+    pub line_endings: Option<String>,
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    #[arg(long = "tag-rule")]
+    pub tag_rules: Vec<String>,
+    #[arg(long, value_parser = ["confidential", "internal", "public"])]
+    pub classification: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub auto_downgrade: bool,
+    #[arg(long, default_value_t = false)]
+    pub verify_output: bool,
+    #[arg(long, default_value_t = false)]
+    pub ascii_names: bool,
+    #[arg(long)]
+    pub report: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub pwa: bool,
+    #[arg(long, default_value_t = false)]
+    pub a11y_strict: bool,
+    #[arg(long, default_value_t = false)]
+    pub open: bool,
+    #[arg(long)]
+    pub key_file_mode: Option<String>,
+    #[arg(long, value_parser = ["zip", "tar", "tar.gz", "7z", "none"])]
+    pub archive_format: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub ignore_case: bool,
+    #[arg(long, value_parser = ["name", "path"])]
+    pub match_on: Option<String>,
+    #[arg(long = "rule")]
+    pub rules: Vec<String>,
+    #[arg(long, default_value_t = false)]
+    pub allow_weak_password: bool,
+    #[arg(long)]
+    pub export_job: Option<String>,
+    #[arg(long)]
+    pub import_job: Option<String>,
+    #[arg(long, value_parser = ["warn", "fail", "reread"])]
+    pub on_change: Option<String>,
+    #[arg(long, value_parser = ["skip", "fail"])]
+    pub on_missing: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub no_preserve_metadata: bool,
+    #[arg(long)]
+    pub message: Option<String>,
+    #[arg(long, value_parser = ["follow", "store", "skip"])]
+    pub symlinks: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub viewer: bool,
+    #[arg(long, default_value_t = false)]
+    pub spill_to_disk: bool,
+    #[arg(long, default_value_t = false)]
+    pub no_js: bool,
+    #[arg(long)]
+    pub data_uri_threshold: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub manifest: bool,
+    #[arg(long, default_value_t = false)]
+    pub wrap_base64: bool,
+    #[arg(long)]
+    pub archive_root: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub strip_prefix: bool,
+    #[arg(long, value_parser = ["path", "base64"])]
+    pub copy_to_clipboard: Option<String>,
+    #[arg(long, help = "以位元組大小（如 50MB、500KB）指定的 --split-size，兩者擇一使用")]
+    pub volume_size: Option<String>,
+    #[arg(long)]
+    pub stats_file: Option<String>,
+    #[arg(long)]
+    pub comment: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub no_passthrough: bool,
+    #[arg(long)]
+    pub config: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub watch: bool,
+    #[arg(long, default_value_t = 2)]
+    pub watch_interval: u64,
+    #[arg(long)]
+    pub metrics_listen: Option<String>,
+    #[arg(long)]
+    pub pid_file: Option<String>,
+    #[arg(long)]
+    pub seed: Option<u64>,
+    #[cfg(feature = "dynamic-plugins")]
+    #[arg(long)]
+    pub plugin: Vec<String>,
     #[arg(long, default_value_t = false)]
     pub show_config: bool,
 }
 
+// 伺服器模式參數：file_to_html serve --listen <位址>（舊名稱 server 仍可使用）
+#[derive(Parser, Clone)]
+#[command(
+    about = "以 HTTP API 接收轉換請求",
+    long_about = "啟動 HTTP 伺服器，提供 POST /convert、POST /upload、GET /jobs/:id 與 GET /jobs/:id/result，讓其他系統遠端提交轉換工作。"
+)]
+pub struct ServerCli {
+    #[arg(long, default_value = "127.0.0.1:9000")]
+    pub listen: String,
+    #[arg(long, default_value_t = 2)]
+    pub workers: usize,
+    #[arg(long)]
+    pub token: Vec<String>,
+    #[arg(long, default_value_t = 50.0)]
+    pub max_upload_size: f64,
+    #[arg(long)]
+    pub quota: Option<f64>,
+    #[arg(long, default_value = "uploads")]
+    pub upload_dir: String,
+    #[arg(long)]
+    pub pid_file: Option<String>,
+    #[arg(long, default_value_t = 30)]
+    pub drain_timeout: u64,
+}
+
+// 環境檢查參數：file_to_html doctor [-o <目錄>] [--input <路徑>]
+#[derive(Parser, Clone)]
+#[command(
+    about = "檢查執行環境並提供修正建議",
+    long_about = "檢查輸出目錄的寫入權限與可用空間、暫存目錄、語系與主控台編碼，並依輸入大小估算產出頁面能否在常見瀏覽器開啟，對每個問題列出修正方式。"
+)]
+pub struct DoctorCli {
+    #[arg(short, long, default_value = "output")]
+    pub output: String,
+    #[arg(short, long)]
+    pub input: Option<String>,
+}
+
+// 解碼工具參數：file_to_html decoder [-o <目錄>] [--lang <語言>]
+#[derive(Parser, Clone)]
+#[command(
+    about = "產生獨立的解碼工具頁",
+    long_about = "在輸出目錄產生獨立的 HTML 解碼工具，收件者可貼上本工具產生的 Base64 或 Base85 資料，或拖放 .b64、.b85 檔案與產出頁面，在瀏覽器中還原為 ZIP 檔案。"
+)]
+pub struct DecoderCli {
+    #[arg(short, long, default_value = "output")]
+    pub output: String,
+    #[arg(long, value_parser = ["zh-TW", "zh-CN", "en", "ja"])]
+    pub lang: Option<String>,
+    #[arg(long, default_value = "decoder.html")]
+    pub name: String,
+}
+
+// 使用統計參數：file_to_html stats <統計檔> [--days <天數>]
+#[derive(Parser, Clone)]
+#[command(
+    about = "顯示本機使用統計",
+    long_about = "讀取轉換時以 --stats-file 累積的本機統計檔，列出合計的轉換次數、檔案數、資料量與平均耗時、最近幾天的每日統計，並與前一段相同天數的期間比較。統計檔只存在本機，不會傳送至任何地方。"
+)]
+pub struct StatsCli {
+    pub stats_file: String,
+    #[arg(long, default_value_t = 7, value_parser = clap::value_parser!(u32).range(1..=366))]
+    pub days: u32,
+}
+
+// 還原參數：file_to_html extract <頁面>... [-o <目錄>] [--password <密碼> | --key-file <密碼檔>]
+#[derive(Parser, Clone)]
+#[command(
+    about = "自產出頁面取出封存檔",
+    long_about = "讀取產出頁面嵌入的資料（外部資料檔模式時讀取頁面旁的資料檔），還原整體壓縮、比對 SHA-256，瀏覽器端加密的頁面以密碼解密後，將封存檔寫入輸出目錄。分段頁面需一併指定所有分段。"
+)]
+pub struct ExtractCli {
+    #[arg(required = true)]
+    pub pages: Vec<String>,
+    #[arg(short, long, default_value = ".")]
+    pub output: String,
+    #[arg(long, conflicts_with = "key_file")]
+    pub password: Option<String>,
+    #[arg(long)]
+    pub key_file: Option<String>,
+}
+
+// 檢查參數：file_to_html inspect <頁面>...
+#[derive(Parser, Clone)]
+#[command(
+    about = "列出產出頁面的結構資訊",
+    long_about = "讀取產出頁面記錄的輸出格式版本、下載檔名、資料編碼與整體壓縮、SHA-256、加密方式、分段與轉換資訊，不需密碼，也不解碼資料。"
+)]
+pub struct InspectCli {
+    #[arg(required = true)]
+    pub pages: Vec<String>,
+}
+
+// 驗證參數：file_to_html verify <頁面>... [--password <密碼> | --key-file <密碼檔>]
+#[derive(Parser, Clone)]
+#[command(
+    about = "驗證產出頁面的資料完整性",
+    long_about = "取出產出頁面的資料並比對 SHA-256，再以密碼開啟封存檔並讀取每個項目，確認頁面可以還原；有任一頁面驗證失敗時結束碼為 1。分段頁面需一併指定所有分段。"
+)]
+pub struct VerifyCli {
+    #[arg(required = true)]
+    pub pages: Vec<String>,
+    #[arg(long, conflicts_with = "key_file")]
+    pub password: Option<String>,
+    #[arg(long)]
+    pub key_file: Option<String>,
+}
+
+// 密碼單參數：file_to_html keys <密碼檔> [--page <頁面檔名>] [--lang <語言>]
+#[derive(Parser, Clone)]
+#[command(
+    about = "為既有的密碼檔產生可列印的密碼單",
+    long_about = "讀取轉換時寫出的密碼檔，在同一目錄產生與 --key-sheet 相同的可列印密碼單（<密碼檔>.html），適用於轉換時未指定 --key-sheet 的情況；密碼單以 --key-file-mode 的權限寫出。"
+)]
+pub struct KeysCli {
+    pub key_file: String,
+    #[arg(long)]
+    pub page: Option<String>,
+    #[arg(long, value_parser = ["zh-TW", "zh-CN", "en", "ja"])]
+    pub lang: Option<String>,
+    #[arg(long)]
+    pub key_file_mode: Option<String>,
+}
+
 #[derive(Clone, ValueEnum, PartialEq)]
 #[derive(Debug)]
 pub enum Mode {
@@ -46,7 +388,8 @@ pub enum Mode {
     Compressed,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PasswordMode {
     Random,
     Manual,
@@ -66,6 +409,302 @@ pub fn validate_input_path(input: &str) -> io::Result<&Path> {
     Ok(path)
 }
 
+pub fn validate_split_size(split_size: Option<f64>) -> io::Result<()> {
+    match split_size {
+        Some(size) if size.is_nan() || size <= 0.0 => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("分段大小必須大於 0 MB: {}", size)
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_max_html_size(max_html_size: f64) -> io::Result<()> {
+    if max_html_size.is_nan() || max_html_size <= 0.0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("頁面大小上限必須大於 0 MB: {}", max_html_size)
+        ));
+    }
+    Ok(())
+}
+
+/// 解析 `--target-size` 等帶單位的大小設定，單位不分大小寫，以 1024 為進位
+/// # 參數
+/// - spec: 大小設定，例如 `15MB`、`500KB`、`1.5G`，未帶單位時視為位元組
+/// # 回傳
+/// - 成功時返回位元組數，格式錯誤或不大於 0 時返回 IO 錯誤
+pub fn parse_byte_size(spec: &str) -> io::Result<usize> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無效的大小設定 '{}'，格式為數字加上單位，例如 15MB、500KB", spec)
+        )
+    };
+    let trimmed = spec.trim();
+    let split = trimmed.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let multiplier: f64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "K" | "KB" | "KIB" => 1024.0,
+        "M" | "MB" | "MIB" => 1024.0 * 1024.0,
+        "G" | "GB" | "GIB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return Err(invalid()),
+    };
+    let value: f64 = number.trim().parse().map_err(|_| invalid())?;
+    let bytes = value * multiplier;
+    if !bytes.is_finite() || bytes < 1.0 {
+        return Err(invalid());
+    }
+    Ok(bytes as usize)
+}
+
+/// 解析 `--data-uri-threshold`，`0` 表示停用資料連結，其餘格式與 `parse_byte_size` 相同
+/// # 參數
+/// - spec: 大小設定，例如 `256KB`
+/// # 回傳
+/// - 成功時返回位元組數，格式錯誤時返回 IO 錯誤
+pub fn parse_data_uri_threshold(spec: &str) -> io::Result<usize> {
+    if spec.trim() == "0" {
+        return Ok(0);
+    }
+    parse_byte_size(spec)
+}
+
+/// 解析 `--key-file-mode` 的八進位權限位元
+/// # 參數
+/// - mode: 權限設定，例如 `600`、`0640`
+/// # 回傳
+/// - 成功時返回權限位元，格式錯誤、超出 777 或擁有者無法讀取時返回 IO 錯誤
+pub fn parse_file_mode(mode: &str) -> io::Result<u32> {
+    let invalid = |reason: &str| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("無效的檔案權限 '{}'：{}", mode, reason))
+    };
+    let bits = u32::from_str_radix(mode.trim(), 8).map_err(|_| invalid("需為八進位數字，例如 600"))?;
+    if bits > 0o777 {
+        return Err(invalid("不可超過 777"));
+    }
+    if bits & 0o400 == 0 {
+        return Err(invalid("擁有者需具備讀取權限"));
+    }
+    Ok(bits)
+}
+
+pub fn validate_format_version(format_version: u32) -> io::Result<()> {
+    if !SUPPORTED_FORMAT_VERSIONS.contains(&format_version) {
+        let supported: Vec<String> = SUPPORTED_FORMAT_VERSIONS.iter().map(|v| v.to_string()).collect();
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("不支援的輸出格式版本 {}，可用值：{}", format_version, supported.join("、"))
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_jobs(jobs: usize) -> io::Result<()> {
+    if jobs == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "平行轉換數必須至少為 1"));
+    }
+    Ok(())
+}
+
+/// 解析 `--slice` 的 `路徑:位移:長度`，位移為負數時自檔案結尾往前計算
+/// # 參數
+/// - spec: 命令列或設定檔中的切片設定，例如 `logs/app.log:-1048576:1048576`
+/// # 回傳
+/// - 成功時返回切片設定，格式錯誤或長度為 0 時返回 IO 錯誤
+pub fn parse_slice(spec: &str) -> io::Result<FileSlice> {
+    let invalid = |reason: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無效的切片設定 '{}'：{}，格式為 路徑:位移:長度", spec, reason)
+        )
+    };
+    // 由右側拆分，路徑本身可包含冒號（例如 Windows 磁碟代號）
+    let mut parts = spec.rsplitn(3, ':');
+    let (Some(len), Some(offset), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid("缺少欄位"));
+    };
+    if path.is_empty() {
+        return Err(invalid("未指定路徑"));
+    }
+    let offset: i64 = offset.trim().parse().map_err(|_| invalid("位移必須為整數"))?;
+    let len: u64 = len.trim().parse().map_err(|_| invalid("長度必須為正整數"))?;
+    if len == 0 {
+        return Err(invalid("長度必須大於 0"));
+    }
+    Ok(FileSlice { path: PathBuf::from(path), offset, len })
+}
+
+pub fn validate_password_hint(hint: &str) -> io::Result<()> {
+    if hint.trim().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "密碼提示不可為空白"));
+    }
+    if hint.chars().count() > MAX_PASSWORD_HINT_CHARS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("密碼提示不可超過 {} 個字元", MAX_PASSWORD_HINT_CHARS)
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_slices(slices: &[String]) -> io::Result<()> {
+    for spec in slices {
+        parse_slice(spec)?;
+    }
+    Ok(())
+}
+
+/// 解析 `--tag` 的 `鍵=值`
+/// # 參數
+/// - spec: 標籤設定，例如 `project=apollo`
+/// # 回傳
+/// - 成功時返回鍵與值，格式錯誤時返回 IO 錯誤
+pub fn parse_tag(spec: &str) -> io::Result<(String, String)> {
+    let invalid = |reason: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無效的標籤 '{}'：{}，格式為 鍵=值", spec, reason)
+        )
+    };
+    let (key, value) = spec.split_once('=').ok_or_else(|| invalid("缺少 ="))?;
+    let (key, value) = (key.trim(), value.trim());
+    if key.is_empty() || !key.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+        return Err(invalid("鍵只能包含字母、數字與 - _ ."));
+    }
+    if value.is_empty() {
+        return Err(invalid("值不可為空白"));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// 解析 `--tag-rule` 的 `模式:鍵=值`，模式比對檔案在 ZIP 中的項目名稱
+/// # 參數
+/// - spec: 標籤規則，例如 `*.pdf:classification=internal`
+/// # 回傳
+/// - 成功時返回標籤規則，格式錯誤時返回 IO 錯誤
+pub fn parse_tag_rule(spec: &str) -> io::Result<TagRule> {
+    let invalid = |reason: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無效的標籤規則 '{}'：{}，格式為 模式:鍵=值", spec, reason)
+        )
+    };
+    // 值可包含冒號，因此先以第一個 = 分出值，再由右側分出鍵
+    let (left, value) = spec.split_once('=').ok_or_else(|| invalid("缺少 ="))?;
+    let (pattern, key) = left.rsplit_once(':').ok_or_else(|| invalid("缺少模式"))?;
+    if pattern.is_empty() {
+        return Err(invalid("模式不可為空白"));
+    }
+    let (key, value) = parse_tag(&format!("{}={}", key, value))?;
+    Ok(TagRule { pattern: pattern.to_string(), key, value })
+}
+
+pub fn validate_tags(tags: &[String], tag_rules: &[String]) -> io::Result<()> {
+    for spec in tags {
+        parse_tag(spec)?;
+    }
+    for spec in tag_rules {
+        parse_tag_rule(spec)?;
+    }
+    Ok(())
+}
+
+/// 檢查 `--rule` 格式，於蒐集檔案前提早回報錯誤
+pub fn validate_rules(rules: &[String]) -> io::Result<()> {
+    for spec in rules {
+        crate::service::rule::parse_rule(spec, false)?;
+    }
+    Ok(())
+}
+
+/// 最小化頁面需以 `minify` 功能編譯，未啟用時明確拒絕而非輸出未最小化的頁面
+pub fn validate_minify(minify: bool) -> io::Result<()> {
+    if minify && !cfg!(feature = "minify") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "此版本未啟用最小化功能，請以 `cargo build --features minify` 重新編譯後使用 --minify"
+        ));
+    }
+    Ok(())
+}
+
+/// 密碼檔的預設命名樣式
+pub const DEFAULT_KEY_NAME: &str = "{name}.{ext}.key";
+/// 索引頁的預設命名樣式
+pub const DEFAULT_INDEX_NAME: &str = "index.{ext}";
+/// 產出頁面的預設副檔名
+pub const DEFAULT_OUTPUT_EXTENSION: &str = "html";
+/// 頁面資料的預設編碼
+pub const DEFAULT_PAYLOAD_ENCODING: &str = "base64";
+/// 頁面資料的預設整體壓縮方式
+pub const DEFAULT_PAYLOAD_COMPRESSION: &str = "none";
+/// 頁面嵌入資料的預設大小上限（MB）
+pub const DEFAULT_MAX_HTML_SIZE: f64 = 1.0;
+/// 頁面嵌入資料超過上限時的預設處理方式
+pub const DEFAULT_OVERSIZE_POLICY: &str = "warn";
+/// 以資料連結直接下載的預設大小上限，`0` 表示停用
+pub const DEFAULT_DATA_URI_THRESHOLD: &str = "256KB";
+/// 頁面資料的預設存放方式，`embedded` 嵌入頁面、`external` 另存為同目錄的 .bin 檔
+pub const DEFAULT_PAYLOAD_MODE: &str = "embedded";
+/// 產出頁面的輸出格式版本，頁面、索引頁與 MHTML 皆以此標示，供下游工具判斷結構
+pub const FORMAT_VERSION: u32 = 2;
+/// 可指定的輸出格式版本，1 為未標示版本的舊版輸出
+pub const SUPPORTED_FORMAT_VERSIONS: [u32; 2] = [1, 2];
+/// 個別模式預設的平行轉換數，1 表示依蒐集順序逐一轉換
+pub const DEFAULT_JOBS: usize = 1;
+/// 密碼提示的字元數上限
+pub const MAX_PASSWORD_HINT_CHARS: usize = 200;
+/// ZIP 項目的預設壓縮方法
+pub const DEFAULT_COMPRESSION_METHOD: &str = "deflate";
+/// ZIP 項目的預設壓縮等級（0–9）
+pub const DEFAULT_COMPRESSION_LEVEL: u8 = 5;
+/// 密碼檔與密碼單的預設權限（八進位 Unix 權限位元），僅限擁有者讀寫
+pub const DEFAULT_KEY_FILE_MODE: &str = "600";
+
+/// 驗證產出物命名樣式，`{name}` 會替換為對應的檔案名稱，`{ext}` 替換為頁面副檔名
+/// # 參數
+/// - option: 選項名稱，用於錯誤訊息
+/// - pattern: 命名樣式
+/// - require_name: 是否必須包含 `{name}`，避免多個產出物寫入同一檔案
+pub fn validate_name_pattern(option: &str, pattern: &str, require_name: bool) -> io::Result<()> {
+    let file_name = pattern.replace("{name}", "");
+    if pattern.trim().is_empty() || pattern.contains(['/', '\\']) || file_name == "." || file_name == ".." {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} 需為不含路徑的檔案名稱: '{}'", option, pattern)
+        ));
+    }
+    if require_name && !pattern.contains("{name}") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} 需包含 {{name}}，避免多個檔案的產出互相覆寫: '{}'", option, pattern)
+        ));
+    }
+    Ok(())
+}
+
+/// 驗證到期時間格式，已過期時僅記錄警告
+pub fn validate_expires(expires: &str) -> io::Result<()> {
+    let expires_at = parse_expires(expires)?;
+    if expires_at <= chrono::Local::now() {
+        log::warn!("到期時間 {} 已過，產生的頁面將無法下載", LocaleFormat::system().format_datetime(&expires_at));
+    }
+    Ok(())
+}
+
+/// 驗證頁面副檔名：1 至 16 個英數字，不含點
+pub fn validate_output_extension(extension: &str) -> io::Result<()> {
+    if extension.is_empty() || extension.len() > 16 || !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("副檔名需為 1 至 16 個英數字且不含點: '{}'", extension)
+        ));
+    }
+    Ok(())
+}
+
 pub fn is_valid_pattern(pattern: &str) -> bool {
     let invalid_chars = ['/', '\\', ':', '?', '"', '<', '>', '|'];
     !pattern.is_empty() && !pattern.contains(&invalid_chars[..])
@@ -1,8 +1,16 @@
 use std::io;
-use crate::config::config::PasswordMode;
+use serde::{Deserialize, Serialize};
+use crate::config::config::{
+    PasswordMode, DEFAULT_COMPRESSION_METHOD, DEFAULT_COMPRESSION_LEVEL, DEFAULT_INDEX_NAME, DEFAULT_KEY_NAME, DEFAULT_OUTPUT_EXTENSION,
+    DEFAULT_MAX_HTML_SIZE, DEFAULT_OVERSIZE_POLICY, DEFAULT_PAYLOAD_MODE, DEFAULT_PAYLOAD_COMPRESSION, DEFAULT_PAYLOAD_ENCODING,
+    FORMAT_VERSION, DEFAULT_JOBS, DEFAULT_KEY_FILE_MODE, DEFAULT_DATA_URI_THRESHOLD,
+};
+use crate::service::i18n::DEFAULT_LANG;
+use crate::service::job_file::JobFileEntry;
 
-// 應用配置結構體，封裝所有參數
-#[derive(Debug, Clone)]
+// 應用配置結構體，封裝所有參數；設定檔中未指定的欄位使用預設值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub input: String,
     pub output: String,
@@ -11,11 +19,188 @@ pub struct AppConfig {
     pub include: Vec<String>,
     pub exclude: Option<Vec<String>>,
     pub password_mode: PasswordMode,
+    // 雙層壓縮時內層與外層 ZIP 各自的密碼模式，未指定時沿用 password_mode
+    pub inner_password_mode: Option<PasswordMode>,
+    pub outer_password_mode: Option<PasswordMode>,
     pub display_password: bool,
+    pub password_qr: bool,
     pub layer: String,
     pub encryption_method: String,
     pub no_progress: bool,
     pub max_size: Option<f64>,
+    pub split_size: Option<f64>,
+    pub view: String,
+    pub index: bool,
+    pub format: String,
+    pub lang: String,
+    pub extra_css: Option<String>,
+    pub extra_js: Option<String>,
+    pub key_name: String,
+    pub index_name: String,
+    pub title: Option<String>,
+    pub logo: Option<String>,
+    pub footer: Option<String>,
+    pub message: Option<String>,
+    pub output_extension: String,
+    pub expires: Option<String>,
+    pub restore: bool,
+    pub payload_encoding: String,
+    pub compression_method: String,
+    pub force_method: bool,
+    pub compression_level: u8,
+    pub payload_compression: String,
+    pub payload_compression_level: Option<i32>,
+    pub max_html_size: f64,
+    pub oversize_policy: String,
+    pub password_verifier: bool,
+    pub payload_mode: String,
+    pub format_version: u32,
+    pub minify: bool,
+    pub jobs: usize,
+    pub group_by: Option<String>,
+    pub low_memory: bool,
+    pub slices: Vec<String>,
+    pub key_sheet: bool,
+    pub password_hint: Option<String>,
+    pub allow_weak_password: bool,
+    pub transcode_text: Option<String>,
+    pub keep_original_text: bool,
+    pub target_size: Option<String>,
+    pub line_endings: Option<String>,
+    pub tags: Vec<String>,
+    pub tag_rules: Vec<String>,
+    pub classification: Option<String>,
+    pub auto_downgrade: bool,
+    pub verify_output: bool,
+    pub ascii_names: bool,
+    pub report: Option<String>,
+    pub export_job: Option<String>,
+    // `--import-job` 記錄的輸入檔案，轉換前比對，不寫入設定檔
+    #[serde(skip)]
+    pub job_files: Option<Vec<JobFileEntry>>,
+    // 手動密碼模式下命令列已詢問的密碼，轉換時直接沿用，不寫入設定檔與工作檔
+    #[serde(skip)]
+    pub preset_password: Option<String>,
+    pub pwa: bool,
+    pub viewer: bool,
+    pub spill_to_disk: bool,
+    pub no_js: bool,
+    pub data_uri_threshold: String,
+    pub manifest: bool,
+    pub wrap_base64: bool,
+    pub archive_root: Option<String>,
+    pub strip_prefix: bool,
+    pub copy_to_clipboard: Option<String>,
+    pub volume_size: Option<String>,
+    pub stats_file: Option<String>,
+    pub comment: Option<String>,
+    pub passthrough: bool,
+    pub a11y_strict: bool,
+    pub key_file_mode: String,
+    pub archive_format: String,
+    pub ignore_case: bool,
+    pub match_on: String,
+    pub rules: Vec<String>,
+    pub on_change: String,
+    pub on_missing: String,
+    pub symlinks: String,
+    pub preserve_metadata: bool,
+}
+
+// 預設配置：壓縮模式、單層壓縮、隨機密碼、AES256 加密
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            input: String::new(),
+            output: "output".to_string(),
+            is_compressed: true,
+            compress: true,
+            include: vec!["*".to_string()],
+            exclude: None,
+            password_mode: PasswordMode::Random,
+            inner_password_mode: None,
+            outer_password_mode: None,
+            display_password: true,
+            password_qr: false,
+            layer: "single".to_string(),
+            encryption_method: "aes256".to_string(),
+            no_progress: false,
+            max_size: None,
+            split_size: None,
+            view: "archive".to_string(),
+            index: false,
+            format: "html".to_string(),
+            lang: DEFAULT_LANG.to_string(),
+            extra_css: None,
+            extra_js: None,
+            key_name: DEFAULT_KEY_NAME.to_string(),
+            index_name: DEFAULT_INDEX_NAME.to_string(),
+            title: None,
+            logo: None,
+            footer: None,
+            message: None,
+            output_extension: DEFAULT_OUTPUT_EXTENSION.to_string(),
+            expires: None,
+            restore: false,
+            payload_encoding: DEFAULT_PAYLOAD_ENCODING.to_string(),
+            compression_method: DEFAULT_COMPRESSION_METHOD.to_string(),
+            force_method: false,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            payload_compression: DEFAULT_PAYLOAD_COMPRESSION.to_string(),
+            payload_compression_level: None,
+            max_html_size: DEFAULT_MAX_HTML_SIZE,
+            oversize_policy: DEFAULT_OVERSIZE_POLICY.to_string(),
+            password_verifier: false,
+            payload_mode: DEFAULT_PAYLOAD_MODE.to_string(),
+            format_version: FORMAT_VERSION,
+            minify: false,
+            jobs: DEFAULT_JOBS,
+            group_by: None,
+            low_memory: false,
+            slices: Vec::new(),
+            key_sheet: false,
+            password_hint: None,
+            allow_weak_password: false,
+            transcode_text: None,
+            keep_original_text: false,
+            target_size: None,
+            line_endings: None,
+            tags: Vec::new(),
+            tag_rules: Vec::new(),
+            classification: None,
+            auto_downgrade: false,
+            verify_output: false,
+            ascii_names: false,
+            report: None,
+            export_job: None,
+            job_files: None,
+            preset_password: None,
+            pwa: false,
+            viewer: false,
+            spill_to_disk: false,
+            no_js: false,
+            data_uri_threshold: DEFAULT_DATA_URI_THRESHOLD.to_string(),
+            manifest: false,
+            wrap_base64: false,
+            archive_root: None,
+            strip_prefix: false,
+            copy_to_clipboard: None,
+            volume_size: None,
+            stats_file: None,
+            comment: None,
+            passthrough: true,
+            a11y_strict: false,
+            key_file_mode: DEFAULT_KEY_FILE_MODE.to_string(),
+            archive_format: "zip".to_string(),
+            ignore_case: false,
+            match_on: "path".to_string(),
+            rules: Vec::new(),
+            on_change: "warn".to_string(),
+            on_missing: "skip".to_string(),
+            symlinks: "skip".to_string(),
+            preserve_metadata: true,
+        }
+    }
 }
 
 // 配置來源的 Port
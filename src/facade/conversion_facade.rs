@@ -1,22 +1,57 @@
 use std::io;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use crate::config::config::{parse_byte_size, parse_data_uri_threshold, parse_file_mode, PasswordMode, FORMAT_VERSION};
 use crate::config::ports::ConfigPort;
-use crate::models::conversion::{ConversionInput, ConversionOutput};
-use crate::models::file::{FileCollectInput, FileCollectOutput};
-use crate::models::zip::{ZipCompressInput, ZipCompressOutput};
+use crate::models::conversion::{ConversionInput, ConversionOutput, GeneratedArtifact, Tags};
+use crate::models::report::{ConversionReport, GroupFailure};
+use crate::models::file::{FileCollectInput, FileCollectOutput, UnmatchedPattern};
+use crate::models::zip::{ArchivePassthroughInput, ByteRange, CompressionSpec, ZipCompressInput, ZipCompressOutput};
 use crate::models::html::{HtmlGenerateInput};
-use crate::service::config_service::ConfigService;
-use zip::write::SimpleFileOptions;
+use crate::facade::job_queue::run_batch;
+use crate::facade::schedule::wave_order;
+use crate::service::clipboard::copy_page;
+use crate::service::change::{check_unchanged, read_stable, FileSnapshot};
+use crate::service::config_service::{validate_low_memory, ConfigService};
+use crate::service::crypto::{encrypt_with_method, is_browser_encryption};
+use crate::service::encoding::payload_encoder;
+use crate::service::file::read_file_range;
+use crate::service::format::{check_low_memory_renderer, output_format_names, output_renderer, OUTPUT_FORMATS};
+use crate::service::gzip::gzip_payload;
+use crate::service::html::{needs_rename_hint, VIEWER_NAME};
+use crate::service::job_file::{job_entries, verify_job_files, JobFile};
+use crate::service::spill::SpillFile;
+use crate::service::stats::{record_run, DailyStats};
+use crate::service::line_ending::LineEndingNormalizer;
+use crate::service::plugin::apply_filters;
+use crate::service::prompt::TerminalPrompter;
+use crate::service::tag::TagSet;
+use crate::service::transcode::TextTranscoder;
+use crate::service::verify::{verify_artifact, VerifySource};
+use crate::service::zip::{create_zip, detect_archive_format, entry_name, resolve_compression, rooted_entry_name, stored_compression};
 use zip::AesMode;
+use indicatif::{ProgressBar, ProgressStyle};
 use log::info;
+use chrono::Local;
 use crate::facade::traits::i_conversion::ConversionFacadeTrait;
+use crate::facade::traits::i_observer::ConversionObserver;
+use crate::service::traits::i_plugin::ContentFilter;
+use crate::service::traits::i_prompt::Prompter;
 use crate::service::traits::i_service::{FileServiceTrait, HtmlServiceTrait, ZipServiceTrait};
+use crate::utils::log_sampler::{file_converted, FILE_LOG_TARGET};
+use crate::utils::utils::get_file_name;
 
 pub struct ConversionFacade {
     config_service: ConfigService,
     file_service: Box<dyn FileServiceTrait>,
     zip_service: Box<dyn ZipServiceTrait>,
     html_service: Box<dyn HtmlServiceTrait>,
+    observers: Vec<Box<dyn ConversionObserver>>,
+    // 手動密碼模式詢問密碼的實作
+    prompter: Arc<dyn Prompter>,
 }
 
 impl ConversionFacade {
@@ -32,29 +67,264 @@ impl ConversionFacade {
             file_service,
             zip_service,
             html_service,
+            observers: Vec::new(),
+            prompter: Arc::new(TerminalPrompter),
         }
     }
+
+    /// 指定手動密碼模式詢問密碼的實作，預設為終端機提示，可鏈式呼叫
+    pub fn with_prompter(mut self, prompter: Arc<dyn Prompter>) -> Self {
+        self.prompter = prompter;
+        self
+    }
+
+    /// 註冊轉換流程觀察者，可鏈式呼叫
+    pub fn with_observer(mut self, observer: Box<dyn ConversionObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// 註冊轉換流程觀察者
+    pub fn add_observer(&mut self, observer: Box<dyn ConversionObserver>) {
+        self.observers.push(observer);
+    }
+
+    // 依序通知所有觀察者，任一觀察者回傳錯誤即中止
+    fn notify<F>(&self, event: F) -> io::Result<()>
+    where
+        F: Fn(&dyn ConversionObserver) -> io::Result<()>,
+    {
+        for observer in &self.observers {
+            event(observer.as_ref())?;
+        }
+        Ok(())
+    }
+
+    // 產出物寫出後的處理（驗證、通知觀察者）失敗時移除該產出物的所有檔案，不留下沒有可用頁面的密碼檔
+    fn discard_on_error(&self, artifact: &GeneratedArtifact, result: io::Result<()>) -> io::Result<()> {
+        let Err(e) = result else {
+            return Ok(());
+        };
+        let locations = artifact.output_locations();
+        match self.html_service.discard_outputs(&locations) {
+            Ok(()) => log::warn!("已移除未完成的產出物：{}", locations.join("、")),
+            Err(discard_error) => log::warn!("無法移除未完成的產出物 {}：{}", artifact.html, discard_error),
+        }
+        Err(e)
+    }
+
+    // 產生頁面密碼與雙層壓縮的內層密碼：雙層壓縮時外層與內層各依 --outer-password-mode 與 --inner-password-mode 產生，
+    // 未指定時沿用 --password-mode；其餘層數只有頁面密碼。命令列已詢問的手動密碼用於第一個沿用 --password-mode 的密碼
+    fn generate_passwords(&self, input: &ConversionInput) -> io::Result<(Option<String>, Option<String>)> {
+        let generate = |mode: &PasswordMode, preset: Option<String>| {
+            crate::utils::utils::generate_password(mode, preset, input.allow_weak_password, self.prompter.as_ref())
+        };
+        let preset = input.preset_password.clone();
+        if input.layer != "double" {
+            return Ok((generate(&input.password_mode, preset)?, None));
+        }
+        let (outer_preset, inner_preset) = if input.outer_password_mode.is_none() { (preset, None) } else { (None, preset) };
+        info!("產生外層 ZIP 的密碼");
+        let outer = match &input.outer_password_mode {
+            Some(mode) => generate(mode, None)?,
+            None => generate(&input.password_mode, outer_preset)?,
+        };
+        info!("產生內層 ZIP 的密碼");
+        let inner = match &input.inner_password_mode {
+            Some(mode) => generate(mode, None)?,
+            None => generate(&input.password_mode, inner_preset)?,
+        };
+        Ok((outer, inner))
+    }
 }
 
 impl ConversionFacadeTrait for ConversionFacade {
     fn execute_conversion(&self, input: ConversionInput) -> io::Result<ConversionOutput> {
+        let copy_to_clipboard = input.copy_to_clipboard.clone();
+        let stats_file = input.stats_file.clone();
+        let started = Instant::now();
+        let result = if input.group_by.is_some() { self.convert_groups(input) } else { self.convert_with_downgrades(input) };
+        if let Some(path) = &stats_file {
+            record_stats(path, &result, started.elapsed().as_millis() as u64);
+        }
+        let output = result?;
+        if let Some(target) = &copy_to_clipboard {
+            copy_output(&output, target);
+        }
+        Ok(output)
+    }
+}
+
+impl ConversionFacade {
+    // 執行轉換，啟用 --auto-downgrade 時依失敗原因調整設定後重試
+    fn convert_with_downgrades(&self, input: ConversionInput) -> io::Result<ConversionOutput> {
+        if !input.auto_downgrade {
+            return self.run_conversion(input);
+        }
+        // 因大小上限或不支援的加密方式失敗時，每次改用一項較保守的設定重新執行整個轉換，直到成功或已無設定可調整
+        let mut input = input;
+        let mut downgrades = Vec::new();
+        loop {
+            match self.run_conversion(input.clone()) {
+                Ok(mut output) => {
+                    output.downgrades = downgrades;
+                    return Ok(output);
+                }
+                Err(e) => {
+                    let Some(change) = downgrade_input(&mut input, &e) else {
+                        return Err(e);
+                    };
+                    log::warn!("轉換失敗：{}；改為{}後重新轉換", e, change);
+                    downgrades.push(change);
+                }
+            }
+        }
+    }
+
+    // --group-by dir：輸入目錄下的每個子目錄各以壓縮模式產生一個頁面，交由 run_batch 以 --jobs 個執行緒平行轉換，
+    // 進度以完成的群組數彙整，所有群組的產出物記錄在同一份報告；群組失敗時其餘群組照常完成，最後返回錯誤
+    fn convert_groups(&self, input: ConversionInput) -> io::Result<ConversionOutput> {
+        let groups = group_directories(&input.input_path)?;
+        // 手動密碼在開始平行轉換前詢問一次，各群組共用
+        let config = self.config_service.get_config()?;
+        let preset_password = match input.preset_password.clone().or(config.preset_password) {
+            None if input.password_mode == PasswordMode::Manual => {
+                Some(crate::utils::utils::prompt_manual_password(self.prompter.as_ref(), input.allow_weak_password)?)
+            }
+            preset => preset,
+        };
+        let input = ConversionInput { preset_password, ..input };
+        let workers = input.jobs.min(groups.len()).max(1);
+        info!("依目錄分組，共 {} 個群組，以 {} 個執行緒平行轉換，輸出目錄：{}", groups.len(), workers, input.output_dir);
+        if input.index {
+            log::warn!("依目錄分組時各群組以壓縮模式轉換，索引頁僅適用於個別模式，已略過");
+        }
+        let mut report = ConversionReport::new(
+            input.input_path.clone(),
+            input.output_dir.clone(),
+            input.lang.clone(),
+            input.index_name.clone(),
+            input.output_extension.clone(),
+            input.format_version,
+            input.classification.clone(),
+        );
+        let progress = group_progress_bar(groups.len() as u64, input.no_progress);
+        let inputs = groups.iter().map(|dir| group_input(&input, dir)).collect();
+        let results = run_batch(inputs, workers, |group| self.convert_with_downgrades(group), |index, result| {
+            match result {
+                Ok(_) => progress.set_message(format!("已完成 {}", group_name(&groups[index]))),
+                Err(e) => log::error!("群組 {} 轉換失敗：{}", groups[index].display(), e),
+            }
+            progress.inc(1);
+        });
+        progress.finish_with_message("群組轉換完成");
+
+        let mut processed_files = 0;
+        let mut downgrades = Vec::new();
+        let mut unmatched_patterns: Option<Vec<UnmatchedPattern>> = None;
+        for (dir, result) in groups.iter().zip(results) {
+            let output = match result {
+                Ok(output) => output,
+                Err(e) => {
+                    report.failed_groups.push(GroupFailure { source: dir.clone(), error: e.to_string() });
+                    continue;
+                }
+            };
+            processed_files += output.processed_files;
+            for artifact in output.artifacts {
+                report.record(artifact);
+            }
+            report.missing_files.extend(output.missing_files);
+            downgrades.extend(output.downgrades.into_iter().map(|change| format!("{}：{}", group_name(dir), change)));
+            // 只列出在每個群組都沒有符合檔案的模式
+            unmatched_patterns = Some(match unmatched_patterns {
+                None => output.unmatched_patterns,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter(|pattern| output.unmatched_patterns.iter().any(|other| other.pattern == pattern.pattern))
+                    .collect(),
+            });
+        }
+
+        if input.pwa {
+            self.html_service.generate_pwa_assets(&input.output_dir)?;
+        }
+        if input.viewer {
+            self.html_service.generate_decoder(&input.output_dir, VIEWER_NAME, &input.lang)?;
+        }
+        if let Some(report_path) = &input.report {
+            write_report(report_path, &report)?;
+        }
+        if !report.failed_groups.is_empty() {
+            let failures: Vec<String> = report
+                .failed_groups
+                .iter()
+                .map(|failure| format!("{}（{}）", failure.source.display(), failure.error))
+                .collect();
+            return Err(io::Error::other(format!(
+                "{} 個群組中有 {} 個轉換失敗：{}",
+                groups.len(),
+                failures.len(),
+                failures.join("；")
+            )));
+        }
+        info!("依目錄分組轉換完成，共 {} 個群組、{} 個檔案", groups.len(), processed_files);
+        Ok(ConversionOutput {
+            output_path: input.output_dir.clone(),
+            processed_files,
+            artifacts: report.artifacts,
+            index_path: None,
+            restore_path: None,
+            downgrades,
+            single_page: None,
+            unmatched_patterns: unmatched_patterns.unwrap_or_default(),
+            missing_files: report.missing_files,
+        })
+    }
+
+    // 執行一次完整的轉換
+    fn run_conversion(&self, input: ConversionInput) -> io::Result<ConversionOutput> {
         let config = self.config_service.get_config()?;
-        let input_path = Path::new(&input.input_path);
-        let output_dir = &input.output_dir;
+        // 命令列模式的輸入直接由參數建立，手動密碼由設定來源在取得配置時詢問
+        let input = ConversionInput { preset_password: input.preset_password.or_else(|| config.preset_password.clone()), ..input };
+        let raw = input.archive_format == "none" || input.layer == "none";
+        validate_low_memory(input.low_memory, raw)?;
+        let input = if input.low_memory { low_memory_input(input) } else { input };
+        let input = if raw { raw_input(input) } else { input };
+        let input = passthrough_input(input);
+        let plugins = crate::service::plugin::snapshot();
+        if input.format != "html" && output_renderer(&input.format, &plugins).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("未知的輸出格式 '{}'，可用格式：{}", input.format, output_format_names(&plugins).join("、")),
+            ));
+        }
+        check_low_memory_renderer(&input.format, &plugins, input.low_memory)?;
 
         let file_input = FileCollectInput {
             input_path: input.input_path.clone(),
             include_patterns: input.include.clone(),
+            ignore_case: input.ignore_case,
+            match_on: input.match_on.clone(),
             exclude_patterns: input.exclude.clone(),
             max_size: input.max_size,
+            rules: input.rules.clone(),
             no_progress: input.no_progress,
+            slices: input.slices.clone(),
+            symlinks: input.symlinks.clone(),
         };
 
-        let file_output = if input.is_compressed {
-            self.file_service.collect_files(file_input)?
-        } else {
-            self.file_service.collect_files(file_input)?
-        };
+        let file_output = self.file_service.collect_files(file_input)?;
+        if let Some(expected) = &input.job_files {
+            verify_job_files(&input.input_path, &file_output.files, expected)?;
+            info!("輸入檔案與工作檔記錄一致，共 {} 個檔案", expected.len());
+        }
+        if let Some(job_path) = &input.export_job {
+            let entries = job_entries(&input.input_path, &file_output.files)?;
+            JobFile::new(&config, entries, crate::utils::random::seed()).save(Path::new(job_path))?;
+            info!("工作檔已儲存至：{}", job_path);
+        }
+        let tags = TagSet::new(&input.tags, &input.tag_rules)?;
 
         let processed_files = file_output.files.len();
         if processed_files == 0 {
@@ -62,32 +332,121 @@ impl ConversionFacadeTrait for ConversionFacade {
             return Ok(ConversionOutput {
                 output_path: input.output_dir.clone(),
                 processed_files: 0,
+                artifacts: Vec::new(),
+                index_path: None,
+                restore_path: None,
+                downgrades: Vec::new(),
+                single_page: None,
+                unmatched_patterns: file_output.unmatched_patterns,
+                missing_files: Vec::new(),
             });
         }
 
+        let mut report = ConversionReport::new(
+            input.input_path.clone(),
+            input.output_dir.clone(),
+            input.lang.clone(),
+            input.index_name.clone(),
+            input.output_extension.clone(),
+            input.format_version,
+            input.classification.clone(),
+        );
+        if input.format_version < FORMAT_VERSION {
+            log::warn!("以輸出格式版本 {} 產生頁面，頁面不標示版本，下游工具需以舊版結構讀取", input.format_version);
+        }
+        if input.password_hint.is_some() && input.password_mode == PasswordMode::None {
+            log::warn!("頁面未加密，已忽略 --password-hint");
+        }
+        if input.keep_original_text && input.transcode_text.is_none() {
+            log::warn!("未指定 --transcode-text，已忽略 --keep-original-text");
+        }
+        if input.key_sheet && input.display_password {
+            log::warn!("密碼已顯示於頁面中，不另外產生密碼單，已忽略 --key-sheet");
+        }
+        if needs_rename_hint(&input.output_extension) {
+            info!(
+                "頁面以 .{} 副檔名輸出，收件者需將副檔名改回 .html（MIME 類型 text/html）後以瀏覽器開啟",
+                input.output_extension
+            );
+        }
         if input.is_compressed {
             info!("開始壓縮轉換，輸入路徑：{}，輸出目錄：{}", input.input_path.display(), input.output_dir);
-            self.process_compressed(input.clone(), &file_output)?;
+            if input.jobs > 1 && input.archive_format != "zip" {
+                log::warn!("平行壓縮僅適用於 ZIP 封存格式，已忽略 --jobs {}", input.jobs);
+            }
+            self.process_compressed(input.clone(), &file_output, &tags, &mut report)?;
         } else {
             info!("開始個別轉換，輸入路徑：{}，輸出目錄：{}", input.input_path.display(), input.output_dir);
-            self.process_individual(input.clone(), &file_output)?;
+            self.process_individual(input.clone(), &file_output, &tags, &mut report)?;
         }
 
+        let index_path = if input.index && !input.is_compressed {
+            Some(self.html_service.generate_index(&report)?.html_file_path)
+        } else {
+            if input.index {
+                log::warn!("索引頁僅適用於個別模式，已略過");
+            }
+            None
+        };
+
+        let restore_path = if input.restore {
+            if report.artifacts.len() > 1 || report.artifacts.iter().any(|a| !a.parts.is_empty()) {
+                Some(self.html_service.generate_restore(&report)?.html_file_path)
+            } else {
+                log::warn!("還原頁僅適用於多個檔案或分段輸出，已略過");
+                None
+            }
+        } else {
+            None
+        };
+
+        if input.pwa {
+            self.html_service.generate_pwa_assets(&input.output_dir)?;
+        }
+
+        // 與頁面放在一起的離線檢視頁，可拖放頁面解密還原，不需信任頁面本身的腳本
+        if input.viewer {
+            self.html_service.generate_decoder(&input.output_dir, VIEWER_NAME, &input.lang)?;
+        }
+
+        if let Some(report_path) = &input.report {
+            write_report(report_path, &report)?;
+        }
+
+        // 索引頁、還原頁與分段頁面都算在內，只有單一頁面時才提供
+        let single_page = match report.artifacts.as_slice() {
+            [artifact] if index_path.is_none()
+                && restore_path.is_none()
+                && artifact.parts.is_empty()
+                && input.format == "html"
+                && !needs_rename_hint(&input.output_extension) => Some(artifact.html.clone()),
+            _ => None,
+        };
+
         Ok(ConversionOutput {
             output_path: input.output_dir.clone(),
-            processed_files,
+            processed_files: processed_files - report.missing_files.len(),
+            artifacts: report.artifacts,
+            index_path,
+            restore_path,
+            downgrades: Vec::new(),
+            single_page,
+            unmatched_patterns: file_output.unmatched_patterns.clone(),
+            missing_files: report.missing_files,
         })
     }
-}
 
-impl ConversionFacade {
-    fn process_compressed(&self, input: ConversionInput, file_output: &FileCollectOutput) -> io::Result<()> {
+    fn process_compressed(
+        &self,
+        input: ConversionInput,
+        file_output: &FileCollectOutput,
+        tags: &TagSet,
+        report: &mut ConversionReport,
+    ) -> io::Result<()> {
         std::fs::create_dir_all(&input.output_dir)?;
-        let options = SimpleFileOptions::default()
-            .compression_method(zip::CompressionMethod::DEFLATE)
-            .compression_level(Some(5));
-
-        let password = crate::utils::utils::generate_password(&input.password_mode, None)?;
+        let (password, inner_password) = self.generate_passwords(&input)?;
+        let payload_compression = resolve_payload_compression(&input, password.as_deref());
+        let compression = archive_compression(&input, payload_compression);
         let aes_mode = match input.encryption_method.as_str() {
             "aes128" => AesMode::Aes128,
             "aes192" => AesMode::Aes192,
@@ -98,20 +457,92 @@ impl ConversionFacade {
         let zip_input = ZipCompressInput {
             files: file_output.files.clone(),
             input_path: input.input_path.clone(),
-            options,
-            password: password.clone(),
+            compression,
+            password: archive_password(&input, password.as_deref(), inner_password.as_deref()),
             aes_mode,
             no_progress: input.no_progress,
+            filters: content_filters(&input),
+            ranges: file_output.ranges.clone(),
+            archive_format: input.archive_format.clone(),
+            snapshots: file_output.snapshots.clone(),
+            on_change: input.on_change.clone(),
+            on_missing: input.on_missing.clone(),
+            symlinks: input.symlinks.clone(),
+            preserve_metadata: input.preserve_metadata,
+            spill_to_disk: input.spill_to_disk,
+            jobs: input.jobs,
+            manifest: input.manifest,
+            archive_root: archive_root(&input),
+            comment: input.comment.clone(),
         };
 
-        let zip_output = self.zip_service.compress_files(zip_input)?;
-        self.finalize_compression(input, &zip_output, file_output.total_size, password.as_deref(), aes_mode)?;
+        self.notify(|o| o.before_archive(&input.input_path, &file_output.files))?;
+        let (mut zip_output, payload_compression) =
+            compress_for_target(&input, password.as_deref(), payload_compression, |compression| {
+                self.zip_service.compress_files(ZipCompressInput { compression, ..zip_input.clone() })
+            })?;
+        self.notify(|o| o.after_archive(&input.input_path, &zip_output))?;
+        let missing_files = std::mem::take(&mut zip_output.missing_files);
+        if input.layer == "double" {
+            zip_output.zip_buffer = wrap_outer_layer(
+                &input,
+                &input.input_path,
+                std::mem::take(&mut zip_output.zip_buffer),
+                zip_output.spill.take(),
+                password.as_deref(),
+                aes_mode,
+            )?;
+        }
+        (zip_output.zip_buffer, zip_output.spill) = protect_payload(
+            std::mem::take(&mut zip_output.zip_buffer),
+            zip_output.spill.take(),
+            password.as_deref(),
+            &input.encryption_method,
+        )?;
+        // 清單中的每個項目標示各自的標籤，頁面只標示所有項目一致的標籤
+        for entry in &mut zip_output.entries {
+            entry.tags = tags.for_entry(&entry.path);
+        }
+        let page_tags = tags.for_entries(zip_output.entries.iter().map(|entry| entry.path.as_str()));
+        let artifact = self.finalize_compression(
+            &input,
+            zip_output,
+            file_output.total_size,
+            (password.as_deref(), inner_password.as_deref()),
+            payload_compression,
+            page_tags,
+        )?;
+        if input.verify_output {
+            let sources: Vec<VerifySource> = file_output
+                .files
+                .iter()
+                .filter(|file| !missing_files.contains(file))
+                .filter_map(|file| {
+                    rooted_entry_name(file, &input.input_path, archive_root(&input).as_deref()).map(|name| VerifySource {
+                        path: file,
+                        entry_name: name,
+                        range: file_output.ranges.get(file).copied(),
+                        symlink: input.symlinks == "store" && file.is_symlink(),
+                    })
+                })
+                .collect();
+            let verified = verify_output(&input, &artifact, &sources, password.as_deref(), inner_password.as_deref(), &zip_input.filters);
+            self.discard_on_error(&artifact, verified)?;
+        }
+        report.record(artifact);
+        report.missing_files.extend(missing_files);
         Ok(())
     }
 
-    fn process_individual(&self, input: ConversionInput, file_output: &FileCollectOutput) -> io::Result<()> {
+    fn process_individual(
+        &self,
+        input: ConversionInput,
+        file_output: &FileCollectOutput,
+        tags: &TagSet,
+        report: &mut ConversionReport,
+    ) -> io::Result<()> {
         std::fs::create_dir_all(&input.output_dir)?;
-        let password = crate::utils::utils::generate_password(&input.password_mode, None)?;
+        let (password, inner_password) = self.generate_passwords(&input)?;
         let aes_mode = match input.encryption_method.as_str() {
             "aes128" => AesMode::Aes128,
             "aes192" => AesMode::Aes192,
@@ -119,62 +550,788 @@ impl ConversionFacade {
             _ => AesMode::Aes256,
         };
 
-        for file_path in &file_output.files {
-            let html_input = HtmlGenerateInput {
-                zip_buffer: self.compress_single_file(file_path, &input, password.clone(), aes_mode)?,
-                input_path: file_path.clone(),
-                output_dir: input.output_dir.clone(),
-                layer: input.layer.clone(),
-                password: password.clone(),
-                display_password: input.display_password,
-                total_size: file_output.total_size,
-            };
-            self.html_service.generate_html(html_input)?;
+        let payload_compression = resolve_payload_compression(&input, password.as_deref());
+        let job = IndividualJob {
+            input: &input,
+            password: password.as_deref(),
+            inner_password: inner_password.as_deref(),
+            aes_mode,
+            payload_compression,
+            compression: archive_compression(&input, payload_compression),
+            filters: content_filters(&input),
+            ranges: &file_output.ranges,
+            snapshots: &file_output.snapshots,
+            total_size: file_output.total_size,
+            tags,
+            missing: Mutex::new(Vec::new()),
+        };
+        let workers = input.jobs.min(file_output.files.len()).max(1);
+        if workers == 1 {
+            for file_path in &file_output.files {
+                if let Some(artifact) = self.convert_single_file(file_path, &job)? {
+                    report.record(artifact);
+                }
+            }
+            report.missing_files.append(&mut lock(&job.missing));
+            return Ok(());
+        }
+
+        // 依大小分波排程，由各執行緒依序取用；產出仍依蒐集順序記錄，索引頁與還原頁的順序不受排程影響
+        info!("以 {} 個執行緒平行轉換 {} 個檔案", workers, file_output.files.len());
+        let queue = Mutex::new(wave_order(&file_output.sizes, workers).into_iter());
+        let results: Mutex<Vec<(usize, GeneratedArtifact)>> = Mutex::new(Vec::with_capacity(file_output.files.len()));
+        let failure: Mutex<Option<io::Error>> = Mutex::new(None);
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    // 任一檔案失敗後不再取用新的檔案，已開始的檔案照常完成
+                    if lock(&failure).is_some() {
+                        break;
+                    }
+                    let Some(index) = lock(&queue).next() else {
+                        break;
+                    };
+                    match self.convert_single_file(&file_output.files[index], &job) {
+                        Ok(Some(artifact)) => lock(&results).push((index, artifact)),
+                        Ok(None) => {}
+                        Err(e) => {
+                            lock(&failure).get_or_insert(e);
+                        }
+                    }
+                });
+            }
+        });
+        if let Some(e) = lock(&failure).take() {
+            return Err(e);
         }
+        let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+        results.sort_by_key(|(index, _)| *index);
+        for (_, artifact) in results {
+            report.record(artifact);
+        }
+        let mut missing_files = std::mem::take(&mut *lock(&job.missing));
+        missing_files.sort();
+        report.missing_files.extend(missing_files);
         Ok(())
     }
 
+    // 轉換個別模式的單一檔案，超過頁面大小上限且策略為 error 時返回 None
+    fn convert_single_file(&self, file_path: &Path, job: &IndividualJob) -> io::Result<Option<GeneratedArtifact>> {
+        let input = job.input;
+        self.notify(|o| o.before_file(file_path))?;
+        let range = job.ranges.get(file_path).copied();
+        // 保存符號連結時以連結本身計算，失效的連結也能轉換
+        let metadata = if input.symlinks == "store" { std::fs::symlink_metadata(file_path) } else { std::fs::metadata(file_path) };
+        let source_size = match range {
+            Some(range) => range.len as usize,
+            None => match metadata {
+                Ok(metadata) => metadata.len() as usize,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    if input.on_missing != "skip" {
+                        return Err(io::Error::new(e.kind(), format!("來源檔案在蒐集後已被刪除：{}", file_path.display())));
+                    }
+                    log::warn!("來源檔案在蒐集後已被刪除，已略過：{}", file_path.display());
+                    lock(&job.missing).push(file_path.to_path_buf());
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            },
+        };
+        let slices = range
+            .map(|range| (file_path.file_name().unwrap_or_default().to_string_lossy().to_string(), range))
+            .into_iter()
+            .collect();
+        let Some((zip_buffer, spill, payload_compression)) = self.compress_single_file(file_path, job, range)? else {
+            lock(&job.missing).push(file_path.to_path_buf());
+            return Ok(None);
+        };
+        let tags = job.tags.for_entry(&entry_name(file_path, &input.input_path).unwrap_or_default());
+        let (zip_buffer, spill) = if input.layer == "double" {
+            (wrap_outer_layer(input, file_path, zip_buffer, spill, job.password, job.aes_mode)?, None)
+        } else {
+            (zip_buffer, spill)
+        };
+        let (zip_buffer, spill) = protect_payload(zip_buffer, spill, job.password, &input.encryption_method)?;
+        let html_input = HtmlGenerateInput {
+            zip_buffer,
+            spill,
+            input_path: file_path.to_path_buf(),
+            output_dir: input.output_dir.clone(),
+            layer: input.layer.clone(),
+            encryption_method: input.encryption_method.clone(),
+            password: job.password.map(str::to_string),
+            inner_password: job.inner_password.map(str::to_string),
+            display_password: input.display_password,
+            password_qr: input.password_qr,
+            total_size: job.total_size,
+            view: "archive".to_string(),
+            entries: Vec::new(),
+            format: input.format.clone(),
+            split_size: split_size_bytes(input.split_size, input.volume_size.as_deref())?,
+            lang: input.lang.clone(),
+            extra_css: input.extra_css.clone(),
+            extra_js: input.extra_js.clone(),
+            key_name: input.key_name.clone(),
+            title: input.title.clone(),
+            logo: input.logo.clone(),
+            footer: input.footer.clone(),
+            message: input.message.clone(),
+            data_uri_threshold: parse_data_uri_threshold(&input.data_uri_threshold)?,
+            wrap_base64: input.wrap_base64,
+            output_extension: input.output_extension.clone(),
+            expires: input.expires.clone(),
+            payload_encoding: input.payload_encoding.clone(),
+            payload_compression: payload_compression.to_string(),
+            payload_compression_level: input.payload_compression_level,
+            max_html_size: max_html_size_bytes(input.max_html_size),
+            oversize_policy: input.oversize_policy.clone(),
+            password_verifier: input.password_verifier,
+            payload_mode: input.payload_mode.clone(),
+            minify: input.minify,
+            low_memory: input.low_memory,
+            password_hint: input.password_hint.clone(),
+            ascii_names: input.ascii_names,
+            pwa: input.pwa,
+            no_js: input.no_js,
+            a11y_strict: input.a11y_strict,
+            archive_format: input.archive_format.clone(),
+            key_file_mode: parse_file_mode(&input.key_file_mode)?,
+            target_size: target_size_bytes(input.target_size.as_deref())?,
+            classification: input.classification.clone(),
+            key_sheet: input.key_sheet,
+            slices,
+            tags: tags.clone(),
+            format_version: input.format_version,
+        };
+        // 超過頁面大小上限且策略為 error 時只中止此檔案，繼續處理其餘檔案；啟用 --auto-downgrade 時改為中止整個轉換以便調整設定重試
+        let html_output = match self.html_service.generate_html(html_input) {
+            Ok(output) => output,
+            Err(e) if e.kind() == io::ErrorKind::FileTooLarge && !input.auto_downgrade => {
+                log::error!("已略過：{}", e);
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let artifact = GeneratedArtifact {
+            html: html_output.html_file_path,
+            key: html_output.key_file_path,
+            key_sheet: html_output.key_sheet_path,
+            source: file_path.to_path_buf(),
+            source_size,
+            html_size: html_output.html_size,
+            parts: html_output.parts,
+            page_sha256: html_output.page_sha256,
+            payload_file: html_output.payload_file_path,
+            tags,
+            generated_at: Local::now(),
+        };
+        if input.verify_output {
+            let source = VerifySource {
+                path: file_path,
+                entry_name: rooted_entry_name(file_path, file_path, archive_root(input).as_deref()).unwrap_or_default(),
+                range,
+                symlink: input.symlinks == "store" && file_path.is_symlink(),
+            };
+            let verified = verify_output(input, &artifact, &[source], job.password, job.inner_password, &job.filters);
+            self.discard_on_error(&artifact, verified)?;
+        }
+        let notified = self.notify(|o| o.after_file(file_path, &artifact));
+        self.discard_on_error(&artifact, notified)?;
+        file_converted();
+        Ok(Some(artifact))
+    }
+
+    // 壓縮單一檔案，返回 ZIP 數據、暫存檔與頁面資料實際使用的整體壓縮方式，檔案已被刪除而略過時返回 None
     fn compress_single_file(
         &self,
         file_path: &Path,
-        input: &ConversionInput,
-        password: Option<String>,
-        aes_mode: AesMode,
-    ) -> io::Result<Vec<u8>> {
-        let (data, _file_size) = crate::service::file::read_file_content(file_path)?;
-        let file_name = file_path.file_name().unwrap().to_string_lossy().to_string();
+        job: &IndividualJob,
+        range: Option<ByteRange>,
+    ) -> io::Result<Option<SingleArchive>> {
+        if job.input.passthrough {
+            return self.read_passthrough_archive(file_path, job);
+        }
+        if job.input.layer == "none" {
+            return self.read_raw_file(file_path, job, range);
+        }
         let zip_input = ZipCompressInput {
             files: vec![file_path.to_path_buf()],
             input_path: file_path.to_path_buf(),
-            options: SimpleFileOptions::default()
-                .compression_method(zip::CompressionMethod::DEFLATE)
-                .compression_level(Some(5)),
-            password,
-            aes_mode,
-            no_progress: input.no_progress,
+            compression: job.compression.clone(),
+            password: archive_password(job.input, job.password, job.inner_password),
+            aes_mode: job.aes_mode,
+            no_progress: job.input.no_progress,
+            filters: job.filters.clone(),
+            ranges: range.map(|range| (file_path.to_path_buf(), range)).into_iter().collect(),
+            archive_format: job.input.archive_format.clone(),
+            snapshots: job.snapshots.get(file_path).map(|snapshot| (file_path.to_path_buf(), snapshot.clone())).into_iter().collect(),
+            on_change: job.input.on_change.clone(),
+            on_missing: job.input.on_missing.clone(),
+            symlinks: job.input.symlinks.clone(),
+            preserve_metadata: job.input.preserve_metadata,
+            spill_to_disk: job.input.spill_to_disk,
+            // 個別模式已平行轉換各檔案，單一檔案的封存檔依序壓縮
+            jobs: 1,
+            manifest: job.input.manifest,
+            archive_root: archive_root(job.input),
+            comment: job.input.comment.clone(),
         };
-        let zip_output = self.zip_service.compress_files(zip_input)?;
-        Ok(zip_output.zip_buffer)
+        self.notify(|o| o.before_archive(file_path, &zip_input.files))?;
+        let (zip_output, payload_compression) =
+            compress_for_target(job.input, job.password, job.payload_compression, |compression| {
+                self.zip_service.compress_files(ZipCompressInput { compression, ..zip_input.clone() })
+            })?;
+        if !zip_output.missing_files.is_empty() {
+            return Ok(None);
+        }
+        self.notify(|o| o.after_archive(file_path, &zip_output))?;
+        Ok(Some((zip_output.zip_buffer, zip_output.spill, payload_compression)))
+    }
+
+    // 不封存時直接讀取來源檔案（只讀取指定範圍）並套用內容過濾器，不經過 ZIP 服務；檔案已被刪除而略過時返回 None
+    fn read_raw_file(&self, file_path: &Path, job: &IndividualJob, range: Option<ByteRange>) -> io::Result<Option<SingleArchive>> {
+        let input = job.input;
+        let read = || read_file_range(file_path, range.as_ref());
+        let snapshot = job.snapshots.get(file_path);
+        let data = match snapshot {
+            Some(snapshot) if input.on_change == "reread" => read_stable(file_path, snapshot, read),
+            _ => read(),
+        };
+        let data = match data {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return skip_missing(file_path, &input.on_missing, e),
+            Err(e) => return Err(e),
+        };
+        if let Some(snapshot) = snapshot.filter(|_| input.on_change != "reread") {
+            check_unchanged(file_path, snapshot, &input.on_change)?;
+        }
+        let data = apply_filters(&job.filters, file_path, data)?;
+        Ok(Some((data, None, job.payload_compression)))
+    }
+
+    // 直接嵌入既有的封存檔，不套用內容過濾器也不重新壓縮；檔案已被刪除而略過時返回 None
+    fn read_passthrough_archive(&self, file_path: &Path, job: &IndividualJob) -> io::Result<Option<SingleArchive>> {
+        let passthrough = ArchivePassthroughInput {
+            path: file_path.to_path_buf(),
+            archive_format: job.input.archive_format.clone(),
+            snapshot: job.snapshots.get(file_path).cloned(),
+            on_change: job.input.on_change.clone(),
+        };
+        self.notify(|o| o.before_archive(file_path, &[file_path.to_path_buf()]))?;
+        let output = match self.zip_service.passthrough_archive(passthrough) {
+            Ok(output) => output,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return skip_missing(file_path, &job.input.on_missing, e),
+            Err(e) => return Err(e),
+        };
+        self.notify(|o| o.after_archive(file_path, &output))?;
+        Ok(Some((output.zip_buffer, None, job.payload_compression)))
     }
 
     fn finalize_compression(
         &self,
-        input: ConversionInput,
-        zip_output: &ZipCompressOutput,
+        input: &ConversionInput,
+        zip_output: ZipCompressOutput,
         total_size: usize,
-        password: Option<&str>,
-        aes_mode: AesMode,
-    ) -> io::Result<()> {
+        (password, inner_password): (Option<&str>, Option<&str>),
+        payload_compression: &str,
+        tags: Tags,
+    ) -> io::Result<GeneratedArtifact> {
+        let slices = zip_output
+            .entries
+            .iter()
+            .filter_map(|entry| entry.range.map(|range| (entry.path.clone(), range)))
+            .collect();
         let html_input = HtmlGenerateInput {
-            zip_buffer: zip_output.zip_buffer.clone(),
+            zip_buffer: zip_output.zip_buffer,
+            spill: zip_output.spill,
             input_path: input.input_path.clone(),
             output_dir: input.output_dir.clone(),
             layer: input.layer.clone(),
+            encryption_method: input.encryption_method.clone(),
             password: password.map(String::from),
+            inner_password: inner_password.map(String::from),
             display_password: input.display_password,
+            password_qr: input.password_qr,
             total_size,
+            view: input.view.clone(),
+            entries: zip_output.entries,
+            format: input.format.clone(),
+            split_size: split_size_bytes(input.split_size, input.volume_size.as_deref())?,
+            lang: input.lang.clone(),
+            extra_css: input.extra_css.clone(),
+            extra_js: input.extra_js.clone(),
+            key_name: input.key_name.clone(),
+            title: input.title.clone(),
+            logo: input.logo.clone(),
+            footer: input.footer.clone(),
+            message: input.message.clone(),
+            data_uri_threshold: parse_data_uri_threshold(&input.data_uri_threshold)?,
+            wrap_base64: input.wrap_base64,
+            output_extension: input.output_extension.clone(),
+            expires: input.expires.clone(),
+            payload_encoding: input.payload_encoding.clone(),
+            payload_compression: payload_compression.to_string(),
+            payload_compression_level: input.payload_compression_level,
+            max_html_size: max_html_size_bytes(input.max_html_size),
+            oversize_policy: input.oversize_policy.clone(),
+            password_verifier: input.password_verifier,
+            payload_mode: input.payload_mode.clone(),
+            minify: input.minify,
+            low_memory: input.low_memory,
+            password_hint: input.password_hint.clone(),
+            ascii_names: input.ascii_names,
+            pwa: input.pwa,
+            no_js: input.no_js,
+            a11y_strict: input.a11y_strict,
+            archive_format: input.archive_format.clone(),
+            key_file_mode: parse_file_mode(&input.key_file_mode)?,
+            target_size: target_size_bytes(input.target_size.as_deref())?,
+            classification: input.classification.clone(),
+            key_sheet: input.key_sheet,
+            slices,
+            tags: tags.clone(),
+            format_version: input.format_version,
         };
-        self.html_service.generate_html(html_input)?;
-        Ok(())
+        let html_output = self.html_service.generate_html(html_input)?;
+        Ok(GeneratedArtifact {
+            html: html_output.html_file_path,
+            key: html_output.key_file_path,
+            key_sheet: html_output.key_sheet_path,
+            source: input.input_path.clone(),
+            source_size: total_size,
+            html_size: html_output.html_size,
+            parts: html_output.parts,
+            page_sha256: html_output.page_sha256,
+            payload_file: html_output.payload_file_path,
+            tags,
+            generated_at: Local::now(),
+        })
     }
-}
\ No newline at end of file
+}
+
+// 單一檔案的封存結果：ZIP 數據、暫存檔與頁面資料實際使用的整體壓縮方式
+type SingleArchive = (Vec<u8>, Option<SpillFile>, &'static str);
+
+// 個別模式中所有檔案共用的轉換設定
+struct IndividualJob<'a> {
+    input: &'a ConversionInput,
+    password: Option<&'a str>,
+    // 雙層壓縮時內層 ZIP 的密碼
+    inner_password: Option<&'a str>,
+    aes_mode: AesMode,
+    payload_compression: &'static str,
+    compression: CompressionSpec,
+    filters: Vec<Arc<dyn ContentFilter>>,
+    ranges: &'a HashMap<PathBuf, ByteRange>,
+    snapshots: &'a HashMap<PathBuf, FileSnapshot>,
+    total_size: usize,
+    tags: &'a TagSet,
+    // 蒐集後已被刪除而略過的檔案，各執行緒共用
+    missing: Mutex<Vec<PathBuf>>,
+}
+
+// 執行緒失敗不影響已蒐集的結果，忽略鎖的中毒狀態
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// 內建的內容過濾器先於插件註冊的過濾器套用，讓插件處理轉碼與統一換行後的內容；先轉碼再統一換行，避免誤判舊式編碼的內容
+fn content_filters(input: &ConversionInput) -> Vec<Arc<dyn ContentFilter>> {
+    let mut filters: Vec<Arc<dyn ContentFilter>> = Vec::new();
+    if input.transcode_text.is_some() {
+        filters.push(Arc::new(TextTranscoder::new(input.keep_original_text)));
+    }
+    if let Some(line_ending) = &input.line_endings {
+        filters.push(Arc::new(LineEndingNormalizer::new(line_ending)));
+    }
+    filters.extend_from_slice(crate::service::plugin::snapshot().filters());
+    filters
+}
+
+// 轉換完成後將唯一的頁面複製到剪貼簿；剪貼簿無法使用不影響已完成的轉換，只記錄警告
+fn copy_output(output: &ConversionOutput, target: &str) {
+    let Some(page) = &output.single_page else {
+        log::warn!("複製到剪貼簿僅適用於只產生單一頁面的轉換，已略過 --copy-to-clipboard");
+        return;
+    };
+    match copy_page(page, target) {
+        Ok(()) => info!("已將 {} 的{}複製到剪貼簿", page, if target == "base64" { " Base64 內容" } else { "路徑" }),
+        Err(e) => log::warn!("無法複製到剪貼簿：{}", e),
+    }
+}
+
+// 寫出 --report 的 JSON 報告
+fn write_report(report_path: &str, report: &ConversionReport) -> io::Result<()> {
+    std::fs::write(report_path, report.to_json()?)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法寫入轉換報告 {}：{}", report_path, e)))?;
+    info!("轉換報告已儲存至：{}", report_path);
+    Ok(())
+}
+
+// 依目錄分組的群組：輸入目錄下的子目錄，依名稱排序；根目錄下的檔案不屬於任何群組，略過並記錄警告
+fn group_directories(input_path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !input_path.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("依目錄分組（--group-by dir）需以目錄作為輸入，{} 不是目錄", input_path.display())
+        ));
+    }
+    let mut groups = Vec::new();
+    let mut skipped = 0;
+    for entry in std::fs::read_dir(input_path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            groups.push(entry.path());
+        } else {
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        log::warn!("依目錄分組只轉換子目錄，已略過 {} 下的 {} 個項目", input_path.display(), skipped);
+    }
+    if groups.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} 下沒有可分組的子目錄", input_path.display())
+        ));
+    }
+    groups.sort();
+    Ok(groups)
+}
+
+// 單一群組的轉換參數：以壓縮模式將子目錄轉為一個頁面並關閉各自的進度列；
+// 報告、統計、剪貼簿、PWA 與檢視頁由分組流程統一處理一次，群組之間已平行執行，群組內不再平行壓縮
+fn group_input(input: &ConversionInput, dir: &Path) -> ConversionInput {
+    ConversionInput {
+        input_path: dir.to_path_buf(),
+        is_compressed: true,
+        group_by: None,
+        no_progress: true,
+        jobs: 1,
+        index: false,
+        restore: false,
+        report: None,
+        stats_file: None,
+        copy_to_clipboard: None,
+        pwa: false,
+        viewer: false,
+        export_job: None,
+        job_files: None,
+        ..input.clone()
+    }
+}
+
+fn group_name(dir: &Path) -> String {
+    dir.file_name().unwrap_or_default().to_string_lossy().into_owned()
+}
+
+// 依目錄分組的彙整進度列，以完成的群組數計算
+fn group_progress_bar(total: u64, no_progress: bool) -> ProgressBar {
+    if no_progress {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg} [{bar:40}] {pos}/{len} 個群組 ETA: {eta_precise}")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+    pb
+}
+
+// 指定 --stats-file 時將本次轉換累加至本機統計檔，寫入失敗只記錄警告，不影響轉換結果
+fn record_stats(path: &str, result: &io::Result<ConversionOutput>, duration_ms: u64) {
+    if let Err(e) = record_run(Path::new(path), &DailyStats::from_run(result, duration_ms)) {
+        log::warn!("統計檔未更新：{}", e);
+    }
+}
+
+// 啟用 --verify-output 時讀回剛寫出的產出物，比對內容與來源檔案，不符時中止轉換
+fn verify_output(
+    input: &ConversionInput,
+    artifact: &GeneratedArtifact,
+    sources: &[VerifySource],
+    password: Option<&str>,
+    inner_password: Option<&str>,
+    filters: &[Arc<dyn ContentFilter>],
+) -> io::Result<()> {
+    // 插件渲染器的輸出格式無法讀回，包括覆寫內建格式的插件
+    if !OUTPUT_FORMATS.contains(&input.format.as_str()) || crate::service::plugin::snapshot().renderer(&input.format).is_some() {
+        log::warn!("產出驗證不支援插件提供的輸出格式 {}，已略過：{}", input.format, artifact.html);
+        return Ok(());
+    }
+    let archive_format = if input.layer == "none" { "none" } else { input.archive_format.as_str() };
+    let count = verify_artifact(artifact, sources, (password, inner_password), filters, &input.layer, archive_format)?;
+    info!(target: FILE_LOG_TARGET, "產出驗證通過：{}，{} 個檔案內容與來源相符", artifact.html, count);
+    Ok(())
+}
+
+// 依失敗原因將一項設定改為較保守的值，返回調整內容的說明；失敗原因無法以調整設定解決或已無設定可調整時返回 None
+// - 超過大小上限：樹狀檢視改為封存檢視、改為分段輸出、降為單層壓縮
+// - 不支援的加密或壓縮方式：瀏覽器端解密改為 ZIP 的 AES 加密、ZIP 項目改為不壓縮、降為單層壓縮
+fn downgrade_input(input: &mut ConversionInput, error: &io::Error) -> Option<String> {
+    match error.kind() {
+        io::ErrorKind::FileTooLarge => {
+            if input.view == "tree" {
+                input.view = "archive".to_string();
+                return Some("封存檢視（--view archive），以便分段輸出".to_string());
+            }
+            if input.oversize_policy != "split" && input.format == "html" && input.target_size.is_none() {
+                input.oversize_policy = "split".to_string();
+                return Some("超過頁面大小上限時分段輸出（--oversize-policy split）".to_string());
+            }
+        }
+        io::ErrorKind::Unsupported => {
+            if is_browser_encryption(&input.encryption_method) && input.view != "tree" && input.password_mode != PasswordMode::None {
+                input.encryption_method = "aes256".to_string();
+                return Some("ZIP 的 AES-256 加密（--encryption-method aes256）".to_string());
+            }
+            if input.compression_method != "stored" {
+                input.compression_method = "stored".to_string();
+                input.force_method = false;
+                return Some("ZIP 項目不壓縮（--compression-method stored）".to_string());
+            }
+        }
+        _ => return None,
+    }
+    if input.layer == "double" {
+        input.layer = "single".to_string();
+        return Some("單層壓縮（--layer single）".to_string());
+    }
+    None
+}
+
+// 低記憶體模式只以單一執行緒轉換、將封存檔與頁面暫存於磁碟，並停用需額外保留整份頁面或資料副本的功能
+fn low_memory_input(mut input: ConversionInput) -> ConversionInput {
+    if input.jobs > 1 {
+        log::warn!("低記憶體模式僅以單一執行緒轉換，已忽略 --jobs {}", input.jobs);
+        input.jobs = 1;
+    }
+    if input.minify {
+        log::warn!("低記憶體模式不最小化頁面，已忽略 --minify");
+        input.minify = false;
+    }
+    if input.payload_compression != "none" {
+        log::warn!("低記憶體模式不整體壓縮頁面資料，已忽略 --payload-compression {}", input.payload_compression);
+        input.payload_compression = "none".to_string();
+    }
+    input.spill_to_disk = true;
+    info!("低記憶體模式：逐一轉換檔案，來源檔案以串流方式寫入 ZIP，封存檔與頁面暫存於磁碟");
+    input
+}
+
+// 不封存（--archive-format none 或 --layer none）時頁面資料即為來源檔案本身：以不分層的方式命名，
+// 停用需要封存檔才能保存的功能
+fn raw_input(mut input: ConversionInput) -> ConversionInput {
+    input.layer = "none".to_string();
+    if input.keep_original_text && input.transcode_text.is_some() {
+        log::warn!("不封存時每個頁面只包含一個檔案，無法另外保存原始內容，已忽略 --keep-original-text");
+        input.keep_original_text = false;
+    }
+    if input.symlinks == "store" {
+        log::warn!("不封存時無法保存符號連結本身，改為讀取連結的檔案內容（--symlinks follow）");
+        input.symlinks = "follow".to_string();
+    }
+    if input.spill_to_disk {
+        log::warn!("不封存時來源檔案直接讀入記憶體，已忽略 --spill-to-disk");
+        input.spill_to_disk = false;
+    }
+    if input.archive_root.is_some() || input.strip_prefix {
+        log::warn!("不封存時沒有項目名稱，已忽略 --archive-root 與 --strip-prefix");
+    }
+    input
+}
+
+// 輸入已是 .zip、.7z 或 .tar.gz 封存檔時不重新壓縮：未加密或以瀏覽器端解密時原樣嵌入頁面，
+// 以 ZIP 的 AES 加密時仍需包裝為加密的外層，外層的項目改為不壓縮
+fn passthrough_input(mut input: ConversionInput) -> ConversionInput {
+    let Some(format) = passthrough_format(&input) else {
+        input.passthrough = false;
+        return input;
+    };
+    if input.password_mode != PasswordMode::None && !is_browser_encryption(&input.encryption_method) {
+        info!("輸入已是 {} 封存檔，以加密的 ZIP 外層包裝，項目不重新壓縮", format);
+        input.passthrough = false;
+        input.compression_method = "stored".to_string();
+        return input;
+    }
+    info!("輸入已是 {} 封存檔，直接嵌入頁面，不重新壓縮", format);
+    if input.manifest || input.comment.is_some() {
+        log::warn!("直接嵌入既有封存檔時不寫入項目清單與封存檔註解，已忽略 --manifest 與 --comment");
+        input.manifest = false;
+        input.comment = None;
+    }
+    input.is_compressed = false;
+    input.archive_format = format.to_string();
+    raw_input(input)
+}
+
+// 可直接嵌入的封存格式；樹狀檢視需要自行寫入的 ZIP 項目位移，指定範圍與內容過濾插件需要處理內容，都仍重新封存
+fn passthrough_format(input: &ConversionInput) -> Option<&'static str> {
+    // 雙層壓縮的內層一律是自行寫入的 ZIP
+    if !input.passthrough || input.layer == "none" || input.layer == "double" || input.archive_format == "none" || !input.input_path.is_file() {
+        return None;
+    }
+    let format = detect_archive_format(&input.input_path).ok().flatten()?;
+    if input.view == "tree" || !input.slices.is_empty() || !crate::service::plugin::snapshot().filters().is_empty() {
+        info!("輸入為 {} 封存檔，但樹狀檢視、--slice 或內容過濾插件需重新封存，未直接嵌入", format);
+        return None;
+    }
+    Some(format)
+}
+
+// 來源檔案在蒐集後已被刪除：`--on-missing skip` 時略過並記錄，否則返回錯誤
+fn skip_missing(file_path: &Path, on_missing: &str, e: io::Error) -> io::Result<Option<SingleArchive>> {
+    if on_missing != "skip" {
+        return Err(io::Error::new(e.kind(), format!("來源檔案在蒐集後已被刪除：{}", file_path.display())));
+    }
+    log::warn!("來源檔案在蒐集後已被刪除，已略過：{}", file_path.display());
+    Ok(None)
+}
+
+// 決定頁面資料實際使用的整體壓縮方式；加密後的資料無法再壓縮，不使用整體壓縮。
+// 樹狀檢視需以位移直接讀取 ZIP 項目，只支援頁面附有解碼器、能先完整還原的 zstd
+fn resolve_payload_compression(input: &ConversionInput, password: Option<&str>) -> &'static str {
+    let compression = match input.payload_compression.as_str() {
+        "gzip" => "gzip",
+        "zstd" => "zstd",
+        _ => return "none",
+    };
+    if password.is_some() {
+        log::warn!("加密後的資料無法再壓縮，已忽略 --payload-compression {}", compression);
+        return "none";
+    }
+    if compression == "gzip" && input.is_compressed && input.view == "tree" {
+        log::warn!("樹狀檢視不支援 --payload-compression gzip，已忽略");
+        return "none";
+    }
+    compression
+}
+
+// 依指定方式壓縮；設定目標大小且預估超過目標時，另以 gzip 整體壓縮試算，採用較小的結果。
+// 加密、樹狀檢視與低記憶體模式不使用 gzip，不另外試算
+fn compress_for_target<F>(
+    input: &ConversionInput,
+    password: Option<&str>,
+    payload_compression: &'static str,
+    compress: F,
+) -> io::Result<(ZipCompressOutput, &'static str)>
+where
+    F: Fn(CompressionSpec) -> io::Result<ZipCompressOutput>,
+{
+    let output = compress(archive_compression(input, payload_compression))?;
+    let Some(target) = target_size_bytes(input.target_size.as_deref())? else {
+        return Ok((output, payload_compression));
+    };
+    let gzip_allowed =
+        !(password.is_some() || input.low_memory || input.spill_to_disk || input.is_compressed && input.view == "tree");
+    let capacity = payload_encoder(&input.payload_encoding)?.raw_capacity(target);
+    if payload_compression != "none" || !gzip_allowed || output.zip_buffer.len() <= capacity {
+        return Ok((output, payload_compression));
+    }
+    let stored = compress(archive_compression(input, "gzip"))?;
+    let gzipped_size = gzip_payload(&stored.zip_buffer)?.len();
+    if gzipped_size < output.zip_buffer.len() {
+        info!(target: FILE_LOG_TARGET, "整體以 gzip 壓縮的資料較小（{} → {} 位元組），改用 gzip 整體壓縮", output.zip_buffer.len(), gzipped_size);
+        return Ok((stored, "gzip"));
+    }
+    Ok((output, payload_compression))
+}
+
+// 項目名稱的頂層資料夾，`--strip-prefix` 以空字串表示去除頂層資料夾
+fn archive_root(input: &ConversionInput) -> Option<String> {
+    if input.strip_prefix {
+        Some(String::new())
+    } else {
+        input.archive_root.clone()
+    }
+}
+
+// 頁面資料整體壓縮時，ZIP 項目改為不壓縮（Stored），讓 gzip 或 zstd 能利用跨檔案的重複內容
+fn archive_compression(input: &ConversionInput, payload_compression: &str) -> CompressionSpec {
+    if payload_compression != "none" {
+        stored_compression()
+    } else {
+        resolve_compression(&input.compression_method, input.compression_level, input.force_method)
+    }
+}
+
+// 將 MB 為單位的頁面大小上限轉換為位元組數
+fn max_html_size_bytes(max_html_size: f64) -> usize {
+    ((max_html_size * 1024.0 * 1024.0) as usize).max(1)
+}
+
+// 將 MB 為單位的分段大小或 `--volume-size` 指定的分卷大小轉換為位元組數
+fn split_size_bytes(split_size: Option<f64>, volume_size: Option<&str>) -> io::Result<Option<usize>> {
+    if let Some(volume_size) = volume_size {
+        return Ok(Some(parse_byte_size(volume_size)?.max(1)));
+    }
+    Ok(split_size.map(|mb| ((mb * 1024.0 * 1024.0) as usize).max(1)))
+}
+
+// 解析 `--target-size` 為位元組數
+fn target_size_bytes(target_size: Option<&str>) -> io::Result<Option<usize>> {
+    target_size.map(parse_byte_size).transpose()
+}
+
+// 瀏覽器端解密模式下 ZIP 本身不加密，改由外層 AES-GCM 保護
+fn zip_password(password: Option<&str>, encryption_method: &str) -> Option<String> {
+    if is_browser_encryption(encryption_method) {
+        None
+    } else {
+        password.map(String::from)
+    }
+}
+
+// 寫入封存檔項目時使用的密碼：雙層壓縮的內層 ZIP 一律以內層密碼的 ZIP AES 加密，外層另由 wrap_outer_layer 或瀏覽器端加密保護
+fn archive_password(input: &ConversionInput, password: Option<&str>, inner_password: Option<&str>) -> Option<String> {
+    if input.layer == "double" {
+        inner_password.map(String::from)
+    } else {
+        zip_password(password, &input.encryption_method)
+    }
+}
+
+// 雙層壓縮：將內層 ZIP 以 `<來源名稱>.zip` 為唯一項目包裝為外層 ZIP，外層以頁面密碼加密（瀏覽器端解密時改由頁面加密），
+// 項目已壓縮過，外層不再壓縮；外層需要完整的內層資料，暫存於磁碟的內層 ZIP 先讀回記憶體
+fn wrap_outer_layer(
+    input: &ConversionInput,
+    source: &Path,
+    zip_buffer: Vec<u8>,
+    spill: Option<SpillFile>,
+    password: Option<&str>,
+    aes_mode: AesMode,
+) -> io::Result<Vec<u8>> {
+    let inner = match spill {
+        Some(spill) => {
+            log::warn!("雙層壓縮需將內層 ZIP 讀入記憶體，--spill-to-disk 無法降低此步驟的記憶體用量");
+            spill.read_all()?
+        }
+        None => zip_buffer,
+    };
+    let (file_name, _) = get_file_name(source, "single", "zip", input.ascii_names);
+    create_zip(&inner, &file_name, "double", &stored_compression(), zip_password(password, &input.encryption_method).as_deref(), aes_mode)
+}
+
+// 瀏覽器端解密模式下以 AES-GCM 包裝 ZIP 數據；加密需要完整的資料，暫存於磁碟的封存檔先讀回記憶體
+fn protect_payload(
+    zip_buffer: Vec<u8>,
+    spill: Option<SpillFile>,
+    password: Option<&str>,
+    encryption_method: &str,
+) -> io::Result<(Vec<u8>, Option<SpillFile>)> {
+    match password {
+        Some(pwd) if is_browser_encryption(encryption_method) => {
+            let zip_buffer = match spill {
+                Some(spill) => {
+                    log::warn!("瀏覽器端加密（{}）需將封存檔讀入記憶體，--spill-to-disk 無法降低此步驟的記憶體用量", encryption_method);
+                    spill.read_all()?
+                }
+                None => zip_buffer,
+            };
+            Ok((encrypt_with_method(&zip_buffer, pwd, encryption_method)?, None))
+        }
+        _ => Ok((zip_buffer, spill)),
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use chrono::{DateTime, Local, TimeDelta};
+use crate::facade::traits::i_conversion::ConversionFacadeTrait;
+use crate::models::conversion::{ConversionInput, ConversionOutput};
+use crate::models::job::{JobInfo, JobStatus};
+use crate::utils::random::random_id;
+
+// 工作識別碼的亂數位元組數
+const JOB_ID_BYTES: usize = 16;
+// 完成的工作保留供查詢的時間，之後自工作表移除
+const FINISHED_RETENTION: TimeDelta = TimeDelta::hours(1);
+
+// 工作資訊與提交者（用戶端識別的雜湊）
+struct JobEntry {
+    info: JobInfo,
+    owner: String,
+}
+
+type JobTable = Arc<Mutex<HashMap<String, JobEntry>>>;
+
+// 轉換工作佇列，由固定數量的背景執行緒依序執行轉換
+pub struct JobQueue {
+    jobs: JobTable,
+    sender: Mutex<Sender<(String, ConversionInput)>>,
+}
+
+impl JobQueue {
+    /// 建立工作佇列並啟動背景執行緒
+    /// # 參數
+    /// - facade: 執行轉換的 Facade，由所有背景執行緒共用
+    /// - workers: 背景執行緒數量，至少為 1
+    pub fn new(facade: Arc<dyn ConversionFacadeTrait>, workers: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let jobs: JobTable = Arc::new(Mutex::new(HashMap::new()));
+        for _ in 0..workers.max(1) {
+            let facade = Arc::clone(&facade);
+            let receiver = Arc::clone(&receiver);
+            let jobs = Arc::clone(&jobs);
+            thread::spawn(move || run_worker(facade, receiver, jobs));
+        }
+        JobQueue {
+            jobs,
+            sender: Mutex::new(sender),
+        }
+    }
+
+    /// 加入一個轉換工作
+    /// # 參數
+    /// - input: 轉換所需的輸入參數
+    /// - owner: 提交者，只有相同的提交者可以查詢此工作
+    /// # 回傳
+    /// - 成功時返回工作資訊，背景執行緒已全部結束時返回錯誤
+    pub fn submit(&self, input: ConversionInput, owner: &str) -> io::Result<JobInfo> {
+        let id = random_id(JOB_ID_BYTES);
+        let info = JobInfo::new(id.clone());
+        {
+            let mut jobs = lock(&self.jobs);
+            prune_finished(&mut jobs, Local::now());
+            jobs.insert(id.clone(), JobEntry { info: info.clone(), owner: owner.to_string() });
+        }
+        lock(&self.sender)
+            .send((id.clone(), input))
+            .map_err(|_| io::Error::other("工作佇列已關閉"))?;
+        log::info!("已加入轉換工作 #{}", id);
+        Ok(info)
+    }
+
+    /// 查詢工作資訊
+    /// # 參數
+    /// - id: 工作識別碼
+    /// - owner: 查詢者，與提交者不同時視為不存在
+    /// # 回傳
+    /// - 工作資訊，工作不存在、已超過保留時間或查詢者不是提交者時返回 None
+    pub fn get(&self, id: &str, owner: &str) -> Option<JobInfo> {
+        let mut jobs = lock(&self.jobs);
+        prune_finished(&mut jobs, Local::now());
+        let entry = jobs.get(id)?;
+        if entry.owner != owner {
+            log::warn!("拒絕查詢非請求者提交的工作：{}", id);
+            return None;
+        }
+        Some(entry.info.clone())
+    }
+
+    /// 尚未完成（排隊中或執行中）的工作數
+    pub fn pending(&self) -> usize {
+        lock(&self.jobs)
+            .values()
+            .filter(|job| matches!(job.info.status, JobStatus::Queued | JobStatus::Running))
+            .count()
+    }
+
+    /// 統計各狀態的工作數，依序為 queued、running、succeeded、failed；完成的工作只計入仍在保留時間內者
+    pub fn status_counts(&self) -> Vec<(&'static str, usize)> {
+        let mut jobs = lock(&self.jobs);
+        prune_finished(&mut jobs, Local::now());
+        [
+            ("queued", JobStatus::Queued),
+            ("running", JobStatus::Running),
+            ("succeeded", JobStatus::Succeeded),
+            ("failed", JobStatus::Failed),
+        ]
+        .into_iter()
+        .map(|(name, status)| (name, jobs.values().filter(|job| job.info.status == status).count()))
+        .collect()
+    }
+}
+
+/// 以固定數量的執行緒平行執行一批轉換，供 `--group-by` 一次產生多個封存頁面；
+/// 與工作佇列相同由各執行緒依序自共用的佇列取用工作，全部完成後才返回
+/// # 參數
+/// - inputs: 各轉換的輸入參數
+/// - workers: 執行緒數量，至少為 1，不超過轉換數
+/// - convert: 執行單一轉換
+/// - on_finished: 每個轉換完成時以輸入的索引與結果呼叫，用於彙整進度
+/// # 回傳
+/// - 依輸入順序排列的轉換結果
+pub fn run_batch<C, F>(inputs: Vec<ConversionInput>, workers: usize, convert: C, on_finished: F) -> Vec<io::Result<ConversionOutput>>
+where
+    C: Fn(ConversionInput) -> io::Result<ConversionOutput> + Sync,
+    F: Fn(usize, &io::Result<ConversionOutput>) + Sync,
+{
+    let count = inputs.len();
+    let (sender, receiver) = mpsc::channel();
+    for job in inputs.into_iter().enumerate() {
+        // 接收端在此仍存在，傳送不會失敗
+        let _ = sender.send(job);
+    }
+    drop(sender);
+    let receiver = Mutex::new(receiver);
+    let results: Mutex<Vec<Option<io::Result<ConversionOutput>>>> = Mutex::new((0..count).map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let next = lock(&receiver).recv();
+                let Ok((index, input)) = next else {
+                    return;
+                };
+                let result = convert(input);
+                on_finished(index, &result);
+                lock(&results)[index] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|| Err(io::Error::other("轉換未執行"))))
+        .collect()
+}
+
+// 移除完成時間超過保留時間的工作，排隊中與執行中的工作不受影響
+fn prune_finished(jobs: &mut HashMap<String, JobEntry>, now: DateTime<Local>) {
+    jobs.retain(|_, job| job.info.finished_at.is_none_or(|finished| now - finished < FINISHED_RETENTION));
+}
+
+fn run_worker(
+    facade: Arc<dyn ConversionFacadeTrait>,
+    receiver: Arc<Mutex<Receiver<(String, ConversionInput)>>>,
+    jobs: JobTable,
+) {
+    loop {
+        // 取得工作後立即釋放接收端的鎖，讓其他執行緒可同時取工作
+        let next = lock(&receiver).recv();
+        let Ok((id, input)) = next else {
+            return;
+        };
+        update(&jobs, &id, |job| job.status = JobStatus::Running);
+        log::info!("開始執行轉換工作 #{}：{}", id, input.input_path.display());
+        let result = facade.execute_conversion(input);
+        update(&jobs, &id, |job| {
+            job.finished_at = Some(Local::now());
+            match &result {
+                Ok(output) => {
+                    job.status = JobStatus::Succeeded;
+                    job.processed_files = output.processed_files;
+                    job.artifacts = output.artifacts.iter().map(|a| a.html.clone()).collect();
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(e.to_string());
+                }
+            }
+        });
+        match result {
+            Ok(_) => log::info!("轉換工作 #{} 完成", id),
+            Err(e) => log::error!("轉換工作 #{} 失敗：{}", id, e),
+        }
+    }
+}
+
+fn update<F: FnOnce(&mut JobInfo)>(jobs: &JobTable, id: &str, f: F) {
+    if let Some(job) = lock(jobs).get_mut(id) {
+        f(&mut job.info);
+    }
+}
+
+// 執行緒於持有鎖時崩潰不影響資料一致性，直接沿用內容
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ports::AppConfig;
+
+    struct FailingFacade;
+
+    impl ConversionFacadeTrait for FailingFacade {
+        fn execute_conversion(&self, _input: ConversionInput) -> io::Result<ConversionOutput> {
+            Err(io::Error::other("測試用的失敗"))
+        }
+    }
+
+    fn entry(status: JobStatus, finished_at: Option<DateTime<Local>>) -> JobEntry {
+        let mut info = JobInfo::new(random_id(JOB_ID_BYTES));
+        info.status = status;
+        info.finished_at = finished_at;
+        JobEntry { info, owner: "owner".to_string() }
+    }
+
+    #[test]
+    fn jobs_are_scoped_to_submitter() {
+        let queue = JobQueue::new(Arc::new(FailingFacade), 1);
+        let input = ConversionInput::from(&AppConfig::default());
+        let first = queue.submit(input.clone(), "alice").unwrap();
+        let second = queue.submit(input, "alice").unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.id.len(), JOB_ID_BYTES * 2);
+        assert!(first.id.chars().all(|c| c.is_ascii_hexdigit()));
+
+        assert!(queue.get(&first.id, "alice").is_some());
+        assert!(queue.get(&first.id, "bob").is_none());
+        assert!(queue.get("1", "alice").is_none());
+    }
+
+    #[test]
+    fn finished_jobs_are_pruned_after_retention() {
+        let now = Local::now();
+        let mut jobs: HashMap<String, JobEntry> = [
+            entry(JobStatus::Queued, None),
+            entry(JobStatus::Running, None),
+            entry(JobStatus::Succeeded, Some(now - TimeDelta::minutes(5))),
+            entry(JobStatus::Failed, Some(now - FINISHED_RETENTION - TimeDelta::minutes(1))),
+            entry(JobStatus::Succeeded, Some(now - FINISHED_RETENTION * 2)),
+        ]
+        .into_iter()
+        .map(|job| (job.info.id.clone(), job))
+        .collect();
+        prune_finished(&mut jobs, now);
+        let mut remaining: Vec<JobStatus> = jobs.values().map(|job| job.info.status).collect();
+        remaining.sort_by_key(|status| *status as u8);
+        assert_eq!(remaining, [JobStatus::Queued, JobStatus::Running, JobStatus::Succeeded]);
+    }
+
+    fn output(processed_files: usize) -> ConversionOutput {
+        ConversionOutput {
+            output_path: String::new(),
+            processed_files,
+            artifacts: Vec::new(),
+            index_path: None,
+            restore_path: None,
+            downgrades: Vec::new(),
+            single_page: None,
+            unmatched_patterns: Vec::new(),
+            missing_files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn batch_results_follow_input_order() {
+        let inputs: Vec<ConversionInput> = (0..6)
+            .map(|i| ConversionInput::from(&AppConfig { jobs: i + 1, ..AppConfig::default() }))
+            .collect();
+        let finished = Mutex::new(Vec::new());
+        let results = run_batch(
+            inputs,
+            3,
+            |input| {
+                // 先送出的工作較慢完成，結果仍依輸入順序排列
+                thread::sleep(std::time::Duration::from_millis(10 * (6 - input.jobs as u64)));
+                if input.jobs == 4 { Err(io::Error::other("測試用的失敗")) } else { Ok(output(input.jobs)) }
+            },
+            |index, result| lock(&finished).push((index, result.is_ok())),
+        );
+        let processed: Vec<Option<usize>> = results.iter().map(|result| result.as_ref().ok().map(|o| o.processed_files)).collect();
+        assert_eq!(processed, [Some(1), Some(2), Some(3), None, Some(5), Some(6)]);
+        let mut finished = finished.into_inner().unwrap();
+        finished.sort();
+        assert_eq!(finished, [(0, true), (1, true), (2, true), (3, false), (4, true), (5, true)]);
+    }
+
+    #[test]
+    fn empty_batch_returns_no_results() {
+        let results = run_batch(Vec::new(), 4, |_| Ok(output(0)), |_, _| panic!("不應有完成的工作"));
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use std::fmt::Write;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use crate::facade::traits::i_conversion::ConversionFacadeTrait;
+use crate::models::conversion::{ConversionInput, ConversionOutput};
+
+// 轉換耗時分佈的上限（秒）
+const DURATION_BUCKETS: [f64; 9] = [0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0];
+// 單次轉換輸入大小分佈的上限（位元組）
+const SIZE_BUCKETS: [f64; 7] = [
+    64.0 * 1024.0,
+    1024.0 * 1024.0,
+    10.0 * 1024.0 * 1024.0,
+    50.0 * 1024.0 * 1024.0,
+    100.0 * 1024.0 * 1024.0,
+    500.0 * 1024.0 * 1024.0,
+    1024.0 * 1024.0 * 1024.0,
+];
+
+// 固定區間的直方圖，輸出時轉為 Prometheus 的累計格式
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Histogram { bounds, counts: vec![0; bounds.len()], sum: 0.0, count: 0 }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(i) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.counts[i] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count);
+        let _ = writeln!(out, "{}_sum {}", name, self.sum);
+        let _ = writeln!(out, "{}_count {}", name, self.count);
+    }
+}
+
+// 轉換流程的統計數據，供伺服器與監看模式以 `/metrics` 輸出
+pub struct Metrics {
+    conversions_succeeded: AtomicU64,
+    conversions_failed: AtomicU64,
+    in_flight: AtomicU64,
+    processed_files: AtomicU64,
+    input_bytes: AtomicU64,
+    output_bytes: AtomicU64,
+    upload_bytes: AtomicU64,
+    last_success: AtomicU64,
+    last_failure: AtomicU64,
+    durations: Mutex<Histogram>,
+    input_sizes: Mutex<Histogram>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            conversions_succeeded: AtomicU64::new(0),
+            conversions_failed: AtomicU64::new(0),
+            in_flight: AtomicU64::new(0),
+            processed_files: AtomicU64::new(0),
+            input_bytes: AtomicU64::new(0),
+            output_bytes: AtomicU64::new(0),
+            upload_bytes: AtomicU64::new(0),
+            last_success: AtomicU64::new(0),
+            last_failure: AtomicU64::new(0),
+            durations: Mutex::new(Histogram::new(&DURATION_BUCKETS)),
+            input_sizes: Mutex::new(Histogram::new(&SIZE_BUCKETS)),
+        }
+    }
+
+    /// 記錄一次轉換的結果與耗時
+    pub fn record_conversion(&self, result: &io::Result<ConversionOutput>, seconds: f64) {
+        match result {
+            Ok(output) => {
+                let input: usize = output.artifacts.iter().map(|a| a.source_size).sum();
+                let html: usize = output.artifacts.iter().map(|a| a.html_size).sum();
+                self.conversions_succeeded.fetch_add(1, Ordering::Relaxed);
+                self.processed_files.fetch_add(output.processed_files as u64, Ordering::Relaxed);
+                self.input_bytes.fetch_add(input as u64, Ordering::Relaxed);
+                self.output_bytes.fetch_add(html as u64, Ordering::Relaxed);
+                self.last_success.store(unix_now(), Ordering::Relaxed);
+                lock(&self.input_sizes).observe(input as f64);
+            }
+            Err(_) => {
+                self.conversions_failed.fetch_add(1, Ordering::Relaxed);
+                self.last_failure.store(unix_now(), Ordering::Relaxed);
+            }
+        }
+        lock(&self.durations).observe(seconds);
+    }
+
+    /// 記錄伺服器接收的上傳量
+    pub fn record_upload(&self, bytes: u64) {
+        self.upload_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// 以 Prometheus 文字格式輸出所有指標
+    /// # 參數
+    /// - jobs: 工作佇列中各狀態的工作數，未使用佇列時為空
+    pub fn render(&self, jobs: &[(&str, usize)]) -> String {
+        let mut out = String::new();
+        let single = |out: &mut String, name: &str, help: &str, kind: &str, value: u64| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        };
+        let _ = writeln!(out, "# HELP file_to_html_conversions_total 依結果統計的轉換次數");
+        let _ = writeln!(out, "# TYPE file_to_html_conversions_total counter");
+        let _ = writeln!(out, "file_to_html_conversions_total{{result=\"succeeded\"}} {}", self.conversions_succeeded.load(Ordering::Relaxed));
+        let _ = writeln!(out, "file_to_html_conversions_total{{result=\"failed\"}} {}", self.conversions_failed.load(Ordering::Relaxed));
+        single(&mut out, "file_to_html_conversions_in_flight", "執行中的轉換數", "gauge", self.in_flight.load(Ordering::Relaxed));
+        single(&mut out, "file_to_html_processed_files_total", "已轉換的來源檔案數", "counter", self.processed_files.load(Ordering::Relaxed));
+        single(&mut out, "file_to_html_input_bytes_total", "已轉換的來源位元組數", "counter", self.input_bytes.load(Ordering::Relaxed));
+        single(&mut out, "file_to_html_output_bytes_total", "產出的位元組數", "counter", self.output_bytes.load(Ordering::Relaxed));
+        single(&mut out, "file_to_html_upload_bytes_total", "上傳接收的位元組數", "counter", self.upload_bytes.load(Ordering::Relaxed));
+        single(&mut out, "file_to_html_last_success_timestamp_seconds", "最後一次轉換成功的 Unix 時間", "gauge", self.last_success.load(Ordering::Relaxed));
+        single(&mut out, "file_to_html_last_failure_timestamp_seconds", "最後一次轉換失敗的 Unix 時間", "gauge", self.last_failure.load(Ordering::Relaxed));
+        if !jobs.is_empty() {
+            let _ = writeln!(out, "# HELP file_to_html_jobs 工作佇列中各狀態的工作數");
+            let _ = writeln!(out, "# TYPE file_to_html_jobs gauge");
+            for (status, count) in jobs {
+                let _ = writeln!(out, "file_to_html_jobs{{status=\"{}\"}} {}", status, count);
+            }
+        }
+        lock(&self.durations).render(&mut out, "file_to_html_conversion_duration_seconds", "轉換耗時（秒）");
+        lock(&self.input_sizes).render(&mut out, "file_to_html_conversion_input_bytes", "每次成功轉換的來源位元組數");
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 在轉換前後記錄統計數據的 Facade 裝飾器
+pub struct MeteredFacade {
+    inner: Arc<dyn ConversionFacadeTrait>,
+    metrics: Arc<Metrics>,
+}
+
+impl MeteredFacade {
+    pub fn new(inner: Arc<dyn ConversionFacadeTrait>, metrics: Arc<Metrics>) -> Self {
+        MeteredFacade { inner, metrics }
+    }
+}
+
+impl ConversionFacadeTrait for MeteredFacade {
+    fn execute_conversion(&self, input: ConversionInput) -> io::Result<ConversionOutput> {
+        self.metrics.in_flight.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+        let result = self.inner.execute_conversion(input);
+        self.metrics.record_conversion(&result, started.elapsed().as_secs_f64());
+        self.metrics.in_flight.fetch_sub(1, Ordering::Relaxed);
+        result
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
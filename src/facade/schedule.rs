@@ -0,0 +1,37 @@
+/// 依檔案大小安排個別模式的平行轉換順序，避免少數大型檔案拖到最後才開始而只剩一條執行緒在工作
+///
+/// 第一波讓每個工作執行緒各取一個最大的檔案；之後每波放入一個剩餘最大的檔案，
+/// 再放入 `workers - 1` 個剩餘最小的檔案，讓其他執行緒在大型檔案處理期間消化小型檔案
+/// # 參數
+/// - sizes: 每個檔案的大小，順序與蒐集順序相同
+/// - workers: 平行轉換的執行緒數
+/// # 回傳
+/// - 依處理順序排列的檔案索引；只有一個執行緒時維持蒐集順序
+pub fn wave_order(sizes: &[usize], workers: usize) -> Vec<usize> {
+    if workers <= 1 {
+        return (0..sizes.len()).collect();
+    }
+    let mut by_size: Vec<usize> = (0..sizes.len()).collect();
+    // 大小相同時維持蒐集順序，讓排程結果可重現
+    by_size.sort_by(|a, b| sizes[*b].cmp(&sizes[*a]).then(a.cmp(b)));
+
+    let mut order = Vec::with_capacity(sizes.len());
+    let mut largest = 0;
+    let mut smallest = by_size.len();
+    while largest < smallest && order.len() < workers {
+        order.push(by_size[largest]);
+        largest += 1;
+    }
+    while largest < smallest {
+        order.push(by_size[largest]);
+        largest += 1;
+        for _ in 1..workers {
+            if largest >= smallest {
+                break;
+            }
+            smallest -= 1;
+            order.push(by_size[smallest]);
+        }
+    }
+    order
+}
@@ -0,0 +1,39 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::models::conversion::GeneratedArtifact;
+use crate::models::zip::ZipCompressOutput;
+
+// 轉換流程觀察者接口，由 Facade 在每個檔案與每個壓縮檔前後呼叫
+// 所有方法皆有預設的空實作，僅需覆寫關心的事件；回傳錯誤會中止轉換
+pub trait ConversionObserver: Send + Sync {
+    /// 個別模式下處理單一檔案之前呼叫
+    /// # 參數
+    /// - file: 即將處理的來源檔案
+    fn before_file(&self, _file: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// 個別模式下單一檔案的 HTML 生成完成後呼叫
+    /// # 參數
+    /// - file: 已處理的來源檔案
+    /// - artifact: 該檔案的產出物
+    fn after_file(&self, _file: &Path, _artifact: &GeneratedArtifact) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// 建立壓縮檔之前呼叫（個別模式每個檔案一次，壓縮模式整體一次）
+    /// # 參數
+    /// - source: 壓縮來源路徑
+    /// - files: 將放入壓縮檔的檔案
+    fn before_archive(&self, _source: &Path, _files: &[PathBuf]) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// 壓縮檔建立完成後呼叫
+    /// # 參數
+    /// - source: 壓縮來源路徑
+    /// - output: 壓縮結果
+    fn after_archive(&self, _source: &Path, _output: &ZipCompressOutput) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -4,32 +4,79 @@ pub mod service {
     pub mod html;
     pub mod zip;
     pub mod config_service;
+    pub mod crypto;
+    pub mod plugin;
+    pub mod sink;
+    pub mod i18n;
+    pub mod encoding;
+    pub mod gzip;
+    pub mod format;
+    pub mod schema;
+    pub mod transcode;
+    pub mod line_ending;
+    pub mod tag;
+    pub mod rule;
+    pub mod classification;
+    pub mod file_type;
+    pub mod verify;
+    pub mod job_file;
+    pub mod change;
+    pub mod pwa;
+    pub mod spill;
+    pub mod a11y;
+    pub mod prompt;
+    pub mod clipboard;
+    pub mod stats;
+    #[cfg(feature = "minify")]
+    pub mod minify;
     pub mod traits {
+        pub mod i_plugin;
         pub mod i_service;
+        pub mod i_sink;
+        pub mod i_encoder;
+        pub mod i_prompt;
     }
 }
 
 pub mod config {
+    #[allow(clippy::module_inception)]
     pub mod config;
     pub mod ports;
 }
 
 pub mod action {
     pub mod cli;
+    pub mod decoder;
+    pub mod doctor;
     pub mod interactive;
+    pub mod keys;
+    pub mod multipart;
+    pub mod page;
+    pub mod server;
+    pub mod stats;
+    pub mod watch;
 }
 
 pub mod utils {
+    #[allow(clippy::module_inception)]
     pub mod utils;
+    pub mod daemon;
+    pub mod locale;
+    pub mod log_sampler;
+    pub mod random;
 }
 
 pub mod facade {
     pub mod conversion_facade;
+    pub mod job_queue;
+    pub mod metrics;
+    pub mod schedule;
     pub mod ports {
         pub mod facade_ports;
     }
     pub mod traits {
         pub mod i_conversion;
+        pub mod i_observer;
     }
 }
 
@@ -38,4 +85,6 @@ pub mod models {
     pub mod file;
     pub mod zip;
     pub mod html;
+    pub mod report;
+    pub mod job;
 }
\ No newline at end of file
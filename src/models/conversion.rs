@@ -1,4 +1,10 @@
 use crate::config::config::{PasswordMode};
+use crate::config::ports::AppConfig;
+use crate::models::file::UnmatchedPattern;
+use crate::service::job_file::JobFileEntry;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Clone)]
@@ -10,15 +16,246 @@ pub struct ConversionInput {
     pub include: Vec<String>,
     pub exclude: Option<Vec<String>>,
     pub password_mode: PasswordMode,
+    pub inner_password_mode: Option<PasswordMode>,
+    pub outer_password_mode: Option<PasswordMode>,
     pub display_password: bool,
+    pub password_qr: bool,
     pub layer: String,
     pub encryption_method: String,
     pub no_progress: bool,
     pub max_size: Option<f64>,
+    pub split_size: Option<f64>,
+    pub view: String,
+    pub index: bool,
+    pub format: String,
+    pub lang: String,
+    pub extra_css: Option<String>,
+    pub extra_js: Option<String>,
+    pub key_name: String,
+    pub index_name: String,
+    pub title: Option<String>,
+    pub logo: Option<String>,
+    pub footer: Option<String>,
+    pub message: Option<String>,
+    pub output_extension: String,
+    pub expires: Option<String>,
+    pub restore: bool,
+    pub payload_encoding: String,
+    pub compression_method: String,
+    pub force_method: bool,
+    pub compression_level: u8,
+    pub payload_compression: String,
+    pub payload_compression_level: Option<i32>,
+    pub max_html_size: f64,
+    pub oversize_policy: String,
+    pub password_verifier: bool,
+    pub payload_mode: String,
+    pub format_version: u32,
+    pub minify: bool,
+    pub jobs: usize,
+    // `--group-by dir` 時輸入目錄下的每個子目錄各產生一個封存頁面
+    pub group_by: Option<String>,
+    pub low_memory: bool,
+    pub slices: Vec<String>,
+    pub key_sheet: bool,
+    pub password_hint: Option<String>,
+    pub allow_weak_password: bool,
+    pub transcode_text: Option<String>,
+    pub keep_original_text: bool,
+    pub target_size: Option<String>,
+    pub line_endings: Option<String>,
+    pub tags: Vec<String>,
+    pub tag_rules: Vec<String>,
+    pub classification: Option<String>,
+    pub auto_downgrade: bool,
+    pub verify_output: bool,
+    pub ascii_names: bool,
+    pub report: Option<String>,
+    pub export_job: Option<String>,
+    // 匯入工作檔時應蒐集到的檔案，不一致時中止轉換
+    pub job_files: Option<Vec<JobFileEntry>>,
+    // 手動密碼模式下已取得的密碼，None 時轉換時再詢問
+    pub preset_password: Option<String>,
+    pub pwa: bool,
+    pub viewer: bool,
+    pub spill_to_disk: bool,
+    pub no_js: bool,
+    pub data_uri_threshold: String,
+    pub manifest: bool,
+    pub wrap_base64: bool,
+    pub archive_root: Option<String>,
+    pub strip_prefix: bool,
+    pub copy_to_clipboard: Option<String>,
+    pub volume_size: Option<String>,
+    pub stats_file: Option<String>,
+    pub comment: Option<String>,
+    pub passthrough: bool,
+    pub a11y_strict: bool,
+    pub key_file_mode: String,
+    pub archive_format: String,
+    pub ignore_case: bool,
+    pub match_on: String,
+    pub rules: Vec<String>,
+    pub on_change: String,
+    pub on_missing: String,
+    pub symlinks: String,
+    pub preserve_metadata: bool,
+}
+
+impl From<&AppConfig> for ConversionInput {
+    fn from(config: &AppConfig) -> Self {
+        ConversionInput {
+            input_path: PathBuf::from(&config.input),
+            output_dir: config.output.clone(),
+            is_compressed: config.is_compressed,
+            compress: config.compress,
+            include: config.include.clone(),
+            exclude: config.exclude.clone(),
+            password_mode: config.password_mode.clone(),
+            inner_password_mode: config.inner_password_mode.clone(),
+            outer_password_mode: config.outer_password_mode.clone(),
+            display_password: config.display_password,
+            password_qr: config.password_qr,
+            layer: config.layer.clone(),
+            encryption_method: config.encryption_method.clone(),
+            no_progress: config.no_progress,
+            max_size: config.max_size,
+            split_size: config.split_size,
+            view: config.view.clone(),
+            index: config.index,
+            format: config.format.clone(),
+            lang: config.lang.clone(),
+            extra_css: config.extra_css.clone(),
+            extra_js: config.extra_js.clone(),
+            key_name: config.key_name.clone(),
+            index_name: config.index_name.clone(),
+            title: config.title.clone(),
+            logo: config.logo.clone(),
+            footer: config.footer.clone(),
+            message: config.message.clone(),
+            output_extension: config.output_extension.clone(),
+            expires: config.expires.clone(),
+            restore: config.restore,
+            payload_encoding: config.payload_encoding.clone(),
+            compression_method: config.compression_method.clone(),
+            force_method: config.force_method,
+            compression_level: config.compression_level,
+            payload_compression: config.payload_compression.clone(),
+            payload_compression_level: config.payload_compression_level,
+            max_html_size: config.max_html_size,
+            oversize_policy: config.oversize_policy.clone(),
+            password_verifier: config.password_verifier,
+            payload_mode: config.payload_mode.clone(),
+            format_version: config.format_version,
+            minify: config.minify,
+            jobs: config.jobs,
+            group_by: config.group_by.clone(),
+            low_memory: config.low_memory,
+            slices: config.slices.clone(),
+            key_sheet: config.key_sheet,
+            password_hint: config.password_hint.clone(),
+            allow_weak_password: config.allow_weak_password,
+            transcode_text: config.transcode_text.clone(),
+            keep_original_text: config.keep_original_text,
+            target_size: config.target_size.clone(),
+            line_endings: config.line_endings.clone(),
+            tags: config.tags.clone(),
+            tag_rules: config.tag_rules.clone(),
+            classification: config.classification.clone(),
+            auto_downgrade: config.auto_downgrade,
+            verify_output: config.verify_output,
+            ascii_names: config.ascii_names,
+            report: config.report.clone(),
+            export_job: config.export_job.clone(),
+            job_files: config.job_files.clone(),
+            preset_password: config.preset_password.clone(),
+            pwa: config.pwa,
+            viewer: config.viewer,
+            spill_to_disk: config.spill_to_disk,
+            no_js: config.no_js,
+            data_uri_threshold: config.data_uri_threshold.clone(),
+            manifest: config.manifest,
+            wrap_base64: config.wrap_base64,
+            archive_root: config.archive_root.clone(),
+            strip_prefix: config.strip_prefix,
+            copy_to_clipboard: config.copy_to_clipboard.clone(),
+            volume_size: config.volume_size.clone(),
+            stats_file: config.stats_file.clone(),
+            comment: config.comment.clone(),
+            passthrough: config.passthrough,
+            a11y_strict: config.a11y_strict,
+            key_file_mode: config.key_file_mode.clone(),
+            archive_format: config.archive_format.clone(),
+            ignore_case: config.ignore_case,
+            match_on: config.match_on.clone(),
+            rules: config.rules.clone(),
+            on_change: config.on_change.clone(),
+            on_missing: config.on_missing.clone(),
+            symlinks: config.symlinks.clone(),
+            preserve_metadata: config.preserve_metadata,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ConversionOutput {
     pub output_path: String,
     pub processed_files: usize,
+    pub artifacts: Vec<GeneratedArtifact>,
+    pub index_path: Option<String>,
+    pub restore_path: Option<String>,
+    // 啟用 --auto-downgrade 時為了完成轉換而調整的設定，依調整順序排列
+    pub downgrades: Vec<String>,
+    // 只產生一個可直接以瀏覽器開啟的 HTML 頁面時為該頁面，供 --open 使用
+    pub single_page: Option<String>,
+    // 沒有符合任何檔案的 include 模式，於轉換結束時列出
+    pub unmatched_patterns: Vec<UnmatchedPattern>,
+    // 蒐集後已被刪除而略過的檔案，於轉換結束時列出
+    pub missing_files: Vec<PathBuf>,
+}
+
+// 單一 HTML 產出物及其對應的密碼檔與來源
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedArtifact {
+    pub html: String,
+    pub key: Option<String>,
+    // 可列印的密碼單，未指定 --key-sheet 時為 None
+    pub key_sheet: Option<String>,
+    pub source: PathBuf,
+    pub source_size: usize,
+    pub html_size: usize,
+    // 分段輸出時的所有分段頁面，未分段時為空
+    pub parts: Vec<String>,
+    // 產出頁面內容的 SHA-256，未分段時為單一頁面，分段時依分段順序排列
+    pub page_sha256: Vec<String>,
+    // 外部資料檔模式下另存的資料檔，資料嵌入頁面時為 None
+    pub payload_file: Option<String>,
+    // `--tag` 與 `--tag-rule` 標示的自訂標籤
+    pub tags: Tags,
+    pub generated_at: DateTime<Local>,
+}
+
+impl GeneratedArtifact {
+    /// 產出物寫出的所有檔案：頁面（分段時為所有分段）、密碼檔、密碼單與外部資料檔
+    pub fn output_locations(&self) -> Vec<String> {
+        let pages = if self.parts.is_empty() { std::slice::from_ref(&self.html) } else { self.parts.as_slice() };
+        pages
+            .iter()
+            .chain(&self.key)
+            .chain(&self.key_sheet)
+            .chain(&self.payload_file)
+            .cloned()
+            .collect()
+    }
+}
+
+/// 自訂標籤（鍵、值），依鍵排序以維持輸出順序穩定
+pub type Tags = BTreeMap<String, String>;
+
+// `--tag-rule` 指定的依路徑套用標籤規則，模式語法與 include/exclude 相同
+#[derive(Debug, Clone)]
+pub struct TagRule {
+    pub pattern: String,
+    pub key: String,
+    pub value: String,
 }
\ No newline at end of file
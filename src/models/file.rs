@@ -1,16 +1,80 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
+use crate::models::zip::ByteRange;
+use crate::service::change::FileSnapshot;
 
 #[derive(Clone)]
 pub struct FileCollectInput {
     pub input_path: PathBuf,
     pub include_patterns: Vec<String>,
     pub exclude_patterns: Option<Vec<String>>,
+    // 模式是否不分大小寫
+    pub ignore_case: bool,
+    // 模式的比對對象：`name` 以模式完整比對檔名，`path` 只需出現在完整路徑中的任一位置
+    pub match_on: String,
     pub max_size: Option<f64>,
+    // `--rule` 設定，依序比對，第一條符合的規則決定是否保留檔案
+    pub rules: Vec<String>,
     pub no_progress: bool,
+    // `--slice` 設定，格式為 `路徑:位移:長度`
+    pub slices: Vec<String>,
+    // 符號連結的處理方式：`follow` 讀取連結目標，`store` 保存連結本身，`skip` 略過
+    pub symlinks: String,
 }
 
 #[derive(Debug)]
 pub struct FileCollectOutput {
     pub files: Vec<PathBuf>,
+    // 每個檔案的大小，順序與 files 相同，供個別模式安排平行轉換的順序
+    pub sizes: Vec<usize>,
     pub total_size: usize,
-}
\ No newline at end of file
+    // 只嵌入部分內容的檔案及其位元組範圍，sizes 與 total_size 已改以範圍長度計算
+    pub ranges: HashMap<PathBuf, ByteRange>,
+    // 蒐集時各檔案的狀態，寫入封存檔時再次比對以偵測來源變更
+    pub snapshots: HashMap<PathBuf, FileSnapshot>,
+    // 沒有符合任何檔案的 include 模式
+    pub unmatched_patterns: Vec<UnmatchedPattern>,
+}
+
+// 單一 include 模式在蒐集檔案時的比對結果，順序與模式相同
+#[derive(Debug, Clone, Default)]
+pub struct PatternMatchCount {
+    pub matches: usize,
+    // 不分大小寫時符合的檔案數
+    pub case_insensitive_matches: usize,
+    // 不分大小寫時符合的其中一個檔案，用於推測正確的大小寫
+    pub case_insensitive_example: Option<PathBuf>,
+}
+
+// 沒有符合任何檔案的 include 模式，以及不分大小寫時的相近結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmatchedPattern {
+    pub pattern: String,
+    pub case_insensitive_matches: usize,
+    // 依不分大小寫時符合的檔案推測的模式，無法推測時為 None
+    pub suggestion: Option<String>,
+}
+
+impl UnmatchedPattern {
+    /// 給使用者的說明，不分大小寫時有符合的檔案則一併提示
+    pub fn describe(&self) -> String {
+        match (self.case_insensitive_matches, &self.suggestion) {
+            (0, _) => format!("include 模式 '{}' 沒有符合任何檔案", self.pattern),
+            (count, Some(suggestion)) => format!(
+                "include 模式 '{}' 沒有符合任何檔案；不分大小寫時有 {} 個檔案符合，是否應為 '{}'（或加上 --ignore-case）？",
+                self.pattern, count, suggestion
+            ),
+            (count, None) => format!(
+                "include 模式 '{}' 沒有符合任何檔案；不分大小寫時有 {} 個檔案符合，請確認大小寫或加上 --ignore-case",
+                self.pattern, count
+            ),
+        }
+    }
+}
+// `--slice` 指定只嵌入部分內容的檔案，位移為負數時自檔案結尾往前計算
+#[derive(Debug, Clone)]
+pub struct FileSlice {
+    pub path: PathBuf,
+    pub offset: i64,
+    pub len: u64,
+}
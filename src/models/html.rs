@@ -1,17 +1,294 @@
 use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::config::ports::AppConfig;
+use crate::models::conversion::Tags;
+use crate::models::zip::{ByteRange, ZipEntryInfo};
+use crate::service::spill::SpillFile;
 
 #[derive(Clone)]
 pub struct HtmlGenerateInput {
+    // 啟用 --spill-to-disk 時為空，封存檔位於 spill 指向的暫存檔
     pub zip_buffer: Vec<u8>,
+    pub spill: Option<SpillFile>,
     pub input_path: PathBuf,
     pub output_dir: String,
     pub layer: String,
+    pub encryption_method: String,
+    // 頁面密碼，雙層壓縮時為外層 ZIP 的密碼
     pub password: Option<String>,
+    // 雙層壓縮時內層 ZIP 的密碼，與頁面密碼一併顯示或寫入密碼檔
+    pub inner_password: Option<String>,
     pub display_password: bool,
+    pub password_qr: bool,
     pub total_size: usize,
+    pub view: String,
+    pub entries: Vec<ZipEntryInfo>,
+    pub format: String,
+    pub split_size: Option<usize>,
+    pub lang: String,
+    pub extra_css: Option<String>,
+    pub extra_js: Option<String>,
+    pub key_name: String,
+    pub title: Option<String>,
+    pub logo: Option<String>,
+    pub footer: Option<String>,
+    pub message: Option<String>,
+    pub data_uri_threshold: usize,
+    pub wrap_base64: bool,
+    pub output_extension: String,
+    pub expires: Option<String>,
+    pub payload_encoding: String,
+    pub payload_compression: String,
+    // 整體壓縮等級，未指定時依壓縮方式使用預設等級
+    pub payload_compression_level: Option<i32>,
+    pub max_html_size: usize,
+    pub oversize_policy: String,
+    pub password_verifier: bool,
+    pub payload_mode: String,
+    pub minify: bool,
+    pub low_memory: bool,
+    pub password_hint: Option<String>,
+    pub ascii_names: bool,
+    pub pwa: bool,
+    // 產生不含腳本的頁面，以資料連結提供下載，過大時改為手動還原的 Base64 文字
+    pub no_js: bool,
+    pub a11y_strict: bool,
+    pub archive_format: String,
+    pub key_file_mode: u32,
+    // 每個頁面的目標大小上限（位元組），設定時自動選擇編碼並分段
+    pub target_size: Option<usize>,
+    // 頁面的分級（`confidential`、`internal`、`public`），顯示為橫幅與浮水印並記錄於轉換資訊中
+    pub classification: Option<String>,
+    pub key_sheet: bool,
+    // 只包含部分內容的檔案（ZIP 項目名稱與位元組範圍），於頁面說明中列出
+    pub slices: Vec<(String, ByteRange)>,
+    // 頁面的自訂標籤，記錄於轉換資訊中
+    pub tags: Tags,
+    pub format_version: u32,
+}
+
+/// `HtmlService::render` 嵌入的封存檔資訊，封存檔由呼叫端自行建立
+#[derive(Debug, Clone)]
+pub struct ArchiveMeta {
+    /// 來源名稱，顯示於頁面；下載檔名為名稱加上封存格式的副檔名（如 `report` → `report.zip`），封存格式為 none 時即為下載檔名
+    pub name: String,
+    /// 封存格式：zip、tar、tar.gz、7z 或 none（資料即為檔案本身）
+    pub archive_format: String,
+    /// 封存前的資料大小（位元組），顯示於頁面
+    pub source_size: usize,
+    /// ZIP 的項目清單，樹狀檢視依各項目的位移讀取，其他檢視可為空
+    pub entries: Vec<ZipEntryInfo>,
+}
+
+impl ArchiveMeta {
+    /// # 參數
+    /// - name: 來源名稱
+    /// - archive_format: 封存格式
+    /// - source_size: 封存前的資料大小（位元組）
+    pub fn new(name: &str, archive_format: &str, source_size: usize) -> Self {
+        ArchiveMeta {
+            name: name.to_string(),
+            archive_format: archive_format.to_string(),
+            source_size,
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// `HtmlService::render` 的頁面選項，預設值與命令列相同
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// 輸出目錄，需先建立；以 MemorySink 輸出時不寫入檔案
+    pub output_dir: String,
+    /// 頁面語言，同 `--lang`
+    pub lang: String,
+    /// 頁面標題，同 `--title`
+    pub title: Option<String>,
+    /// 頁面上方的訊息，同 `--message`
+    pub message: Option<String>,
+    /// 頁尾文字，同 `--footer`
+    pub footer: Option<String>,
+    /// 檢視方式：`archive` 或 `tree`
+    pub view: String,
+    /// 頁面資料編碼：`base64` 或 `base85`
+    pub payload_encoding: String,
+    /// 頁面資料的整體壓縮，設定密碼時忽略
+    pub payload_compression: String,
+    /// 瀏覽器端解密（webcrypto、webcrypto-chunked）時以此密碼加密封存檔；
+    /// ZIP 的 AES 加密（aes128、aes192、aes256）時封存檔需已由呼叫端以此密碼與相同強度加密，
+    /// 頁面只負責說明與保存密碼，`HtmlService::render` 會確認封存檔已加密且密碼正確
+    pub password: Option<String>,
+    /// 加密方式，同 `--encryption-method`，未設定密碼時不使用
+    pub encryption_method: String,
+    /// 是否在頁面顯示密碼
+    pub display_password: bool,
+    /// 每個分段頁面的資料大小上限（位元組），None 時不分段
+    pub split_size: Option<usize>,
+    /// 產生不含 JavaScript 的頁面，同 `--no-js`
+    pub no_js: bool,
+    /// 最小化頁面，需以 `minify` 功能編譯
+    pub minify: bool,
+    /// 分級標示，同 `--classification`
+    pub classification: Option<String>,
+    /// 記錄於轉換資訊的自訂標籤
+    pub tags: Tags,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        let config = AppConfig::default();
+        RenderOptions {
+            output_dir: config.output,
+            lang: config.lang,
+            title: config.title,
+            message: config.message,
+            footer: config.footer,
+            view: config.view,
+            payload_encoding: config.payload_encoding,
+            payload_compression: config.payload_compression,
+            password: None,
+            encryption_method: config.encryption_method,
+            display_password: config.display_password,
+            split_size: None,
+            no_js: config.no_js,
+            minify: config.minify,
+            classification: config.classification,
+            tags: Tags::new(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct HtmlGenerateOutput {
     pub html_file_path: String,
+    pub key_file_path: Option<String>,
+    // 另外產生的可列印密碼單路徑
+    pub key_sheet_path: Option<String>,
+    pub html_size: usize,
+    pub parts: Vec<String>,
+    // 產出頁面內容的 SHA-256，分段時依分段順序排列
+    pub page_sha256: Vec<String>,
+    // 外部資料檔模式下另存的資料檔路徑
+    pub payload_file_path: Option<String>,
+}
+
+// 嵌入頁面的轉換資訊，記錄頁面的產生方式，供收件者與檢查工具讀取
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionMetadata {
+    pub format_version: u32,
+    pub tool: String,
+    pub tool_version: String,
+    // 產生時間（RFC 3339）
+    pub created_at: String,
+    pub layer: String,
+    // 封存格式，ZIP 時省略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_format: Option<String>,
+    // 未設定密碼時為 `none`
+    pub encryption_method: String,
+    // 來源路徑的 SHA-256，只記錄雜湊以免洩漏目錄結構
+    pub source_path_sha256: String,
+    // `--tag` 與 `--tag-rule` 標示的自訂標籤，未標示時省略
+    #[serde(default, skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+    // `--classification` 指定的分級，未指定時省略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<String>,
+}
+
+// 密碼的呈現方式
+pub struct PasswordDisplayOptions<'a> {
+    pub display_password: bool,
+    pub password_qr: bool,
+    // 密碼寫入密碼檔時是否一併產生可列印的密碼單
+    pub key_sheet: bool,
+    pub key_file: &'a str,
+    // 雙層壓縮時內層 ZIP 的密碼，與外層密碼分別標示
+    pub inner_password: Option<&'a str>,
+    // 密碼檔與密碼單的權限位元
+    pub key_file_mode: u32,
+    // 密碼單上標示的檔案與頁面名稱
+    pub file_name: &'a str,
+    pub page_name: &'a str,
+}
+
+// 密碼的呈現結果：頁面中的密碼提示與顯示區塊，以及另外寫出的密碼檔與密碼單
+pub struct PasswordDisplay {
+    pub info: String,
+    pub display: String,
+    // 密碼檔與密碼單在輸出批次中的序號，未產生時為 None
+    pub key_file: Option<usize>,
+    pub key_sheet: Option<usize>,
+}
+
+// 暫存頁面在輸出批次中的序號，以及實際寫出內容（最小化後）的大小與 SHA-256
+pub struct StagedPage {
+    pub index: usize,
+    pub size: usize,
+    pub sha256: String,
+}
+
+// 模板佔位符對應的內容，亦作為輸出渲染器的輸入
+pub struct HtmlTemplateData<'a> {
+    // 編碼後的完整資料，編碼方式見 payload_encoding
+    pub zip_base64: &'a str,
+    // 以多個 script 標籤嵌入的 Base64 分塊
+    pub payload_chunks: &'a str,
+    pub file_name: &'a str,
+    pub download_zip_name: &'a str,
+    pub instructions: &'a str,
+    pub file_size_str: &'a str,
+    pub password_info: &'a str,
+    pub password_display: &'a str,
+    // 密碼輸入區旁的提示段落，未指定提示或未加密時為空字串
+    pub password_hint: &'a str,
+    pub manifest_json: &'a str,
+    pub browser_decrypt: bool,
+    // 是否以分塊 AES-GCM 加密，頁面腳本可逐塊解密並邊解密邊下載
+    pub chunked_encryption: bool,
+    // 頁面資料的編碼名稱，如 `base64`、`base85`
+    pub payload_encoding: &'a str,
+    // 頁面資料的整體壓縮方式，`none`、`gzip` 或 `zstd`
+    pub payload_compression: &'a str,
+    // 是否附上 zstd 解碼器，僅樹狀檢視需在頁面中完整還原 zstd 壓縮的資料
+    pub zstd_decoder: bool,
+    // 壓縮層級與 ZIP 本身是否以 AES 加密，供頁面腳本顯示對應平台的解壓說明
+    pub layer: &'a str,
+    pub zip_encrypted: bool,
+    // 封存格式，頁面腳本依此顯示 tar 或 ZIP 的解壓說明
+    pub archive_format: &'a str,
+    pub payload_sha256: &'a str,
+    // 頁面文字使用的語言代碼
+    pub lang: &'a str,
+    // 自訂樣式與腳本，已包成 style/script 標籤，未指定時為空字串
+    pub extra_css: &'a str,
+    pub extra_js: &'a str,
+    // 品牌資訊：頁面標題、已包成 img 標籤的標誌與頁尾，未指定標誌或頁尾時為空字串
+    pub title: &'a str,
+    pub logo: &'a str,
+    pub footer: &'a str,
+    // 已包成區塊的寄件人留言，未指定時為空字串
+    pub message: &'a str,
+    // 頁面副檔名（不含點）
+    pub output_extension: &'a str,
+    // 到期時間（Unix 毫秒），未設定時為 `null`
+    pub expires_at: &'a str,
+    // 頁面的分級，未指定時不加標示
+    pub classification: Option<&'a str>,
+    // 來源的檔案類型（見 file_type::file_kind），決定頁面的圖示與強調色
+    pub file_kind: &'a str,
+    // 密碼的 Argon2id 驗證值（PHC 格式），未啟用時為空字串
+    pub password_verifier: &'a str,
+    // 外部資料檔的檔名，資料嵌入頁面時為空字串
+    pub payload_file: &'a str,
+    // 輸出格式版本，決定頁面是否標示版本
+    pub format_version: u32,
+    // 已包成註解的轉換資訊區塊，格式版本 1 時為空字串
+    pub conversion_metadata: &'a str,
+    // 離線安裝的 Manifest 連結與 Service Worker 註冊腳本，未啟用時為空字串
+    pub pwa_head: &'a str,
+    // 小型資料的資料連結下載按鈕，未啟用或超過 --data-uri-threshold 時為空字串
+    pub direct_download: &'a str,
+    // 複製與另存的 Base64 文字每行字元數（`--wrap-base64`），0 表示不換行
+    pub payload_line_width: usize,
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+// 轉換工作的狀態
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+// 轉換工作的對外資訊，作為伺服器模式的回應內容
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    // 隨機產生的十六進位識別碼
+    pub id: String,
+    pub status: JobStatus,
+    pub created_at: DateTime<Local>,
+    pub finished_at: Option<DateTime<Local>>,
+    pub error: Option<String>,
+    pub processed_files: usize,
+    // 產出的 HTML 路徑
+    pub artifacts: Vec<String>,
+}
+
+impl JobInfo {
+    pub fn new(id: String) -> Self {
+        JobInfo {
+            id,
+            status: JobStatus::Queued,
+            created_at: Local::now(),
+            finished_at: None,
+            error: None,
+            processed_files: 0,
+            artifacts: Vec::new(),
+        }
+    }
+}
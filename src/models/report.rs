@@ -0,0 +1,96 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::models::conversion::GeneratedArtifact;
+
+// 單次轉換過程中蒐集的報告，用於產生索引頁等彙整輸出
+#[derive(Debug, Clone)]
+pub struct ConversionReport {
+    pub input_path: PathBuf,
+    pub output_dir: String,
+    pub lang: String,
+    // 索引頁的命名樣式
+    pub index_name: String,
+    // 產出頁面的副檔名
+    pub output_extension: String,
+    // 索引頁與還原頁標示的輸出格式版本
+    pub format_version: u32,
+    // 索引頁標示的分級，未指定時不加標示
+    pub classification: Option<String>,
+    pub started_at: DateTime<Local>,
+    pub artifacts: Vec<GeneratedArtifact>,
+    // 蒐集後已被刪除而略過的檔案
+    pub missing_files: Vec<PathBuf>,
+    // `--group-by` 時轉換失敗的群組
+    pub failed_groups: Vec<GroupFailure>,
+}
+
+/// 依目錄分組時轉換失敗的群組及失敗原因
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupFailure {
+    pub source: PathBuf,
+    pub error: String,
+}
+
+impl ConversionReport {
+    pub fn new(
+        input_path: PathBuf,
+        output_dir: String,
+        lang: String,
+        index_name: String,
+        output_extension: String,
+        format_version: u32,
+        classification: Option<String>,
+    ) -> Self {
+        ConversionReport {
+            input_path,
+            output_dir,
+            lang,
+            index_name,
+            output_extension,
+            format_version,
+            classification,
+            started_at: Local::now(),
+            artifacts: Vec::new(),
+            missing_files: Vec::new(),
+            failed_groups: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, artifact: GeneratedArtifact) {
+        self.artifacts.push(artifact);
+    }
+
+    /// 產生 `--report` 的 JSON 報告，逐檔記錄每個產出物的來源、頁面、大小與 SHA-256，不受日誌取樣影響
+    /// # 回傳
+    /// - 成功時返回 JSON 文字，序列化失敗時返回 IO 錯誤
+    pub fn to_json(&self) -> io::Result<String> {
+        let document = ReportDocument {
+            input_path: &self.input_path,
+            output_dir: &self.output_dir,
+            started_at: self.started_at,
+            finished_at: Local::now(),
+            artifact_count: self.artifacts.len(),
+            artifacts: &self.artifacts,
+            missing_files: &self.missing_files,
+            failed_groups: &self.failed_groups,
+        };
+        serde_json::to_string_pretty(&document).map_err(io::Error::other)
+    }
+}
+
+// `--report` 寫出的 JSON 報告
+#[derive(Serialize)]
+struct ReportDocument<'a> {
+    input_path: &'a Path,
+    output_dir: &'a str,
+    started_at: DateTime<Local>,
+    finished_at: DateTime<Local>,
+    artifact_count: usize,
+    artifacts: &'a [GeneratedArtifact],
+    #[serde(skip_serializing_if = "<[PathBuf]>::is_empty")]
+    missing_files: &'a [PathBuf],
+    #[serde(skip_serializing_if = "<[GroupFailure]>::is_empty")]
+    failed_groups: &'a [GroupFailure],
+}
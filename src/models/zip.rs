@@ -1,18 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use zip::write::SimpleFileOptions;
+use std::time::SystemTime;
+use std::sync::Arc;
+use crate::models::conversion::Tags;
+use crate::service::change::FileSnapshot;
+use crate::service::spill::SpillFile;
+use crate::service::traits::i_plugin::ContentFilter;
+
+// 項目壓縮方法與等級（0–9），ZIP 用於各項目，tar.gz 用於整體的 gzip，7z 用於 LZMA2 的預設等級
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionSpec {
+    pub method: String,
+    pub level: u8,
+}
+
+// 項目的修改時間與 Unix 權限，未指定時使用固定值，相同內容產生相同的封存檔
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntryMetadata {
+    pub modified: Option<SystemTime>,
+    pub mode: Option<u32>,
+}
 
 #[derive(Clone)]
 pub struct ZipCompressInput {
     pub files: Vec<PathBuf>,
     pub input_path: PathBuf,
-    pub options: SimpleFileOptions,
+    pub compression: CompressionSpec,
     pub password: Option<String>,
     pub aes_mode: zip::AesMode,
     pub no_progress: bool,
+    pub filters: Vec<Arc<dyn ContentFilter>>,
+    // 只寫入指定位元組範圍的檔案
+    pub ranges: HashMap<PathBuf, ByteRange>,
+    // 封存格式：zip、tar 或 tar.gz
+    pub archive_format: String,
+    // 蒐集時各檔案的狀態，寫入時比對以偵測來源變更
+    pub snapshots: HashMap<PathBuf, FileSnapshot>,
+    // 來源變更的處理方式：warn、fail 或 reread
+    pub on_change: String,
+    // 來源檔案已被刪除時的處理方式：skip 或 fail
+    pub on_missing: String,
+    // 是否保留來源檔案的修改時間與 Unix 權限
+    pub preserve_metadata: bool,
+    // 符號連結的處理方式，`store` 時保存為連結項目
+    pub symlinks: String,
+    // 是否將封存檔寫入暫存檔而非記憶體
+    pub spill_to_disk: bool,
+    // 平行壓縮 ZIP 項目的執行緒數，1 表示依序壓縮
+    pub jobs: usize,
+    // 是否在 ZIP 的第一個項目寫入 manifest.json
+    pub manifest: bool,
+    // 項目名稱的頂層資料夾，取代輸入目錄的名稱；空字串表示去除頂層資料夾，None 時沿用輸入目錄的名稱
+    pub archive_root: Option<String>,
+    // 附加於 ZIP 封存檔註解的說明，工具資訊與建立時間一律寫入
+    pub comment: Option<String>,
+}
+
+// 直接嵌入既有封存檔所需的參數
+#[derive(Clone)]
+pub struct ArchivePassthroughInput {
+    pub path: PathBuf,
+    // 偵測到的封存格式：zip、7z 或 tar.gz
+    pub archive_format: String,
+    // 蒐集時的檔案狀態，寫入時比對以偵測來源變更
+    pub snapshot: Option<FileSnapshot>,
+    // 來源變更的處理方式：warn、fail 或 reread
+    pub on_change: String,
+}
+
+/// 封存檔內的清單（`--manifest`），以 manifest.json 寫在 ZIP 的第一個項目，供收件者與 `verify` 確認內容完整
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub manifest_version: u32,
+    pub tool_version: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// 清單中的單一項目，內容經過濾器轉換時記錄轉換後的大小與 SHA-256，符號連結記錄連結目標的 SHA-256
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    // 來源檔案的修改時間（RFC 3339），未保留中繼資料或無法取得時為 null
+    pub modified: Option<String>,
+    pub sha256: String,
 }
 
 #[derive(Debug)]
 pub struct ZipCompressOutput {
+    // 啟用 --spill-to-disk 時為空，封存檔位於 spill 指向的暫存檔
     pub zip_buffer: Vec<u8>,
+    pub spill: Option<SpillFile>,
     pub total_size: usize,
+    pub entries: Vec<ZipEntryInfo>,
+    // 蒐集後已被刪除而略過的檔案
+    pub missing_files: Vec<PathBuf>,
+}
+
+// ZIP 內單一項目的清單資訊，供 HTML 端直接擷取個別檔案
+#[derive(Debug, Clone, Serialize)]
+pub struct ZipEntryInfo {
+    pub path: String,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub data_offset: u64,
+    pub method: String,
+    pub encrypted: bool,
+    // 只包含原檔部分內容時記錄擷取的範圍
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<ByteRange>,
+    // `--tag` 與 `--tag-rule` 標示的自訂標籤
+    #[serde(skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+}
+
+// 檔案中實際寫入 ZIP 的位元組範圍
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ByteRange {
+    pub offset: u64,
+    pub len: u64,
+    // 原檔大小
+    pub file_size: u64,
 }
\ No newline at end of file
@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+
+// 不需要標籤的 input 類型
+const UNLABELED_INPUT_TYPES: [&str; 5] = ["hidden", "submit", "button", "reset", "image"];
+
+// 內容不含標籤、掃描時整段略過的元素
+const RAW_TEXT_ELEMENTS: [&str; 3] = ["script", "style", "textarea"];
+
+// 單一標籤的名稱與屬性
+struct Tag {
+    name: String,
+    closing: bool,
+    attributes: Vec<(String, String)>,
+}
+
+impl Tag {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    fn has_non_empty(&self, name: &str) -> bool {
+        self.attribute(name).is_some_and(|value| !value.trim().is_empty())
+    }
+
+    // 以 aria-label、aria-labelledby 或 title 提供可存取名稱
+    fn has_accessible_name(&self) -> bool {
+        self.has_non_empty("aria-label") || self.has_non_empty("aria-labelledby") || self.has_non_empty("title")
+    }
+}
+
+/// 檢查頁面是否具備鍵盤與螢幕閱讀器使用所需的結構：
+/// `<html lang>`、main 地標、有腳本時的狀態通知區域與 `<noscript>` 說明、表單欄位與進度條的標籤，以及圖片的替代文字
+/// # 參數
+/// - html: 頁面內容
+/// # 回傳
+/// - 發現的問題說明，符合要求時為空
+pub fn check_page(html: &str) -> Vec<String> {
+    let mut has_lang = false;
+    let mut has_main = false;
+    let mut has_live_region = false;
+    let mut has_script = false;
+    let mut has_noscript = false;
+    let mut label_depth = 0usize;
+    let mut label_targets = HashSet::new();
+    // 未以外層 label 或可存取名稱標示的欄位，待收集完所有 label 的 for 屬性後再比對 id
+    let mut pending_fields: Vec<(String, Option<String>)> = Vec::new();
+    let mut issues = Vec::new();
+
+    for tag in scan_tags(html) {
+        if tag.closing {
+            if tag.name == "label" {
+                label_depth = label_depth.saturating_sub(1);
+            }
+            continue;
+        }
+        let role = tag.attribute("role").unwrap_or_default().to_ascii_lowercase();
+        if role == "main" {
+            has_main = true;
+        }
+        if matches!(role.as_str(), "status" | "alert" | "log") || tag.attribute("aria-live").is_some() {
+            has_live_region = true;
+        }
+        match tag.name.as_str() {
+            "html" => has_lang = tag.has_non_empty("lang"),
+            "main" => has_main = true,
+            "script" => has_script = true,
+            "noscript" => has_noscript = true,
+            "label" => {
+                label_depth += 1;
+                if let Some(target) = tag.attribute("for") {
+                    label_targets.insert(target.to_string());
+                }
+            }
+            "input" | "select" | "textarea" => {
+                let input_type = tag.attribute("type").unwrap_or("text").to_ascii_lowercase();
+                if tag.name == "input" && UNLABELED_INPUT_TYPES.contains(&input_type.as_str()) {
+                    continue;
+                }
+                if label_depth == 0 && !tag.has_accessible_name() {
+                    let description = match tag.attribute("id") {
+                        Some(id) => format!("<{} id=\"{}\">", tag.name, id),
+                        None => format!("<{}>", tag.name),
+                    };
+                    pending_fields.push((description, tag.attribute("id").map(str::to_string)));
+                }
+            }
+            "progress" if !tag.has_accessible_name() => {
+                issues.push("進度條缺少 aria-label 或 aria-labelledby".to_string());
+            }
+            "img" if tag.attribute("alt").is_none() => {
+                issues.push(format!("圖片缺少 alt 替代文字：{}", tag.attribute("src").map_or("", truncate_src)));
+            }
+            _ => {}
+        }
+    }
+
+    for (description, id) in pending_fields {
+        if !id.is_some_and(|id| label_targets.contains(&id)) {
+            issues.push(format!("表單欄位缺少標籤（label、aria-label 或 aria-labelledby）：{}", description));
+        }
+    }
+    if !has_lang {
+        issues.push("缺少 <html lang> 語言標示".to_string());
+    }
+    if !has_main {
+        issues.push("缺少 main 地標（<main> 或 role=\"main\"）".to_string());
+    }
+    // 不含腳本的頁面（`--no-js`）內容不會變動，不需要狀態通知區域
+    if has_script && !has_live_region {
+        issues.push("缺少狀態通知區域（role=\"status\"、role=\"alert\" 或 aria-live）".to_string());
+    }
+    if has_script && !has_noscript {
+        issues.push("頁面需要腳本但缺少 <noscript> 替代說明".to_string());
+    }
+    issues
+}
+
+// 依序取出頁面中的標籤，略過註解、宣告以及 script、style、textarea 的內容
+fn scan_tags(html: &str) -> Vec<Tag> {
+    let mut tags = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if let Some(after) = rest.strip_prefix("!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        let closing = rest.starts_with('/');
+        let body = if closing { &rest[1..] } else { rest };
+        let name_len = body.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(body.len());
+        if name_len == 0 || !body.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            continue;
+        }
+        let name = body[..name_len].to_ascii_lowercase();
+        let end = tag_end(&body[name_len..]);
+        let attributes = parse_attributes(&body[name_len..name_len + end]);
+        rest = body.get(name_len + end + 1..).unwrap_or("");
+        if !closing && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let close = format!("</{}", name);
+            rest = find_ignore_case(rest, &close).map_or("", |i| &rest[i..]);
+        }
+        tags.push(Tag { name, closing, attributes });
+    }
+    tags
+}
+
+// 找出標籤結尾的 `>`，略過引號內的內容
+fn tag_end(s: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return i,
+            None => {}
+        }
+    }
+    s.len()
+}
+
+// 解析標籤內的屬性，名稱轉為小寫，沒有值的屬性視為空字串
+fn parse_attributes(s: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = s.trim_start_matches('/');
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        let name_len = rest.find(|c: char| c.is_whitespace() || c == '=' || c == '/').unwrap_or(rest.len());
+        if name_len == 0 {
+            break;
+        }
+        let name = rest[..name_len].to_ascii_lowercase();
+        rest = rest[name_len..].trim_start();
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, remaining) = match after.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let inner = &after[1..];
+                    let end = inner.find(q).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            value = raw.to_string();
+            rest = remaining;
+        }
+        attributes.push((name, value));
+    }
+    attributes
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+// data URI 可能非常長，問題說明只保留開頭
+fn truncate_src(src: &str) -> &str {
+    match src.char_indices().nth(60) {
+        Some((i, _)) => &src[..i],
+        None => src,
+    }
+}
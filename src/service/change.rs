@@ -0,0 +1,107 @@
+use std::fs::{File, Metadata};
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::SystemTime;
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+
+/// 狀態中雜湊的檔案開頭長度（位元組）
+pub const SNAPSHOT_PREFIX_LEN: u64 = 4096;
+/// `--on-change reread` 在讀取期間持續變更時最多重新讀取的次數
+pub const MAX_REREADS: usize = 3;
+
+/// 檔案在某一時間點的狀態：大小、修改時間、權限與開頭內容的雜湊，蒐集時記錄並於寫入封存檔時再次比對，
+/// 修改時間與權限也用於封存檔的項目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSnapshot {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    // Unix 權限位元，其他平台為 None
+    pub mode: Option<u32>,
+    // 前 `SNAPSHOT_PREFIX_LEN` 位元組的 SHA-256
+    pub prefix_sha256: Vec<u8>,
+}
+
+impl FileSnapshot {
+    /// 讀取檔案目前的狀態
+    /// # 參數
+    /// - path: 檔案路徑
+    /// # 回傳
+    /// - 成功時返回狀態，無法讀取時返回 IO 錯誤
+    pub fn capture(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let metadata = file.metadata()?;
+        let mut hasher = Sha256::new();
+        io::copy(&mut file.take(SNAPSHOT_PREFIX_LEN), &mut hasher)?;
+        Ok(FileSnapshot {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            mode: unix_mode(&metadata),
+            prefix_sha256: hasher.finalize().to_vec(),
+        })
+    }
+}
+
+// 檔案的 Unix 權限位元
+#[cfg(unix)]
+fn unix_mode(metadata: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode() & 0o7777)
+}
+
+// 非 Unix 平台沒有權限位元
+#[cfg(not(unix))]
+fn unix_mode(_metadata: &Metadata) -> Option<u32> {
+    None
+}
+
+/// 寫入項目後確認來源檔案自蒐集以來未變更，`warn` 記錄警告，`fail` 中止轉換
+/// # 參數
+/// - path: 來源檔案
+/// - collected: 蒐集時記錄的狀態
+/// - on_change: 變更處理方式
+/// # 回傳
+/// - 未變更或僅需警告時返回 Ok，`fail` 且已變更時返回 IO 錯誤
+pub fn check_unchanged(path: &Path, collected: &FileSnapshot, on_change: &str) -> io::Result<()> {
+    if FileSnapshot::capture(path).ok().as_ref() == Some(collected) {
+        return Ok(());
+    }
+    if on_change == "fail" {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("來源檔案在蒐集後已變更：{}，可使用 --on-change reread 重新讀取", path.display()),
+        ));
+    }
+    warn!("來源檔案在蒐集後已變更，封存檔中的內容可能不完整：{}", path.display());
+    Ok(())
+}
+
+/// 將來源檔案讀入記憶體，讀取前後狀態不同時重新讀取，確保內容為同一時間點的完整副本
+/// # 參數
+/// - path: 來源檔案
+/// - collected: 蒐集時記錄的狀態，內容與其不同時記錄已改用新內容
+/// - read: 讀取內容的方式，例如只讀取指定範圍
+/// # 回傳
+/// - 成功時返回讀取的內容，重新讀取 `MAX_REREADS` 次後仍在變更時返回 IO 錯誤
+pub fn read_stable<F>(path: &Path, collected: &FileSnapshot, mut read: F) -> io::Result<Vec<u8>>
+where
+    F: FnMut() -> io::Result<Vec<u8>>,
+{
+    for attempt in 0..=MAX_REREADS {
+        let before = FileSnapshot::capture(path)?;
+        let data = read()?;
+        if FileSnapshot::capture(path)? == before {
+            if before != *collected {
+                info!("來源檔案在蒐集後已變更，已讀取變更後的內容：{}", path.display());
+            }
+            return Ok(data);
+        }
+        if attempt < MAX_REREADS {
+            warn!("來源檔案在讀取期間變更，重新讀取（第 {} 次）：{}", attempt + 1, path.display());
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("來源檔案持續變更，重新讀取 {} 次後仍不一致：{}", MAX_REREADS, path.display()),
+    ))
+}
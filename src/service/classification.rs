@@ -0,0 +1,85 @@
+/// `--classification` 可指定的分級
+pub const CLASSIFICATIONS: [&str; 3] = ["confidential", "internal", "public"];
+
+// 各分級的標示文字與底色，配色沿用常見的分級標示慣例（機密藍、內部紫、公開綠），
+// 標示文字一律使用英文大寫，不隨頁面語言翻譯以免與標準標示不符
+const MARKINGS: [(&str, &str, &str); 3] = [
+    ("confidential", "CONFIDENTIAL", "#0033a0"),
+    ("internal", "INTERNAL", "#502b85"),
+    ("public", "PUBLIC", "#007a33"),
+];
+
+// 分級橫幅的高度（像素），頁面上下各保留相同空間避免遮住內容
+const BANNER_HEIGHT: u32 = 28;
+
+/// 分級標示：頁面上下固定的橫幅與背景浮水印，列印時同樣保留
+#[derive(Debug, Default)]
+pub struct ClassificationMarking {
+    // 放在 <head> 內的樣式
+    pub style: String,
+    // 緊接 <body> 之後的橫幅與浮水印
+    pub banner: String,
+}
+
+/// 產生分級標示
+/// # 參數
+/// - classification: 分級，未指定時不加標示
+/// # 回傳
+/// - 分級標示；未指定或無法識別的分級返回空內容
+pub fn classification_marking(classification: Option<&str>) -> ClassificationMarking {
+    let Some((_, label, color)) = classification.and_then(|level| MARKINGS.iter().find(|(name, _, _)| *name == level)) else {
+        return ClassificationMarking::default();
+    };
+    let style = format!(
+        r#"<style>
+        body {{
+            padding-top: {height}px;
+            padding-bottom: {height}px;
+        }}
+        .classification-banner {{
+            position: fixed;
+            left: 0;
+            right: 0;
+            z-index: 1000;
+            height: {height}px;
+            line-height: {height}px;
+            background-color: {color};
+            color: #ffffff;
+            font-weight: bold;
+            letter-spacing: 2px;
+            text-align: center;
+            -webkit-print-color-adjust: exact;
+            print-color-adjust: exact;
+        }}
+        .classification-banner.top {{
+            top: 0;
+        }}
+        .classification-banner.bottom {{
+            bottom: 0;
+        }}
+        .classification-watermark {{
+            position: fixed;
+            top: 50%;
+            left: 50%;
+            z-index: 999;
+            transform: translate(-50%, -50%) rotate(-30deg);
+            font-size: 96px;
+            font-weight: bold;
+            color: {color};
+            opacity: 0.08;
+            white-space: nowrap;
+            pointer-events: none;
+            user-select: none;
+        }}
+    </style>"#,
+        height = BANNER_HEIGHT,
+        color = color,
+    );
+    let banner = format!(
+        "<div class=\"classification-banner top\" role=\"banner\">{label}</div>\n\
+         <div class=\"classification-banner bottom\">{label}</div>\n\
+         <div class=\"classification-watermark\" aria-hidden=\"true\">{label}</div>",
+        label = label,
+    );
+    ClassificationMarking { style, banner }
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::io;
+use base64::{engine::general_purpose, Engine as _};
+
+/// `--copy-to-clipboard` 可指定的內容：頁面的絕對路徑或頁面內容的 Base64
+pub const CLIPBOARD_TARGETS: [&str; 2] = ["path", "base64"];
+
+/// 將產生的頁面複製到剪貼簿
+/// # 參數
+/// - page: 頁面路徑
+/// - target: `path` 複製頁面的絕對路徑，`base64` 複製以 Base64 編碼的頁面內容
+/// # 回傳
+/// - 頁面無法讀取、未啟用剪貼簿功能或剪貼簿無法使用時返回 IO 錯誤
+pub fn copy_page(page: &str, target: &str) -> io::Result<()> {
+    let text = match target {
+        "base64" => general_purpose::STANDARD.encode(fs::read(page)?),
+        _ => fs::canonicalize(page)?.to_string_lossy().into_owned(),
+    };
+    set_text(text)
+}
+
+/// 複製到剪貼簿需以 `clipboard` 功能編譯，未啟用時在轉換前明確拒絕
+pub fn validate_clipboard(copy_to_clipboard: Option<&str>) -> io::Result<()> {
+    if copy_to_clipboard.is_some() && !cfg!(feature = "clipboard") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "此版本未啟用剪貼簿功能，請以 `cargo build --features clipboard` 重新編譯後使用 --copy-to-clipboard"
+        ));
+    }
+    Ok(())
+}
+
+// Linux 上剪貼簿內容由本程式提供，結束時 arboard 會嘗試交給剪貼簿管理程式保存
+#[cfg(feature = "clipboard")]
+fn set_text(text: String) -> io::Result<()> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| io::Error::other(format!("無法開啟剪貼簿：{}", e)))?;
+    clipboard.set_text(text).map_err(|e| io::Error::other(format!("無法寫入剪貼簿：{}", e)))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn set_text(_text: String) -> io::Result<()> {
+    validate_clipboard(Some(""))
+}
@@ -1,6 +1,23 @@
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use serde_json::Value;
+use crate::config::config::{
+    validate_expires, validate_file_patterns, validate_input_path, validate_name_pattern,
+    validate_max_html_size, validate_output_extension, validate_split_size, validate_format_version, validate_minify, validate_jobs, validate_slices,
+    validate_password_hint, parse_byte_size, parse_data_uri_threshold, parse_file_mode, validate_tags, validate_rules, PasswordMode, DEFAULT_COMPRESSION_METHOD,
+};
 use crate::config::ports::{AppConfig, ConfigPort};
-use crate::config::config::PasswordMode;
+use crate::service::crypto::is_browser_encryption;
+use crate::service::classification::CLASSIFICATIONS;
+use crate::service::encoding::PAYLOAD_ENCODINGS;
+use crate::service::gzip::{validate_payload_compression_level, PAYLOAD_COMPRESSIONS};
+use crate::service::i18n::SUPPORTED_LANGS;
+use crate::service::line_ending::LINE_ENDINGS;
+use crate::service::transcode::TRANSCODE_TARGETS;
+use crate::service::clipboard::{validate_clipboard, CLIPBOARD_TARGETS};
+use crate::service::zip::{is_limited_compression_method, validate_archive_comment, validate_archive_root, ARCHIVE_FORMATS, COMPRESSION_METHODS};
 
 // 配置服務，負責選擇適當的配置適配器
 pub struct ConfigService {
@@ -13,10 +30,36 @@ impl ConfigService {
     }
 
     pub fn get_config(&self) -> io::Result<AppConfig> {
-        self.config_port.get_config()
+        let config = self.config_port.get_config()?;
+        if needs_native_extractor_warning(&config) {
+            log::warn!(
+                "ZIP 將以 AES（{}）加密，macOS 的封存工具程式與 Windows 檔案總管無法開啟，收件者需使用 7-Zip、WinRAR、Keka 等工具；可改用 --encryption-method webcrypto 在瀏覽器中解密",
+                config.encryption_method
+            );
+        }
+        if config.archive_format != "zip" && config.compression_method != DEFAULT_COMPRESSION_METHOD {
+            log::warn!("{} 封存格式不使用 ZIP 項目壓縮方法，已忽略 --compression-method {}", config.archive_format, config.compression_method);
+        }
+        Ok(config)
     }
 }
 
+/// 判斷產出的 ZIP 是否以 AES 加密，作業系統內建的解壓工具無法開啟
+pub fn needs_native_extractor_warning(config: &AppConfig) -> bool {
+    let (inner, outer) = match config.layer.as_str() {
+        "double" => (config.inner_password_mode.as_ref(), config.outer_password_mode.as_ref()),
+        _ => (None, None),
+    };
+    // 雙層壓縮的內層 ZIP 一律以 ZIP 的 AES 加密
+    if config.layer == "double" && *inner.unwrap_or(&config.password_mode) != PasswordMode::None {
+        return true;
+    }
+    *outer.unwrap_or(&config.password_mode) != PasswordMode::None
+        && config.layer != "none"
+        && config.archive_format == "zip"
+        && config.encryption_method.starts_with("aes")
+}
+
 // 預設配置適配器
 pub struct DefaultConfigAdapter {
     input: String,
@@ -34,16 +77,420 @@ impl ConfigPort for DefaultConfigAdapter {
         Ok(AppConfig {
             input: self.input.clone(),
             output: self.output.clone(),
-            is_compressed: true, // 壓縮模式
-            compress: true,
-            include: vec!["*".to_string()],
-            exclude: None,
-            password_mode: PasswordMode::Random,
-            display_password: true,
-            layer: "single".to_string(), // 單層壓縮
-            encryption_method: "aes256".to_string(),
-            no_progress: false,
-            max_size: None,
+            ..AppConfig::default()
         })
     }
-}
\ No newline at end of file
+}
+// 固定配置適配器，直接回傳已載入的配置
+pub struct StaticConfigAdapter {
+    config: AppConfig,
+}
+
+impl StaticConfigAdapter {
+    pub fn new(config: AppConfig) -> Self {
+        StaticConfigAdapter { config }
+    }
+}
+
+impl ConfigPort for StaticConfigAdapter {
+    fn get_config(&self) -> io::Result<AppConfig> {
+        Ok(self.config.clone())
+    }
+}
+
+// 設定檔配置適配器，從 JSON 設定檔讀取配置，命令列指定的輸入/輸出路徑優先
+pub struct FileConfigAdapter {
+    path: PathBuf,
+    input: Option<String>,
+    output: Option<String>,
+}
+
+impl FileConfigAdapter {
+    pub fn new(path: PathBuf, input: Option<String>, output: Option<String>) -> Self {
+        FileConfigAdapter { path, input, output }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ConfigPort for FileConfigAdapter {
+    fn get_config(&self) -> io::Result<AppConfig> {
+        let content = fs::read_to_string(&self.path)?;
+        let mut config: AppConfig = serde_json::from_str(&content).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("設定檔 '{}' 格式錯誤: {}", self.path.display(), e),
+            )
+        })?;
+        if let Some(input) = &self.input {
+            config.input = input.clone();
+        }
+        if let Some(output) = &self.output {
+            config.output = output.clone();
+        }
+        validate_app_config(&config)?;
+        Ok(config)
+    }
+}
+
+/// 驗證 `--volume-size`：分卷大小是以位元組大小指定的 `--split-size`，兩者擇一
+/// # 參數
+/// - volume_size: 分卷大小設定
+/// - split_size: `--split-size` 的 MB 數
+/// # 回傳
+/// - 成功時返回 Ok，同時指定或大小格式錯誤時返回 IO 錯誤
+pub fn validate_volume_size(volume_size: Option<&str>, split_size: Option<f64>) -> io::Result<()> {
+    let Some(volume_size) = volume_size else {
+        return Ok(());
+    };
+    if split_size.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--volume-size 與 --split-size 無法同時使用"
+        ));
+    }
+    parse_byte_size(volume_size).map(|_| ())
+}
+
+/// 驗證雙層壓縮的設定：內層與外層的密碼模式只適用於雙層壓縮，外層 ZIP 需包住完整的內層 ZIP
+/// # 參數
+/// - layer: 壓縮層數
+/// - password_modes: 是否指定了內層或外層的密碼模式
+/// - conflicts: 無法搭配雙層壓縮的設定是否啟用，以及對應的參數名稱
+/// # 回傳
+/// - 成功時返回 Ok，非雙層壓縮卻指定內外層密碼模式，或搭配無法使用的設定時返回 IO 錯誤
+pub fn validate_double_layer(layer: &str, password_modes: bool, conflicts: &[(bool, &str)]) -> io::Result<()> {
+    if layer != "double" {
+        if password_modes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--inner-password-mode 與 --outer-password-mode 僅適用於 '--layer double'"
+            ));
+        }
+        return Ok(());
+    }
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, option)) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("雙層壓縮（--layer double）無法搭配 '{}'", option)
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 驗證低記憶體模式：不封存時頁面資料即為來源檔案，需將整個檔案讀入記憶體，無法維持低記憶體模式的記憶體上限
+/// # 參數
+/// - low_memory: 是否啟用 `--low-memory`
+/// - raw: 是否不封存（`--archive-format none` 或 `--layer none`）
+/// # 回傳
+/// - 成功時返回 Ok，低記憶體模式搭配不封存時返回 IO 錯誤
+pub fn validate_low_memory(low_memory: bool, raw: bool) -> io::Result<()> {
+    if low_memory && raw {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "低記憶體模式（--low-memory）無法搭配 '--archive-format none' 或 '--layer none'：不封存時來源檔案需整個讀入記憶體，請改用封存格式或移除 --low-memory"
+        ));
+    }
+    Ok(())
+}
+
+/// 驗證 `--group-by`：目前只支援依子目錄分組，各群組分別轉換，無法搭配需要單一轉換結果的設定
+/// # 參數
+/// - group_by: 分組方式，None 表示不分組
+/// - conflicts: 無法搭配分組的設定是否啟用，以及對應的參數名稱
+/// # 回傳
+/// - 成功時返回 Ok，分組方式無效或搭配無法使用的設定時返回 IO 錯誤
+pub fn validate_group_by(group_by: Option<&str>, conflicts: &[(bool, &str)]) -> io::Result<()> {
+    let Some(group_by) = group_by else {
+        return Ok(());
+    };
+    if group_by != "dir" {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無效的 group_by 值 '{}'，可用值：dir", group_by)
+        ));
+    }
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, option)) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("依目錄分組（--group-by dir）無法搭配 '{}'", option)
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 驗證配置內容，規則與命令列參數一致
+pub fn validate_app_config(config: &AppConfig) -> io::Result<()> {
+    validate_input_path(&config.input)?;
+    validate_file_patterns(&Some(config.include.clone()), &config.exclude)?;
+    validate_split_size(config.split_size)?;
+    validate_max_html_size(config.max_html_size)?;
+    validate_format_version(config.format_version)?;
+    validate_minify(config.minify)?;
+    validate_clipboard(config.copy_to_clipboard.as_deref())?;
+    validate_slices(&config.slices)?;
+    validate_tags(&config.tags, &config.tag_rules)?;
+    validate_rules(&config.rules)?;
+    validate_payload_compression_level(&config.payload_compression, config.payload_compression_level)?;
+    if config.compression_level > 9 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("compression_level 需介於 0 到 9，卻是 {}", config.compression_level)
+        ));
+    }
+    if let Some(hint) = &config.password_hint {
+        validate_password_hint(hint)?;
+    }
+    if let Some(target_size) = &config.target_size {
+        parse_byte_size(target_size)?;
+    }
+    validate_volume_size(config.volume_size.as_deref(), config.split_size)?;
+    parse_data_uri_threshold(&config.data_uri_threshold)?;
+    parse_file_mode(&config.key_file_mode)?;
+    validate_jobs(config.jobs)?;
+    validate_name_pattern("key_name", &config.key_name, true)?;
+    validate_name_pattern("index_name", &config.index_name, false)?;
+    validate_output_extension(&config.output_extension)?;
+    if let Some(expires) = &config.expires {
+        validate_expires(expires)?;
+    }
+    for (name, value, allowed) in [
+        ("layer", &config.layer, &["none", "single", "double"][..]),
+        ("encryption_method", &config.encryption_method, &["aes128", "aes192", "aes256", "webcrypto", "webcrypto-chunked"][..]),
+        ("view", &config.view, &["archive", "tree"][..]),
+        ("lang", &config.lang, &SUPPORTED_LANGS[..]),
+        ("payload_encoding", &config.payload_encoding, &PAYLOAD_ENCODINGS[..]),
+        ("compression_method", &config.compression_method, &COMPRESSION_METHODS[..]),
+        ("payload_compression", &config.payload_compression, &PAYLOAD_COMPRESSIONS[..]),
+        ("oversize_policy", &config.oversize_policy, &["warn", "error", "split"][..]),
+        ("payload_mode", &config.payload_mode, &["embedded", "external"][..]),
+        ("archive_format", &config.archive_format, &ARCHIVE_FORMATS[..]),
+        ("match_on", &config.match_on, &["name", "path"][..]),
+        ("on_change", &config.on_change, &["warn", "fail", "reread"][..]),
+        ("on_missing", &config.on_missing, &["skip", "fail"][..]),
+        ("symlinks", &config.symlinks, &["follow", "store", "skip"][..]),
+    ] {
+        if !allowed.contains(&value.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("無效的 {} 值 '{}'，可用值：{}", name, value, allowed.join("、"))
+            ));
+        }
+    }
+    for (name, value, allowed) in [
+        ("transcode_text", &config.transcode_text, &TRANSCODE_TARGETS[..]),
+        ("line_endings", &config.line_endings, &LINE_ENDINGS[..]),
+        ("classification", &config.classification, &CLASSIFICATIONS[..]),
+        ("copy_to_clipboard", &config.copy_to_clipboard, &CLIPBOARD_TARGETS[..]),
+    ] {
+        if let Some(value) = value.as_deref().filter(|value| !allowed.contains(value)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("無效的 {} 值 '{}'，可用值：{}", name, value, allowed.join("、"))
+            ));
+        }
+    }
+    validate_double_layer(
+        &config.layer,
+        config.inner_password_mode.is_some() || config.outer_password_mode.is_some(),
+        &[
+            (config.archive_format != "zip", "--archive-format"),
+            (config.view == "tree", "--view tree"),
+            (config.low_memory, "--low-memory"),
+            (config.key_sheet, "--key-sheet"),
+        ],
+    )?;
+    validate_low_memory(config.low_memory, config.archive_format == "none" || config.layer == "none")?;
+    validate_group_by(
+        config.group_by.as_deref(),
+        &[
+            (
+                config.layer == "double"
+                    && [Some(&config.password_mode), config.inner_password_mode.as_ref(), config.outer_password_mode.as_ref()]
+                        .contains(&Some(&PasswordMode::Manual)),
+                "--layer double 的手動密碼",
+            ),
+            (config.restore, "--restore"),
+            (config.export_job.is_some(), "--export-job"),
+            (config.job_files.is_some(), "--import-job"),
+        ],
+    )?;
+    if config.is_compressed && config.layer == "none" {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "壓縮模式下不支援 'none' 層數，請選擇 'single' 或 'double'"
+        ));
+    }
+    if config.view == "tree" && config.password_mode != PasswordMode::None && !is_browser_encryption(&config.encryption_method) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "樹狀檢視需搭配 '--password-mode none' 或 '--encryption-method webcrypto'"
+        ));
+    }
+    if config.archive_format.starts_with("tar")
+        && config.password_mode != PasswordMode::None
+        && !is_browser_encryption(&config.encryption_method)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "tar 封存格式不支援 ZIP 的 AES 加密，請搭配 '--password-mode none' 或 '--encryption-method webcrypto'"
+        ));
+    }
+    if config.archive_format == "none" && config.is_compressed {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "不封存（--archive-format none）時每個檔案各自產生頁面，無法搭配壓縮模式，請改用 '--mode individual'"
+        ));
+    }
+    if (config.archive_format == "none" || config.layer == "none")
+        && config.password_mode != PasswordMode::None
+        && !is_browser_encryption(&config.encryption_method)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "不封存時頁面資料即為來源檔案，無法以 ZIP 的 AES 加密，請搭配 '--password-mode none' 或 '--encryption-method webcrypto'"
+        ));
+    }
+    if config.archive_format == "7z"
+        && config.password_mode != PasswordMode::None
+        && matches!(config.encryption_method.as_str(), "aes128" | "aes192")
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "7z 封存格式僅支援 AES-256 加密，請改用 '--encryption-method aes256' 或 'webcrypto'"
+        ));
+    }
+    if matches!(config.archive_format.as_str(), "tar.gz" | "7z") && config.view == "tree" {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("樹狀檢視無法讀取 {} 中的項目，請改用 '--archive-format zip'、'tar' 或 '--view archive'", config.archive_format)
+        ));
+    }
+    if config.manifest && config.archive_format != "zip" {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("項目清單（--manifest）僅適用於 ZIP 封存格式，無法搭配 '--archive-format {}'", config.archive_format)
+        ));
+    }
+    if config.manifest && config.layer == "none" {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "項目清單（--manifest）需寫入封存檔，無法搭配 '--layer none'"
+        ));
+    }
+    if let Some(comment) = &config.comment {
+        if config.archive_format != "zip" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("封存檔註解（--comment）僅適用於 ZIP 封存格式，無法搭配 '--archive-format {}'", config.archive_format)
+            ));
+        }
+        if config.layer == "none" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "封存檔註解（--comment）需寫入封存檔，無法搭配 '--layer none'"
+            ));
+        }
+        validate_archive_comment(comment)?;
+    }
+    if let Some(archive_root) = &config.archive_root {
+        if config.strip_prefix {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--archive-root 與 --strip-prefix 無法同時使用"
+            ));
+        }
+        validate_archive_root(archive_root)?;
+    }
+    if config.format == "mhtml" && (config.view == "tree" || is_browser_encryption(&config.encryption_method)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "MHTML 格式不支援樹狀檢視與瀏覽器端解密（webcrypto），請改用 ZIP 的 AES 加密"
+        ));
+    }
+    if config.format == "json" && is_browser_encryption(&config.encryption_method) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "JSON 格式不支援瀏覽器端解密（webcrypto），請改用 ZIP 的 AES 加密"
+        ));
+    }
+    if config.pwa && config.format != "html" {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("離線安裝（--pwa）僅適用於 HTML 格式，無法搭配 '--format {}'", config.format)
+        ));
+    }
+    if config.view == "tree" && config.force_method && is_limited_compression_method(&config.compression_method) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("樹狀檢視僅能在瀏覽器中解壓 deflate 項目，無法搭配 '--compression-method {} --force-method'", config.compression_method)
+        ));
+    }
+    Ok(())
+}
+
+// 設定檔監看器，偵測設定檔修改並重新載入，驗證失敗時沿用上一份有效配置
+pub struct ConfigWatcher {
+    adapter: FileConfigAdapter,
+    current: AppConfig,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    /// 載入初始配置，初始配置無效時直接返回錯誤
+    pub fn new(adapter: FileConfigAdapter) -> io::Result<Self> {
+        let modified = modified_time(adapter.path());
+        let current = adapter.get_config()?;
+        Ok(ConfigWatcher { adapter, current, modified })
+    }
+
+    pub fn current(&self) -> &AppConfig {
+        &self.current
+    }
+
+    /// 檢查設定檔是否已修改，若已修改且驗證通過則套用新配置
+    /// # 回傳
+    /// - 套用新配置時返回 true，未修改或新配置無效時返回 false
+    pub fn reload_if_changed(&mut self) -> bool {
+        let modified = modified_time(self.adapter.path());
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        match self.adapter.get_config() {
+            Ok(config) => {
+                let changes = describe_changes(&self.current, &config);
+                if changes.is_empty() {
+                    log::info!("設定檔已重新載入，內容無變更：{}", self.adapter.path().display());
+                    return false;
+                }
+                log::info!("設定檔已重新載入：{}，變更項目：{}", self.adapter.path().display(), changes.join("，"));
+                self.current = config;
+                true
+            }
+            Err(e) => {
+                log::error!("設定檔重新載入失敗，沿用原有配置：{}", e);
+                false
+            }
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// 比較兩份配置，列出有差異的欄位，格式為 `欄位: 舊值 -> 新值`
+pub fn describe_changes(old: &AppConfig, new: &AppConfig) -> Vec<String> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return Vec::new();
+    };
+    new.iter()
+        .filter_map(|(key, value)| {
+            let previous = old.get(key).unwrap_or(&Value::Null);
+            (previous != value).then(|| format!("{}: {} -> {}", key, previous, value))
+        })
+        .collect()
+}
@@ -0,0 +1,277 @@
+use std::io;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, Key, KeyInit, Nonce};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use log::info;
+use rand::Rng;
+use sha2::Sha256;
+use crate::utils::log_sampler::FILE_LOG_TARGET;
+use crate::utils::random::rng_for;
+
+/// PBKDF2 迭代次數，需與模板中的 JS 解密參數一致
+pub const PBKDF2_ITERATIONS: u32 = 100_000;
+/// 鹽值長度（位元組）
+pub const SALT_LEN: usize = 16;
+/// AES-GCM 初始向量長度（位元組）
+pub const IV_LEN: usize = 12;
+/// AES-GCM 驗證標籤長度（位元組）
+pub const TAG_LEN: usize = 16;
+/// 分塊加密時每塊的明文大小（位元組），需與模板中的 JS 解密參數一致
+pub const STREAM_CHUNK_SIZE: usize = 1024 * 1024;
+/// 分塊加密時 IV 的固定前綴長度（位元組），其餘 4 位元組為塊序號
+pub const NONCE_PREFIX_LEN: usize = 8;
+/// 分塊加密時標記最後一塊的附加資料，用於偵測截斷
+const FINAL_CHUNK_AAD: &[u8] = &[1];
+const MIDDLE_CHUNK_AAD: &[u8] = &[0];
+
+/// 判斷加密方法是否由瀏覽器端 WebCrypto 解密，此時 ZIP 本身不加密
+pub fn is_browser_encryption(encryption_method: &str) -> bool {
+    matches!(encryption_method, "webcrypto" | "webcrypto-chunked")
+}
+
+/// 使用 PBKDF2-SHA256 從密碼推導 AES-256 金鑰
+pub fn derive_key(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+/// 以 AES-GCM 加密資料，供瀏覽器端 WebCrypto 解密
+/// # 參數
+/// - data: 待加密的資料（通常為未加密的 ZIP）
+/// - password: 使用者密碼
+/// # 回傳
+/// - 成功時返回 `鹽值(16) || IV(12) || 密文+標籤`，失敗時返回 IO 錯誤
+pub fn encrypt_for_browser(data: &[u8], password: &str) -> io::Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut iv = [0u8; IV_LEN];
+    let mut rng = rng_for("webcrypto", &[password.as_bytes(), data]);
+    rng.fill(&mut salt);
+    rng.fill(&mut iv);
+
+    let key = derive_key(password, &salt, PBKDF2_ITERATIONS);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&iv), data)
+        .map_err(|e| io::Error::other(format!("AES-GCM 加密失敗: {}", e)))?;
+
+    let mut payload = Vec::with_capacity(SALT_LEN + IV_LEN + ciphertext.len());
+    payload.extend_from_slice(&salt);
+    payload.extend_from_slice(&iv);
+    payload.extend_from_slice(&ciphertext);
+    info!(target: FILE_LOG_TARGET, "WebCrypto 加密完成，大小：{} 位元組", payload.len());
+    Ok(payload)
+}
+
+/// 依加密方法以 AES-GCM 加密資料，`webcrypto-chunked` 使用分塊格式
+pub fn encrypt_with_method(data: &[u8], password: &str, encryption_method: &str) -> io::Result<Vec<u8>> {
+    if encryption_method == "webcrypto-chunked" {
+        encrypt_chunked_for_browser(data, password)
+    } else {
+        encrypt_for_browser(data, password)
+    }
+}
+
+/// 以 AES-GCM 分塊加密資料，供瀏覽器逐塊解密並邊解密邊下載
+/// # 參數
+/// - data: 待加密的資料（通常為未加密的 ZIP）
+/// - password: 使用者密碼
+/// # 回傳
+/// - 成功時返回 `鹽值(16) || IV 前綴(8) || 各塊密文+標籤`，失敗時返回 IO 錯誤；
+///   每塊明文為 `STREAM_CHUNK_SIZE` 位元組（最後一塊可較小），IV 為前綴加上 4 位元組大端序塊序號
+pub fn encrypt_chunked_for_browser(data: &[u8], password: &str) -> io::Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    let mut rng = rng_for("webcrypto-chunked", &[password.as_bytes(), data]);
+    rng.fill(&mut salt);
+    rng.fill(&mut prefix);
+
+    let key = derive_key(password, &salt, PBKDF2_ITERATIONS);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let chunk_count = data.len().div_ceil(STREAM_CHUNK_SIZE).max(1);
+    let mut payload = Vec::with_capacity(SALT_LEN + NONCE_PREFIX_LEN + data.len() + chunk_count * TAG_LEN);
+    payload.extend_from_slice(&salt);
+    payload.extend_from_slice(&prefix);
+    for index in 0..chunk_count {
+        let start = index * STREAM_CHUNK_SIZE;
+        let chunk = &data[start..(start + STREAM_CHUNK_SIZE).min(data.len())];
+        let aad = if index + 1 == chunk_count { FINAL_CHUNK_AAD } else { MIDDLE_CHUNK_AAD };
+        let ciphertext = cipher
+            .encrypt(&chunk_nonce(&prefix, index)?, Payload { msg: chunk, aad })
+            .map_err(|e| io::Error::other(format!("AES-GCM 加密失敗: {}", e)))?;
+        payload.extend_from_slice(&ciphertext);
+    }
+    info!(target: FILE_LOG_TARGET, "WebCrypto 分塊加密完成，共 {} 塊，大小：{} 位元組", chunk_count, payload.len());
+    Ok(payload)
+}
+
+/// 解密 `encrypt_chunked_for_browser` 產生的資料
+pub fn decrypt_chunked_from_browser(payload: &[u8], password: &str) -> io::Result<Vec<u8>> {
+    if payload.len() < SALT_LEN + NONCE_PREFIX_LEN + TAG_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "加密資料長度不足"));
+    }
+    let (salt, rest) = payload.split_at(SALT_LEN);
+    let (prefix, ciphertext) = rest.split_at(NONCE_PREFIX_LEN);
+    let key = derive_key(password, salt, PBKDF2_ITERATIONS);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let sealed_size = STREAM_CHUNK_SIZE + TAG_LEN;
+    let chunk_count = ciphertext.len().div_ceil(sealed_size);
+    let mut plain = Vec::with_capacity(ciphertext.len());
+    for (index, chunk) in ciphertext.chunks(sealed_size).enumerate() {
+        let aad = if index + 1 == chunk_count { FINAL_CHUNK_AAD } else { MIDDLE_CHUNK_AAD };
+        let part = cipher
+            .decrypt(&chunk_nonce(prefix, index)?, Payload { msg: chunk, aad })
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "解密失敗，密碼錯誤或資料已損毀"))?;
+        plain.extend_from_slice(&part);
+    }
+    Ok(plain)
+}
+
+// 分塊 IV：固定前綴加上 4 位元組大端序塊序號
+fn chunk_nonce(prefix: &[u8], index: usize) -> io::Result<Nonce<aes_gcm::aead::consts::U12>> {
+    let index = u32::try_from(index)
+        .map_err(|_| io::Error::new(io::ErrorKind::Unsupported, "資料過大，超出分塊加密的塊數上限"))?;
+    let mut iv = [0u8; IV_LEN];
+    iv[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    iv[NONCE_PREFIX_LEN..].copy_from_slice(&index.to_be_bytes());
+    Ok(*Nonce::from_slice(&iv))
+}
+
+/// 解密 `encrypt_for_browser` 產生的資料
+pub fn decrypt_from_browser(payload: &[u8], password: &str) -> io::Result<Vec<u8>> {
+    if payload.len() < SALT_LEN + IV_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "加密資料長度不足"));
+    }
+    let (salt, rest) = payload.split_at(SALT_LEN);
+    let (iv, ciphertext) = rest.split_at(IV_LEN);
+    let key = derive_key(password, salt, PBKDF2_ITERATIONS);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    cipher
+        .decrypt(Nonce::from_slice(iv), ciphertext)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "解密失敗，密碼錯誤或資料已損毀"))
+}
+
+/// 密碼驗證值的 Argon2id 記憶體成本（KiB），需與模板中的 JS 實作可負擔的範圍一致
+pub const VERIFIER_MEMORY_COST: u32 = 4096;
+/// 密碼驗證值的 Argon2id 迭代次數
+pub const VERIFIER_TIME_COST: u32 = 1;
+
+/// 產生密碼的 Argon2id 驗證值，嵌入頁面後可在解密前先確認密碼是否正確
+/// # 參數
+/// - password: 使用者密碼
+/// # 回傳
+/// - 成功時返回 PHC 格式字串（`$argon2id$v=19$m=...,t=...,p=1$鹽值$雜湊`），失敗時返回 IO 錯誤
+pub fn password_verifier(password: &str) -> io::Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    rng_for("password-verifier", &[password.as_bytes()]).fill(&mut salt);
+    let salt = SaltString::encode_b64(&salt)
+        .map_err(|e| io::Error::other(format!("無法編碼驗證值鹽值: {}", e)))?;
+    let params = Params::new(VERIFIER_MEMORY_COST, VERIFIER_TIME_COST, 1, None)
+        .map_err(|e| io::Error::other(format!("Argon2 參數無效: {}", e)))?;
+    let hash = Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| io::Error::other(format!("無法產生密碼驗證值: {}", e)))?;
+    Ok(hash.to_string())
+}
+
+/// 以 `password_verifier` 產生的驗證值檢查密碼
+/// # 參數
+/// - password: 待檢查的密碼
+/// - verifier: PHC 格式的驗證值
+/// # 回傳
+/// - 密碼正確時返回 true、錯誤時返回 false，驗證值格式無效時返回 IO 錯誤
+pub fn verify_password(password: &str, verifier: &str) -> io::Result<bool> {
+    let hash = PasswordHash::new(verifier)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("密碼驗證值格式無效: {}", e)))?;
+    Ok(Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "correct-horse";
+    const SEALED_CHUNK: usize = STREAM_CHUNK_SIZE + TAG_LEN;
+    const HEADER_LEN: usize = SALT_LEN + NONCE_PREFIX_LEN;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn assert_rejected(payload: &[u8], password: &str) {
+        let error = decrypt_chunked_from_browser(payload, password).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn chunked_empty_payload() {
+        let payload = encrypt_chunked_for_browser(b"", PASSWORD).unwrap();
+        assert_eq!(payload.len(), HEADER_LEN + TAG_LEN);
+        assert!(decrypt_chunked_from_browser(&payload, PASSWORD).unwrap().is_empty());
+        assert_rejected(&payload[..HEADER_LEN], PASSWORD);
+    }
+
+    #[test]
+    fn chunked_exact_multiple_of_chunk_size() {
+        let data = sample(2 * STREAM_CHUNK_SIZE);
+        let payload = encrypt_chunked_for_browser(&data, PASSWORD).unwrap();
+        assert_eq!(payload.len(), HEADER_LEN + 2 * SEALED_CHUNK);
+        assert_eq!(decrypt_chunked_from_browser(&payload, PASSWORD).unwrap(), data);
+
+        // 去掉整個最後一塊時，剩下的最後一塊不帶結尾標記
+        assert_rejected(&payload[..HEADER_LEN + SEALED_CHUNK], PASSWORD);
+        assert_rejected(&payload[..payload.len() - 1], PASSWORD);
+    }
+
+    #[test]
+    fn chunked_truncated_final_chunk_rejected() {
+        let data = sample(STREAM_CHUNK_SIZE + 100);
+        let payload = encrypt_chunked_for_browser(&data, PASSWORD).unwrap();
+        assert_eq!(decrypt_chunked_from_browser(&payload, PASSWORD).unwrap(), data);
+        assert_rejected(&payload[..payload.len() - 50], PASSWORD);
+        assert_rejected(&payload[..HEADER_LEN + SEALED_CHUNK], PASSWORD);
+    }
+
+    #[test]
+    fn chunked_reordered_chunks_rejected() {
+        let data = sample(2 * STREAM_CHUNK_SIZE + 100);
+        let mut payload = encrypt_chunked_for_browser(&data, PASSWORD).unwrap();
+        let (first, rest) = payload[HEADER_LEN..].split_at_mut(SEALED_CHUNK);
+        first.swap_with_slice(&mut rest[..SEALED_CHUNK]);
+        assert_rejected(&payload, PASSWORD);
+    }
+
+    #[test]
+    fn chunked_wrong_password_rejected() {
+        let payload = encrypt_chunked_for_browser(&sample(1000), PASSWORD).unwrap();
+        assert_rejected(&payload, "correct-horsf");
+    }
+
+    #[test]
+    fn browser_round_trip_layout() {
+        let data = sample(1000);
+        let payload = encrypt_for_browser(&data, PASSWORD).unwrap();
+        assert_eq!(payload.len(), SALT_LEN + IV_LEN + data.len() + TAG_LEN);
+        assert_eq!(decrypt_from_browser(&payload, PASSWORD).unwrap(), data);
+
+        // 依 鹽值 || IV || 密文 的排列自行解密，確認與頁面腳本使用相同的格式
+        let (salt, rest) = payload.split_at(SALT_LEN);
+        let (iv, ciphertext) = rest.split_at(IV_LEN);
+        let key = derive_key(PASSWORD, salt, PBKDF2_ITERATIONS);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        assert_eq!(cipher.decrypt(Nonce::from_slice(iv), ciphertext).unwrap(), data);
+    }
+
+    #[test]
+    fn browser_payload_bit_flips_rejected() {
+        let payload = encrypt_for_browser(&sample(100), PASSWORD).unwrap();
+        for (field, index) in [("鹽值", 0), ("IV", SALT_LEN), ("密文", SALT_LEN + IV_LEN)] {
+            let mut tampered = payload.clone();
+            tampered[index] ^= 0x01;
+            let error = decrypt_from_browser(&tampered, PASSWORD).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{}", field);
+        }
+        assert!(decrypt_from_browser(&payload[..SALT_LEN + IV_LEN - 1], PASSWORD).is_err());
+    }
+}
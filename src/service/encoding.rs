@@ -0,0 +1,227 @@
+use std::io;
+use base64::{engine::general_purpose, Engine};
+use crate::service::traits::i_encoder::PayloadEncoder;
+
+/// 支援的頁面資料編碼
+pub const PAYLOAD_ENCODINGS: [&str; 2] = ["base64", "base85"];
+
+/// Base64 編碼，體積增加約 33%，可用 `base64 -d` 或 `certutil -decode` 手動還原
+pub struct Base64Encoder;
+
+impl PayloadEncoder for Base64Encoder {
+    fn name(&self) -> &'static str {
+        "base64"
+    }
+
+    fn label(&self) -> &'static str {
+        "Base64"
+    }
+
+    fn suffix(&self) -> &'static str {
+        "b64"
+    }
+
+    fn chunk_bytes(&self) -> usize {
+        3 * 256 * 1024
+    }
+
+    fn raw_capacity(&self, encoded_len: usize) -> usize {
+        encoded_len / 4 * 3
+    }
+
+    fn encode(&self, data: &[u8]) -> String {
+        general_purpose::STANDARD.encode(data)
+    }
+
+    fn decode(&self, text: &str) -> io::Result<Vec<u8>> {
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        general_purpose::STANDARD
+            .decode(compact)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Base64 資料無效: {}", e)))
+    }
+}
+
+/// Base85 編碼，使用 Ascii85 字元集但不使用 `z` 縮寫，體積增加約 25%。
+/// `<` 以 `v` 表示，避免資料中出現 `</script` 或 `<!--` 而提早結束 script 標籤；
+/// 頁面複製或另存時會換回 `<`，可用 Python 的 `base64.a85decode` 手動還原
+pub struct Base85Encoder;
+
+// Ascii85 字元 `<` 在頁面中的替代字元，不在 Ascii85 字元範圍內
+const BASE85_LT_SUBSTITUTE: u8 = b'v';
+
+impl PayloadEncoder for Base85Encoder {
+    fn name(&self) -> &'static str {
+        "base85"
+    }
+
+    fn label(&self) -> &'static str {
+        "Base85"
+    }
+
+    fn suffix(&self) -> &'static str {
+        "b85"
+    }
+
+    fn chunk_bytes(&self) -> usize {
+        4 * 192 * 1024
+    }
+
+    fn raw_capacity(&self, encoded_len: usize) -> usize {
+        encoded_len / 5 * 4
+    }
+
+    fn encode(&self, data: &[u8]) -> String {
+        let mut output = Vec::with_capacity(data.len().div_ceil(4) * 5);
+        for group in data.chunks(4) {
+            let mut padded = [0u8; 4];
+            padded[..group.len()].copy_from_slice(group);
+            let mut value = u32::from_be_bytes(padded);
+            let mut digits = [0u8; 5];
+            for digit in digits.iter_mut().rev() {
+                *digit = (value % 85) as u8 + b'!';
+                value /= 85;
+            }
+            for digit in &digits[..group.len() + 1] {
+                output.push(if *digit == b'<' { BASE85_LT_SUBSTITUTE } else { *digit });
+            }
+        }
+        String::from_utf8(output).unwrap_or_default()
+    }
+
+    fn decode(&self, text: &str) -> io::Result<Vec<u8>> {
+        let digits: Vec<u8> = text
+            .bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .map(|b| if b == BASE85_LT_SUBSTITUTE { b'<' } else { b })
+            .collect();
+        let mut output = Vec::with_capacity(digits.len() / 5 * 4 + 4);
+        for group in digits.chunks(5) {
+            if group.len() == 1 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Base85 資料長度無效"));
+            }
+            let mut value: u64 = 0;
+            for i in 0..5 {
+                let digit = match group.get(i) {
+                    Some(b) if (b'!'..=b'u').contains(b) => (b - b'!') as u64,
+                    Some(b) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Base85 資料含無效字元 '{}'", *b as char)
+                        ));
+                    }
+                    None => 84,
+                };
+                value = value * 85 + digit;
+            }
+            let value = u32::try_from(value)
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Base85 資料超出範圍"))?;
+            output.extend_from_slice(&value.to_be_bytes()[..group.len() - 1]);
+        }
+        Ok(output)
+    }
+}
+
+static BASE64: Base64Encoder = Base64Encoder;
+static BASE85: Base85Encoder = Base85Encoder;
+
+/// 依名稱取得頁面資料編碼
+/// # 回傳
+/// - 成功時返回編碼實作，名稱未知時返回 IO 錯誤
+pub fn payload_encoder(name: &str) -> io::Result<&'static dyn PayloadEncoder> {
+    match name {
+        "base64" => Ok(&BASE64),
+        "base85" => Ok(&BASE85),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("未知的資料編碼 '{}'，可用值：{}", name, PAYLOAD_ENCODINGS.join("、"))
+        )),
+    }
+}
+
+/// 將嵌入頁面的編碼文字換回標準格式（Base85 的替代字元換回 `<`），供頁面以外的輸出使用
+/// # 參數
+/// - encoding: 編碼名稱
+/// - text: `encode` 產生的文字
+pub fn standard_payload_text(encoding: &str, text: String) -> String {
+    if encoding == "base85" {
+        text.replace(BASE85_LT_SUBSTITUTE as char, "<")
+    } else {
+        text
+    }
+}
+
+/// `--wrap-base64` 的每行字元數，與 MIME 的 Base64 行長相同
+pub const BASE64_LINE_WIDTH: usize = 76;
+
+/// 將編碼文字依固定字元數換行，換行後的文字仍可由 `base64 -d`、`certutil -decode` 與頁面腳本解碼
+/// # 參數
+/// - text: 編碼文字，僅含 ASCII 字元
+/// - width: 每行字元數，0 表示不換行
+pub fn wrap_lines(text: &str, width: usize) -> String {
+    if width == 0 {
+        return text.to_string();
+    }
+    let mut wrapped = String::with_capacity(text.len() + text.len() / width + 1);
+    for (i, line) in text.as_bytes().chunks(width).enumerate() {
+        if i > 0 {
+            wrapped.push('\n');
+        }
+        wrapped.push_str(std::str::from_utf8(line).unwrap_or_default());
+    }
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_error(text: &str) -> String {
+        let error = Base85Encoder.decode(text).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{:?}", text);
+        error.to_string()
+    }
+
+    #[test]
+    fn base85_empty_input() {
+        assert_eq!(Base85Encoder.encode(b""), "");
+        assert_eq!(Base85Encoder.decode("").unwrap(), b"");
+    }
+
+    // 與 Python 的 base64.a85encode 結果相同
+    #[test]
+    fn base85_partial_final_groups() {
+        for (data, text) in [(&b"Man "[..], "9jqo^"), (b"Man", "9jqo"), (b"Ma", "9jn"), (b"M", "9`")] {
+            assert_eq!(Base85Encoder.encode(data), text);
+            assert_eq!(Base85Encoder.decode(text).unwrap(), data);
+        }
+        let data: Vec<u8> = (0..=255).collect();
+        for len in 0..12 {
+            let encoded = Base85Encoder.encode(&data[..len]);
+            assert_eq!(encoded.len(), len / 4 * 5 + if len % 4 == 0 { 0 } else { len % 4 + 1 });
+            assert_eq!(Base85Encoder.decode(&encoded).unwrap(), &data[..len]);
+        }
+        assert_eq!(Base85Encoder.decode("9jqo^\n9jn").unwrap(), b"Man Ma");
+    }
+
+    #[test]
+    fn base85_substitutes_less_than() {
+        // 第一個字元為 Ascii85 的 `<`
+        let data = (27 * 85u32.pow(4)).to_be_bytes();
+        let encoded = Base85Encoder.encode(&data);
+        assert_eq!(encoded, "v!!!!");
+        assert!(!encoded.contains('<'));
+        assert_eq!(Base85Encoder.decode(&encoded).unwrap(), data);
+        assert_eq!(Base85Encoder.decode("<!!!!").unwrap(), data);
+        assert_eq!(standard_payload_text("base85", encoded), "<!!!!");
+    }
+
+    #[test]
+    fn base85_rejects_invalid_input() {
+        for text in ["9jqo~", "9jqoz", "9jqow", "9j qo\u{7f}"] {
+            assert!(decode_error(text).contains("無效字元"), "{:?}", text);
+        }
+        assert!(decode_error("9jqo^9").contains("長度無效"));
+        assert!(decode_error("9").contains("長度無效"));
+        assert!(decode_error("uuuuu").contains("超出範圍"));
+    }
+}
@@ -1,13 +1,20 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use regex::RegexSet;
-use log::{info, warn};
+use regex::{RegexSet, RegexSetBuilder};
+use log::{debug, info, warn};
 use jwalk::WalkDir;
 use rayon::prelude::*;
-use crate::models::file::{FileCollectInput, FileCollectOutput};
+use crate::config::config::parse_slice;
+use crate::models::file::{FileCollectInput, FileCollectOutput, PatternMatchCount, UnmatchedPattern};
+use crate::models::zip::ByteRange;
+use crate::service::change::FileSnapshot;
+use crate::service::rule::{FileRules, RuleAction};
 use crate::service::traits::i_service::FileServiceTrait;
 use crate::utils::utils::create_progress_bar;
+use crate::utils::log_sampler::FILE_LOG_TARGET;
 
 pub struct FileService;
 
@@ -17,27 +24,184 @@ impl FileService {
     }
 }
 
+impl Default for FileService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FileServiceTrait for FileService {
     fn collect_files(&self, input: FileCollectInput) -> io::Result<FileCollectOutput> {
         let exclude_patterns = input.exclude_patterns.clone().unwrap_or_default();
-        let include_set = crate::utils::utils::create_regex_sets(&input.include_patterns, &exclude_patterns).0;
-        let exclude_set = crate::utils::utils::create_regex_sets(&input.include_patterns, &exclude_patterns).1;
+        let match_on_name = input.match_on == "name";
+        let (include_set, exclude_set) = crate::utils::utils::create_regex_sets(
+            &input.include_patterns,
+            &exclude_patterns,
+            input.ignore_case,
+            match_on_name,
+        );
 
         let collector = crate::service::file::FileCollector::new(
             include_set,
             exclude_set,
             input.max_size,
             input.no_progress,
-        );
+        )
+        .match_file_name(match_on_name)
+        .with_symlink_policy(&input.symlinks)
+        .with_rules(FileRules::new(&input.rules, input.ignore_case)?);
         let pm = create_progress_bar(0, input.no_progress);
         let mut files = Vec::new();
-        let total_size = collector.collect_and_measure_files(&input.input_path, &mut files, true, &pm)?;
+        let mut sizes = Vec::new();
+        let mut pattern_matches = Vec::new();
+        let collected = collector.collect_and_measure_files(
+            &input.input_path,
+            &mut files,
+            Some(&mut sizes),
+            Some(&mut pattern_matches),
+            true,
+            &pm,
+        );
+        let unmatched_patterns = unmatched_patterns(&input.include_patterns, &pattern_matches);
+        // 沒有任何檔案時將診斷併入錯誤訊息，否則逐一記錄警告
+        let mut total_size = collected.map_err(|e| {
+            if unmatched_patterns.is_empty() {
+                return e;
+            }
+            let details: Vec<String> = unmatched_patterns.iter().map(UnmatchedPattern::describe).collect();
+            io::Error::new(e.kind(), format!("{}：{}", e, details.join("；")))
+        })?;
+        for pattern in &unmatched_patterns {
+            warn!("{}", pattern.describe());
+        }
+
+        let ranges = resolve_slices(&input.slices, &input.input_path, &files)?;
+        for (file, size) in files.iter().zip(sizes.iter_mut()) {
+            if let Some(range) = ranges.get(file) {
+                total_size = total_size - *size + range.len as usize;
+                *size = range.len as usize;
+            }
+        }
+
+        // 已無法讀取的檔案不記錄狀態，寫入封存檔時再回報錯誤
+        let snapshots = files
+            .par_iter()
+            .filter_map(|file| FileSnapshot::capture(file).ok().map(|snapshot| (file.clone(), snapshot)))
+            .collect();
 
         Ok(FileCollectOutput {
             files,
+            sizes,
             total_size,
+            ranges,
+            snapshots,
+            unmatched_patterns,
+        })
+    }
+}
+
+// 找出沒有符合任何檔案的 include 模式，並依不分大小寫時符合的檔案推測正確的模式
+fn unmatched_patterns(patterns: &[String], counts: &[PatternMatchCount]) -> Vec<UnmatchedPattern> {
+    patterns
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| count.matches == 0)
+        .map(|(pattern, count)| UnmatchedPattern {
+            pattern: pattern.clone(),
+            case_insensitive_matches: count.case_insensitive_matches,
+            suggestion: count.case_insensitive_example.as_deref().and_then(|example| suggest_pattern(pattern, example)),
         })
+        .collect()
+}
+
+// 將模式中以 `*` 分隔的各段文字換成範例路徑中的實際大小寫，如 `*.TXT` 與 `a.txt` → `*.txt`；
+// 最後一段通常是副檔名，自路徑結尾往前尋找；找不到對應文字或結果與原模式相同時返回 None
+fn suggest_pattern(pattern: &str, example: &Path) -> Option<String> {
+    let path = example.to_string_lossy();
+    let lower_path = path.to_ascii_lowercase();
+    let segments: Vec<&str> = pattern.split('*').collect();
+    let mut position = 0;
+    let mut suggestion = String::with_capacity(pattern.len());
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            suggestion.push('*');
+        }
+        if segment.is_empty() {
+            continue;
+        }
+        let lower_segment = segment.to_ascii_lowercase();
+        let found = if i == segments.len() - 1 && i > 0 {
+            lower_path[position..].rfind(&lower_segment)
+        } else {
+            lower_path[position..].find(&lower_segment)
+        }? + position;
+        suggestion.push_str(&path[found..found + segment.len()]);
+        position = found + segment.len();
+    }
+    (suggestion != pattern).then_some(suggestion)
+}
+
+/// 將 `--slice` 設定對應到已蒐集的檔案，計算實際擷取的位元組範圍
+/// # 參數
+/// - slices: 切片設定，格式為 `路徑:位移:長度`
+/// - input_path: 輸入路徑，切片路徑不存在時改以輸入目錄為基準尋找
+/// - files: 已蒐集的檔案
+/// # 回傳
+/// - 以檔案路徑為鍵的擷取範圍，長度超過檔案結尾時截至結尾；
+///   檔案不在轉換範圍內、重複指定或位移超出檔案大小時返回 IO 錯誤
+pub fn resolve_slices(slices: &[String], input_path: &Path, files: &[PathBuf]) -> io::Result<HashMap<PathBuf, ByteRange>> {
+    let mut ranges = HashMap::new();
+    if slices.is_empty() {
+        return Ok(ranges);
     }
+    let collected: Vec<(PathBuf, &PathBuf)> = files
+        .iter()
+        .filter_map(|file| fs::canonicalize(file).ok().map(|canonical| (canonical, file)))
+        .collect();
+    for spec in slices {
+        let slice = parse_slice(spec)?;
+        let candidate = if slice.path.exists() || !input_path.is_dir() {
+            slice.path.clone()
+        } else {
+            input_path.join(&slice.path)
+        };
+        let canonical = fs::canonicalize(&candidate).map_err(|e| {
+            io::Error::new(e.kind(), format!("找不到切片指定的檔案 {}: {}", slice.path.display(), e))
+        })?;
+        let file = collected
+            .iter()
+            .find(|(path, _)| *path == canonical)
+            .map(|(_, file)| (*file).clone())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("切片指定的檔案 {} 不在轉換範圍內，需位於輸入路徑中且符合 include/exclude 與大小限制", slice.path.display())
+                )
+            })?;
+
+        if ranges.contains_key(&file) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("同一檔案只能指定一個切片：{}", file.display())
+            ));
+        }
+        let file_size = fs::metadata(&file)?.len();
+        let offset = if slice.offset < 0 {
+            file_size.saturating_sub(slice.offset.unsigned_abs())
+        } else {
+            slice.offset as u64
+        };
+        if offset >= file_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("切片位移 {} 超出檔案大小 {} 位元組：{}", slice.offset, file_size, file.display())
+            ));
+        }
+        let len = slice.len.min(file_size - offset);
+        info!(target: FILE_LOG_TARGET, "{} 僅嵌入位移 {} 起 {} 位元組（原檔 {} 位元組）", file.display(), offset, len, file_size);
+        ranges.insert(file, ByteRange { offset, len, file_size });
+    }
+    Ok(ranges)
 }
 
 // 讀取檔案內容，保持串流讀寫
@@ -47,35 +211,81 @@ pub fn read_file_content(file_path: &Path) -> io::Result<(Vec<u8>, usize)> {
     Ok((buffer, file_size))
 }
 
-// 檢查檔案是否有效，批次處理正則表達式
+/// 讀取檔案內容，指定範圍時只讀取該範圍
+/// # 參數
+/// - file_path: 檔案路徑
+/// - range: `--slice` 指定的位元組範圍
+/// # 回傳
+/// - 成功時返回讀取的內容，檔案無法開啟或讀取時返回 IO 錯誤
+pub fn read_file_range(file_path: &Path, range: Option<&ByteRange>) -> io::Result<Vec<u8>> {
+    use std::io::{Read, Seek, SeekFrom};
+    let Some(range) = range else {
+        return fs::read(file_path);
+    };
+    let mut file = fs::File::open(file_path)?;
+    file.seek(SeekFrom::Start(range.offset))?;
+    let mut data = Vec::with_capacity(range.len as usize);
+    file.take(range.len).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+// 模式的比對對象：檔名或完整路徑
+fn match_subject(path: &Path, match_on_name: bool) -> Cow<'_, str> {
+    match path.file_name() {
+        Some(name) if match_on_name => name.to_string_lossy(),
+        _ => path.to_string_lossy(),
+    }
+}
+
+// 檢查檔案是否有效，批次處理正則表達式，通過模式後再依序比對規則
 pub fn is_file_valid(
     path: &Path,
     include_set: &RegexSet,
     exclude_set: &RegexSet,
-    max_size: Option<f64>,
+    rules: &FileRules,
+    match_on_name: bool,
 ) -> io::Result<bool> {
-    let path_str = path.to_string_lossy();
-    if !include_set.is_match(&path_str) || exclude_set.is_match(&path_str) {
+    let subject = match_subject(path, match_on_name);
+    if !include_set.is_match(&subject) || exclude_set.is_match(&subject) {
         return Ok(false);
     }
-    if let Some(max) = max_size {
-        let file_size = fs::metadata(path)?.len() as f64 / 1_048_576.0;
-        if file_size > max {
-            warn!("檔案 {} 超過大小限制（{} MB > {} MB)，跳過", path.display(), file_size, max);
-            return Ok(false);
+    if rules.is_empty() {
+        return Ok(true);
+    }
+    // 失效的符號連結以連結本身的大小比對
+    let file_size = fs::metadata(path).or_else(|_| fs::symlink_metadata(path))?.len();
+    match rules.evaluate(path, file_size) {
+        Some(rule) if rule.action == RuleAction::Exclude => {
+            if let Some(max) = rule.max_size {
+                warn!("檔案 {} 超過大小限制（{} MB > {} MB)，跳過", path.display(), file_size as f64 / 1_048_576.0, max);
+            } else {
+                debug!(target: FILE_LOG_TARGET, "檔案 {} 符合規則 '{}'，跳過", path.display(), rule.spec);
+            }
+            Ok(false)
         }
+        _ => Ok(true),
     }
-    Ok(true)
 }
 
 // 過濾目錄，記錄跳過的目錄數
+// 只比對檔名時，輸入路徑以下任一層的名稱符合排除模式即排除，與比對完整路徑時排除目錄下所有檔案的效果一致
 fn filter_entry(
     entry: &jwalk::DirEntry<((), ())>,
+    input_path: &Path,
     exclude_set: &RegexSet,
+    match_on_name: bool,
     skipped_dirs: &mut u64,
 ) -> bool {
-    let path_str = entry.path().to_string_lossy().into_owned();
-    if exclude_set.is_match(&path_str) {
+    let path = entry.path();
+    let excluded = if match_on_name {
+        path.strip_prefix(input_path)
+            .unwrap_or(&path)
+            .components()
+            .any(|component| exclude_set.is_match(&component.as_os_str().to_string_lossy()))
+    } else {
+        exclude_set.is_match(&path.to_string_lossy())
+    };
+    if excluded {
         if entry.file_type().is_dir() {
             *skipped_dirs += 1;
         }
@@ -85,12 +295,34 @@ fn filter_entry(
     }
 }
 
+// 追蹤連結時不進入指向上層目錄的連結，避免無限循環
+fn skip_symlink_loops(dir: &Path, children: &mut [jwalk::Result<jwalk::DirEntry<((), ())>>]) {
+    for child in children.iter_mut().flatten() {
+        if !child.path_is_symlink() || child.read_children_path.is_none() {
+            continue;
+        }
+        let (Ok(target), Ok(parent)) = (fs::canonicalize(child.path()), fs::canonicalize(dir)) else {
+            continue;
+        };
+        if parent.starts_with(&target) {
+            warn!("符號連結指向上層目錄，不進入以避免循環：{} -> {}", child.path().display(), target.display());
+            child.read_children_path = None;
+        }
+    }
+}
+
 // 檔案蒐集器結構體，移除 pm 字段
 pub struct FileCollector {
     include_set: RegexSet,
     exclude_set: RegexSet,
     max_size: Option<f64>,
+    // `--rule` 與 `--max-size` 組成的規則
+    rules: FileRules,
     no_progress: bool,
+    // 模式只比對檔名，否則比對完整路徑
+    match_on_name: bool,
+    // 符號連結的處理方式：follow、store 或 skip
+    symlinks: String,
 }
 
 impl FileCollector {
@@ -104,30 +336,71 @@ impl FileCollector {
             include_set,
             exclude_set,
             max_size,
+            rules: FileRules::default().with_max_size(max_size),
             no_progress,
+            match_on_name: false,
+            symlinks: "skip".to_string(),
         }
     }
 
+    /// 指定 `--rule` 規則，`--max-size` 轉換的規則排在最後
+    pub fn with_rules(mut self, rules: FileRules) -> Self {
+        self.rules = rules.with_max_size(self.max_size);
+        self
+    }
+
+    /// 指定模式只比對檔名（需完整符合），預設比對完整路徑
+    pub fn match_file_name(mut self, enabled: bool) -> Self {
+        self.match_on_name = enabled;
+        self
+    }
+
+    /// 指定符號連結的處理方式：`follow` 進入連結的目錄並讀取連結的檔案（重複或循環的連結可能使檔案重複），
+    /// `store` 將連結本身列為檔案，`skip` 略過所有連結，預設為 `skip`
+    pub fn with_symlink_policy(mut self, symlinks: &str) -> Self {
+        self.symlinks = symlinks.to_string();
+        self
+    }
+
     pub fn collect_and_measure_files(
         &self,
         input_path: &Path,
         files: &mut Vec<PathBuf>,
+        mut sizes: Option<&mut Vec<usize>>,
+        pattern_matches: Option<&mut Vec<PatternMatchCount>>,
         measure_size: bool,
         pm: &crate::utils::utils::ProgressManager,
     ) -> io::Result<usize> {
         let mut total_size = 0;
         let mut skipped_dirs = 0;
+        let mut skipped_symlinks = 0;
+        let store_symlinks = self.symlinks == "store";
         let _ = std::time::Instant::now();
 
-        // 使用 jwalk 進行平行遍歷
+        // 使用 jwalk 進行平行遍歷，追蹤連結時循環的連結會產生錯誤
         let entries: Vec<_> = WalkDir::new(input_path)
             .skip_hidden(false)
+            .follow_links(self.symlinks == "follow")
+            .process_read_dir(|_, dir, _, children| skip_symlink_loops(dir, children))
             .parallelism(jwalk::Parallelism::RayonNewPool(4))
             .into_iter()
-            .filter(|e| e.as_ref().map_or(true, |e| filter_entry(e, &self.exclude_set, &mut skipped_dirs)))
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
+            .filter(|e| e.as_ref().map_or(true, |e| filter_entry(e, input_path, &self.exclude_set, self.match_on_name, &mut skipped_dirs)))
+            .filter_map(|e| e.map_err(|e| warn!("略過無法讀取的路徑：{}", e)).ok())
+            .filter(|e| {
+                let file_type = e.file_type();
+                if file_type.is_symlink() && !store_symlinks {
+                    debug!(target: FILE_LOG_TARGET, "略過符號連結：{}", e.path().display());
+                    skipped_symlinks += 1;
+                }
+                file_type.is_file() || (store_symlinks && file_type.is_symlink())
+            })
             .collect();
+        if skipped_symlinks > 0 {
+            info!("略過 {} 個符號連結（--symlinks {}）", skipped_symlinks, self.symlinks);
+        }
+        if let Some(pattern_matches) = pattern_matches {
+            *pattern_matches = self.count_pattern_matches(&entries);
+        }
 
         // 批次檢查檔案有效性
         let batch_size = 1000;
@@ -136,9 +409,11 @@ impl FileCollector {
                 .par_iter()
                 .filter_map(|entry| {
                     let path = entry.path();
-                    match is_file_valid(&path, &self.include_set, &self.exclude_set, self.max_size) {
+                    match is_file_valid(&path, &self.include_set, &self.exclude_set, &self.rules, self.match_on_name) {
                         Ok(true) => {
-                            let size = if measure_size {
+                            let size = if measure_size && store_symlinks {
+                                fs::symlink_metadata(&path).map(|m| m.len() as usize).unwrap_or(0)
+                            } else if measure_size {
                                 fs::metadata(&path).map(|m| m.len() as usize).unwrap_or(0)
                             } else {
                                 0
@@ -156,8 +431,11 @@ impl FileCollector {
 
             for (path, size) in batch_results {
                 files.push(path);
+                if let Some(sizes) = sizes.as_deref_mut() {
+                    sizes.push(size);
+                }
                 total_size += size;
-                if !self.no_progress && files.len() % 1000 == 0 {
+                if !self.no_progress && files.len().is_multiple_of(1000) {
                     pm.update(
                         files.len() as u64,
                         if measure_size { Some(total_size) } else { None },
@@ -167,7 +445,7 @@ impl FileCollector {
             }
         }
 
-        if !self.no_progress && files.len() % 1000 != 0 {
+        if !self.no_progress && !files.len().is_multiple_of(1000) {
             pm.update(
                 files.len() as u64,
                 if measure_size { Some(total_size) } else { None },
@@ -177,7 +455,7 @@ impl FileCollector {
 
         if files.is_empty() {
             pm.finish(0, None, skipped_dirs);
-            return Err(io::Error::new(io::ErrorKind::Other, "無有效檔案可壓縮"));
+            return Err(io::Error::other("無有效檔案可壓縮"));
         }
 
         pm.finish(files.len() as u64, if measure_size { Some(total_size) } else { None }, skipped_dirs);
@@ -189,6 +467,59 @@ impl FileCollector {
         );
         Ok(total_size)
     }
+
+    // 統計每個 include 模式符合的檔案數（已套用 exclude），沒有符合任何檔案的模式再以不分大小寫比對，
+    // 供診斷 `*.TXT` 之類打錯大小寫的模式
+    fn count_pattern_matches(&self, entries: &[jwalk::DirEntry<((), ())>]) -> Vec<PatternMatchCount> {
+        let patterns = self.include_set.patterns();
+        let mut counts = vec![PatternMatchCount::default(); patterns.len()];
+        // 預設的 `*` 符合所有檔案，不需逐一比對
+        if patterns.iter().all(|pattern| pattern == ".*" || pattern == "^(?:.*)$") {
+            counts.iter_mut().for_each(|count| count.matches = entries.len());
+            return counts;
+        }
+        let paths: Vec<String> = entries
+            .iter()
+            .map(|e| match_subject(&e.path(), self.match_on_name).into_owned())
+            .collect();
+        let matches = paths
+            .par_iter()
+            .fold(
+                || vec![0usize; patterns.len()],
+                |mut acc, path| {
+                    self.include_set.matches(path).iter().for_each(|i| acc[i] += 1);
+                    acc
+                },
+            )
+            .reduce(
+                || vec![0usize; patterns.len()],
+                |a, b| a.iter().zip(&b).map(|(x, y)| x + y).collect(),
+            );
+        for (count, matched) in counts.iter_mut().zip(matches) {
+            count.matches = matched;
+        }
+
+        let unmatched: Vec<usize> = (0..patterns.len()).filter(|&i| counts[i].matches == 0).collect();
+        if unmatched.is_empty() {
+            return counts;
+        }
+        let Ok(case_insensitive) = RegexSetBuilder::new(unmatched.iter().map(|&i| &patterns[i]))
+            .case_insensitive(true)
+            .build()
+        else {
+            return counts;
+        };
+        for (path, entry) in paths.iter().zip(entries) {
+            for j in case_insensitive.matches(path).iter() {
+                let count = &mut counts[unmatched[j]];
+                count.case_insensitive_matches += 1;
+                if count.case_insensitive_example.is_none() {
+                    count.case_insensitive_example = Some(entry.path());
+                }
+            }
+        }
+        counts
+    }
 }
 
 // 更新 collect_files
@@ -207,7 +538,7 @@ pub fn collect_files(
         no_progress,
     );
     let pm = crate::utils::utils::create_progress_bar(0, no_progress);
-    collector.collect_and_measure_files(path, files, false, &pm)?;
+    collector.collect_and_measure_files(path, files, None, None, false, &pm)?;
     Ok(())
 }
 
@@ -227,6 +558,6 @@ pub fn collect_and_measure_files(
     );
     let pm = crate::utils::utils::create_progress_bar(0, no_progress);
     let mut files = Vec::new();
-    let total_size = collector.collect_and_measure_files(input_path, &mut files, true, &pm)?;
+    let total_size = collector.collect_and_measure_files(input_path, &mut files, None, None, true, &pm)?;
     Ok((files, total_size))
 }
\ No newline at end of file
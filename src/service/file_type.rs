@@ -0,0 +1,83 @@
+use std::path::Path;
+use crate::utils::utils::download_mime_type;
+
+// 各檔案類型的圖示與強調色；圖示為 24×24 的線條 SVG，以 currentColor 繪製，隨強調色變化
+const FILE_TYPES: [(&str, &str, &str); 10] = [
+    ("folder", "#b7791f", "<path d=\"M3 6a1 1 0 0 1 1-1h5l2 2h9a1 1 0 0 1 1 1v10a1 1 0 0 1-1 1H4a1 1 0 0 1-1-1z\"/>"),
+    ("archive", "#8d6e63", "<rect x=\"4\" y=\"3\" width=\"16\" height=\"18\" rx=\"1\"/><path d=\"M12 3v2m0 2v2m0 2v2\"/><rect x=\"10\" y=\"13\" width=\"4\" height=\"4\"/>"),
+    ("image", "#2e7d32", "<rect x=\"3\" y=\"4\" width=\"18\" height=\"16\" rx=\"1\"/><circle cx=\"9\" cy=\"10\" r=\"2\"/><path d=\"M21 17l-5-5-9 8\"/>"),
+    ("audio", "#6a1b9a", "<path d=\"M9 18V5l11-2v13\"/><circle cx=\"6\" cy=\"18\" r=\"3\"/><circle cx=\"17\" cy=\"16\" r=\"3\"/>"),
+    ("video", "#ad1457", "<rect x=\"3\" y=\"5\" width=\"18\" height=\"14\" rx=\"1\"/><path d=\"M10 9l5 3-5 3z\"/>"),
+    ("pdf", "#c62828", "<path d=\"M6 2h9l5 5v15H6z\"/><path d=\"M15 2v5h5\"/><path d=\"M9 17h6M9 13h6\"/>"),
+    ("document", "#1565c0", "<path d=\"M6 2h9l5 5v15H6z\"/><path d=\"M15 2v5h5\"/><path d=\"M9 12h6M9 15h6M9 18h4\"/>"),
+    ("data", "#00838f", "<path d=\"M8 4H7a2 2 0 0 0-2 2v4l-2 2 2 2v4a2 2 0 0 0 2 2h1M16 4h1a2 2 0 0 1 2 2v4l2 2-2 2v4a2 2 0 0 1-2 2h-1\"/>"),
+    ("text", "#546e7a", "<path d=\"M6 2h9l5 5v15H6z\"/><path d=\"M15 2v5h5\"/><path d=\"M9 12h6M9 15h6\"/>"),
+    ("other", "#607d8b", "<path d=\"M6 2h9l5 5v15H6z\"/><path d=\"M15 2v5h5\"/>"),
+];
+
+/// 依來源判斷檔案類型：目錄為 `folder`，檔案依副檔名推斷的 MIME 類型分類
+/// # 參數
+/// - path: 來源檔案或目錄
+/// # 回傳
+/// - 檔案類型名稱，無法分類時為 `other`
+pub fn file_kind(path: &Path) -> &'static str {
+    if path.is_dir() {
+        return "folder";
+    }
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let mime = download_mime_type("none", "none", &name);
+    match mime.split_once('/') {
+        Some(("image", _)) => "image",
+        Some(("audio", _)) => "audio",
+        Some(("video", _)) => "video",
+        _ if mime == "application/pdf" => "pdf",
+        _ if matches!(mime, "application/zip" | "application/gzip" | "application/x-tar" | "application/x-7z-compressed") => "archive",
+        _ if mime.starts_with("application/vnd.") || mime == "application/msword" => "document",
+        _ if matches!(mime, "application/json" | "application/xml" | "application/yaml" | "text/csv") => "data",
+        Some(("text", _)) => "text",
+        _ => "other",
+    }
+}
+
+// 檔案類型的強調色與圖示路徑，未知的類型視為 other
+fn lookup(kind: &str) -> (&'static str, &'static str) {
+    let (_, color, paths) = FILE_TYPES.iter().find(|(name, _, _)| *name == kind).unwrap_or(&FILE_TYPES[FILE_TYPES.len() - 1]);
+    (color, paths)
+}
+
+/// 檔案類型的強調色
+pub fn file_kind_color(kind: &str) -> &'static str {
+    lookup(kind).0
+}
+
+/// 以內嵌 SVG 繪製的檔案類型圖示，顏色為類型的強調色；圖示僅供辨識，對輔助技術隱藏
+/// # 參數
+/// - kind: file_kind 返回的檔案類型
+/// # 回傳
+/// - 帶有 `file-icon` 類別的 span 標籤
+pub fn file_kind_icon(kind: &str) -> String {
+    let (color, paths) = lookup(kind);
+    format!(
+        "<span class=\"file-icon file-{kind}\" style=\"color: {color}\" aria-hidden=\"true\">\
+         <svg viewBox=\"0 0 24 24\" width=\"20\" height=\"20\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"1.8\" \
+         stroke-linecap=\"round\" stroke-linejoin=\"round\">{paths}</svg></span>",
+    )
+}
+
+/// 頁面依檔案類型加上的樣式：容器頂端的強調色邊框與圖示的排版
+pub fn file_kind_style(kind: &str) -> String {
+    format!(
+        r#"<style>
+        .container {{
+            border-top: 6px solid {color};
+        }}
+        .file-icon {{
+            display: inline-block;
+            vertical-align: text-bottom;
+            margin-right: 6px;
+            line-height: 0;
+        }}
+    </style>"#,
+        color = file_kind_color(kind),
+    )
+}
@@ -0,0 +1,226 @@
+use std::io;
+use std::sync::Arc;
+use base64::{engine::general_purpose, Engine};
+use chrono::Local;
+use serde::Serialize;
+use crate::models::html::{ConversionMetadata, HtmlTemplateData};
+use crate::service::encoding::{payload_encoder, standard_payload_text};
+use crate::service::html::{escape_html, fill_placeholders, generate_html_content, unescape_html};
+use crate::service::i18n::messages;
+use crate::service::plugin::PluginRegistry;
+use crate::service::schema::{read_metadata, FORMAT_HEADER_NAME};
+use crate::utils::random::rng_for;
+use crate::service::traits::i_plugin::{OutputRenderer, RenderContext};
+
+/// 內建的輸出格式，`html` 由 HtmlService 直接寫出，其餘由對應的渲染器產生
+pub const OUTPUT_FORMATS: [&str; 3] = ["html", "mhtml", "json"];
+
+// MIME 規範的每行長度上限（不含換行）
+const MIME_LINE_LEN: usize = 76;
+
+/// 依格式名稱取得渲染器，已註冊的插件可覆寫同名的內建格式
+/// # 參數
+/// - name: `--format` 指定的格式名稱
+/// - plugins: 本次轉換使用的插件註冊表
+/// # 回傳
+/// - 找到對應渲染器時返回 Some，`html` 或未知格式返回 None
+pub fn output_renderer(name: &str, plugins: &PluginRegistry) -> Option<Arc<dyn OutputRenderer>> {
+    plugins.renderer(name).or_else(|| match name {
+        "mhtml" => Some(Arc::new(MhtmlRenderer) as Arc<dyn OutputRenderer>),
+        "json" => Some(Arc::new(JsonRenderer) as Arc<dyn OutputRenderer>),
+        _ => None,
+    })
+}
+
+/// 確認渲染器可在低記憶體模式下使用：低記憶體模式不串接完整的編碼資料，
+/// 插件渲染器取得的 `zip_base64` 會是空字串，因此只允許內建格式
+/// # 參數
+/// - name: `--format` 指定的格式名稱
+/// - plugins: 本次轉換使用的插件註冊表
+/// - low_memory: 是否啟用 `--low-memory`
+/// # 回傳
+/// - 可使用時返回 Ok，插件提供的格式搭配低記憶體模式時返回 IO 錯誤
+pub fn check_low_memory_renderer(name: &str, plugins: &PluginRegistry, low_memory: bool) -> io::Result<()> {
+    if low_memory && plugins.renderer(name).is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("插件提供的輸出格式 '{}' 需要完整的編碼資料，無法搭配 --low-memory", name),
+        ));
+    }
+    Ok(())
+}
+
+/// 所有可用的輸出格式名稱，內建格式在前
+pub fn output_format_names(plugins: &PluginRegistry) -> Vec<String> {
+    let mut names: Vec<String> = OUTPUT_FORMATS.iter().map(|name| name.to_string()).collect();
+    for name in plugins.renderer_names() {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// MHTML 輸出：以 `multipart/related` 將頁面與 ZIP 附件包成單一 .mht 檔，
+/// 供不接受 .html 附件的郵件閘道使用；多數檢視器不執行 MHTML 內的腳本，
+/// 因此頁面不嵌入資料，改以附件提供，收件者可將副檔名改為 .eml 以郵件程式取出
+pub struct MhtmlRenderer;
+
+impl OutputRenderer for MhtmlRenderer {
+    fn name(&self) -> &str {
+        "mhtml"
+    }
+
+    fn extension(&self) -> &str {
+        "mht"
+    }
+
+    fn render(&self, context: &RenderContext) -> io::Result<Vec<u8>> {
+        let data = context.data;
+        let msgs = messages(data.lang);
+        let page_name = format!("{}.html", data.file_name);
+        let instructions = format!(
+            "{}{}",
+            data.instructions,
+            fill_placeholders(msgs.mhtml_note, &[("DOWNLOAD_ZIP_NAME", &escape_html(data.download_zip_name))])
+        );
+        let page = generate_html_content(context.template, &HtmlTemplateData {
+            zip_base64: "",
+            payload_chunks: "",
+            direct_download: "",
+            instructions: &instructions,
+            ..*data
+        });
+
+        let boundary = format!("----=_file_to_html_{:016x}", rng_for("mhtml-boundary", &[page_name.as_bytes()]).next_u64());
+        let mut out = String::new();
+        out.push_str("From: <Saved by file_to_html>\r\n");
+        // 標題已經過 HTML 跳脫，放入郵件標頭前還原
+        out.push_str(&format!("Subject: {}\r\n", encoded_word(&unescape_html(data.title))));
+        out.push_str(&format!("Date: {}\r\n", Local::now().to_rfc2822()));
+        out.push_str(&format!("Snapshot-Content-Location: {}\r\n", content_location(&page_name)));
+        if data.format_version >= 2 {
+            out.push_str(&format!("{}: {}\r\n", FORMAT_HEADER_NAME, data.format_version));
+        }
+        out.push_str("MIME-Version: 1.0\r\n");
+        out.push_str(&format!(
+            "Content-Type: multipart/related;\r\n\ttype=\"text/html\";\r\n\tboundary=\"{}\"\r\n\r\n",
+            boundary
+        ));
+
+        out.push_str(&format!("--{}\r\n", boundary));
+        out.push_str("Content-Type: text/html; charset=\"utf-8\"\r\n");
+        out.push_str("Content-Transfer-Encoding: base64\r\n");
+        out.push_str(&format!("Content-Location: {}\r\n\r\n", content_location(&page_name)));
+        push_base64_lines(&mut out, page.as_bytes());
+
+        out.push_str(&format!("--{}\r\n", boundary));
+        out.push_str("Content-Type: application/zip\r\n");
+        out.push_str("Content-Transfer-Encoding: base64\r\n");
+        out.push_str(&format!("Content-Location: {}\r\n", content_location(data.download_zip_name)));
+        out.push_str(&format!(
+            "Content-Disposition: attachment; filename*=UTF-8''{}\r\n\r\n",
+            urlencoding::encode(data.download_zip_name)
+        ));
+        push_base64_lines(&mut out, context.payload);
+        out.push_str(&format!("--{}--\r\n", boundary));
+        Ok(out.into_bytes())
+    }
+}
+
+/// JSON 輸出：不產生頁面，只輸出 ZIP 名稱、大小、SHA-256、轉換資訊與依 `--payload-encoding` 編碼的完整資料，
+/// 供其他程式直接取用；資料一律為未經 gzip 整體壓縮的 ZIP，Base85 為標準 Ascii85，`sha256` 為 ZIP 本身的雜湊
+pub struct JsonRenderer;
+
+// JSON 輸出的內容
+#[derive(Serialize)]
+struct JsonPayload<'a> {
+    name: &'a str,
+    size: usize,
+    sha256: &'a str,
+    encoding: &'a str,
+    payload: String,
+    // 轉換資訊，輸出格式版本 1 時省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<ConversionMetadata>,
+}
+
+impl OutputRenderer for JsonRenderer {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn render(&self, context: &RenderContext) -> io::Result<Vec<u8>> {
+        let data = context.data;
+        let encoder = payload_encoder(data.payload_encoding)?;
+        let mut out = serde_json::to_vec_pretty(&JsonPayload {
+            name: data.download_zip_name,
+            size: context.payload.len(),
+            sha256: data.payload_sha256,
+            encoding: encoder.name(),
+            payload: standard_payload_text(encoder.name(), encoder.encode(context.payload)),
+            metadata: read_metadata(data.conversion_metadata)?,
+        })
+        .map_err(io::Error::other)?;
+        out.push(b'\n');
+        Ok(out)
+    }
+}
+
+// 非 ASCII 的標頭值以 RFC 2047 編碼
+fn encoded_word(text: &str) -> String {
+    if text.is_ascii() && !text.contains(['\r', '\n']) {
+        text.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", general_purpose::STANDARD.encode(text))
+    }
+}
+
+fn content_location(name: &str) -> String {
+    format!("file_to_html:///{}", urlencoding::encode(name))
+}
+
+fn push_base64_lines(out: &mut String, data: &[u8]) {
+    let encoded = general_purpose::STANDARD.encode(data);
+    for line in encoded.as_bytes().chunks(MIME_LINE_LEN) {
+        // Base64 字元皆為 ASCII，可安全轉回字串
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct PluginRenderer;
+
+    impl OutputRenderer for PluginRenderer {
+        fn name(&self) -> &str {
+            "plugin"
+        }
+
+        fn extension(&self) -> &str {
+            "txt"
+        }
+
+        fn render(&self, context: &RenderContext) -> io::Result<Vec<u8>> {
+            Ok(context.data.zip_base64.as_bytes().to_vec())
+        }
+    }
+
+    #[test]
+    fn low_memory_rejects_plugin_renderers() {
+        let mut plugins = PluginRegistry::new();
+        plugins.register_renderer(Arc::new(PluginRenderer));
+        assert!(check_low_memory_renderer("plugin", &plugins, true).is_err());
+        assert!(check_low_memory_renderer("plugin", &plugins, false).is_ok());
+        for name in OUTPUT_FORMATS {
+            assert!(check_low_memory_renderer(name, &plugins, true).is_ok(), "{} 不使用 zip_base64", name);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::{self, Read, Write};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+/// 可指定的頁面資料整體壓縮方式
+pub const PAYLOAD_COMPRESSIONS: [&str; 3] = ["none", "gzip", "zstd"];
+
+/// 未指定 `--payload-compression-level` 時 zstd 使用的壓縮等級
+pub const DEFAULT_ZSTD_LEVEL: i32 = 19;
+
+/// 將頁面資料整體以 gzip 壓縮，頁面腳本以 `DecompressionStream('gzip')` 還原
+/// # 參數
+/// - data: 原始資料（ZIP 數據）
+/// # 回傳
+/// - 成功時返回 gzip 格式的數據，失敗時返回 IO 錯誤
+pub fn gzip_payload(data: &[u8]) -> io::Result<Vec<u8>> {
+    gzip_payload_level(data, Compression::best())
+}
+
+fn gzip_payload_level(data: &[u8], level: Compression) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 2), level);
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// 依指定方式整體壓縮頁面資料
+/// # 參數
+/// - data: 原始資料（ZIP 數據）
+/// - method: `gzip` 或 `zstd`，其他值不壓縮
+/// - level: 壓縮等級，gzip 為 0–9、zstd 為 1–22，未指定時 gzip 使用最高等級、zstd 使用 DEFAULT_ZSTD_LEVEL
+/// # 回傳
+/// - 成功時返回壓縮後的數據，失敗時返回 IO 錯誤
+pub fn compress_payload(data: &[u8], method: &str, level: Option<i32>) -> io::Result<Vec<u8>> {
+    match method {
+        "gzip" => gzip_payload_level(data, level.map_or(Compression::best(), |level| Compression::new(level as u32))),
+        "zstd" => zstd::bulk::compress(data, level.unwrap_or(DEFAULT_ZSTD_LEVEL)),
+        _ => Ok(data.to_vec()),
+    }
+}
+
+/// 還原以 gzip 整體壓縮的頁面資料
+/// # 參數
+/// - data: gzip 格式的數據
+/// # 回傳
+/// - 成功時返回原始資料，內容不是有效的 gzip 數據時返回 IO 錯誤
+pub fn gunzip_payload(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoder = GzDecoder::new(data);
+    let mut output = Vec::with_capacity(data.len() * 2);
+    decoder.read_to_end(&mut output)?;
+    Ok(output)
+}
+
+/// 還原整體壓縮的頁面資料
+/// # 參數
+/// - data: 壓縮後的數據
+/// - method: `gzip` 或 `zstd`，其他值視為未壓縮
+/// # 回傳
+/// - 成功時返回原始資料，內容與壓縮方式不符時返回 IO 錯誤
+pub fn decompress_payload(data: Vec<u8>, method: &str) -> io::Result<Vec<u8>> {
+    match method {
+        "gzip" => gunzip_payload(&data),
+        "zstd" => zstd::stream::decode_all(data.as_slice()),
+        _ => Ok(data),
+    }
+}
+
+/// 檢查整體壓縮等級是否在壓縮方式的範圍內
+/// # 參數
+/// - method: 頁面資料的整體壓縮方式
+/// - level: `--payload-compression-level` 設定
+/// # 回傳
+/// - 範圍正確或未指定時返回 Ok，否則返回 IO 錯誤
+pub fn validate_payload_compression_level(method: &str, level: Option<i32>) -> io::Result<()> {
+    let Some(level) = level else {
+        return Ok(());
+    };
+    let range = match method {
+        "gzip" => 0..=9,
+        "zstd" => 1..=22,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--payload-compression-level 需搭配 --payload-compression gzip 或 zstd"
+            ));
+        }
+    };
+    if !range.contains(&level) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} 的壓縮等級需介於 {} 到 {}，卻是 {}", method, range.start(), range.end(), level)
+        ));
+    }
+    Ok(())
+}
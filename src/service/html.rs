@@ -1,21 +1,425 @@
-use std::fs;
-use std::io::{self, BufWriter, Write};
-use std::path::Path;
-use base64::{engine::general_purpose, write::EncoderWriter};
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use base64::{engine::general_purpose, Engine};
+use chrono::Local;
+use zip::AesMode;
+use qrcodegen::{QrCode, QrCodeEcc};
 use log::{info, warn};
-use crate::models::html::{HtmlGenerateInput, HtmlGenerateOutput};
+use sha2::{Digest, Sha256};
+use pulldown_cmark::{html::push_html, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use crate::models::html::{
+    ArchiveMeta, ConversionMetadata, HtmlGenerateInput, HtmlGenerateOutput, HtmlTemplateData, PasswordDisplay, PasswordDisplayOptions,
+    RenderOptions, StagedPage,
+};
+use crate::models::conversion::Tags;
+use crate::models::report::ConversionReport;
+use crate::models::zip::{ByteRange, ZipEntryInfo};
+use crate::config::config::{parse_data_uri_threshold, parse_file_mode, validate_minify};
+use crate::config::ports::AppConfig;
+use crate::service::classification::{classification_marking, CLASSIFICATIONS};
+use crate::service::file_type::{file_kind, file_kind_color, file_kind_icon, file_kind_style};
+use crate::service::crypto::{encrypt_with_method, is_browser_encryption, password_verifier, PBKDF2_ITERATIONS, STREAM_CHUNK_SIZE};
+use crate::service::encoding::{payload_encoder, PAYLOAD_ENCODINGS, wrap_lines, Base64Encoder, BASE64_LINE_WIDTH};
+use crate::service::format::{check_low_memory_renderer, output_renderer};
+use crate::service::gzip::{compress_payload, PAYLOAD_COMPRESSIONS};
+use crate::service::schema::{format_meta, metadata_comment};
+use crate::service::i18n::{js_messages_json, messages, Messages, SUPPORTED_LANGS};
+use crate::service::sink::{FileSink, OutputBatch};
+use crate::service::spill::{summarize, SpillBuffer, SpillFile};
+use crate::service::verify::{archive_entry_hashes, hash_reader};
+use crate::service::zip::{archive_extension, split_payload, ARCHIVE_FORMATS};
+use crate::service::traits::i_plugin::{InstructionContext, RenderContext};
 use crate::service::traits::i_service::HtmlServiceTrait;
-use crate::utils::utils::{format_file_size, get_file_name};
+use crate::service::traits::i_encoder::PayloadEncoder;
+use crate::service::traits::i_sink::OutputSink;
+use crate::utils::utils::{apply_name_pattern, download_mime_type, get_file_name, parse_expires};
+use crate::utils::locale::LocaleFormat;
+use crate::service::a11y::check_page;
+use crate::service::pwa::{icon_svg, manifest_name, pwa_head, service_worker, web_manifest, ICON_NAME, SERVICE_WORKER_NAME};
+use crate::utils::log_sampler::FILE_LOG_TARGET;
 
 const HTML_TEMPLATE: &str = include_str!("../../assets/template/html_template.html");
+const WEBCRYPTO_TEMPLATE: &str = include_str!("../../assets/template/html_webcrypto_template.html");
+const TREE_TEMPLATE: &str = include_str!("../../assets/template/html_tree_template.html");
+const NO_JS_TEMPLATE: &str = include_str!("../../assets/template/html_nojs_template.html");
+const SPLIT_TEMPLATE: &str = include_str!("../../assets/template/html_split_template.html");
+const INDEX_TEMPLATE: &str = include_str!("../../assets/template/index_template.html");
+const RESTORE_TEMPLATE: &str = include_str!("../../assets/template/restore_template.html");
+const DECODER_TEMPLATE: &str = include_str!("../../assets/template/decoder_template.html");
+const KEY_SHEET_TEMPLATE: &str = include_str!("../../assets/template/key_sheet_template.html");
+const COMMON_SCRIPT: &str = include_str!("../../assets/template/common_script.js");
+const CODEC_SCRIPT: &str = include_str!("../../assets/template/codec_script.js");
+const CRYPTO_SCRIPT: &str = include_str!("../../assets/template/crypto_script.js");
+const ARGON2_SCRIPT: &str = include_str!("../../assets/template/argon2.js");
+const ZSTD_SCRIPT: &str = include_str!("../../assets/template/zstd.js");
+
+/// `--viewer` 在輸出目錄寫出的離線檢視頁檔名
+pub const VIEWER_NAME: &str = "viewer.html";
+/// `--no-js` 頁面的資料連結長度上限（位元組），Chromium 不接受超過 2 MiB 的網址，超過時改為手動還原的 Base64 文字
+pub const NO_JS_DATA_URI_LIMIT: usize = 2 * 1024 * 1024;
+
+// 依目標大小重新分段的最多次數
+const MAX_FIT_ATTEMPTS: usize = 5;
+// 重新分段時額外保留的位元組數
+const FIT_MARGIN: usize = 1024;
 
 /// HTML 服務，負責生成 HTML 檔案並實現 HtmlServiceTrait
-pub struct HtmlService;
+pub struct HtmlService {
+    sink: Box<dyn OutputSink>,
+}
 
 impl HtmlService {
-    /// 創建新的 HtmlService 實例
+    /// 創建新的 HtmlService 實例，產出物寫入輸出目錄
     pub fn new() -> Self {
-        HtmlService
+        HtmlService { sink: Box::new(FileSink) }
+    }
+
+    /// 創建使用指定輸出目的地的 HtmlService 實例
+    pub fn with_sink(sink: Box<dyn OutputSink>) -> Self {
+        HtmlService { sink }
+    }
+
+    /// 將呼叫端已建立的封存檔嵌入頁面，不經過檔案蒐集與壓縮，供其他 Rust 工具只使用本套件產生頁面的部分；
+    /// 產出物寫入此服務的輸出目的地，以 `HtmlService::with_sink` 搭配 `MemorySink` 時可直接取得頁面內容
+    /// # 參數
+    /// - archive: 封存檔數據
+    /// - meta: 封存檔的名稱、格式、原始大小與項目清單
+    /// - options: 頁面選項，未列出的設定使用命令列的預設值；ZIP 的 AES 加密（aes128、aes192、aes256）不會加密封存檔，
+    ///   呼叫端需先以 `options.password` 與相同強度加密
+    /// # 回傳
+    /// - 成功時返回產出頁面的位置與大小，選項無效、AES 加密的封存檔未加密或密碼不符、加密或寫入失敗時返回 IO 錯誤
+    pub fn render(&self, archive: Vec<u8>, meta: ArchiveMeta, options: RenderOptions) -> io::Result<HtmlGenerateOutput> {
+        for (name, value, allowed) in [
+            ("archive_format", &meta.archive_format, &ARCHIVE_FORMATS[..]),
+            ("encryption_method", &options.encryption_method, &["aes128", "aes192", "aes256", "webcrypto", "webcrypto-chunked"][..]),
+            ("view", &options.view, &["archive", "tree"][..]),
+            ("lang", &options.lang, &SUPPORTED_LANGS[..]),
+            ("payload_encoding", &options.payload_encoding, &PAYLOAD_ENCODINGS[..]),
+            ("payload_compression", &options.payload_compression, &PAYLOAD_COMPRESSIONS[..]),
+        ] {
+            if !allowed.contains(&value.as_str()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("無效的 {} 值 '{}'，可用值：{}", name, value, allowed.join("、")),
+                ));
+            }
+        }
+        if let Some(classification) = options.classification.as_deref().filter(|c| !CLASSIFICATIONS.contains(c)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("無效的 classification 值 '{}'，可用值：{}", classification, CLASSIFICATIONS.join("、")),
+            ));
+        }
+        validate_minify(options.minify)?;
+        // 樹狀檢視依項目位移直接讀取 ZIP，需要呼叫端提供項目清單
+        if options.view == "tree" && (meta.archive_format != "zip" || meta.entries.is_empty()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "樹狀檢視需要 ZIP 封存檔及其項目清單（ArchiveMeta.entries）",
+            ));
+        }
+
+        let config = AppConfig::default();
+        let payload_compression = if options.password.is_some() && options.payload_compression != "none" {
+            warn!("加密後的資料無法再壓縮，已忽略 payload_compression {}", options.payload_compression);
+            "none".to_string()
+        } else {
+            options.payload_compression
+        };
+        let zip_buffer = match options.password.as_deref() {
+            Some(password) if is_browser_encryption(&options.encryption_method) => {
+                encrypt_with_method(&archive, password, &options.encryption_method)?
+            }
+            Some(password) => {
+                check_pre_encrypted(&archive, &meta.archive_format, &options.encryption_method, password)?;
+                archive
+            }
+            None => archive,
+        };
+        self.generate_html(HtmlGenerateInput {
+            zip_buffer,
+            spill: None,
+            input_path: PathBuf::from(&meta.name),
+            output_dir: options.output_dir,
+            layer: if meta.archive_format == "none" { "none" } else { "single" }.to_string(),
+            encryption_method: options.encryption_method,
+            password: options.password,
+            inner_password: None,
+            display_password: options.display_password,
+            password_qr: config.password_qr,
+            total_size: meta.source_size,
+            view: options.view,
+            entries: meta.entries,
+            format: "html".to_string(),
+            split_size: options.split_size,
+            lang: options.lang,
+            extra_css: None,
+            extra_js: None,
+            key_name: config.key_name,
+            title: options.title,
+            logo: None,
+            footer: options.footer,
+            message: options.message,
+            data_uri_threshold: parse_data_uri_threshold(&config.data_uri_threshold)?,
+            wrap_base64: config.wrap_base64,
+            output_extension: config.output_extension,
+            expires: None,
+            payload_encoding: options.payload_encoding,
+            payload_compression,
+            payload_compression_level: config.payload_compression_level,
+            max_html_size: ((config.max_html_size * 1024.0 * 1024.0) as usize).max(1),
+            oversize_policy: config.oversize_policy,
+            password_verifier: config.password_verifier,
+            payload_mode: config.payload_mode,
+            minify: options.minify,
+            low_memory: false,
+            password_hint: None,
+            ascii_names: config.ascii_names,
+            pwa: false,
+            no_js: options.no_js,
+            a11y_strict: false,
+            archive_format: meta.archive_format,
+            key_file_mode: parse_file_mode(&config.key_file_mode)?,
+            target_size: None,
+            classification: options.classification,
+            key_sheet: false,
+            slices: Vec::new(),
+            tags: options.tags,
+            format_version: config.format_version,
+        })
+    }
+}
+
+impl HtmlService {
+    // 一次寫出產出物的所有檔案，返回各檔案的位置以及密碼檔、密碼單的位置
+    fn commit_batch(&self, batch: OutputBatch, password: &PasswordDisplay) -> io::Result<(Vec<String>, Option<String>, Option<String>)> {
+        let paths = batch.commit(self.sink.as_ref())?;
+        let key_file_path = password.key_file.map(|i| paths[i].clone());
+        if let Some(path) = &key_file_path {
+            info!(target: FILE_LOG_TARGET, "密碼已儲存至：{}", path);
+        }
+        let key_sheet_path = password.key_sheet.map(|i| paths[i].clone());
+        if let Some(path) = &key_sheet_path {
+            info!(target: FILE_LOG_TARGET, "密碼單已儲存至：{}", path);
+        }
+        Ok((paths, key_file_path, key_sheet_path))
+    }
+
+    // 將數據切成多個分段頁面，每頁只嵌入自己的分段，並可在任一頁合併所有分段；
+    // 設定目標大小時先產生所有分段，最大的分段超過目標就依超出量縮小每段資料再重新產生
+    fn generate_split_html(
+        &self,
+        input: &HtmlGenerateInput,
+        data: &HtmlTemplateData,
+        payload: &[u8],
+        part_size: usize,
+        mut batch: OutputBatch,
+        password: &PasswordDisplay,
+    ) -> io::Result<HtmlGenerateOutput> {
+        let encoder = payload_encoder(&input.payload_encoding)?;
+        let mut part_size = part_size;
+        let mut pages = render_split_pages(data, payload, part_size, encoder)?;
+        if let Some(target) = input.target_size {
+            for _ in 0..MAX_FIT_ATTEMPTS {
+                let largest = pages.iter().map(String::len).max().unwrap_or(0);
+                if largest <= target {
+                    break;
+                }
+                // 多扣除一段資料塊標籤的餘裕，避免分段數增加時說明文字變長又再次超出
+                let shrink = encoder.raw_capacity(largest - target + FIT_MARGIN).max(1);
+                if shrink >= part_size {
+                    return Err(target_too_small(target));
+                }
+                part_size -= shrink;
+                info!(target: FILE_LOG_TARGET, "分段頁面 {} 位元組超過目標大小 {} 位元組，改為每段 {} 位元組重新分段", largest, target, part_size);
+                pages = render_split_pages(data, payload, part_size, encoder)?;
+            }
+            let largest = pages.iter().map(String::len).max().unwrap_or(0);
+            if largest > target {
+                warn!("分段頁面仍有 {} 位元組，超過目標大小 {} 位元組：{}", largest, target, data.file_name);
+            }
+        }
+
+        if input.a11y_strict {
+            for (i, page) in pages.iter().enumerate() {
+                check_accessibility(page.as_bytes(), &format!("{}.part{}", data.file_name, i + 1), true)?;
+            }
+        }
+
+        let part_count = pages.len();
+        let mut staged = Vec::with_capacity(part_count);
+        for (i, html_content) in pages.into_iter().enumerate() {
+            staged.push(stage_html_page(
+                &mut batch,
+                html_content,
+                &format!("{}.part{}", data.file_name, i + 1),
+                data.output_extension,
+                input.minify,
+            )?);
+        }
+        if input.pwa {
+            stage_manifest(&mut batch, input, data.file_name, &format!("{}.part1.{}", data.file_name, data.output_extension))?;
+        }
+
+        let (paths, key_file_path, key_sheet_path) = self.commit_batch(batch, password)?;
+        let mut parts = Vec::with_capacity(part_count);
+        let mut page_sha256 = Vec::with_capacity(part_count);
+        let mut html_size = 0;
+        for (i, page) in staged.into_iter().enumerate() {
+            info!(target: FILE_LOG_TARGET, "生成分段 {}/{}：{}，大小：{} 位元組", i + 1, part_count, paths[page.index], page.size);
+            html_size += page.size;
+            page_sha256.push(page.sha256);
+            parts.push(paths[page.index].clone());
+        }
+
+        Ok(HtmlGenerateOutput {
+            html_file_path: parts[0].clone(),
+            key_file_path,
+            key_sheet_path,
+            html_size,
+            parts,
+            page_sha256,
+            payload_file_path: None,
+        })
+    }
+}
+
+// ZIP 的 AES 加密由 render 的呼叫端預先加密封存檔，確認每個檔案項目都以指定強度的 AES 加密，且密碼可解開；
+// 7z 只支援 AES-256，確認不提供密碼時無法讀取、以密碼可讀取所有項目
+fn check_pre_encrypted(archive: &[u8], archive_format: &str, method: &str, password: &str) -> io::Result<()> {
+    let wrong_password = || io::Error::new(io::ErrorKind::InvalidInput, "無法以 password 開啟封存檔，請確認封存檔以相同的密碼加密");
+    match archive_format {
+        "zip" => {
+            let expected = match method {
+                "aes128" => AesMode::Aes128,
+                "aes192" => AesMode::Aes192,
+                _ => AesMode::Aes256,
+            };
+            let mut zip = zip::ZipArchive::new(io::Cursor::new(archive))?;
+            let mut password_checked = false;
+            for i in 0..zip.len() {
+                if zip.by_index_raw(i)?.is_dir() {
+                    continue;
+                }
+                match zip.get_aes_verification_key_and_salt(i)? {
+                    Some(info) if info.aes_mode == expected => {}
+                    _ => {
+                        let name = zip.by_index_raw(i)?.name().to_string();
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("封存檔的項目 {} 未以 {} 加密，{} 需由呼叫端預先加密封存檔", name, method, method),
+                        ));
+                    }
+                }
+                if !password_checked {
+                    zip.by_index_decrypt(i, password.as_bytes()).map_err(|_| wrong_password())?;
+                    password_checked = true;
+                }
+            }
+            Ok(())
+        }
+        "7z" if method == "aes256" => {
+            if archive_entry_hashes(archive, "7z", None).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "封存檔未加密，aes256 需由呼叫端預先以密碼加密 7z 封存檔",
+                ));
+            }
+            archive_entry_hashes(archive, "7z", Some(password)).map_err(|_| wrong_password())?;
+            Ok(())
+        }
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} 封存格式不支援 {} 加密，請改用 webcrypto 或不設定密碼", archive_format, method),
+        )),
+    }
+}
+
+// 依分段大小產生每個分段頁面的內容
+fn render_split_pages(
+    data: &HtmlTemplateData,
+    payload: &[u8],
+    part_size: usize,
+    encoder: &dyn PayloadEncoder,
+) -> io::Result<Vec<String>> {
+    let chunks = split_payload(payload, part_size)?;
+    let part_count = chunks.len();
+    let msgs = messages(data.lang);
+    let mut instructions = generate_split_instructions(
+        part_count,
+        data.download_zip_name,
+        data.browser_decrypt,
+        data.output_extension,
+        msgs,
+    );
+    // 整體壓縮時分段串接後為 .gz 或 .zst 檔
+    let part_suffix = match data.payload_compression {
+        "gzip" => {
+            instructions.push_str(msgs.gzip_note);
+            ".gz"
+        }
+        "zstd" => {
+            instructions.push_str(msgs.zstd_note);
+            ".zst"
+        }
+        _ => "",
+    };
+    let mut pages = Vec::with_capacity(part_count);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let part_index = (i + 1).to_string();
+        let part_file_name = format!("{}{}.{:03}", data.download_zip_name, part_suffix, i + 1);
+        let part_values = [("PART_INDEX", part_index.as_str()), ("PART_COUNT", &part_count.to_string())];
+        let part_heading = fill_placeholders(msgs.part_heading, &part_values);
+        let template = fill_placeholders(SPLIT_TEMPLATE, &[
+            part_values[0],
+            part_values[1],
+            ("PART_FILE_NAME_JS", &js_string_literal(&part_file_name)),
+            ("PART_HEADING", &part_heading),
+        ]);
+        let part_chunks = encode_payload(chunk, encoder);
+        let part_base64 = part_chunks.concat();
+        let part_tags = payload_chunk_tags(&part_chunks, encoder.name());
+        pages.push(generate_html_content(&template, &HtmlTemplateData {
+            zip_base64: &part_base64,
+            payload_chunks: &part_tags,
+            instructions: &instructions,
+            ..*data
+        }));
+    }
+    Ok(pages)
+}
+
+// 目標大小不足以容納頁面固定內容時的錯誤
+fn target_too_small(target: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("目標大小 {} 位元組不足以容納頁面本身的內容，請提高 --target-size", target)
+    )
+}
+
+// 自訂渲染器輸出時視為 HTML 頁面、需通過無障礙檢查的副檔名
+const HTML_EXTENSIONS: [&str; 3] = ["html", "htm", "xhtml"];
+
+// 檢查頁面的無障礙結構，strict 時有問題即返回錯誤，否則只記錄警告
+fn check_accessibility(html: &[u8], name: &str, strict: bool) -> io::Result<()> {
+    let issues = check_page(&String::from_utf8_lossy(html));
+    if issues.is_empty() {
+        return Ok(());
+    }
+    if strict {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("頁面未通過無障礙檢查（--a11y-strict）：{}：{}", name, issues.join("；"))
+        ));
+    }
+    warn!("頁面未通過無障礙檢查：{}：{}", name, issues.join("；"));
+    Ok(())
+}
+
+impl Default for HtmlService {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -25,50 +429,495 @@ impl HtmlServiceTrait for HtmlService {
     /// - input: HTML 生成的輸入參數，包含 ZIP 數據、路徑、密碼等
     /// # 回傳
     /// - 成功時返回生成的 HTML 檔案路徑，失敗時返回 IO 錯誤
-    fn generate_html(&self, input: HtmlGenerateInput) -> io::Result<HtmlGenerateOutput> {
+    fn generate_html(&self, mut input: HtmlGenerateInput) -> io::Result<HtmlGenerateOutput> {
         // 取得檔案名稱與下載名稱
-        let (file_name, download_zip_name) = get_file_name(&input.input_path, &input.layer);
+        let (file_name, download_zip_name) = get_file_name(&input.input_path, &input.layer, &input.archive_format, input.ascii_names);
+        let msgs = messages(&input.lang);
+
+        // 封存檔暫存於磁碟時，頁面資料逐塊由暫存檔編碼寫出；需要完整資料的功能先讀回記憶體
+        let mut spill = input.spill.take();
+        if let Some(reason) = spill.as_ref().and_then(|file| spill_needs_memory(&input, file.len())) {
+            warn!("{} 需要完整的頁面資料，已將暫存檔讀入記憶體：{}", reason, file_name);
+            input.zip_buffer = spill.take().map(|file| file.read_all()).transpose()?.unwrap_or_default();
+        }
+
+        // 指定整體壓縮時先壓縮；gzip 由頁面腳本以 DecompressionStream 還原，zstd 只在樹狀檢視以內嵌的解碼器還原，其餘頁面直接下載 .zst 檔
+        let payload = if input.payload_compression != "none" {
+            let compressed = compress_payload(&input.zip_buffer, &input.payload_compression, input.payload_compression_level)?;
+            info!(
+                target: FILE_LOG_TARGET,
+                "{} 壓縮頁面資料：{} → {} 位元組",
+                input.payload_compression,
+                input.zip_buffer.len(),
+                compressed.len()
+            );
+            Cow::Owned(compressed)
+        } else {
+            Cow::Borrowed(input.zip_buffer.as_slice())
+        };
+
+        // 外部資料檔模式不嵌入資料，頁面改由同目錄的 .bin 檔讀取
+        let external = input.payload_mode == "external" && input.format == "html";
+        if input.payload_mode == "external" && !external {
+            warn!("外部資料檔僅適用於 HTML 格式，已改為嵌入頁面：{}", file_name);
+        }
+
+        // 設定目標大小且 Base64 預估會超過目標時，改用體積較小的 Base85 以減少分段數
+        if let Some(target) = input.target_size {
+            if input.payload_encoding == "base64" && !external && payload.len().div_ceil(3) * 4 > target {
+                info!(target: FILE_LOG_TARGET, "Base64 資料預估超過目標大小 {} 位元組，改用 Base85 編碼：{}", target, file_name);
+                input.payload_encoding = "base85".to_string();
+            }
+        }
+
+        // 不含腳本的頁面只能以 Base64 資料連結下載，也只有 Base64 能以系統內建的指令手動還原
+        if input.no_js && input.payload_encoding != "base64" {
+            warn!("不含腳本的頁面（--no-js）僅支援 Base64 編碼，已改用 Base64：{}", file_name);
+            input.payload_encoding = "base64".to_string();
+        }
+
+        // 將 ZIP 數據編碼為可嵌入頁面的文字
+        let encoder = payload_encoder(&input.payload_encoding)?;
+        // 供複製的 Base64 文字依固定字元數換行，嵌入頁面的資料塊維持單行
+        let line_width = if input.wrap_base64 && encoder.name() == "base64" { BASE64_LINE_WIDTH } else { 0 };
+        if input.wrap_base64 && line_width == 0 {
+            warn!("{} 編碼不換行，已忽略 --wrap-base64：{}", encoder.label(), file_name);
+        }
+        let encoded_chunks = if external || spill.is_some() { Vec::new() } else { encode_payload(&payload, encoder) };
+        let summary = spill.as_ref().map(|file| summarize(file, encoder)).transpose()?;
+        let (encoded_size, chunk_count) = match &summary {
+            Some(summary) if !external => (summary.encoded_size, summary.chunk_count),
+            _ => (encoded_chunks.iter().map(String::len).sum(), encoded_chunks.len()),
+        };
+        // 低記憶體模式不另外串接完整的編碼資料，zip_base64 為空字串；內建模板與渲染器不使用此欄位，插件渲染器由 check_low_memory_renderer 拒絕
+        let zip_base64 = if input.low_memory { String::new() } else { encoded_chunks.concat() };
+        // 暫存於磁碟的資料先以標記佔位，寫出頁面時再逐塊替換為資料塊
+        let browser_decrypt = is_browser_encryption(&input.encryption_method) && input.password.is_some();
+        let tree_view = input.view == "tree" && !input.entries.is_empty();
+        // 不超過 --data-uri-threshold 的 Base64 資料另以資料連結直接下載，頁面不再顯示複製與另存編碼文字的按鈕
+        let direct_download = if !input.no_js
+            && !browser_decrypt
+            && !tree_view
+            && !external
+            && input.format == "html"
+            && input.payload_compression == "none"
+            && encoder.name() == "base64"
+            && payload.len() <= input.data_uri_threshold
+            && fits_data_uri(encoded_size)
+        {
+            data_uri_link(&encoded_chunks, &download_zip_name, msgs.download_zip)
+        } else {
+            String::new()
+        };
+        let payload_chunks = if input.no_js {
+            no_js_payload(&encoded_chunks, &download_zip_name, line_width, msgs)
+        } else if spill.is_some() && !external {
+            SPILL_MARKER.to_string()
+        } else {
+            payload_chunk_tags(&encoded_chunks, encoder.name())
+        };
+        drop(encoded_chunks);
+        info!(
+            target: FILE_LOG_TARGET,
+            "生成 {} 數據，總大小：{} 位元組，共 {} 塊",
+            encoder.label(),
+            encoded_size,
+            chunk_count
+        );
+        let payload_sha256 = match summary {
+            Some(summary) => summary.sha256,
+            None => sha256_hex(&input.zip_buffer),
+        };
+
+        // 生成使用說明，瀏覽器端解密使用專屬說明
+
+        // 嵌入資料超過頁面大小上限時依策略處理：警告、中止此檔案或自動分段；設定目標大小時改依目標大小分段
+        let mut split_size = input.split_size;
+        if input.target_size.is_none() && encoded_size > input.max_html_size {
+            match input.oversize_policy.as_str() {
+                "error" => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::FileTooLarge,
+                        format!(
+                            "{} 資料 {} 位元組超過頁面大小上限 {} 位元組：{}",
+                            encoder.label(), encoded_size, input.max_html_size, input.input_path.display()
+                        ),
+                    ));
+                }
+                "split" if !tree_view && !input.no_js && input.format == "html" => {
+                    let part_size = encoder.raw_capacity(input.max_html_size).max(1);
+                    split_size = Some(split_size.map_or(part_size, |size| size.min(part_size)));
+                    info!(target: FILE_LOG_TARGET, "{} 資料超過頁面大小上限 {} 位元組，改為每段 {} 位元組分段輸出", encoder.label(), input.max_html_size, part_size);
+                }
+                _ if input.no_js => warn!(
+                    "{} 資料過大：{} 位元組，超過頁面大小上限 {} 位元組，不含腳本的頁面無法分段：{}",
+                    encoder.label(), encoded_size, input.max_html_size, input.input_path.display()
+                ),
+                _ => warn!(
+                    "{} 資料過大：{} 位元組，超過頁面大小上限 {} 位元組，已分為 {} 塊逐步解碼，可考慮使用 --split-size 或 --oversize-policy split 分段：{}",
+                    encoder.label(), encoded_size, input.max_html_size, chunk_count, input.input_path.display()
+                ),
+            }
+        }
 
-        // 將 ZIP 數據編碼為 Base64
-        let zip_base64 = encode_to_base64(&input.zip_buffer, &input.input_path)?;
-        info!("生成 Base64 數據，總大小：{} 位元組", zip_base64.len());
+        let plugins = crate::service::plugin::snapshot();
+        // 雙層壓縮時外層或內層任一加密即需密碼
+        let has_password = input.password.is_some() || input.inner_password.is_some();
+        let plugin_instructions = plugins.instructions(&InstructionContext {
+            layer: &input.layer,
+            has_password,
+            encryption_method: &input.encryption_method,
+            view: &input.view,
+            lang: &input.lang,
+        });
+        let mut instructions = if let Some(instructions) = plugin_instructions {
+            instructions
+        } else if input.no_js {
+            generate_no_js_instructions(&download_zip_name, encoded_size, has_password, msgs)
+        } else if tree_view {
+            generate_tree_instructions(browser_decrypt, msgs)
+        } else if browser_decrypt {
+            generate_webcrypto_instructions(msgs)
+        } else {
+            generate_instructions(&input.layer, &input.archive_format, has_password, encoder, msgs)
+        };
+        match input.payload_compression.as_str() {
+            "gzip" => instructions.push_str(msgs.gzip_note),
+            "zstd" => instructions.push_str(msgs.zstd_note),
+            _ => {}
+        }
+        // 換行說明只在頁面提供可複製的 Base64 文字時加入：不含腳本時為超過資料連結上限的文字區塊，否則為單一頁面的複製與另存按鈕
+        let copyable_text = if input.no_js {
+            !fits_data_uri(encoded_size)
+        } else {
+            direct_download.is_empty() && !browser_decrypt && !tree_view && !external && split_size.is_none()
+        };
+        if line_width > 0 && copyable_text {
+            instructions.push_str(&fill_placeholders(msgs.wrap_note, &[("WIDTH", &line_width.to_string())]));
+        }
+        let payload_file = if external { format!("{}.bin", file_name) } else { String::new() };
+        if external {
+            instructions.push_str(&fill_placeholders(msgs.external_note, &[("PAYLOAD_FILE", &escape_html(&payload_file))]));
+        }
+        if !input.slices.is_empty() {
+            instructions.push_str(&slice_note(&input.slices, msgs));
+        }
 
-        // 生成使用說明
-        let instructions = generate_instructions(&input.layer, input.password.is_some());
+        // 產出物的所有檔案先暫存於批次，頁面生成成功後才一併寫出，生成失敗時不留下密碼檔
+        let mut batch = OutputBatch::new(&input.output_dir);
 
         // 處理密碼顯示邏輯
-        let (password_info, password_display) = handle_password_display(
+        let password = handle_password_display(
             input.password.as_deref(),
-            input.display_password,
-            &file_name,
-            &input.output_dir,
+            &PasswordDisplayOptions {
+                display_password: input.display_password,
+                password_qr: input.password_qr,
+                key_sheet: input.key_sheet,
+                key_file: &apply_name_pattern(&input.key_name, &file_name, &input.output_extension),
+                inner_password: input.inner_password.as_deref(),
+                key_file_mode: input.key_file_mode,
+                file_name: &file_name,
+                page_name: &format!("{}.{}", file_name, input.output_extension),
+            },
+            &mut batch,
+            msgs,
         )?;
+        let password_hint = password_hint_html(input.password.as_deref(), input.password_hint.as_deref(), msgs)?;
+
+        // 讀取自訂樣式與腳本
+        let extra_css = load_extra_asset(input.extra_css.as_deref(), "style")?;
+        let extra_js = load_extra_asset(input.extra_js.as_deref(), "script")?;
+
+        // 品牌資訊：自訂標題、標誌與頁尾
+        let title = input.title.as_deref().map(escape_html).unwrap_or_else(|| msgs.title.to_string());
+        let logo = load_logo(input.logo.as_deref())?;
+        let footer = input
+            .footer
+            .as_deref()
+            .map(|footer| format!("<footer class=\"footer\">{}</footer>", footer))
+            .unwrap_or_default();
+        let message = load_message(input.message.as_deref(), msgs)?;
+
+        // 到期時間以 Unix 毫秒嵌入頁面，由頁面腳本判斷是否隱藏下載
+        let expires_at = match input.expires.as_deref() {
+            Some(expires) => parse_expires(expires)?.timestamp_millis().to_string(),
+            None => "null".to_string(),
+        };
+
+        // 嵌入密碼驗證值，讓頁面在長時間解密前先確認密碼是否正確
+        let conversion_metadata = if input.format_version >= 2 {
+            metadata_comment(&build_conversion_metadata(&input))?
+        } else {
+            String::new()
+        };
+        let password_verifier = match input.password.as_deref() {
+            Some(password) if input.password_verifier => password_verifier(password)?,
+            _ => String::new(),
+        };
 
         // 格式化檔案大小
-        let file_size_str = format_file_size(input.total_size);
+        let file_size_str = LocaleFormat::for_locale(&input.lang).format_size(input.total_size);
 
         // 生成 HTML 內容
-        let html_content = generate_html_content(
-            &zip_base64,
-            &file_name,
-            &download_zip_name,
-            &instructions,
-            &file_size_str,
-            &password_info,
-            &password_display,
+        let template = if input.no_js {
+            NO_JS_TEMPLATE
+        } else if tree_view {
+            TREE_TEMPLATE
+        } else if browser_decrypt {
+            WEBCRYPTO_TEMPLATE
+        } else {
+            HTML_TEMPLATE
+        };
+        let manifest_json = if tree_view { entries_to_json(&input.entries)? } else { "[]".to_string() };
+        // 離線安裝：頁面連結同名的 Manifest，輸出目錄共用的 Service Worker 與圖示由 generate_pwa_assets 寫出
+        let pwa_head = if input.pwa { pwa_head(&manifest_name(&file_name), &payload_file) } else { String::new() };
+        let template_data = HtmlTemplateData {
+            zip_base64: &zip_base64,
+            payload_chunks: &payload_chunks,
+            file_name: &file_name,
+            download_zip_name: &download_zip_name,
+            instructions: &instructions,
+            file_size_str: &file_size_str,
+            password_info: &password.info,
+            password_display: &password.display,
+            password_hint: &password_hint,
+            manifest_json: &manifest_json,
+            browser_decrypt,
+            chunked_encryption: browser_decrypt && input.encryption_method == "webcrypto-chunked",
+            payload_encoding: encoder.name(),
+            payload_compression: &input.payload_compression,
+            zstd_decoder: tree_view && input.payload_compression == "zstd",
+            layer: &input.layer,
+            zip_encrypted: input.password.is_some() && !browser_decrypt || input.inner_password.is_some(),
+            archive_format: &input.archive_format,
+            payload_sha256: &payload_sha256,
+            lang: &input.lang,
+            extra_css: &extra_css,
+            extra_js: &extra_js,
+            title: &title,
+            logo: &logo,
+            footer: &footer,
+            message: &message,
+            output_extension: &input.output_extension,
+            expires_at: &expires_at,
+            classification: input.classification.as_deref(),
+            file_kind: file_kind(&input.input_path),
+            password_verifier: &password_verifier,
+            payload_file: &payload_file,
+            format_version: input.format_version,
+            conversion_metadata: &conversion_metadata,
+            pwa_head: &pwa_head,
+            direct_download: &direct_download,
+            payload_line_width: line_width,
+        };
+        let html_content = generate_html_content(template, &template_data);
+        if input.a11y_strict {
+            check_accessibility(html_content.as_bytes(), &file_name, true)?;
+        }
+
+        // 整頁超過目標大小時，以頁面扣除嵌入資料後的固定內容推算每段可容納的資料量
+        if let Some(target) = input.target_size.filter(|target| html_content.len() > *target) {
+            if tree_view || external || input.no_js || input.format != "html" {
+                warn!("頁面 {} 位元組超過目標大小 {} 位元組，但此輸出方式無法分段：{}", html_content.len(), target, file_name);
+            } else {
+                let overhead = html_content.len().saturating_sub(payload_chunks.len());
+                if overhead >= target {
+                    return Err(target_too_small(target));
+                }
+                let part_size = encoder.raw_capacity(target - overhead).max(1);
+                split_size = Some(split_size.map_or(part_size, |size| size.min(part_size)));
+                info!(target: FILE_LOG_TARGET, "頁面 {} 位元組超過目標大小 {} 位元組，改為每段 {} 位元組分段輸出", html_content.len(), target, part_size);
+            }
+        }
+
+        // 非 HTML 格式交由已註冊的渲染器處理
+        if input.format != "html" {
+            let renderer = output_renderer(&input.format, &plugins).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("未知的輸出格式 '{}'", input.format))
+            })?;
+            check_low_memory_renderer(&input.format, &plugins, input.low_memory)?;
+            let content = renderer.render(&RenderContext {
+                data: &template_data,
+                html: &html_content,
+                template,
+                payload: &input.zip_buffer,
+            })?;
+            let output_name = format!("{}.{}", file_name, renderer.extension());
+            // 自訂渲染器以自己的模板產生頁面，輸出 HTML 時一律檢查，未啟用 --a11y-strict 時只警告
+            if HTML_EXTENSIONS.iter().any(|ext| renderer.extension().eq_ignore_ascii_case(ext)) {
+                check_accessibility(&content, &output_name, input.a11y_strict)?;
+            }
+            let html_size = content.len();
+            let page_sha256 = vec![sha256_hex(&content)];
+            let index = batch.add(&output_name, content);
+            let (mut paths, key_file_path, key_sheet_path) = self.commit_batch(batch, &password)?;
+            let output_path = paths.swap_remove(index);
+            info!(target: FILE_LOG_TARGET, "以 {} 格式生成：{}，大小：{} 位元組", renderer.name(), output_path, html_size);
+            return Ok(HtmlGenerateOutput {
+                html_file_path: output_path,
+                key_file_path,
+                key_sheet_path,
+                html_size,
+                parts: Vec::new(),
+                page_sha256,
+                payload_file_path: None,
+            });
+        }
+
+        // 超過分段大小時拆成多個頁面
+        if let Some(part_size) = split_size.filter(|size| payload.len() > *size) {
+            if tree_view {
+                warn!("樹狀檢視不支援分段，已輸出為單一頁面：{}", file_name);
+            } else if external {
+                warn!("外部資料檔模式不需分段，已輸出為單一頁面：{}", file_name);
+            } else if input.no_js {
+                warn!("不含腳本的頁面無法分段，已輸出為單一頁面：{}", file_name);
+            } else {
+                return self.generate_split_html(&input, &template_data, &payload, part_size, batch, &password);
+            }
+        }
+
+        // 外部資料檔的內容與嵌入時的資料相同（已加密或壓縮）
+        let payload_index = match &spill {
+            Some(file) if external => Some(batch.add_spilled(&payload_file, file.clone())),
+            _ => external.then(|| batch.add(&payload_file, payload.into_owned())),
+        };
+
+        let page = match &spill {
+            Some(file) if !external => {
+                stage_spilled_page(&mut batch, &html_content, file, encoder, &file_name, &input.output_extension)?
+            }
+            _ => stage_html_page(&mut batch, html_content, &file_name, &input.output_extension, input.minify)?,
+        };
+        if input.pwa {
+            stage_manifest(&mut batch, &input, &file_name, &format!("{}.{}", file_name, input.output_extension))?;
+        }
+
+        // 一併寫出頁面、密碼檔與外部資料檔
+        let (paths, key_file_path, key_sheet_path) = self.commit_batch(batch, &password)?;
+        let payload_file_path = payload_index.map(|i| paths[i].clone());
+        if let Some(path) = &payload_file_path {
+            info!(target: FILE_LOG_TARGET, "生成外部資料檔：{}", path);
+        }
+        let html_file_path = paths[page.index].clone();
+        info!(
+            target: FILE_LOG_TARGET,
+            "生成 HTML 檔案：{}，大小：{} 位元組",
+            html_file_path,
+            page.size
         );
 
-        // 寫入 HTML 檔案
-        write_html_file(&html_content, &input.output_dir, &file_name)?;
+        Ok(HtmlGenerateOutput {
+            html_file_path,
+            key_file_path,
+            key_sheet_path,
+            html_size: page.size,
+            parts: Vec::new(),
+            page_sha256: vec![page.sha256],
+            payload_file_path,
+        })
+    }
+
+    /// 根據轉換報告生成 index.html
+    /// # 參數
+    /// - report: 轉換報告，包含所有產出頁面
+    /// # 回傳
+    /// - 成功時返回索引頁路徑，失敗時返回 IO 錯誤
+    fn generate_index(&self, report: &ConversionReport) -> io::Result<HtmlGenerateOutput> {
+        let index_content = generate_index_content(report);
+        let index_name = apply_name_pattern(
+            &report.index_name,
+            &get_file_name(&report.input_path, "none", "zip", false).0,
+            &report.output_extension,
+        );
+        let html_file_path = self.sink.write(&report.output_dir, &index_name, index_content.as_bytes())?;
         info!(
-            "生成 HTML 檔案：{}/{}.html，大小：{} 位元組",
-            input.output_dir,
-            file_name,
-            html_content.len()
+            "生成索引頁：{}，共 {} 個項目",
+            html_file_path,
+            report.artifacts.len()
         );
+        Ok(HtmlGenerateOutput {
+            html_file_path,
+            key_file_path: None,
+            key_sheet_path: None,
+            html_size: index_content.len(),
+            parts: Vec::new(),
+            page_sha256: Vec::new(),
+            payload_file_path: None,
+        })
+    }
+
+    /// 根據轉換報告生成 restore.html
+    /// # 參數
+    /// - report: 轉換報告，包含所有產出頁面及其 SHA-256
+    /// # 回傳
+    /// - 成功時返回還原頁路徑，失敗時返回 IO 錯誤
+    fn generate_restore(&self, report: &ConversionReport) -> io::Result<HtmlGenerateOutput> {
+        let restore_content = generate_restore_content(report);
+        let restore_name = format!("restore.{}", report.output_extension);
+        let html_file_path = self.sink.write(&report.output_dir, &restore_name, restore_content.as_bytes())?;
+        info!(
+            "生成還原頁：{}，共 {} 個來源檔案",
+            html_file_path,
+            report.artifacts.len()
+        );
+        Ok(HtmlGenerateOutput {
+            html_file_path,
+            key_file_path: None,
+            key_sheet_path: None,
+            html_size: restore_content.len(),
+            parts: Vec::new(),
+            page_sha256: Vec::new(),
+            payload_file_path: None,
+        })
+    }
+
+    /// 在輸出目錄寫出離線安裝共用的 Service Worker 與圖示
+    /// # 參數
+    /// - output_dir: 輸出目錄
+    /// # 回傳
+    /// - 成功時返回寫出的檔案路徑，失敗時返回 IO 錯誤
+    fn generate_pwa_assets(&self, output_dir: &str) -> io::Result<Vec<String>> {
+        let worker = self.sink.write(output_dir, SERVICE_WORKER_NAME, service_worker().as_bytes())?;
+        let icon = self.sink.write(output_dir, ICON_NAME, icon_svg().as_bytes())?;
+        info!("生成離線安裝檔案：{}、{}", worker, icon);
+        Ok(vec![worker, icon])
+    }
 
+    /// 移除已寫出的產出物檔案，逐一嘗試所有檔案
+    /// # 參數
+    /// - locations: 產出物檔案的位置
+    /// # 回傳
+    /// - 全部移除成功時返回 Ok，任一檔案無法移除時返回最後一個 IO 錯誤
+    fn discard_outputs(&self, locations: &[String]) -> io::Result<()> {
+        let mut result = Ok(());
+        for location in locations {
+            if let Err(e) = self.sink.remove(location) {
+                result = Err(io::Error::new(e.kind(), format!("{}：{}", location, e)));
+            }
+        }
+        result
+    }
+
+    /// 在輸出目錄生成獨立的解碼工具頁
+    /// # 參數
+    /// - output_dir: 輸出目錄
+    /// - file_name: 解碼工具頁的檔名
+    /// - lang: 頁面語言
+    /// # 回傳
+    /// - 成功時返回解碼工具頁路徑，失敗時返回 IO 錯誤
+    fn generate_decoder(&self, output_dir: &str, file_name: &str, lang: &str) -> io::Result<HtmlGenerateOutput> {
+        let decoder_content = generate_decoder_content(lang);
+        let html_file_path = self.sink.write(output_dir, file_name, decoder_content.as_bytes())?;
+        info!("生成解碼工具頁：{}", html_file_path);
         Ok(HtmlGenerateOutput {
-            html_file_path: format!("{}/{}.html", input.output_dir, file_name),
+            html_file_path,
+            key_file_path: None,
+            key_sheet_path: None,
+            html_size: decoder_content.len(),
+            parts: Vec::new(),
+            page_sha256: vec![sha256_hex(decoder_content.as_bytes())],
+            payload_file_path: None,
         })
     }
 }
@@ -76,86 +925,983 @@ impl HtmlServiceTrait for HtmlService {
 // 以下是原有的 HTML 生成相關函數，保持不變
 
 /// 生成 HTML 內容，替換模板中的佔位符
-pub fn generate_html_content(
-    zip_base64: &str,
-    file_name: &str,
+pub fn generate_html_content(template: &str, data: &HtmlTemplateData) -> String {
+    let template = template
+        .replace("{{COMMON_SCRIPT}}", COMMON_SCRIPT)
+        .replace("{{CODEC_SCRIPT}}", CODEC_SCRIPT)
+        .replace("{{CRYPTO_SCRIPT}}", CRYPTO_SCRIPT);
+    let kdf_iterations = PBKDF2_ITERATIONS.to_string();
+    let stream_chunk_size = if data.chunked_encryption { STREAM_CHUNK_SIZE.to_string() } else { "0".to_string() };
+    let msgs = messages(data.lang);
+    let encoder = payload_encoder(data.payload_encoding).unwrap_or(&Base64Encoder);
+    let marking = classification_marking(data.classification);
+    // AES 加密的 ZIP 無法以作業系統內建的解壓工具開啟，於頁面明確提示
+    let compat_notice = if data.zip_encrypted && data.layer != "none" && data.archive_format == "zip" {
+        format!("<p class=\"compat-notice\">{}</p>", msgs.compat_notice)
+    } else {
+        String::new()
+    };
+    // 未啟用腳本時，嵌入頁面且未以瀏覽器解密加密的資料仍可自原始碼複製出來解碼
+    let noscript_payload = if data.browser_decrypt || !data.payload_file.is_empty() {
+        String::new()
+    } else {
+        format!("<p>{}</p>", fill_placeholders(msgs.noscript_payload, &[("ENCODING", encoder.label())]))
+    };
+    fill_placeholders(&template, &[
+        ("FORMAT_META", &format_meta(data.format_version)),
+        ("CONVERSION_METADATA", data.conversion_metadata),
+        ("ZIP_BASE64", data.zip_base64),
+        ("PAYLOAD_CHUNKS", data.payload_chunks),
+        ("FILE_NAME", &escape_html(data.file_name)),
+        ("DOWNLOAD_ZIP_NAME_JS", &js_string_literal(data.download_zip_name)),
+        ("DOWNLOAD_ZIP_NAME", &escape_html(data.download_zip_name)),
+        ("INSTRUCTIONS", data.instructions),
+        ("FILE_SIZE", data.file_size_str),
+        ("PASSWORD", data.password_info),
+        ("PASSWORD_DISPLAY", data.password_display),
+        ("PASSWORD_HINT", data.password_hint),
+        ("KDF_ITERATIONS", &kdf_iterations),
+        ("STREAM_CHUNK_SIZE", &stream_chunk_size),
+        ("PAYLOAD_LINE_WIDTH", &data.payload_line_width.to_string()),
+        ("MANIFEST_JSON", data.manifest_json),
+        ("ENCRYPTED", if data.browser_decrypt { "true" } else { "false" }),
+        ("PAYLOAD_SHA256", data.payload_sha256),
+        ("PAYLOAD_ENCODING", encoder.name()),
+        ("PAYLOAD_SUFFIX", encoder.suffix()),
+        ("PAYLOAD_COMPRESSION", data.payload_compression),
+        ("LAYER", data.layer),
+        ("ARCHIVE_FORMAT", data.archive_format),
+        ("DOWNLOAD_MIME", download_mime_type(data.layer, data.archive_format, data.download_zip_name)),
+        ("COMPAT_NOTICE", &compat_notice),
+        ("ZIP_ENCRYPTED", if data.zip_encrypted { "true" } else { "false" }),
+        ("MESSAGES_JSON", &js_messages_json(msgs)),
+        ("EXTRA_CSS", data.extra_css),
+        ("EXTRA_JS", data.extra_js),
+        ("CLASSIFICATION_STYLE", &marking.style),
+        ("CLASSIFICATION_BANNER", &marking.banner),
+        ("FILE_TYPE_STYLE", &file_kind_style(data.file_kind)),
+        ("FILE_ICON", &file_kind_icon(data.file_kind)),
+        ("PWA_HEAD", data.pwa_head),
+        ("DIRECT_DOWNLOAD", data.direct_download),
+        ("DOWNLOAD_HIDDEN", if data.direct_download.is_empty() { "" } else { " hidden" }),
+        ("TITLE", data.title),
+        ("LOGO", data.logo),
+        ("FOOTER", data.footer),
+        ("MESSAGE", data.message),
+        ("OUTPUT_EXTENSION", data.output_extension),
+        ("EXPIRES_AT", data.expires_at),
+        ("PAYLOAD_FILE_JS", &if data.payload_file.is_empty() { "null".to_string() } else { js_string_literal(data.payload_file) }),
+        ("PASSWORD_VERIFIER", data.password_verifier),
+        ("ARGON2_SCRIPT", if data.password_verifier.is_empty() { "" } else { ARGON2_SCRIPT }),
+        ("ZSTD_SCRIPT", if data.zstd_decoder { ZSTD_SCRIPT } else { "" }),
+        ("MSG_HTML_LANG", msgs.html_lang),
+        ("MSG_FILE_NAME_LABEL", msgs.file_name_label),
+        ("MSG_FILE_SIZE_LABEL", msgs.file_size_label),
+        ("MSG_INTEGRITY_CHECKING", msgs.integrity_checking),
+        ("MSG_INTEGRITY_SELECT_PARTS", msgs.integrity_select_parts),
+        ("MSG_PASSWORD_PLACEHOLDER", msgs.password_placeholder),
+        ("MSG_DOWNLOAD_ZIP", msgs.download_zip),
+        ("MSG_COPY_PAYLOAD", &fill_placeholders(msgs.copy_payload, &[("ENCODING", encoder.label())])),
+        ("MSG_SAVE_PAYLOAD", &fill_placeholders(msgs.save_payload, &[("SUFFIX", encoder.suffix())])),
+        ("MSG_DECRYPT_AND_DOWNLOAD", msgs.decrypt_and_download),
+        ("MSG_UNLOCK", msgs.unlock),
+        ("MSG_DOWNLOAD_FULL_ZIP", msgs.download_full_zip),
+        ("MSG_ASSEMBLE_AND_DOWNLOAD", msgs.assemble_and_download),
+        ("MSG_DOWNLOAD_PART", msgs.download_part),
+        ("MSG_NOSCRIPT", msgs.noscript),
+        ("NOSCRIPT_PAYLOAD", &noscript_payload),
+        ("MSG_NO_JS_SHA256_LABEL", msgs.no_js_sha256_label),
+        ("MSG_DECODE_PROGRESS_LABEL", msgs.decode_progress_label),
+        ("MSG_SELECT_PAGES_LABEL", msgs.select_pages_label),
+    ])
+}
+
+/// 依生成參數建立嵌入頁面的轉換資訊
+/// # 參數
+/// - input: HTML 生成的輸入參數
+/// # 回傳
+/// - 轉換資訊，產生時間為呼叫當下
+pub fn build_conversion_metadata(input: &HtmlGenerateInput) -> ConversionMetadata {
+    ConversionMetadata {
+        format_version: input.format_version,
+        tool: "file_to_html".to_string(),
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Local::now().to_rfc3339(),
+        layer: input.layer.clone(),
+        archive_format: Some(input.archive_format.clone()).filter(|format| format != "zip"),
+        encryption_method: if input.password.is_some() { input.encryption_method.clone() } else { "none".to_string() },
+        source_path_sha256: sha256_hex(input.input_path.to_string_lossy().as_bytes()),
+        tags: input.tags.clone(),
+        classification: input.classification.clone(),
+    }
+}
+
+/// 單次掃描替換 `{{NAME}}` 佔位符，替換後的內容不會再被解析，未知的佔位符保持原樣
+pub fn fill_placeholders(template: &str, values: &[(&str, &str)]) -> String {
+    let extra: usize = values.iter().map(|(_, v)| v.len()).sum();
+    let mut output = String::with_capacity(template.len() + extra);
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = &after[..end];
+                match values.iter().find(|(key, _)| *key == name) {
+                    Some((_, value)) => output.push_str(value),
+                    None => output.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 計算資料的 SHA-256 並以十六進位字串表示
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 生成索引頁內容，每個產出頁面一列
+pub fn generate_index_content(report: &ConversionReport) -> String {
+    let locale = LocaleFormat::for_locale(&report.lang);
+    let rows: Vec<String> = report
+        .artifacts
+        .iter()
+        .map(|artifact| {
+            let page_name = Path::new(&artifact.html)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| artifact.html.clone());
+            let kind = file_kind(&artifact.source);
+            format!(
+                "        <tr{}>\n            <td class=\"page\" style=\"border-left-color: {}\">{}<a href=\"{}\">{}</a></td>\n            <td>{}{}</td>\n            <td class=\"size\">{}</td>\n            <td>{}</td>\n        </tr>",
+                tags_attribute(&artifact.tags),
+                file_kind_color(kind),
+                file_kind_icon(kind),
+                escape_html(&encode_href(&page_name)),
+                escape_html(&page_name),
+                escape_html(&artifact.source.display().to_string()),
+                tag_badges(&artifact.tags),
+                locale.format_size(artifact.source_size),
+                locale.format_datetime(&artifact.generated_at),
+            )
+        })
+        .collect();
+    let total_size: usize = report.artifacts.iter().map(|a| a.source_size).sum();
+    let msgs = messages(&report.lang);
+    let index_note = if needs_rename_hint(&report.output_extension) {
+        format!(
+            "<p class=\"note\">{}</p>",
+            fill_placeholders(msgs.rename_hint, &[("EXT", &report.output_extension)])
+        )
+    } else {
+        String::new()
+    };
+    let summary = fill_placeholders(msgs.index_summary, &[
+        ("FILE_COUNT", &report.artifacts.len().to_string()),
+        ("TOTAL_SIZE", &locale.format_size(total_size)),
+        ("GENERATED_AT", &locale.format_datetime(&report.started_at)),
+    ]);
+
+    let marking = classification_marking(report.classification.as_deref());
+
+    fill_placeholders(INDEX_TEMPLATE, &[
+        ("FORMAT_META", &format_meta(report.format_version)),
+        ("CLASSIFICATION_STYLE", &marking.style),
+        ("CLASSIFICATION_BANNER", &marking.banner),
+        ("INPUT_PATH", &escape_html(&report.input_path.display().to_string())),
+        ("INDEX_SUMMARY", &summary),
+        ("INDEX_NOTE", &index_note),
+        ("INDEX_ROWS", &rows.join("\n")),
+        ("MSG_HTML_LANG", msgs.html_lang),
+        ("MSG_INDEX_TITLE", msgs.index_title),
+        ("MSG_INDEX_SOURCE", msgs.index_source),
+        ("MSG_INDEX_PAGE", msgs.index_page),
+        ("MSG_INDEX_SOURCE_PATH", msgs.index_source_path),
+        ("MSG_INDEX_SOURCE_SIZE", msgs.index_source_size),
+        ("MSG_INDEX_GENERATED_AT", msgs.index_generated_at),
+    ])
+}
+
+/// 生成解碼工具頁內容，與產出頁面共用 Base64／Base85 解碼、AES-GCM 解密與 Zstandard 解壓縮的腳本
+pub fn generate_decoder_content(lang: &str) -> String {
+    let msgs = messages(lang);
+    let template = DECODER_TEMPLATE
+        .replace("{{CODEC_SCRIPT}}", CODEC_SCRIPT)
+        .replace("{{CRYPTO_SCRIPT}}", CRYPTO_SCRIPT)
+        .replace("{{ZSTD_SCRIPT}}", ZSTD_SCRIPT);
+    fill_placeholders(&template, &[
+        ("MESSAGES_JSON", &js_messages_json(msgs)),
+        ("MSG_HTML_LANG", msgs.html_lang),
+        ("MSG_DECODER_TITLE", msgs.decoder_title),
+        ("MSG_DECODER_INTRO", msgs.decoder_intro),
+        ("MSG_DECODER_PLACEHOLDER", msgs.decoder_placeholder),
+        ("MSG_NOSCRIPT", msgs.noscript),
+        ("MSG_DECODER_FILE_LABEL", msgs.decoder_file_label),
+        ("MSG_DECODER_ENCODING_LABEL", msgs.decoder_encoding_label),
+        ("MSG_DECODER_ENCODING_AUTO", msgs.decoder_encoding_auto),
+        ("MSG_DECODER_BUTTON", msgs.decoder_button),
+        ("MSG_VIEWER_PASSWORD_LABEL", msgs.viewer_password_label),
+        ("MSG_PASSWORD_PLACEHOLDER", msgs.password_placeholder),
+    ])
+}
+
+// 索引頁列上的 data-tags 屬性，以 JSON 記錄標籤供下游工具讀取，沒有標籤時為空字串
+fn tags_attribute(tags: &Tags) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let json = serde_json::to_string(tags).unwrap_or_default();
+    format!(" data-tags=\"{}\"", escape_html(&json))
+}
+
+// 索引頁來源欄位下方顯示的標籤
+fn tag_badges(tags: &Tags) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let badges: String = tags
+        .iter()
+        .map(|(key, value)| format!("<span class=\"tag\">{}={}</span>", escape_html(key), escape_html(value)))
+        .collect();
+    format!("<br>{}", badges)
+}
+
+/// 生成還原頁內容，依產出順序列出每個頁面的名稱、來源與 SHA-256
+pub fn generate_restore_content(report: &ConversionReport) -> String {
+    let msgs = messages(&report.lang);
+    let mut rows = Vec::new();
+    for artifact in &report.artifacts {
+        let pages = if artifact.parts.is_empty() {
+            std::slice::from_ref(&artifact.html)
+        } else {
+            artifact.parts.as_slice()
+        };
+        for (i, (page, sha256)) in pages.iter().zip(&artifact.page_sha256).enumerate() {
+            let page_name = Path::new(page)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| page.clone());
+            let mut source = artifact.source.display().to_string();
+            if pages.len() > 1 {
+                source.push_str(&fill_placeholders(msgs.part_heading, &[
+                    ("PART_INDEX", &(i + 1).to_string()),
+                    ("PART_COUNT", &pages.len().to_string()),
+                ]));
+            }
+            rows.push(format!(
+                "        <tr data-name=\"{}\" data-sha256=\"{}\">\n            <td><a href=\"{}\">{}</a></td>\n            <td>{}</td>\n            <td class=\"status\">{}</td>\n        </tr>",
+                escape_html(&page_name),
+                sha256,
+                escape_html(&encode_href(&page_name)),
+                escape_html(&page_name),
+                escape_html(&source),
+                msgs.restore_pending,
+            ));
+        }
+    }
+    let index_note = if needs_rename_hint(&report.output_extension) {
+        format!(
+            "<p class=\"note\">{}</p>",
+            fill_placeholders(msgs.rename_hint, &[("EXT", &report.output_extension)])
+        )
+    } else {
+        String::new()
+    };
+    let summary = fill_placeholders(msgs.restore_summary, &[
+        ("FILE_COUNT", &report.artifacts.len().to_string()),
+        ("PAGE_COUNT", &rows.len().to_string()),
+    ]);
+
+    fill_placeholders(RESTORE_TEMPLATE, &[
+        ("FORMAT_META", &format_meta(report.format_version)),
+        ("INPUT_PATH", &escape_html(&report.input_path.display().to_string())),
+        ("RESTORE_SUMMARY", &summary),
+        ("INDEX_NOTE", &index_note),
+        ("RESTORE_ROWS", &rows.join("\n")),
+        ("MESSAGES_JSON", &js_messages_json(msgs)),
+        ("MSG_HTML_LANG", msgs.html_lang),
+        ("MSG_RESTORE_TITLE", msgs.restore_title),
+        ("MSG_NOSCRIPT", msgs.noscript),
+        ("MSG_SELECT_PAGES_LABEL", msgs.select_pages_label),
+        ("MSG_INDEX_SOURCE", msgs.index_source),
+        ("MSG_INDEX_PAGE", msgs.index_page),
+        ("MSG_INDEX_SOURCE_PATH", msgs.index_source_path),
+        ("MSG_RESTORE_STEP_COLLECT", msgs.restore_step_collect),
+        ("MSG_RESTORE_STEP_VERIFY", msgs.restore_step_verify),
+        ("MSG_RESTORE_STEP_OPEN", msgs.restore_step_open),
+        ("MSG_RESTORE_STATUS", msgs.restore_status),
+    ])
+}
+
+/// 跳脫 HTML 特殊字元
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 還原 escape_html 跳脫的字元
+pub fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 將字串轉為可直接嵌入 script 標籤的 JS 字串常值（含引號），`<` 以 `\u003c` 表示，避免提前結束標籤
+pub fn js_string_literal(text: &str) -> String {
+    serde_json::to_string(text)
+        .unwrap_or_else(|_| "\"\"".to_string())
+        .replace('<', "\\u003c")
+}
+
+/// 將相對連結中的保留字元以百分比編碼，避免 `#`、`%`、空白等破壞連結
+pub fn encode_href(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// 生成使用說明，根據層數、封存格式、是否有密碼及頁面資料的編碼
+pub fn generate_instructions(layer: &str, archive_format: &str, has_password: bool, encoder: &dyn PayloadEncoder, msgs: &Messages) -> String {
+    if layer != "none" && archive_format == "7z" {
+        let template = if has_password { msgs.instructions_7z_password } else { msgs.instructions_7z };
+        return fill_placeholders(template, &[("ENCODING", encoder.label()), ("SUFFIX", encoder.suffix())]);
+    }
+    if layer != "none" && archive_format != "zip" {
+        return fill_placeholders(msgs.instructions_tar, &[
+            ("ENCODING", encoder.label()),
+            ("SUFFIX", encoder.suffix()),
+            ("EXT", archive_extension(archive_format)),
+        ]);
+    }
+    let template = match (layer, has_password) {
+        ("double", true) => msgs.instructions_double_password,
+        ("double", false) => msgs.instructions_double,
+        ("single", true) => msgs.instructions_single_password,
+        ("single", false) => msgs.instructions_single,
+        _ => msgs.instructions_none,
+    };
+    fill_placeholders(template, &[("ENCODING", encoder.label()), ("SUFFIX", encoder.suffix())])
+}
+
+/// 生成不含腳本頁面的使用說明：資料連結可用時點擊下載，超過上限時複製 Base64 文字手動還原
+/// # 參數
+/// - download_name: 下載的檔案名稱
+/// - encoded_size: Base64 資料的長度
+/// - has_password: 封存檔是否以密碼加密
+/// - msgs: 輸出語言的訊息目錄
+pub fn generate_no_js_instructions(download_name: &str, encoded_size: usize, has_password: bool, msgs: &Messages) -> String {
+    let name = escape_html(download_name);
+    let extract = if has_password { msgs.no_js_password } else { "" };
+    if fits_data_uri(encoded_size) {
+        fill_placeholders(msgs.no_js_link, &[("NAME", &name), ("EXTRACT", extract)])
+    } else {
+        let limit = LocaleFormat::for_locale(msgs.html_lang).format_size(NO_JS_DATA_URI_LIMIT);
+        fill_placeholders(msgs.no_js_manual, &[("NAME", &name), ("LIMIT", &limit), ("EXTRACT", extract)])
+    }
+}
+
+/// 生成不含腳本頁面的資料區塊：資料連結未超過上限時為下載連結，否則為可全選複製的 Base64 文字
+/// # 參數
+/// - chunks: Base64 編碼後的分塊
+/// - download_name: 下載的檔案名稱
+/// - line_width: Base64 文字的每行字元數，0 表示不換行
+pub fn no_js_payload(chunks: &[String], download_name: &str, line_width: usize, msgs: &Messages) -> String {
+    let encoded_size: usize = chunks.iter().map(String::len).sum();
+    if fits_data_uri(encoded_size) {
+        let label = fill_placeholders(msgs.no_js_download, &[("NAME", &escape_html(download_name))]);
+        format!("<p>{}</p>", data_uri_link(chunks, download_name, &label))
+    } else {
+        warn!(
+            "Base64 資料 {} 位元組超過資料連結上限 {} 位元組，頁面改為提供手動還原的 Base64 文字：{}",
+            encoded_size, NO_JS_DATA_URI_LIMIT, download_name
+        );
+        format!(
+            "<textarea class=\"payload-text\" readonly aria-label=\"{}\">{}</textarea>",
+            msgs.no_js_payload_label,
+            wrap_lines(&chunks.concat(), line_width)
+        )
+    }
+}
+
+/// 資料連結的開頭，以 application/octet-stream 讓瀏覽器一律下載而不嘗試開啟
+pub const DATA_URI_PREFIX: &str = "data:application/octet-stream;base64,";
+
+// 資料連結的總長度是否在瀏覽器可接受的範圍內
+fn fits_data_uri(encoded_size: usize) -> bool {
+    DATA_URI_PREFIX.len() + encoded_size <= NO_JS_DATA_URI_LIMIT
+}
+
+// 以 Base64 分塊組成的資料連結下載按鈕
+fn data_uri_link(chunks: &[String], download_name: &str, label: &str) -> String {
+    format!(
+        "<a class=\"download-link\" href=\"{}{}\" download=\"{}\">{}</a>",
+        DATA_URI_PREFIX,
+        chunks.concat(),
+        escape_html(download_name),
+        label
+    )
+}
+
+/// 生成瀏覽器端解密的使用說明
+pub fn generate_webcrypto_instructions(msgs: &Messages) -> String {
+    msgs.instructions_webcrypto.to_string()
+}
+
+/// 生成樹狀檢視的使用說明
+pub fn generate_tree_instructions(browser_decrypt: bool, msgs: &Messages) -> String {
+    if browser_decrypt {
+        msgs.instructions_tree_encrypted.to_string()
+    } else {
+        msgs.instructions_tree.to_string()
+    }
+}
+
+/// 生成分段頁面的使用說明
+/// # 參數
+/// - part_count: 分段數量
+/// - download_zip_name: 合併後的檔案名稱
+/// - browser_decrypt: 是否需在瀏覽器端解密
+/// - extension: 分段頁面的副檔名
+/// - msgs: 輸出語言的訊息目錄
+pub fn generate_split_instructions(
+    part_count: usize,
     download_zip_name: &str,
-    instructions: &str,
-    file_size_str: &str,
-    password_info: &str,
-    password_display: &str,
+    browser_decrypt: bool,
+    extension: &str,
+    msgs: &Messages,
 ) -> String {
-    HTML_TEMPLATE
-        .replace("{{ZIP_BASE64}}", zip_base64)
-        .replace("{{FILE_NAME}}", file_name)
-        .replace("{{DOWNLOAD_ZIP_NAME}}", download_zip_name)
-        .replace("{{INSTRUCTIONS}}", instructions)
-        .replace("{{FILE_SIZE}}", file_size_str)
-        .replace("{{PASSWORD}}", password_info)
-        .replace("{{PASSWORD_DISPLAY}}", password_display)
+    fill_placeholders(msgs.instructions_split, &[
+        ("PART_COUNT", &part_count.to_string()),
+        ("EXT", extension),
+        ("DECRYPT_HINT", if browser_decrypt { msgs.split_decrypt_hint } else { "" }),
+        ("ZIP_NAME", &escape_html(download_zip_name)),
+    ])
+}
+
+/// 自訂樣式或腳本檔的大小上限
+pub const MAX_EXTRA_ASSET_SIZE: u64 = 1024 * 1024;
+
+/// 讀取自訂 CSS 或 JS 檔，並包成可直接嵌入頁面的標籤
+/// # 參數
+/// - path: 檔案路徑，未指定時返回空字串
+/// - tag: `style` 或 `script`
+/// # 回傳
+/// - 成功時返回標籤內容，檔案不存在、過大或非 UTF-8 時返回 IO 錯誤
+pub fn load_extra_asset(path: Option<&str>, tag: &str) -> io::Result<String> {
+    let Some(path) = path else {
+        return Ok(String::new());
+    };
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法讀取自訂 {} 檔 {}: {}", tag, path, e)))?;
+    if !metadata.is_file() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("自訂 {} 路徑不是檔案：{}", tag, path)));
+    }
+    if metadata.len() > MAX_EXTRA_ASSET_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("自訂 {} 檔超過 {} 位元組上限：{}", tag, MAX_EXTRA_ASSET_SIZE, path),
+        ));
+    }
+    let content = String::from_utf8(std::fs::read(path)?)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("自訂 {} 檔需為 UTF-8 編碼：{}", tag, path)))?;
+    Ok(format!("<{0}>\n{1}\n</{0}>", tag, escape_closing_tag(content.trim_end(), tag)))
+}
+
+/// 留言檔的大小上限
+pub const MAX_MESSAGE_SIZE: u64 = 256 * 1024;
+
+/// 讀取留言檔並包成頁面上方的留言區塊；`.md`、`.markdown` 以 Markdown 轉換，其他副檔名視為純文字，
+/// Markdown 中的原始 HTML 以文字顯示，`javascript:` 等連結不會保留
+/// # 參數
+/// - path: 留言檔路徑，未指定時返回空字串
+/// - msgs: 輸出語言的訊息目錄
+/// # 回傳
+/// - 留言區塊的 HTML，讀取失敗、超過大小上限或不是 UTF-8 時返回 IO 錯誤
+pub fn load_message(path: Option<&str>, msgs: &Messages) -> io::Result<String> {
+    let Some(path) = path else {
+        return Ok(String::new());
+    };
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法讀取留言檔 {}: {}", path, e)))?;
+    if !metadata.is_file() || metadata.len() > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("留言檔需為不超過 {} 位元組的檔案：{}", MAX_MESSAGE_SIZE, path),
+        ));
+    }
+    let content = String::from_utf8(std::fs::read(path)?)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("留言檔需為 UTF-8 編碼：{}", path)))?;
+    let content = content.trim_start_matches('\u{feff}');
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let body = match extension.as_str() {
+        "md" | "markdown" => markdown_to_html(content),
+        _ => plain_text_to_html(content),
+    };
+    Ok(format!("<section class=\"message\" aria-label=\"{}\">\n{}</section>", msgs.message_label, body))
 }
 
-/// 生成使用說明，根據層數和是否有密碼
-pub fn generate_instructions(layer: &str, has_password: bool) -> String {
-    match (layer, has_password) {
-        ("double", true) => "<p>請使用下載連結或複製 Base64 資料手動解碼為 ZIP 檔案，然後使用密碼解壓外層和內層 ZIP（使用相同密碼）。建議使用 7-Zip 或 WinRAR。</p>".to_string(),
-        ("double", false) => "<p>請使用下載連結或複製 Base64 資料手動解碼為 ZIP 檔案，然後無需密碼解壓外層和內層 ZIP。建議使用 7-Zip 或 WinRAR。</p>".to_string(),
-        ("single", true) => "<p>請使用下載連結或複製 Base64 資料手動解碼為 ZIP 檔案，然後使用密碼解壓 ZIP。建議使用 7-Zip 或 WinRAR。</p>".to_string(),
-        ("single", false) => "<p>請使用下載連結或複製 Base64 資料手動解碼為 ZIP 檔案，然後無需密碼解壓 ZIP。建議使用 7-Zip 或 WinRAR。</p>".to_string(),
-        _ => "<p>請使用下載連結或複製 Base64 資料手動解碼為檔案，無需解壓。</p>".to_string(),
+// 將 Markdown 轉為 HTML，標題降一級以免與頁面標題並列，原始 HTML 改為文字，可執行腳本的連結改為空連結
+fn markdown_to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Heading { level, id, classes, attrs }) => {
+            Event::Start(Tag::Heading { level: demote_heading(level), id, classes, attrs })
+        }
+        Event::End(TagEnd::Heading(level)) => Event::End(TagEnd::Heading(demote_heading(level))),
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Link { link_type, dest_url: safe_url(dest_url), title, id })
+        }
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Image { link_type, dest_url: safe_url(dest_url), title, id })
+        }
+        event => event,
+    });
+    let mut html = String::new();
+    push_html(&mut html, events);
+    html
+}
+
+// 標題降一級，h6 維持不變
+fn demote_heading(level: HeadingLevel) -> HeadingLevel {
+    match level {
+        HeadingLevel::H1 => HeadingLevel::H2,
+        HeadingLevel::H2 => HeadingLevel::H3,
+        HeadingLevel::H3 => HeadingLevel::H4,
+        HeadingLevel::H4 => HeadingLevel::H5,
+        HeadingLevel::H5 | HeadingLevel::H6 => HeadingLevel::H6,
+    }
+}
+
+// 移除 `javascript:`、`vbscript:` 與 `data:` 連結
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let scheme = url.trim_start().to_ascii_lowercase();
+    if ["javascript:", "vbscript:", "data:"].iter().any(|prefix| scheme.starts_with(prefix)) {
+        CowStr::Borrowed("")
+    } else {
+        url
     }
 }
 
+// 純文字以空行分段，段內換行保留
+fn plain_text_to_html(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>\n", escape_html(paragraph).replace('\n', "<br>\n")))
+        .collect()
+}
+
+/// 標誌圖片的大小上限
+pub const MAX_LOGO_SIZE: u64 = 512 * 1024;
+
+/// 讀取標誌圖片並包成以 data URI 嵌入的 img 標籤
+/// # 參數
+/// - path: 圖片路徑，支援 png、jpg、gif、svg、webp，未指定時返回空字串
+/// # 回傳
+/// - 成功時返回 img 標籤，檔案不存在、格式不支援或過大時返回 IO 錯誤
+pub fn load_logo(path: Option<&str>) -> io::Result<String> {
+    let Some(path) = path else {
+        return Ok(String::new());
+    };
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("不支援的標誌圖片格式：{}（可用 png、jpg、gif、svg、webp）", path),
+            ))
+        }
+    };
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法讀取標誌圖片 {}: {}", path, e)))?;
+    if !metadata.is_file() || metadata.len() > MAX_LOGO_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("標誌圖片需為不超過 {} 位元組的檔案：{}", MAX_LOGO_SIZE, path),
+        ));
+    }
+    let data = std::fs::read(path)?;
+    Ok(format!(
+        "<img class=\"logo\" src=\"data:{};base64,{}\" alt=\"\">",
+        mime,
+        general_purpose::STANDARD.encode(data)
+    ))
+}
+
+/// 跳脫內容中的 `</tag`（不分大小寫），script 另跳脫 `<!--`，避免提前結束標籤
+pub fn escape_closing_tag(content: &str, tag: &str) -> String {
+    let closing = format!("</{}", tag);
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        if candidate.get(..closing.len()).is_some_and(|s| s.eq_ignore_ascii_case(&closing)) {
+            output.push_str("<\\/");
+            rest = &candidate[2..];
+        } else if tag == "script" && candidate.starts_with("<!--") {
+            output.push_str("<\\!");
+            rest = &candidate[2..];
+        } else {
+            output.push('<');
+            rest = &candidate[1..];
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 將 ZIP 項目清單序列化為可安全嵌入 script 標籤的 JSON
+pub fn entries_to_json(entries: &[ZipEntryInfo]) -> io::Result<String> {
+    let json = serde_json::to_string(entries)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(json.replace("</", "<\\/"))
+}
+
 /// 處理密碼顯示邏輯，決定是否將密碼嵌入 HTML 或儲存到檔案
+/// # 參數
+/// - options: 密碼的呈現方式、密碼檔名稱，以及密碼單上標示的檔案與頁面名稱
+/// - msgs: 輸出語言的訊息目錄
+/// # 回傳
+/// - 密碼提示、密碼顯示區塊，以及寫出的密碼檔與密碼單路徑（若有）
 pub fn handle_password_display(
     password: Option<&str>,
-    display_password: bool,
-    file_name: &str,
-    output_dir: &str,
-) -> io::Result<(String, String)> {
-    if let Some(pwd) = password {
-        if display_password {
-            Ok(("下方密碼".to_string(), format!("<p>密碼：<span class=\"password-display\">{}</span></p>", pwd)))
-        } else {
-            let key_file = format!("{}.html.key", file_name);
-            let path = Path::new(output_dir).join(&key_file);
-            let mut file = BufWriter::new(fs::File::create(&path)?);
-            file.write_all(pwd.as_bytes())?;
-            file.flush()?;
-            info!("密碼已儲存至：{}", key_file);
-            Ok((format!("{}.html.key 檔案", file_name), "".to_string()))
+    options: &PasswordDisplayOptions,
+    batch: &mut OutputBatch,
+    msgs: &Messages,
+) -> io::Result<PasswordDisplay> {
+    // 雙層壓縮時外層與內層密碼分別標示，不加密的一層不列出
+    let labelled: Vec<(&str, &str)> = match options.inner_password {
+        Some(inner) => password
+            .map(|pwd| (msgs.outer_password_label, pwd))
+            .into_iter()
+            .chain([(msgs.inner_password_label, inner)])
+            .collect(),
+        None => password.map(|pwd| (msgs.password_label, pwd)).into_iter().collect(),
+    };
+    if labelled.is_empty() {
+        return Ok(PasswordDisplay {
+            info: msgs.no_password.to_string(),
+            display: String::new(),
+            key_file: None,
+            key_sheet: None,
+        });
+    }
+    if options.display_password {
+        let mut lines = Vec::with_capacity(labelled.len());
+        for (label, pwd) in &labelled {
+            let mut display = format!("<p>{}<span class=\"password-display\">{}</span></p>", label, escape_html(pwd));
+            if options.password_qr {
+                display.push_str(&format!(
+                    "\n    <p><img class=\"password-qr\" src=\"{}\" alt=\"{}\" width=\"160\" height=\"160\"></p>",
+                    password_qr_data_uri(pwd)?,
+                    msgs.password_qr_alt
+                ));
+            }
+            lines.push(display);
         }
+        return Ok(PasswordDisplay {
+            info: msgs.password_below.to_string(),
+            display: lines.join("\n    "),
+            key_file: None,
+            key_sheet: None,
+        });
+    }
+
+    // 密碼檔與密碼單都含有密碼，以 --key-file-mode 的權限寫出；單一密碼時密碼檔只含密碼本身，雙層壓縮時每行一個標示層別的密碼
+    let pwd = labelled[0].1;
+    let content = match options.inner_password {
+        None => pwd.to_string(),
+        Some(_) => labelled.iter().map(|(label, pwd)| format!("{}{}\n", label, pwd)).collect(),
+    };
+    let key_file = batch.add_private(options.key_file, content.into_bytes(), options.key_file_mode);
+    let key_sheet = if options.key_sheet && options.inner_password.is_none() {
+        let sheet_name = format!("{}.html", options.key_file);
+        let sheet = generate_key_sheet_content(pwd, options.file_name, options.page_name, msgs)?;
+        Some(batch.add_private(&sheet_name, sheet.into_bytes(), options.key_file_mode))
     } else {
-        Ok(("無需密碼".to_string(), "".to_string()))
+        None
+    };
+    Ok(PasswordDisplay {
+        info: fill_placeholders(msgs.password_in_key_file, &[("KEY_FILE", &escape_html(options.key_file))]),
+        display: String::new(),
+        key_file: Some(key_file),
+        key_sheet,
+    })
+}
+
+/// 生成顯示於密碼輸入區旁的提示段落，提示內容經 HTML 跳脫後嵌入
+/// # 參數
+/// - password: 頁面密碼，未加密時不顯示提示
+/// - hint: `--password-hint` 指定的提示
+/// - msgs: 輸出語言的訊息目錄
+/// # 回傳
+/// - 提示段落，未指定提示或未加密時為空字串；提示包含密碼本身時返回 IO 錯誤
+pub fn password_hint_html(password: Option<&str>, hint: Option<&str>, msgs: &Messages) -> io::Result<String> {
+    let (Some(password), Some(hint)) = (password, hint) else {
+        return Ok(String::new());
+    };
+    if hint.to_lowercase().contains(&password.to_lowercase()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "密碼提示不可包含密碼本身"));
     }
+    Ok(format!("<p class=\"password-hint\">{}{}</p>", msgs.password_hint_label, escape_html(hint)))
 }
 
-/// 將數據編碼為 Base64 格式
-pub fn encode_to_base64(data: &[u8], file_path: &Path) -> io::Result<String> {
-    let mut base64_buffer = Vec::new();
+/// 生成可列印的密碼單，列出密碼、檔案名稱、產生時間與密碼的 QR Code
+/// # 參數
+/// - password: 頁面密碼
+/// - file_name: 原始檔案名稱
+/// - page_name: 對應的頁面檔名
+/// - msgs: 輸出語言的訊息目錄
+/// # 回傳
+/// - 密碼單的 HTML，密碼過長無法生成 QR Code 時返回 IO 錯誤
+pub fn generate_key_sheet_content(password: &str, file_name: &str, page_name: &str, msgs: &Messages) -> io::Result<String> {
+    Ok(fill_placeholders(KEY_SHEET_TEMPLATE, &[
+        ("FILE_NAME", &escape_html(file_name)),
+        ("PAGE_NAME", &escape_html(page_name)),
+        ("PASSWORD", &escape_html(password)),
+        ("CREATED_AT", &LocaleFormat::for_locale(msgs.html_lang).format_datetime(&Local::now())),
+        ("PASSWORD_QR", &password_qr_data_uri(password)?),
+        ("MSG_HTML_LANG", msgs.html_lang),
+        ("MSG_KEY_SHEET_TITLE", msgs.key_sheet_title),
+        ("MSG_KEY_SHEET_FILE", msgs.key_sheet_file),
+        ("MSG_KEY_SHEET_PAGE", msgs.key_sheet_page),
+        ("MSG_KEY_SHEET_PASSWORD", msgs.key_sheet_password),
+        ("MSG_KEY_SHEET_CREATED", msgs.key_sheet_created),
+        ("MSG_PASSWORD_QR_ALT", msgs.password_qr_alt),
+        ("MSG_KEY_SHEET_NOTE", msgs.key_sheet_note),
+    ]))
+}
+
+/// 將密碼編碼為 QR Code，並以 SVG data URI 表示
+pub fn password_qr_data_uri(password: &str) -> io::Result<String> {
+    let qr = QrCode::encode_text(password, QrCodeEcc::Medium)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("密碼過長，無法生成 QR Code: {:?}", e)))?;
+    // 四周保留 4 個模組寬的靜區，方便手機掃描
+    const BORDER: i32 = 4;
+    let size = qr.size();
+    let mut path = String::new();
+    for y in 0..size {
+        for x in 0..size {
+            if qr.get_module(x, y) {
+                path.push_str(&format!("M{},{}h1v1h-1z", x + BORDER, y + BORDER));
+            }
+        }
+    }
+    let dimension = size + BORDER * 2;
+    let svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {0} {0}\" shape-rendering=\"crispEdges\"><rect width=\"{0}\" height=\"{0}\" fill=\"#fff\"/><path d=\"{1}\" fill=\"#000\"/></svg>",
+        dimension, path
+    );
+    Ok(format!("data:image/svg+xml;base64,{}", general_purpose::STANDARD.encode(svg)))
+}
+
+/// 列出只包含部分內容的檔案及其位元組範圍，附加於頁面說明
+/// # 參數
+/// - slices: ZIP 項目名稱與擷取範圍
+/// - msgs: 頁面語系的訊息
+/// # 回傳
+/// - 說明段落的 HTML
+pub fn slice_note(slices: &[(String, ByteRange)], msgs: &Messages) -> String {
+    let locale = LocaleFormat::for_locale(msgs.html_lang);
+    let items: String = slices
+        .iter()
+        .map(|(name, range)| {
+            fill_placeholders(msgs.slice_item, &[
+                ("NAME", &escape_html(name)),
+                ("OFFSET", &locale.format_number(range.offset)),
+                ("LEN", &locale.format_number(range.len)),
+                ("SIZE", &locale.format_size(range.file_size as usize)),
+            ])
+        })
+        .collect();
+    fill_placeholders(msgs.slice_note, &[("SLICES", &items)])
+}
+
+/// 將數據編碼為多個固定大小的分塊，每塊可獨立解碼，依序串接後仍為合法的編碼資料
+pub fn encode_payload(data: &[u8], encoder: &dyn PayloadEncoder) -> Vec<String> {
+    let mut chunks: Vec<String> = data
+        .chunks(encoder.chunk_bytes())
+        .map(|chunk| encoder.encode(chunk))
+        .collect();
+    if chunks.is_empty() {
+        chunks.push(String::new());
+    }
+    chunks
+}
+
+// 包住單一資料塊的 script 標籤
+const PAYLOAD_CHUNK_OPEN: &str = "<script type=\"application/octet-stream\" class=\"payload-chunk\">";
+const PAYLOAD_CHUNK_CLOSE: &str = "</script>";
+
+/// 將編碼後的分塊輸出為不會執行的 script 標籤，並集中於 `#payload` 容器，由頁面腳本逐塊讀取解碼或複製
+/// # 參數
+/// - encoding: 編碼名稱，記錄於容器的 `data-encoding` 屬性
+pub fn payload_chunk_tags(chunks: &[String], encoding: &str) -> String {
+    let tags = chunks
+        .iter()
+        .map(|chunk| format!("{}{}{}", PAYLOAD_CHUNK_OPEN, chunk, PAYLOAD_CHUNK_CLOSE))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("<div id=\"payload\" data-encoding=\"{}\" hidden>\n{}\n</div>", encoding, tags)
+}
+
+// 暫存於磁碟的資料在頁面中的佔位標記，不會出現在模板或轉義後的內容中
+const SPILL_MARKER: &str = "\u{1}SPILLED_PAYLOAD\u{1}";
+
+// 暫存於磁碟的資料無法逐塊寫出時的原因：整體壓縮、非 HTML 格式、目標大小、最小化與分段皆需要完整的資料
+fn spill_needs_memory(input: &HtmlGenerateInput, len: u64) -> Option<&'static str> {
+    let exceeds = |size: usize| len > size as u64;
+    let encoder = payload_encoder(&input.payload_encoding).ok()?;
+    if input.payload_compression != "none" {
+        Some("整體壓縮")
+    } else if input.format != "html" {
+        Some("非 HTML 輸出格式")
+    } else if input.target_size.is_some() {
+        Some("--target-size")
+    } else if input.minify {
+        Some("--minify")
+    } else if input.no_js {
+        Some("--no-js")
+    } else if input.payload_mode == "external" || (input.view == "tree" && !input.entries.is_empty()) {
+        None
+    } else if input.split_size.is_some_and(exceeds)
+        || (input.oversize_policy == "split" && exceeds(encoder.raw_capacity(input.max_html_size)))
     {
-        let mut encoder = EncoderWriter::new(&mut base64_buffer, &general_purpose::STANDARD);
-        encoder.write_all(data)?;
-        encoder.flush()?;
+        Some("分段輸出")
+    } else {
+        None
     }
-    let zip_base64 = String::from_utf8(base64_buffer)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    const MAX_BASE64_SIZE: usize = 1_000_000;
-    if zip_base64.len() > MAX_BASE64_SIZE {
-        warn!(
-            "Base64 資料過大：{} 位元組，超過建議限制 {} 位元組，可能影響顯示或下載：{}",
-            zip_base64.len(), MAX_BASE64_SIZE, file_path.display()
-        );
+}
+
+// 將頁面加入輸出批次，嵌入資料由暫存檔逐塊編碼寫入另一個暫存檔，內容與 payload_chunk_tags 產生的相同
+fn stage_spilled_page(
+    batch: &mut OutputBatch,
+    html_content: &str,
+    spill: &SpillFile,
+    encoder: &dyn PayloadEncoder,
+    file_name: &str,
+    extension: &str,
+) -> io::Result<StagedPage> {
+    let (head, tail) = html_content.split_once(SPILL_MARKER).unwrap_or((html_content, ""));
+    let mut page = SpillBuffer::new(true)?;
+    let mut first = true;
+    writeln!(page, "{}<div id=\"payload\" data-encoding=\"{}\" hidden>", head, encoder.name())?;
+    let chunk_count = spill.for_each_chunk(encoder.chunk_bytes(), |chunk| {
+        write!(page, "{}{}{}{}", if first { "" } else { "\n" }, PAYLOAD_CHUNK_OPEN, encoder.encode(chunk), PAYLOAD_CHUNK_CLOSE)?;
+        first = false;
+        Ok(())
+    })?;
+    // 空資料仍輸出一個空的資料塊，與 encode_payload 相同
+    if chunk_count == 0 {
+        write!(page, "{}{}", PAYLOAD_CHUNK_OPEN, PAYLOAD_CHUNK_CLOSE)?;
     }
-    Ok(zip_base64)
+    write!(page, "\n</div>{}", tail)?;
+    let (_, file) = page.finish()?;
+    let file = file.ok_or_else(|| io::Error::other("暫存檔未建立"))?;
+    let sha256 = hash_reader(&mut file.open()?)?;
+    let size = file.len() as usize;
+    let index = batch.add_spilled(&format!("{}.{}", file_name, extension), file);
+    Ok(StagedPage { index, size, sha256 })
 }
 
-/// 將 HTML 內容寫入檔案
-pub fn write_html_file(html_content: &str, output_dir: &str, file_name: &str) -> io::Result<()> {
-    let output_path = Path::new(output_dir).join(format!("{}.html", file_name));
-    let file = fs::File::create(&output_path)?;
-    let mut writer = BufWriter::new(file);
-    writer.write_all(html_content.as_bytes())?;
-    writer.flush()?;
+// 將頁面對應的 Manifest 加入輸出批次，安裝後開啟指定的頁面（分段時為第一段）
+fn stage_manifest(batch: &mut OutputBatch, input: &HtmlGenerateInput, file_name: &str, page_name: &str) -> io::Result<()> {
+    let name = input.title.as_deref().unwrap_or(file_name);
+    let manifest = web_manifest(name, page_name, &input.lang)?;
+    batch.add(&manifest_name(file_name), manifest.into_bytes());
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 將 HTML 內容加入輸出批次，指定最小化時先移除註解與縮排
+/// # 參數
+/// - extension: 頁面副檔名（不含點），如 `html`、`htm` 或 `txt`
+/// - minify: 是否最小化頁面，需以 `minify` 功能編譯
+/// # 回傳
+/// - 成功時返回頁面在批次中的序號與實際寫出內容的大小及 SHA-256，失敗時返回 IO 錯誤
+pub fn stage_html_page(
+    batch: &mut OutputBatch,
+    html_content: String,
+    file_name: &str,
+    extension: &str,
+    minify: bool,
+) -> io::Result<StagedPage> {
+    // 未最小化時直接移入批次，不另外複製頁面內容
+    let minified = match minify_page(&html_content, minify)? {
+        Cow::Owned(minified) => Some(minified),
+        Cow::Borrowed(_) => None,
+    };
+    let content = minified.unwrap_or(html_content);
+    let size = content.len();
+    let sha256 = sha256_hex(content.as_bytes());
+    let index = batch.add(&format!("{}.{}", file_name, extension), content.into_bytes());
+    Ok(StagedPage { index, size, sha256 })
+}
+
+#[cfg(feature = "minify")]
+fn minify_page(html_content: &str, minify: bool) -> io::Result<Cow<'_, str>> {
+    if !minify {
+        return Ok(Cow::Borrowed(html_content));
+    }
+    let minified = crate::service::minify::minify_html(html_content);
+    info!(target: FILE_LOG_TARGET, "最小化頁面：{} → {} 位元組", html_content.len(), minified.len());
+    Ok(Cow::Owned(minified))
+}
+
+#[cfg(not(feature = "minify"))]
+fn minify_page(html_content: &str, minify: bool) -> io::Result<Cow<'_, str>> {
+    crate::config::config::validate_minify(minify)?;
+    Ok(Cow::Borrowed(html_content))
+}
+
+/// 副檔名不是 html 或 htm 時，瀏覽器與作業系統不會以網頁開啟
+pub fn needs_rename_hint(extension: &str) -> bool {
+    !extension.eq_ignore_ascii_case("html") && !extension.eq_ignore_ascii_case("htm")
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malicious_names_are_escaped() {
+        let name = "\"><script>alert('x')</script>&.pdf";
+        let escaped = escape_html(name);
+        assert!(!escaped.contains(['<', '>', '"', '\'']));
+        assert_eq!(unescape_html(&escaped), name);
+
+        let literal = js_string_literal("</script><script>alert(1)</script>\"\n");
+        assert!(!literal.contains('<'));
+        assert_eq!(serde_json::from_str::<String>(&literal).unwrap(), "</script><script>alert(1)</script>\"\n");
+    }
+}
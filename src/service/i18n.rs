@@ -0,0 +1,752 @@
+use serde::Serialize;
+
+/// 支援的輸出語言代碼
+pub const SUPPORTED_LANGS: [&str; 4] = ["zh-TW", "zh-CN", "en", "ja"];
+
+/// 預設輸出語言
+pub const DEFAULT_LANG: &str = "zh-TW";
+
+// 輸出頁面的訊息目錄，`{{NAME}}` 為執行時替換的參數
+pub struct Messages {
+    pub html_lang: &'static str,
+    pub title: &'static str,
+    pub file_name_label: &'static str,
+    pub file_size_label: &'static str,
+    pub integrity_checking: &'static str,
+    pub integrity_select_parts: &'static str,
+    pub password_placeholder: &'static str,
+    pub download_zip: &'static str,
+    // 參數：ENCODING
+    pub copy_payload: &'static str,
+    // 參數：SUFFIX
+    pub save_payload: &'static str,
+    pub decrypt_and_download: &'static str,
+    pub unlock: &'static str,
+    pub download_full_zip: &'static str,
+    pub assemble_and_download: &'static str,
+    pub download_part: &'static str,
+    pub noscript: &'static str,
+    // 參數：ENCODING
+    pub noscript_payload: &'static str,
+    pub decode_progress_label: &'static str,
+    pub select_pages_label: &'static str,
+    // 參數：PART_INDEX、PART_COUNT
+    pub part_heading: &'static str,
+    pub compat_notice: &'static str,
+    pub gzip_note: &'static str,
+    pub zstd_note: &'static str,
+    // 參數：PAYLOAD_FILE
+    pub external_note: &'static str,
+    // 參數：WIDTH
+    pub wrap_note: &'static str,
+    // 參數：SLICES
+    pub slice_note: &'static str,
+    // 參數：NAME、OFFSET、LEN、SIZE
+    pub slice_item: &'static str,
+    // 參數：NAME、EXTRACT
+    pub no_js_link: &'static str,
+    // 參數：NAME、LIMIT、EXTRACT
+    pub no_js_manual: &'static str,
+    pub no_js_password: &'static str,
+    // 參數：NAME
+    pub no_js_download: &'static str,
+    pub no_js_payload_label: &'static str,
+    pub no_js_sha256_label: &'static str,
+    // 參數：DOWNLOAD_ZIP_NAME
+    pub mhtml_note: &'static str,
+    // 參數：ENCODING、SUFFIX
+    pub instructions_double_password: &'static str,
+    pub instructions_double: &'static str,
+    pub instructions_single_password: &'static str,
+    pub instructions_single: &'static str,
+    pub instructions_none: &'static str,
+    // 參數：ENCODING、SUFFIX、EXT
+    pub instructions_tar: &'static str,
+    // 參數：ENCODING、SUFFIX
+    pub instructions_7z_password: &'static str,
+    pub instructions_7z: &'static str,
+    pub instructions_webcrypto: &'static str,
+    pub instructions_tree_encrypted: &'static str,
+    pub instructions_tree: &'static str,
+    // 參數：PART_COUNT、EXT、DECRYPT_HINT、ZIP_NAME
+    pub instructions_split: &'static str,
+    pub split_decrypt_hint: &'static str,
+    pub password_below: &'static str,
+    pub password_label: &'static str,
+    // 雙層壓縮時外層與內層密碼的標籤
+    pub outer_password_label: &'static str,
+    pub inner_password_label: &'static str,
+    // 參數：KEY_FILE
+    pub password_in_key_file: &'static str,
+    pub no_password: &'static str,
+    pub password_qr_alt: &'static str,
+    pub password_hint_label: &'static str,
+    // 寄件人留言區塊的無障礙名稱
+    pub message_label: &'static str,
+    pub key_sheet_title: &'static str,
+    pub key_sheet_file: &'static str,
+    pub key_sheet_page: &'static str,
+    pub key_sheet_password: &'static str,
+    pub key_sheet_created: &'static str,
+    pub key_sheet_note: &'static str,
+    pub index_title: &'static str,
+    pub index_source: &'static str,
+    // 參數：FILE_COUNT、TOTAL_SIZE、GENERATED_AT
+    pub index_summary: &'static str,
+    pub index_page: &'static str,
+    pub index_source_path: &'static str,
+    pub index_source_size: &'static str,
+    pub index_generated_at: &'static str,
+    // 參數：EXT
+    pub rename_hint: &'static str,
+    pub restore_title: &'static str,
+    // 參數：FILE_COUNT、PAGE_COUNT
+    pub restore_summary: &'static str,
+    pub restore_step_collect: &'static str,
+    pub restore_step_verify: &'static str,
+    pub restore_step_open: &'static str,
+    pub restore_status: &'static str,
+    pub restore_pending: &'static str,
+    pub decoder_title: &'static str,
+    pub decoder_intro: &'static str,
+    pub decoder_placeholder: &'static str,
+    pub decoder_file_label: &'static str,
+    pub decoder_encoding_label: &'static str,
+    pub decoder_encoding_auto: &'static str,
+    pub decoder_button: &'static str,
+    pub viewer_password_label: &'static str,
+    pub js: JsMessages,
+}
+
+// 頁面腳本使用的訊息，以 JSON 嵌入頁面，`{name}` 由腳本替換
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsMessages {
+    pub download: &'static str,
+    pub decoding: &'static str,
+    pub integrity_unavailable: &'static str,
+    pub integrity_ok: &'static str,
+    pub integrity_bad: &'static str,
+    pub integrity_chunked: &'static str,
+    pub confirm_download_bad: &'static str,
+    pub confirm_decrypt_bad: &'static str,
+    pub download_failed: &'static str,
+    pub decrypt_failed: &'static str,
+    pub wrong_password: &'static str,
+    pub no_webcrypto: &'static str,
+    pub unlock_first: &'static str,
+    pub copied: &'static str,
+    pub copy_failed: &'static str,
+    pub tips_windows: &'static str,
+    pub tips_windows_aes: &'static str,
+    pub tips_mac: &'static str,
+    pub tips_mac_aes: &'static str,
+    pub tips_linux: &'static str,
+    pub tips_linux_aes: &'static str,
+    pub tips_mobile: &'static str,
+    pub tips_mobile_aes: &'static str,
+    pub tips_double: &'static str,
+    pub tips_tar: &'static str,
+    pub tips_7z: &'static str,
+    pub unsupported_method: &'static str,
+    pub gzip_unsupported: &'static str,
+    pub zstd_download: &'static str,
+    pub select_payload: &'static str,
+    pub parts_complete: &'static str,
+    pub parts_progress: &'static str,
+    pub list_separator: &'static str,
+    pub assemble_failed: &'static str,
+    pub not_a_part: &'static str,
+    pub expires_on: &'static str,
+    pub expired: &'static str,
+    pub restore_ok: &'static str,
+    pub restore_mismatch: &'static str,
+    pub restore_unverified: &'static str,
+    pub restore_unknown: &'static str,
+    pub restore_progress: &'static str,
+    pub restore_complete: &'static str,
+    pub decoder_empty: &'static str,
+    pub decoder_invalid: &'static str,
+    pub decoder_done: &'static str,
+    pub decoder_not_zip: &'static str,
+    pub viewer_info: &'static str,
+    pub viewer_password_required: &'static str,
+    pub viewer_missing_payload: &'static str,
+}
+
+static ZH_TW: Messages = Messages {
+    html_lang: "zh-TW",
+    title: "檔案下載",
+    file_name_label: "檔案名稱：",
+    file_size_label: "檔案大小：",
+    integrity_checking: "正在驗證檔案完整性…",
+    integrity_select_parts: "請選擇其餘分段以驗證檔案完整性",
+    password_placeholder: "請輸入密碼",
+    download_zip: "下載 ZIP 檔案",
+    copy_payload: "複製 {{ENCODING}}",
+    save_payload: "另存為 .{{SUFFIX}} 檔案",
+    decrypt_and_download: "解密並下載",
+    unlock: "解鎖",
+    download_full_zip: "下載完整 ZIP 檔案",
+    assemble_and_download: "合併並下載 ZIP 檔案",
+    download_part: "下載此分段",
+    noscript: "此頁面需要啟用 JavaScript 才能運作，請改用已啟用 JavaScript 的瀏覽器開啟。",
+    noscript_payload: "也可以檢視頁面原始碼，將其中以 {{ENCODING}} 編碼的資料區段複製出來，以解碼工具還原為 ZIP 檔案。",
+    decode_progress_label: "解碼進度",
+    select_pages_label: "選擇頁面檔案",
+    part_heading: "（分段 {{PART_INDEX}}/{{PART_COUNT}}）",
+    compat_notice: "注意：此 ZIP 以 AES 加密，macOS 的「封存工具程式」與 Windows 檔案總管無法開啟，請使用 7-Zip、WinRAR、Keka 或 The Unarchiver 解壓。",
+    gzip_note: "<p>頁面資料以 gzip 壓縮：手動解碼取得的是 .gz 檔，需先以 gzip 或 7-Zip 解壓後才是 ZIP 檔案。</p>",
+    zstd_note: "<p>頁面資料以 zstd 壓縮：手動解碼取得的是 .zst 檔，頁面未附解碼器時下載的檔案也是 .zst 檔，需先執行 <code>zstd -d</code> 或以 PeaZip 解壓後才是 ZIP 檔案。</p>",
+    external_note: "<p>下載資料另存於 {{PAYLOAD_FILE}}，請將此頁面與該檔案放在同一個資料夾；瀏覽器無法自動讀取時，請依頁面提示選擇該檔案。</p>",
+    wrap_note: "<p>Base64 文字每 {{WIDTH}} 個字元換行，方便經由聊天軟體或郵件轉貼；解碼時會忽略換行與空白，轉貼後自動斷行或多出空白也能還原。</p>",
+    slice_note: "<p>此頁面僅包含下列檔案的部分內容：</p><ul>{{SLICES}}</ul>",
+    slice_item: "<li>{{NAME}}：自位移 {{OFFSET}} 起 {{LEN}} 位元組（原檔 {{SIZE}}）</li>",
+    no_js_link: "<p>此頁面不使用 JavaScript，請點擊下方連結下載 {{NAME}}。{{EXTRACT}}</p>",
+    no_js_manual: "<p>此頁面不使用 JavaScript，且資料超過瀏覽器資料連結的大小上限（{{LIMIT}}），無法直接下載。請全選並複製下方的 Base64 文字，另存為 {{NAME}}.b64，再執行 <code>base64 -d {{NAME}}.b64 &gt; {{NAME}}</code>（Windows：<code>certutil -decode {{NAME}}.b64 {{NAME}}</code>）還原。{{EXTRACT}}</p>",
+    no_js_password: "解壓時需輸入密碼，建議使用 7-Zip 或 WinRAR。",
+    no_js_download: "下載 {{NAME}}",
+    no_js_payload_label: "Base64 資料",
+    no_js_sha256_label: "下載檔案的 SHA-256：",
+    mhtml_note: "<p>此 MHTML 文件的資料以附件 {{DOWNLOAD_ZIP_NAME}} 提供：若頁面無法下載，請將副檔名由 .mht 改為 .eml，以郵件程式開啟後儲存附件。</p>",
+    instructions_double_password: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為 ZIP 檔案，然後以外層密碼解壓外層 ZIP，再以內層密碼解壓其中的內層 ZIP（無密碼的一層直接解壓）。建議使用 7-Zip 或 WinRAR。</p>",
+    instructions_double: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為 ZIP 檔案，然後無需密碼解壓外層和內層 ZIP。建議使用 7-Zip 或 WinRAR。</p>",
+    instructions_single_password: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為 ZIP 檔案，然後使用密碼解壓 ZIP。建議使用 7-Zip 或 WinRAR。</p>",
+    instructions_single: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為 ZIP 檔案，然後無需密碼解壓 ZIP。建議使用 7-Zip 或 WinRAR。</p>",
+    instructions_none: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為檔案，無需解壓。</p>",
+    instructions_tar: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為 .{{EXT}} 檔案，然後以 <code>tar -xf</code> 解開。Linux、macOS 與 Windows 10 以上皆內建 tar 指令，也可使用 7-Zip。</p>",
+    instructions_7z_password: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為 .7z 檔案，然後使用密碼解壓。請使用 7-Zip、Keka 或 The Unarchiver。</p>",
+    instructions_7z: "<p>請使用下載按鈕，或以「複製 {{ENCODING}}」、「另存為 .{{SUFFIX}} 檔案」取得 {{ENCODING}} 資料後手動解碼為 .7z 檔案，然後無需密碼解壓。請使用 7-Zip、Keka 或 The Unarchiver。</p>",
+    instructions_webcrypto: "<p>此檔案已使用 AES-GCM 加密，請在下方輸入密碼後點擊「解密並下載」，瀏覽器將直接解密並下載 ZIP 檔案，無需支援 AES 的解壓工具。</p>",
+    instructions_tree_encrypted: "<p>請先輸入密碼解鎖，再從下方檔案樹點擊「下載」取得個別檔案，或下載完整 ZIP 檔案。</p>",
+    instructions_tree: "<p>請從下方檔案樹點擊「下載」取得個別檔案，或下載完整 ZIP 檔案。</p>",
+    instructions_split: "<p>此檔案已拆分為 {{PART_COUNT}} 個分段頁面（.part1.{{EXT}} 至 .part{{PART_COUNT}}.{{EXT}}）。請在任一分段頁面選擇其餘分段的 HTML 檔案，{{DECRYPT_HINT}}點擊「合併並下載 ZIP 檔案」即可取得完整檔案。</p>\n    <p>也可在每個分段頁面點擊「下載此分段」，再依序合併：<code>cat {{ZIP_NAME}}.* &gt; {{ZIP_NAME}}</code>（Windows：<code>copy /b {{ZIP_NAME}}.001+{{ZIP_NAME}}.002 {{ZIP_NAME}}</code>）。</p>",
+    split_decrypt_hint: "輸入密碼後",
+    password_below: "下方密碼",
+    password_label: "密碼：",
+    outer_password_label: "外層密碼：",
+    inner_password_label: "內層密碼：",
+    password_in_key_file: "{{KEY_FILE}} 檔案",
+    no_password: "無需密碼",
+    password_qr_alt: "密碼 QR Code",
+    password_hint_label: "密碼提示：",
+    message_label: "寄件人留言",
+    key_sheet_title: "密碼單",
+    key_sheet_file: "檔案",
+    key_sheet_page: "頁面",
+    key_sheet_password: "密碼",
+    key_sheet_created: "產生時間",
+    key_sheet_note: "請將此密碼單列印後妥善保管，並刪除電子檔；密碼遺失將無法開啟對應的頁面。",
+    index_title: "檔案索引",
+    index_source: "來源路徑：",
+    index_summary: "共 {{FILE_COUNT}} 個檔案，總大小：{{TOTAL_SIZE}}，產生時間：{{GENERATED_AT}}",
+    index_page: "頁面",
+    index_source_path: "原始路徑",
+    index_source_size: "原始大小",
+    index_generated_at: "產生時間",
+    rename_hint: "頁面以 .{{EXT}} 副檔名輸出，請將下載的檔案副檔名改為 .html（MIME 類型 text/html）後再以瀏覽器開啟。",
+    restore_title: "還原說明",
+    restore_summary: "共 {{FILE_COUNT}} 個來源檔案，{{PAGE_COUNT}} 個頁面",
+    restore_step_collect: "將下表列出的所有頁面下載至同一資料夾，密碼檔請另行取得。",
+    restore_step_verify: "於下方選擇已下載的頁面，本頁會比對 SHA-256 確認內容完整，檔案改名後仍可辨識。",
+    restore_step_open: "全部驗證通過後開啟各頁面下載；分段檔案請開啟第 1 段並選擇其餘分段即可合併。",
+    restore_status: "狀態",
+    restore_pending: "尚未選擇",
+    decoder_title: "解碼工具",
+    decoder_intro: "貼上由 file_to_html 產生的 Base64 或 Base85 資料，或拖放 .b64、.b85 檔案與產生的頁面，即可還原為 ZIP 檔案。多個檔案會依檔名順序串接，可用於還原分段；拖放的頁面已加密時，輸入密碼即可在瀏覽器中解密，資料另存於 .bin 檔的頁面請一併選擇該檔案。",
+    decoder_placeholder: "在此貼上編碼資料，或將檔案拖放到這裡",
+    decoder_file_label: "選擇檔案：",
+    decoder_encoding_label: "編碼：",
+    decoder_encoding_auto: "自動偵測",
+    decoder_button: "還原並下載",
+    viewer_password_label: "此頁面已加密，請輸入密碼：",
+    js: JsMessages {
+        download: "下載",
+        decoding: "正在解碼資料…",
+        integrity_unavailable: "此瀏覽器無法驗證檔案完整性",
+        integrity_ok: "✔ 完整性驗證通過（SHA-256：{hash}…）",
+        integrity_bad: "✘ 完整性驗證失敗，檔案內容與產生時不一致，可能已損毀或遭竄改",
+        integrity_chunked: "此頁面以分塊 AES-GCM 加密，各分塊會在解密時驗證完整性",
+        confirm_download_bad: "完整性驗證失敗，仍要下載嗎？",
+        confirm_decrypt_bad: "完整性驗證失敗，仍要解密嗎？",
+        download_failed: "下載失敗：",
+        decrypt_failed: "解密失敗，請確認密碼是否正確。",
+        wrong_password: "密碼錯誤，請重新輸入。",
+        no_webcrypto: "此瀏覽器不支援 WebCrypto，請改用新版 Chrome、Edge、Firefox 或 Safari。",
+        unlock_first: "請先輸入密碼解鎖。",
+        copied: "已複製資料（{size}）",
+        copy_failed: "無法複製，請改用另存檔案的按鈕",
+        tips_windows: "Windows：建議使用 7-Zip 解壓，在檔案上按右鍵選擇「7-Zip」→「解壓縮至此」；內建的解壓縮功能亦可開啟。",
+        tips_windows_aes: "Windows：內建的解壓縮功能不支援 AES 加密的 ZIP，請使用 7-Zip 或 WinRAR 解壓並輸入密碼。",
+        tips_mac: "macOS：按兩下 ZIP 檔案即可用「封存工具程式」解壓，或在終端機執行：",
+        tips_mac_aes: "macOS：內建的「封存工具程式」無法解壓 AES 加密的 ZIP，請使用 Keka 或 The Unarchiver，或以 Homebrew 安裝 p7zip 後在終端機執行：",
+        tips_linux: "Linux：在終端機執行：",
+        tips_linux_aes: "Linux：unzip 不支援 AES 加密，請安裝 p7zip（p7zip-full）後在終端機執行：",
+        tips_mobile: "行動裝置：請使用支援 ZIP 的檔案管理 App 解壓。",
+        tips_mobile_aes: "行動裝置：多數內建檔案管理 App 無法解壓 AES 加密的 ZIP，建議改用支援 AES 的 App 或在電腦上處理。",
+        tips_double: "此為雙層 ZIP：解壓外層後，再以相同方式解壓其中的內層 ZIP。",
+        tips_tar: "此為 tar 封存檔：在終端機（Windows 10 以上為命令提示字元）執行：",
+        tips_7z: "此為 7z 封存檔：請使用 7-Zip、Keka 或 The Unarchiver 解壓，或在終端機執行：",
+        unsupported_method: "此瀏覽器無法解壓縮 {method} 格式，請下載完整 ZIP 檔案",
+        gzip_unsupported: "此瀏覽器不支援 DecompressionStream，下載的檔案為 gzip 壓縮（.gz），請先以 gzip 或 7-Zip 解壓後再開啟 ZIP",
+        zstd_download: "頁面資料以 zstd 壓縮，下載的檔案為 .zst，請先執行 zstd -d 或以 PeaZip 解壓後再開啟 ZIP",
+        select_payload: "無法自動讀取資料檔，請選擇與此頁面一同提供的 {name}：",
+        parts_complete: "已取得全部 {count} 個分段",
+        parts_progress: "已取得 {have}/{count} 個分段，尚缺第 {missing} 段",
+        list_separator: "、",
+        assemble_failed: "合併失敗：",
+        not_a_part: "以下檔案不是此檔案的分段：",
+        expires_on: "下載期限：{date}",
+        expired: "此檔案已於 {date} 到期，無法再下載",
+        restore_ok: "✔ 已驗證",
+        restore_mismatch: "✘ 內容不符，請重新下載",
+        restore_unverified: "已選擇（此瀏覽器無法驗證）",
+        restore_unknown: "以下檔案不屬於此次產出：",
+        restore_progress: "已驗證 {have}/{count} 個頁面",
+        restore_complete: "全部 {count} 個頁面均已驗證",
+        decoder_empty: "請先貼上資料或選擇檔案",
+        decoder_invalid: "資料無法解碼，請確認內容完整且編碼正確",
+        decoder_done: "已還原 {name}（{size} 位元組），SHA-256：{hash}",
+        decoder_not_zip: "還原的資料不是 ZIP 檔案，可能已加密，請改為拖放原頁面並輸入密碼",
+        viewer_info: "{name}（輸出格式版本 {version}，{generator}，整體壓縮：{compression}）",
+        viewer_password_required: "此頁面已加密，請先輸入密碼",
+        viewer_missing_payload: "頁面資料存放於 {name}，請一併選擇該檔案",
+    },
+};
+
+static ZH_CN: Messages = Messages {
+    html_lang: "zh-CN",
+    title: "文件下载",
+    file_name_label: "文件名称：",
+    file_size_label: "文件大小：",
+    integrity_checking: "正在验证文件完整性…",
+    integrity_select_parts: "请选择其余分段以验证文件完整性",
+    password_placeholder: "请输入密码",
+    download_zip: "下载 ZIP 文件",
+    copy_payload: "复制 {{ENCODING}}",
+    save_payload: "另存为 .{{SUFFIX}} 文件",
+    decrypt_and_download: "解密并下载",
+    unlock: "解锁",
+    download_full_zip: "下载完整 ZIP 文件",
+    assemble_and_download: "合并并下载 ZIP 文件",
+    download_part: "下载此分段",
+    noscript: "此页面需要启用 JavaScript 才能运行，请改用已启用 JavaScript 的浏览器打开。",
+    noscript_payload: "也可以查看页面源代码，将其中以 {{ENCODING}} 编码的数据段复制出来，用解码工具还原为 ZIP 文件。",
+    decode_progress_label: "解码进度",
+    select_pages_label: "选择页面文件",
+    part_heading: "（分段 {{PART_INDEX}}/{{PART_COUNT}}）",
+    compat_notice: "注意：此 ZIP 使用 AES 加密，macOS 的“归档实用工具”与 Windows 资源管理器无法打开，请使用 7-Zip、WinRAR、Keka 或 The Unarchiver 解压。",
+    gzip_note: "<p>页面数据以 gzip 压缩：手动解码得到的是 .gz 文件，需先用 gzip 或 7-Zip 解压后才是 ZIP 文件。</p>",
+    zstd_note: "<p>页面数据以 zstd 压缩：手动解码得到的是 .zst 文件，页面未附解码器时下载的文件也是 .zst 文件，需先执行 <code>zstd -d</code> 或用 PeaZip 解压后才是 ZIP 文件。</p>",
+    external_note: "<p>下载数据另存于 {{PAYLOAD_FILE}}，请将此页面与该文件放在同一个文件夹；浏览器无法自动读取时，请按页面提示选择该文件。</p>",
+    wrap_note: "<p>Base64 文本每 {{WIDTH}} 个字符换行，方便通过聊天软件或邮件转发；解码时会忽略换行与空白，转发后自动断行或多出空白也能还原。</p>",
+    slice_note: "<p>此页面仅包含下列文件的部分内容：</p><ul>{{SLICES}}</ul>",
+    slice_item: "<li>{{NAME}}：自偏移 {{OFFSET}} 起 {{LEN}} 字节（原文件 {{SIZE}}）</li>",
+    no_js_link: "<p>此页面不使用 JavaScript，请点击下方链接下载 {{NAME}}。{{EXTRACT}}</p>",
+    no_js_manual: "<p>此页面不使用 JavaScript，且数据超过浏览器数据链接的大小上限（{{LIMIT}}），无法直接下载。请全选并复制下方的 Base64 文本，另存为 {{NAME}}.b64，再执行 <code>base64 -d {{NAME}}.b64 &gt; {{NAME}}</code>（Windows：<code>certutil -decode {{NAME}}.b64 {{NAME}}</code>）还原。{{EXTRACT}}</p>",
+    no_js_password: "解压时需输入密码，建议使用 7-Zip 或 WinRAR。",
+    no_js_download: "下载 {{NAME}}",
+    no_js_payload_label: "Base64 数据",
+    no_js_sha256_label: "下载文件的 SHA-256：",
+    mhtml_note: "<p>此 MHTML 文档的数据以附件 {{DOWNLOAD_ZIP_NAME}} 提供：若页面无法下载，请将扩展名由 .mht 改为 .eml，用邮件程序打开后保存附件。</p>",
+    instructions_double_password: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为 ZIP 文件，然后用外层密码解压外层 ZIP，再用内层密码解压其中的内层 ZIP（无密码的一层直接解压）。建议使用 7-Zip 或 WinRAR。</p>",
+    instructions_double: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为 ZIP 文件，然后无需密码解压外层和内层 ZIP。建议使用 7-Zip 或 WinRAR。</p>",
+    instructions_single_password: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为 ZIP 文件，然后使用密码解压 ZIP。建议使用 7-Zip 或 WinRAR。</p>",
+    instructions_single: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为 ZIP 文件，然后无需密码解压 ZIP。建议使用 7-Zip 或 WinRAR。</p>",
+    instructions_none: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为文件，无需解压。</p>",
+    instructions_tar: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为 .{{EXT}} 文件，然后用 <code>tar -xf</code> 解开。Linux、macOS 与 Windows 10 以上均自带 tar 命令，也可使用 7-Zip。</p>",
+    instructions_7z_password: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为 .7z 文件，然后使用密码解压。请使用 7-Zip、Keka 或 The Unarchiver。</p>",
+    instructions_7z: "<p>请使用下载按钮，或通过“复制 {{ENCODING}}”、“另存为 .{{SUFFIX}} 文件”获取 {{ENCODING}} 数据后手动解码为 .7z 文件，然后无需密码解压。请使用 7-Zip、Keka 或 The Unarchiver。</p>",
+    instructions_webcrypto: "<p>此文件已使用 AES-GCM 加密，请在下方输入密码后点击“解密并下载”，浏览器将直接解密并下载 ZIP 文件，无需支持 AES 的解压工具。</p>",
+    instructions_tree_encrypted: "<p>请先输入密码解锁，再从下方文件树点击“下载”获取单个文件，或下载完整 ZIP 文件。</p>",
+    instructions_tree: "<p>请从下方文件树点击“下载”获取单个文件，或下载完整 ZIP 文件。</p>",
+    instructions_split: "<p>此文件已拆分为 {{PART_COUNT}} 个分段页面（.part1.{{EXT}} 至 .part{{PART_COUNT}}.{{EXT}}）。请在任一分段页面选择其余分段的 HTML 文件，{{DECRYPT_HINT}}点击“合并并下载 ZIP 文件”即可获取完整文件。</p>\n    <p>也可在每个分段页面点击“下载此分段”，再依次合并：<code>cat {{ZIP_NAME}}.* &gt; {{ZIP_NAME}}</code>（Windows：<code>copy /b {{ZIP_NAME}}.001+{{ZIP_NAME}}.002 {{ZIP_NAME}}</code>）。</p>",
+    split_decrypt_hint: "输入密码后",
+    password_below: "下方密码",
+    password_label: "密码：",
+    outer_password_label: "外层密码：",
+    inner_password_label: "内层密码：",
+    password_in_key_file: "{{KEY_FILE}} 文件",
+    no_password: "无需密码",
+    password_qr_alt: "密码二维码",
+    password_hint_label: "密码提示：",
+    message_label: "发件人留言",
+    key_sheet_title: "密码单",
+    key_sheet_file: "文件",
+    key_sheet_page: "页面",
+    key_sheet_password: "密码",
+    key_sheet_created: "生成时间",
+    key_sheet_note: "请将此密码单打印后妥善保管，并删除电子文件；密码遗失将无法打开对应的页面。",
+    index_title: "文件索引",
+    index_source: "来源路径：",
+    index_summary: "共 {{FILE_COUNT}} 个文件，总大小：{{TOTAL_SIZE}}，生成时间：{{GENERATED_AT}}",
+    index_page: "页面",
+    index_source_path: "原始路径",
+    index_source_size: "原始大小",
+    index_generated_at: "生成时间",
+    rename_hint: "页面以 .{{EXT}} 扩展名输出，请将下载的文件扩展名改为 .html（MIME 类型 text/html）后再用浏览器打开。",
+    restore_title: "还原说明",
+    restore_summary: "共 {{FILE_COUNT}} 个源文件，{{PAGE_COUNT}} 个页面",
+    restore_step_collect: "将下表列出的所有页面下载到同一文件夹，密码文件请另行获取。",
+    restore_step_verify: "在下方选择已下载的页面，本页会比对 SHA-256 确认内容完整，文件改名后仍可识别。",
+    restore_step_open: "全部验证通过后打开各页面下载；分段文件请打开第 1 段并选择其余分段即可合并。",
+    restore_status: "状态",
+    restore_pending: "尚未选择",
+    decoder_title: "解码工具",
+    decoder_intro: "粘贴由 file_to_html 生成的 Base64 或 Base85 数据，或拖放 .b64、.b85 文件与生成的页面，即可还原为 ZIP 文件。多个文件会按文件名顺序拼接，可用于还原分段；拖放的页面已加密时，输入密码即可在浏览器中解密，数据另存于 .bin 文件的页面请一并选择该文件。",
+    decoder_placeholder: "在此粘贴编码数据，或将文件拖放到这里",
+    decoder_file_label: "选择文件：",
+    decoder_encoding_label: "编码：",
+    decoder_encoding_auto: "自动检测",
+    decoder_button: "还原并下载",
+    viewer_password_label: "此页面已加密，请输入密码：",
+    js: JsMessages {
+        download: "下载",
+        decoding: "正在解码数据…",
+        integrity_unavailable: "此浏览器无法验证文件完整性",
+        integrity_ok: "✔ 完整性验证通过（SHA-256：{hash}…）",
+        integrity_bad: "✘ 完整性验证失败，文件内容与生成时不一致，可能已损坏或被篡改",
+        integrity_chunked: "此页面以分块 AES-GCM 加密，各分块会在解密时验证完整性",
+        confirm_download_bad: "完整性验证失败，仍要下载吗？",
+        confirm_decrypt_bad: "完整性验证失败，仍要解密吗？",
+        download_failed: "下载失败：",
+        decrypt_failed: "解密失败，请确认密码是否正确。",
+        wrong_password: "密码错误，请重新输入。",
+        no_webcrypto: "此浏览器不支持 WebCrypto，请改用新版 Chrome、Edge、Firefox 或 Safari。",
+        unlock_first: "请先输入密码解锁。",
+        copied: "已复制数据（{size}）",
+        copy_failed: "无法复制，请改用另存文件的按钮",
+        tips_windows: "Windows：建议使用 7-Zip 解压，在文件上右键选择“7-Zip”→“提取到当前位置”；系统自带的解压功能也可打开。",
+        tips_windows_aes: "Windows：系统自带的解压功能不支持 AES 加密的 ZIP，请使用 7-Zip 或 WinRAR 解压并输入密码。",
+        tips_mac: "macOS：双击 ZIP 文件即可用“归档实用工具”解压，或在终端执行：",
+        tips_mac_aes: "macOS：系统自带的“归档实用工具”无法解压 AES 加密的 ZIP，请使用 Keka 或 The Unarchiver，或通过 Homebrew 安装 p7zip 后在终端执行：",
+        tips_linux: "Linux：在终端执行：",
+        tips_linux_aes: "Linux：unzip 不支持 AES 加密，请安装 p7zip（p7zip-full）后在终端执行：",
+        tips_mobile: "移动设备：请使用支持 ZIP 的文件管理 App 解压。",
+        tips_mobile_aes: "移动设备：多数自带文件管理 App 无法解压 AES 加密的 ZIP，建议改用支持 AES 的 App 或在电脑上处理。",
+        tips_double: "这是双层 ZIP：解压外层后，再以相同方式解压其中的内层 ZIP。",
+        tips_tar: "这是 tar 归档文件：在终端（Windows 10 以上为命令提示符）执行：",
+        tips_7z: "这是 7z 归档文件：请使用 7-Zip、Keka 或 The Unarchiver 解压，或在终端执行：",
+        unsupported_method: "此浏览器无法解压 {method} 格式，请下载完整 ZIP 文件",
+        gzip_unsupported: "此浏览器不支持 DecompressionStream，下载的文件为 gzip 压缩（.gz），请先用 gzip 或 7-Zip 解压后再打开 ZIP",
+        zstd_download: "页面数据以 zstd 压缩，下载的文件为 .zst，请先执行 zstd -d 或用 PeaZip 解压后再打开 ZIP",
+        select_payload: "无法自动读取数据文件，请选择与此页面一同提供的 {name}：",
+        parts_complete: "已获取全部 {count} 个分段",
+        parts_progress: "已获取 {have}/{count} 个分段，尚缺第 {missing} 段",
+        list_separator: "、",
+        assemble_failed: "合并失败：",
+        not_a_part: "以下文件不是此文件的分段：",
+        expires_on: "下载期限：{date}",
+        expired: "此文件已于 {date} 过期，无法再下载",
+        restore_ok: "✔ 已验证",
+        restore_mismatch: "✘ 内容不符，请重新下载",
+        restore_unverified: "已选择（此浏览器无法验证）",
+        restore_unknown: "以下文件不属于此次输出：",
+        restore_progress: "已验证 {have}/{count} 个页面",
+        restore_complete: "全部 {count} 个页面均已验证",
+        decoder_empty: "请先粘贴数据或选择文件",
+        decoder_invalid: "数据无法解码，请确认内容完整且编码正确",
+        decoder_done: "已还原 {name}（{size} 字节），SHA-256：{hash}",
+        decoder_not_zip: "还原的数据不是 ZIP 文件，可能已加密，请改为拖放原页面并输入密码",
+        viewer_info: "{name}（输出格式版本 {version}，{generator}，整体压缩：{compression}）",
+        viewer_password_required: "此页面已加密，请先输入密码",
+        viewer_missing_payload: "页面数据存放于 {name}，请一并选择该文件",
+    },
+};
+
+static EN: Messages = Messages {
+    html_lang: "en",
+    title: "File Download",
+    file_name_label: "File name: ",
+    file_size_label: "File size: ",
+    integrity_checking: "Verifying file integrity…",
+    integrity_select_parts: "Select the remaining parts to verify file integrity",
+    password_placeholder: "Enter password",
+    download_zip: "Download ZIP file",
+    copy_payload: "Copy {{ENCODING}}",
+    save_payload: "Save as .{{SUFFIX}} file",
+    decrypt_and_download: "Decrypt and download",
+    unlock: "Unlock",
+    download_full_zip: "Download full ZIP file",
+    assemble_and_download: "Join and download ZIP file",
+    download_part: "Download this part",
+    noscript: "This page needs JavaScript. Open it in a browser with JavaScript enabled.",
+    noscript_payload: "You can also view the page source, copy the {{ENCODING}}-encoded data blocks and decode them with any decoder to get the ZIP file back.",
+    decode_progress_label: "Decoding progress",
+    select_pages_label: "Select page files",
+    part_heading: " (part {{PART_INDEX}}/{{PART_COUNT}})",
+    compat_notice: "Note: this ZIP is AES-encrypted. macOS Archive Utility and Windows File Explorer cannot open it; use 7-Zip, WinRAR, Keka or The Unarchiver instead.",
+    gzip_note: "<p>The page data is gzip-compressed: manually decoded data is a .gz file; decompress it with gzip or 7-Zip to get the ZIP file.</p>",
+    zstd_note: "<p>The page data is zstd-compressed: manually decoded data is a .zst file, and so is the download when the page has no built-in decoder; run <code>zstd -d</code> or use PeaZip to get the ZIP file.</p>",
+    external_note: "<p>The download data is stored separately in {{PAYLOAD_FILE}}. Keep this page and that file in the same folder; if the browser cannot read it automatically, select the file when prompted.</p>",
+    wrap_note: "<p>The Base64 text is wrapped every {{WIDTH}} characters so it survives chat apps and email; line breaks and spaces are ignored when decoding, so re-wrapped or padded text still restores correctly.</p>",
+    slice_note: "<p>This page contains only part of the following files:</p><ul>{{SLICES}}</ul>",
+    slice_item: "<li>{{NAME}}: {{LEN}} bytes starting at offset {{OFFSET}} (original file {{SIZE}})</li>",
+    no_js_link: "<p>This page does not use JavaScript. Click the link below to download {{NAME}}.{{EXTRACT}}</p>",
+    no_js_manual: "<p>This page does not use JavaScript, and the data is larger than browsers allow for a data link ({{LIMIT}}), so it cannot be downloaded directly. Select and copy all of the Base64 text below, save it as {{NAME}}.b64, then run <code>base64 -d {{NAME}}.b64 &gt; {{NAME}}</code> (Windows: <code>certutil -decode {{NAME}}.b64 {{NAME}}</code>) to restore it.{{EXTRACT}}</p>",
+    no_js_password: " The archive needs the password to extract; 7-Zip or WinRAR is recommended.",
+    no_js_download: "Download {{NAME}}",
+    no_js_payload_label: "Base64 data",
+    no_js_sha256_label: "SHA-256 of the downloaded file: ",
+    mhtml_note: "<p>This MHTML document carries the data as the attachment {{DOWNLOAD_ZIP_NAME}}: if the page cannot download it, rename the file from .mht to .eml, open it in a mail client and save the attachment.</p>",
+    instructions_double_password: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a ZIP file manually, then extract the outer ZIP with the outer password and the inner ZIP inside it with the inner password (a layer without a password extracts directly). 7-Zip or WinRAR is recommended.</p>",
+    instructions_double: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a ZIP file manually, then extract the outer and inner ZIP; no password is needed. 7-Zip or WinRAR is recommended.</p>",
+    instructions_single_password: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a ZIP file manually, then extract the ZIP with the password. 7-Zip or WinRAR is recommended.</p>",
+    instructions_single: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a ZIP file manually, then extract the ZIP; no password is needed. 7-Zip or WinRAR is recommended.</p>",
+    instructions_none: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a file manually; no extraction is needed.</p>",
+    instructions_tar: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a .{{EXT}} file manually, then extract it with <code>tar -xf</code>. Linux, macOS and Windows 10 or later include the tar command; 7-Zip also works.</p>",
+    instructions_7z_password: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a .7z file manually, then extract it with the password. Use 7-Zip, Keka or The Unarchiver.</p>",
+    instructions_7z: "<p>Use the download button, or get the {{ENCODING}} data with \"Copy {{ENCODING}}\" or \"Save as .{{SUFFIX}} file\" and decode it into a .7z file manually, then extract it; no password is needed. Use 7-Zip, Keka or The Unarchiver.</p>",
+    instructions_webcrypto: "<p>This file is encrypted with AES-GCM. Enter the password below and click \"Decrypt and download\"; the browser decrypts and downloads the ZIP file directly, so no AES-capable archiver is needed.</p>",
+    instructions_tree_encrypted: "<p>Enter the password to unlock, then click \"Download\" in the file tree below to get individual files, or download the full ZIP file.</p>",
+    instructions_tree: "<p>Click \"Download\" in the file tree below to get individual files, or download the full ZIP file.</p>",
+    instructions_split: "<p>This file is split into {{PART_COUNT}} part pages (.part1.{{EXT}} to .part{{PART_COUNT}}.{{EXT}}). On any part page, select the HTML files of the other parts, {{DECRYPT_HINT}}then click \"Join and download ZIP file\" to get the complete file.</p>\n    <p>Alternatively, click \"Download this part\" on every part page and join them in order: <code>cat {{ZIP_NAME}}.* &gt; {{ZIP_NAME}}</code> (Windows: <code>copy /b {{ZIP_NAME}}.001+{{ZIP_NAME}}.002 {{ZIP_NAME}}</code>).</p>",
+    split_decrypt_hint: "enter the password, ",
+    password_below: "the password below",
+    password_label: "Password: ",
+    outer_password_label: "Outer password: ",
+    inner_password_label: "Inner password: ",
+    password_in_key_file: "the {{KEY_FILE}} file",
+    no_password: "no password required",
+    password_qr_alt: "Password QR code",
+    password_hint_label: "Password hint: ",
+    message_label: "Message from the sender",
+    key_sheet_title: "Password sheet",
+    key_sheet_file: "File",
+    key_sheet_page: "Page",
+    key_sheet_password: "Password",
+    key_sheet_created: "Created",
+    key_sheet_note: "Print this sheet, store it somewhere safe and delete the electronic copy. Without the password the page cannot be opened.",
+    index_title: "File Index",
+    index_source: "Source path: ",
+    index_summary: "{{FILE_COUNT}} files, total size: {{TOTAL_SIZE}}, generated at: {{GENERATED_AT}}",
+    index_page: "Page",
+    index_source_path: "Source path",
+    index_source_size: "Source size",
+    index_generated_at: "Generated at",
+    rename_hint: "Pages use the .{{EXT}} extension. Rename downloaded files to .html (MIME type text/html) before opening them in a browser.",
+    restore_title: "Restore guide",
+    restore_summary: "{{FILE_COUNT}} source files, {{PAGE_COUNT}} pages",
+    restore_step_collect: "Download every page listed below into the same folder. Obtain key files separately.",
+    restore_step_verify: "Select the downloaded pages below. This page compares their SHA-256 to confirm they are intact, even if they were renamed.",
+    restore_step_open: "Once everything is verified, open each page to download. For split files, open part 1 and select the remaining parts to reassemble.",
+    restore_status: "Status",
+    restore_pending: "Not selected",
+    decoder_title: "Decoder",
+    decoder_intro: "Paste Base64 or Base85 data produced by file_to_html, or drop .b64 and .b85 files or generated pages, to get the ZIP file back. Multiple files are joined in file name order, so split parts can be restored too. Encrypted pages are decrypted in the browser after you enter the password; for pages whose data is stored in a .bin file, select that file as well.",
+    decoder_placeholder: "Paste encoded data here, or drop files here",
+    decoder_file_label: "Choose files: ",
+    decoder_encoding_label: "Encoding: ",
+    decoder_encoding_auto: "Auto-detect",
+    decoder_button: "Restore and download",
+    viewer_password_label: "This page is encrypted. Enter the password: ",
+    js: JsMessages {
+        download: "Download",
+        decoding: "Decoding data…",
+        integrity_unavailable: "This browser cannot verify file integrity",
+        integrity_ok: "✔ Integrity check passed (SHA-256: {hash}…)",
+        integrity_bad: "✘ Integrity check failed: the content differs from when it was generated and may be corrupted or tampered with",
+        integrity_chunked: "This page uses chunked AES-GCM encryption; each chunk is verified as it is decrypted",
+        confirm_download_bad: "The integrity check failed. Download anyway?",
+        confirm_decrypt_bad: "The integrity check failed. Decrypt anyway?",
+        download_failed: "Download failed: ",
+        decrypt_failed: "Decryption failed. Please check the password.",
+        wrong_password: "Incorrect password. Please try again.",
+        no_webcrypto: "This browser does not support WebCrypto. Please use a recent Chrome, Edge, Firefox or Safari.",
+        unlock_first: "Please enter the password to unlock first.",
+        copied: "Copied the data ({size})",
+        copy_failed: "Copy failed. Use the save-as-file button instead.",
+        tips_windows: "Windows: 7-Zip is recommended; right-click the file and choose \"7-Zip\" → \"Extract Here\". The built-in extractor also works.",
+        tips_windows_aes: "Windows: the built-in extractor cannot open AES-encrypted ZIP files. Use 7-Zip or WinRAR and enter the password.",
+        tips_mac: "macOS: double-click the ZIP file to extract it with Archive Utility, or run in Terminal:",
+        tips_mac_aes: "macOS: Archive Utility cannot extract AES-encrypted ZIP files. Use Keka or The Unarchiver, or install p7zip with Homebrew and run in Terminal:",
+        tips_linux: "Linux: run in a terminal:",
+        tips_linux_aes: "Linux: unzip does not support AES encryption. Install p7zip (p7zip-full) and run in a terminal:",
+        tips_mobile: "Mobile: use a file manager app that supports ZIP files.",
+        tips_mobile_aes: "Mobile: most built-in file managers cannot extract AES-encrypted ZIP files. Use an app that supports AES, or extract on a computer.",
+        tips_double: "This is a double-layer ZIP: after extracting the outer ZIP, extract the inner ZIP the same way.",
+        tips_tar: "This is a tar archive: run the following in a terminal (Command Prompt on Windows 10 or later):",
+        tips_7z: "This is a 7z archive: extract it with 7-Zip, Keka or The Unarchiver, or run the following in a terminal:",
+        unsupported_method: "This browser cannot decompress the {method} format; please download the full ZIP file",
+        gzip_unsupported: "This browser does not support DecompressionStream; the download is gzip-compressed (.gz). Decompress it with gzip or 7-Zip before opening the ZIP",
+        zstd_download: "The page data is zstd-compressed and the download is a .zst file. Run zstd -d or use PeaZip before opening the ZIP",
+        select_payload: "The data file could not be loaded automatically. Please select {name}, which was provided with this page:",
+        parts_complete: "All {count} parts loaded",
+        parts_progress: "{have}/{count} parts loaded, missing part {missing}",
+        list_separator: ", ",
+        assemble_failed: "Join failed: ",
+        not_a_part: "These files are not parts of this file: ",
+        expires_on: "Available until {date}",
+        expired: "This file expired on {date} and can no longer be downloaded",
+        restore_ok: "✔ Verified",
+        restore_mismatch: "✘ Content mismatch, download again",
+        restore_unverified: "Selected (this browser cannot verify)",
+        restore_unknown: "These files are not part of this output: ",
+        restore_progress: "{have}/{count} pages verified",
+        restore_complete: "All {count} pages verified",
+        decoder_empty: "Paste data or choose files first",
+        decoder_invalid: "The data could not be decoded. Make sure it is complete and the encoding is correct",
+        decoder_done: "Restored {name} ({size} bytes), SHA-256: {hash}",
+        decoder_not_zip: "The restored data is not a ZIP file and may be encrypted. Drop the original page here instead and enter the password",
+        viewer_info: "{name} (output format version {version}, {generator}, payload compression: {compression})",
+        viewer_password_required: "This page is encrypted. Enter the password first",
+        viewer_missing_payload: "The page data is stored in {name}. Select that file as well",
+    },
+};
+
+static JA: Messages = Messages {
+    html_lang: "ja",
+    title: "ファイルのダウンロード",
+    file_name_label: "ファイル名：",
+    file_size_label: "ファイルサイズ：",
+    integrity_checking: "ファイルの整合性を検証しています…",
+    integrity_select_parts: "残りの分割ファイルを選択すると整合性を検証します",
+    password_placeholder: "パスワードを入力",
+    download_zip: "ZIP ファイルをダウンロード",
+    copy_payload: "{{ENCODING}} をコピー",
+    save_payload: ".{{SUFFIX}} ファイルとして保存",
+    decrypt_and_download: "復号してダウンロード",
+    unlock: "ロック解除",
+    download_full_zip: "ZIP ファイル全体をダウンロード",
+    assemble_and_download: "結合して ZIP ファイルをダウンロード",
+    download_part: "この分割をダウンロード",
+    noscript: "このページを使用するには JavaScript が必要です。JavaScript が有効なブラウザで開いてください。",
+    noscript_payload: "ページのソースを表示し、{{ENCODING}} でエンコードされたデータ部分をコピーしてデコードツールで ZIP ファイルに復元することもできます。",
+    decode_progress_label: "デコードの進行状況",
+    select_pages_label: "ページファイルを選択",
+    part_heading: "（分割 {{PART_INDEX}}/{{PART_COUNT}}）",
+    compat_notice: "注意：この ZIP は AES で暗号化されているため、macOS の「アーカイブユーティリティ」や Windows エクスプローラーでは開けません。7-Zip、WinRAR、Keka または The Unarchiver を使用してください。",
+    gzip_note: "<p>ページのデータは gzip で圧縮されています。手動でデコードしたデータは .gz ファイルのため、gzip または 7-Zip で展開すると ZIP ファイルになります。</p>",
+    zstd_note: "<p>ページのデータは zstd で圧縮されています。手動でデコードしたデータは .zst ファイルで、ページにデコーダーが含まれていない場合はダウンロードされるファイルも .zst です。<code>zstd -d</code> を実行するか PeaZip で展開すると ZIP ファイルになります。</p>",
+    external_note: "<p>ダウンロードデータは {{PAYLOAD_FILE}} に別途保存されています。このページと同じフォルダーに置いてください。ブラウザーが自動で読み込めない場合は、画面の案内に従ってファイルを選択してください。</p>",
+    wrap_note: "<p>Base64 テキストはチャットアプリやメールで転送しやすいよう {{WIDTH}} 文字ごとに改行されています。デコード時は改行と空白を無視するため、転送後に折り返しや余分な空白が入っても復元できます。</p>",
+    slice_note: "<p>このページには次のファイルの一部のみが含まれています：</p><ul>{{SLICES}}</ul>",
+    slice_item: "<li>{{NAME}}：オフセット {{OFFSET}} から {{LEN}} バイト（元のファイル {{SIZE}}）</li>",
+    no_js_link: "<p>このページは JavaScript を使用しません。下のリンクをクリックして {{NAME}} をダウンロードしてください。{{EXTRACT}}</p>",
+    no_js_manual: "<p>このページは JavaScript を使用しません。また、データがブラウザーのデータリンクの上限（{{LIMIT}}）を超えているため直接ダウンロードできません。下の Base64 テキストをすべて選択してコピーし、{{NAME}}.b64 として保存してから <code>base64 -d {{NAME}}.b64 &gt; {{NAME}}</code>（Windows：<code>certutil -decode {{NAME}}.b64 {{NAME}}</code>）を実行して復元してください。{{EXTRACT}}</p>",
+    no_js_password: "展開時にはパスワードが必要です。7-Zip または WinRAR の使用をおすすめします。",
+    no_js_download: "{{NAME}} をダウンロード",
+    no_js_payload_label: "Base64 データ",
+    no_js_sha256_label: "ダウンロードしたファイルの SHA-256：",
+    mhtml_note: "<p>この MHTML 文書のデータは添付ファイル {{DOWNLOAD_ZIP_NAME}} として含まれています。ページからダウンロードできない場合は、拡張子を .mht から .eml に変更してメールソフトで開き、添付ファイルを保存してください。</p>",
+    instructions_double_password: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動で ZIP ファイルにデコードし、外側のパスワードで外側の ZIP を、内側のパスワードで中の内側 ZIP を展開してください（パスワードのない層はそのまま展開できます）。7-Zip または WinRAR を推奨します。</p>",
+    instructions_double: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動で ZIP ファイルにデコードし、外側と内側の ZIP を展開してください。パスワードは不要です。7-Zip または WinRAR を推奨します。</p>",
+    instructions_single_password: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動で ZIP ファイルにデコードし、パスワードで ZIP を展開してください。7-Zip または WinRAR を推奨します。</p>",
+    instructions_single: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動で ZIP ファイルにデコードし、ZIP を展開してください。パスワードは不要です。7-Zip または WinRAR を推奨します。</p>",
+    instructions_none: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動でファイルにデコードしてください。展開は不要です。</p>",
+    instructions_tar: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動で .{{EXT}} ファイルにデコードし、<code>tar -xf</code> で展開してください。Linux、macOS、Windows 10 以降には tar コマンドが標準で含まれており、7-Zip でも展開できます。</p>",
+    instructions_7z_password: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動で .7z ファイルにデコードし、パスワードを入力して展開してください。7-Zip、Keka または The Unarchiver を使用してください。</p>",
+    instructions_7z: "<p>ダウンロードボタンを使うか、「{{ENCODING}} をコピー」または「.{{SUFFIX}} ファイルとして保存」で {{ENCODING}} データを取得して手動で .7z ファイルにデコードし、展開してください。パスワードは不要です。7-Zip、Keka または The Unarchiver を使用してください。</p>",
+    instructions_webcrypto: "<p>このファイルは AES-GCM で暗号化されています。下にパスワードを入力して「復号してダウンロード」をクリックすると、ブラウザが直接復号して ZIP ファイルをダウンロードします。AES 対応の展開ツールは不要です。</p>",
+    instructions_tree_encrypted: "<p>パスワードを入力してロックを解除し、下のファイルツリーの「ダウンロード」から個別のファイルを取得するか、ZIP ファイル全体をダウンロードしてください。</p>",
+    instructions_tree: "<p>下のファイルツリーの「ダウンロード」から個別のファイルを取得するか、ZIP ファイル全体をダウンロードしてください。</p>",
+    instructions_split: "<p>このファイルは {{PART_COUNT}} 個の分割ページ（.part1.{{EXT}} ～ .part{{PART_COUNT}}.{{EXT}}）に分かれています。いずれかの分割ページで残りの分割 HTML ファイルを選択し、{{DECRYPT_HINT}}「結合して ZIP ファイルをダウンロード」をクリックすると完全なファイルを取得できます。</p>\n    <p>各分割ページで「この分割をダウンロード」をクリックし、順番に結合することもできます：<code>cat {{ZIP_NAME}}.* &gt; {{ZIP_NAME}}</code>（Windows：<code>copy /b {{ZIP_NAME}}.001+{{ZIP_NAME}}.002 {{ZIP_NAME}}</code>）。</p>",
+    split_decrypt_hint: "パスワードを入力してから",
+    password_below: "下記のパスワード",
+    password_label: "パスワード：",
+    outer_password_label: "外側のパスワード：",
+    inner_password_label: "内側のパスワード：",
+    password_in_key_file: "{{KEY_FILE}} ファイル",
+    no_password: "パスワード不要",
+    password_qr_alt: "パスワードの QR コード",
+    password_hint_label: "パスワードのヒント：",
+    message_label: "送信者からのメッセージ",
+    key_sheet_title: "パスワードシート",
+    key_sheet_file: "ファイル",
+    key_sheet_page: "ページ",
+    key_sheet_password: "パスワード",
+    key_sheet_created: "作成日時",
+    key_sheet_note: "このシートを印刷して安全な場所に保管し、電子ファイルは削除してください。パスワードを紛失するとページを開けなくなります。",
+    index_title: "ファイル一覧",
+    index_source: "元のパス：",
+    index_summary: "全 {{FILE_COUNT}} ファイル、合計サイズ：{{TOTAL_SIZE}}、生成日時：{{GENERATED_AT}}",
+    index_page: "ページ",
+    index_source_path: "元のパス",
+    index_source_size: "元のサイズ",
+    index_generated_at: "生成日時",
+    rename_hint: "ページは .{{EXT}} 拡張子で出力されています。ダウンロードしたファイルの拡張子を .html（MIME タイプ text/html）に変更してからブラウザで開いてください。",
+    restore_title: "復元ガイド",
+    restore_summary: "元ファイル {{FILE_COUNT}} 件、ページ {{PAGE_COUNT}} 件",
+    restore_step_collect: "下の表のすべてのページを同じフォルダーにダウンロードしてください。パスワードファイルは別途入手してください。",
+    restore_step_verify: "ダウンロードしたページを下で選択すると、SHA-256 を照合して内容が完全か確認します。ファイル名を変更していても識別できます。",
+    restore_step_open: "すべて検証できたら各ページを開いてダウンロードします。分割ファイルは第 1 部を開き、残りの分割を選択すると結合できます。",
+    restore_status: "状態",
+    restore_pending: "未選択",
+    decoder_title: "デコードツール",
+    decoder_intro: "file_to_html で生成した Base64 または Base85 データを貼り付けるか、.b64・.b85 ファイルや生成したページをドロップすると ZIP ファイルに復元できます。複数のファイルはファイル名順に結合されるため、分割の復元にも使えます。暗号化されたページはパスワードを入力するとブラウザーで復号でき、データが .bin ファイルに保存されているページはそのファイルも選択してください。",
+    decoder_placeholder: "ここにエンコードされたデータを貼り付けるか、ファイルをドロップしてください",
+    decoder_file_label: "ファイルを選択：",
+    decoder_encoding_label: "エンコード：",
+    decoder_encoding_auto: "自動検出",
+    decoder_button: "復元してダウンロード",
+    viewer_password_label: "このページは暗号化されています。パスワードを入力してください：",
+    js: JsMessages {
+        download: "ダウンロード",
+        decoding: "データをデコードしています…",
+        integrity_unavailable: "このブラウザではファイルの整合性を検証できません",
+        integrity_ok: "✔ 整合性の検証に成功しました（SHA-256：{hash}…）",
+        integrity_bad: "✘ 整合性の検証に失敗しました。内容が生成時と異なり、破損または改ざんされている可能性があります",
+        integrity_chunked: "このページは分割 AES-GCM で暗号化されており、各ブロックは復号時に完全性が検証されます",
+        confirm_download_bad: "整合性の検証に失敗しました。それでもダウンロードしますか？",
+        confirm_decrypt_bad: "整合性の検証に失敗しました。それでも復号しますか？",
+        download_failed: "ダウンロードに失敗しました：",
+        decrypt_failed: "復号に失敗しました。パスワードを確認してください。",
+        wrong_password: "パスワードが正しくありません。もう一度入力してください。",
+        no_webcrypto: "このブラウザは WebCrypto に対応していません。最新の Chrome、Edge、Firefox または Safari をご利用ください。",
+        unlock_first: "先にパスワードを入力してロックを解除してください。",
+        copied: "データをコピーしました（{size}）",
+        copy_failed: "コピーできませんでした。ファイルとして保存するボタンを使用してください",
+        tips_windows: "Windows：7-Zip を推奨します。ファイルを右クリックして「7-Zip」→「ここに展開」を選択してください。標準の展開機能でも開けます。",
+        tips_windows_aes: "Windows：標準の展開機能は AES 暗号化された ZIP に対応していません。7-Zip または WinRAR で展開し、パスワードを入力してください。",
+        tips_mac: "macOS：ZIP ファイルをダブルクリックすると「アーカイブユーティリティ」で展開できます。ターミナルでは次を実行します：",
+        tips_mac_aes: "macOS：「アーカイブユーティリティ」は AES 暗号化された ZIP を展開できません。Keka または The Unarchiver を使うか、Homebrew で p7zip をインストールしてターミナルで次を実行してください：",
+        tips_linux: "Linux：ターミナルで次を実行します：",
+        tips_linux_aes: "Linux：unzip は AES 暗号化に対応していません。p7zip（p7zip-full）をインストールしてターミナルで次を実行してください：",
+        tips_mobile: "モバイル：ZIP に対応したファイル管理アプリで展開してください。",
+        tips_mobile_aes: "モバイル：多くの標準ファイル管理アプリは AES 暗号化された ZIP を展開できません。AES 対応アプリを使うか、パソコンで展開してください。",
+        tips_double: "これは二重 ZIP です：外側の ZIP を展開した後、中の内側 ZIP も同じ方法で展開してください。",
+        tips_tar: "これは tar アーカイブです：ターミナル（Windows 10 以降はコマンドプロンプト）で次を実行してください：",
+        tips_7z: "これは 7z アーカイブです：7-Zip、Keka または The Unarchiver で展開するか、ターミナルで次を実行してください：",
+        unsupported_method: "このブラウザでは {method} 形式を展開できません。ZIP ファイル全体をダウンロードしてください",
+        gzip_unsupported: "このブラウザは DecompressionStream に対応していないため、ダウンロードされるファイルは gzip 圧縮（.gz）です。gzip または 7-Zip で展開してから ZIP を開いてください",
+        zstd_download: "ページのデータは zstd で圧縮されているため、ダウンロードされるファイルは .zst です。zstd -d を実行するか PeaZip で展開してから ZIP を開いてください",
+        select_payload: "データファイルを自動で読み込めませんでした。このページと一緒に提供された {name} を選択してください：",
+        parts_complete: "全 {count} 個の分割を取得しました",
+        parts_progress: "{have}/{count} 個の分割を取得済み、第 {missing} 分割が不足しています",
+        list_separator: "、",
+        assemble_failed: "結合に失敗しました：",
+        not_a_part: "次のファイルはこのファイルの分割ではありません：",
+        expires_on: "ダウンロード期限：{date}",
+        expired: "このファイルは {date} に期限切れとなり、ダウンロードできません",
+        restore_ok: "✔ 検証済み",
+        restore_mismatch: "✘ 内容が一致しません。再度ダウンロードしてください",
+        restore_unverified: "選択済み（このブラウザーでは検証できません）",
+        restore_unknown: "次のファイルは今回の出力に含まれません：",
+        restore_progress: "{have}/{count} ページを検証済み",
+        restore_complete: "全 {count} ページを検証済み",
+        decoder_empty: "先にデータを貼り付けるか、ファイルを選択してください",
+        decoder_invalid: "データをデコードできません。内容が完全でエンコードが正しいか確認してください",
+        decoder_done: "{name}（{size} バイト）を復元しました。SHA-256：{hash}",
+        decoder_not_zip: "復元したデータは ZIP ファイルではありません。暗号化されている可能性があるため、元のページをここにドロップしてパスワードを入力してください",
+        viewer_info: "{name}（出力形式バージョン {version}、{generator}、全体圧縮：{compression}）",
+        viewer_password_required: "このページは暗号化されています。先にパスワードを入力してください",
+        viewer_missing_payload: "ページのデータは {name} に保存されています。そのファイルも選択してください",
+    },
+};
+
+/// 取得指定語言的訊息目錄，未知的語言代碼使用預設語言
+pub fn messages(lang: &str) -> &'static Messages {
+    match lang {
+        "zh-CN" => &ZH_CN,
+        "en" => &EN,
+        "ja" => &JA,
+        _ => &ZH_TW,
+    }
+}
+
+/// 將頁面腳本訊息序列化為可安全嵌入 script 標籤的 JSON
+pub fn js_messages_json(messages: &Messages) -> String {
+    serde_json::to_string(&messages.js)
+        .unwrap_or_else(|_| "{}".to_string())
+        .replace("</", "<\\/")
+}
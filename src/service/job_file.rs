@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use crate::config::ports::AppConfig;
+use crate::service::verify::hash_reader;
+use crate::service::zip::entry_name;
+
+/// 工作檔的格式版本，結構不相容時遞增
+pub const JOB_FILE_VERSION: u32 = 1;
+
+// 比對失敗時最多列出的檔案數
+const MAX_LISTED_DIFFERENCES: usize = 20;
+
+/// `--export-job` 寫出的工作檔：完整配置、亂數種子與輸入檔案清單，以 `--import-job` 在其他環境重新執行相同的轉換
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobFile {
+    pub job_version: u32,
+    pub tool_version: String,
+    pub created_at: DateTime<Local>,
+    // 匯出時的 `--seed`，匯入時未另外指定則沿用
+    pub seed: Option<u64>,
+    pub config: AppConfig,
+    pub files: Vec<JobFileEntry>,
+}
+
+/// 工作檔記錄的單一輸入檔案
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobFileEntry {
+    // 與 ZIP 項目名稱相同，以輸入路徑的上層目錄為基準並以 `/` 分隔
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+impl JobFile {
+    /// # 參數
+    /// - config: 實際使用的配置，匯出路徑不寫入工作檔
+    /// - files: 已蒐集檔案的記錄
+    /// - seed: 本次執行的亂數種子
+    pub fn new(config: &AppConfig, files: Vec<JobFileEntry>, seed: Option<u64>) -> Self {
+        JobFile {
+            job_version: JOB_FILE_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Local::now(),
+            seed,
+            config: AppConfig { export_job: None, job_files: None, ..config.clone() },
+            files,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+            .map_err(|e| io::Error::new(e.kind(), format!("無法寫入工作檔 {}：{}", path.display(), e)))
+    }
+
+    /// 讀取工作檔，版本不相容時返回錯誤，工具版本不同時只警告
+    /// # 參數
+    /// - path: 工作檔路徑
+    /// # 回傳
+    /// - 成功時返回工作檔內容，讀取或格式錯誤時返回 IO 錯誤
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("無法讀取工作檔 {}：{}", path.display(), e)))?;
+        let job: JobFile = serde_json::from_str(&content).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("工作檔 '{}' 格式錯誤: {}", path.display(), e))
+        })?;
+        if job.job_version != JOB_FILE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("不支援的工作檔版本 {}（目前為 {}）：{}", job.job_version, JOB_FILE_VERSION, path.display()),
+            ));
+        }
+        if job.tool_version != env!("CARGO_PKG_VERSION") {
+            log::warn!(
+                "工作檔由 file_to_html {} 產生，目前版本為 {}，產出頁面可能與原本不同",
+                job.tool_version,
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+        Ok(job)
+    }
+}
+
+/// 記錄已蒐集檔案的項目名稱、大小與 SHA-256
+/// # 參數
+/// - input_path: 輸入路徑
+/// - files: 已蒐集的檔案
+/// # 回傳
+/// - 成功時返回依路徑排序的記錄，讀取檔案失敗時返回 IO 錯誤
+pub fn job_entries(input_path: &Path, files: &[PathBuf]) -> io::Result<Vec<JobFileEntry>> {
+    let mut entries = files
+        .iter()
+        .map(|file| {
+            let mut reader = BufReader::new(File::open(file)?);
+            Ok(JobFileEntry {
+                path: entry_name(file, input_path).unwrap_or_else(|| file.to_string_lossy().into_owned()),
+                size: fs::metadata(file)?.len(),
+                sha256: hash_reader(&mut reader)?,
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// 比對本次蒐集的檔案與工作檔的記錄，新增、缺少或內容不同的檔案皆視為錯誤
+/// # 參數
+/// - input_path: 輸入路徑
+/// - files: 本次蒐集的檔案
+/// - expected: 工作檔記錄的檔案
+/// # 回傳
+/// - 完全一致時返回 Ok，否則返回列出差異的 IO 錯誤
+pub fn verify_job_files(input_path: &Path, files: &[PathBuf], expected: &[JobFileEntry]) -> io::Result<()> {
+    let actual: BTreeMap<String, JobFileEntry> =
+        job_entries(input_path, files)?.into_iter().map(|entry| (entry.path.clone(), entry)).collect();
+    let expected: BTreeMap<&str, &JobFileEntry> = expected.iter().map(|entry| (entry.path.as_str(), entry)).collect();
+    let mut differences = Vec::new();
+    for (path, entry) in &expected {
+        match actual.get(*path) {
+            None => differences.push(format!("缺少 {}", path)),
+            Some(found) if found.size != entry.size || found.sha256 != entry.sha256 => {
+                differences.push(format!("內容不同 {}", path))
+            }
+            Some(_) => {}
+        }
+    }
+    differences.extend(actual.keys().filter(|path| !expected.contains_key(path.as_str())).map(|path| format!("新增 {}", path)));
+    if differences.is_empty() {
+        return Ok(());
+    }
+    let total = differences.len();
+    differences.truncate(MAX_LISTED_DIFFERENCES);
+    let more = if total > MAX_LISTED_DIFFERENCES { format!("，另有 {} 項未列出", total - MAX_LISTED_DIFFERENCES) } else { String::new() };
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("輸入檔案與工作檔記錄不符（{} 項）：{}{}", total, differences.join("、"), more),
+    ))
+}
@@ -0,0 +1,79 @@
+use std::io;
+use std::path::Path;
+use log::debug;
+use crate::service::traits::i_plugin::ContentFilter;
+use crate::service::transcode::looks_binary;
+
+/// `--line-endings` 可指定的換行格式
+pub const LINE_ENDINGS: [&str; 2] = ["lf", "crlf"];
+
+// 一定是二進位內容的副檔名，不必讀取內容判斷
+const BINARY_EXTENSIONS: [&str; 30] = [
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "webp", "pdf", "zip", "gz", "tgz", "bz2", "xz", "zst", "7z",
+    "rar", "jar", "exe", "dll", "so", "dylib", "a", "o", "class", "wasm", "woff", "woff2", "ttf", "mp3", "mp4",
+];
+
+/// 將文字檔的換行統一為 LF 或 CRLF 的內容過濾器；單獨的 CR 不視為換行，維持原樣
+pub struct LineEndingNormalizer {
+    crlf: bool,
+}
+
+impl LineEndingNormalizer {
+    /// # 參數
+    /// - line_ending: `lf` 或 `crlf`
+    pub fn new(line_ending: &str) -> Self {
+        LineEndingNormalizer { crlf: line_ending == "crlf" }
+    }
+}
+
+impl ContentFilter for LineEndingNormalizer {
+    fn name(&self) -> &str {
+        "line-endings"
+    }
+
+    fn applies_to(&self, path: &Path) -> bool {
+        !path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| BINARY_EXTENSIONS.contains(&ext.as_str()))
+    }
+
+    fn filter(&self, path: &Path, data: Vec<u8>) -> io::Result<Vec<u8>> {
+        if looks_binary(&data) {
+            return Ok(data);
+        }
+        let Some(normalized) = normalize_line_endings(&data, self.crlf) else {
+            return Ok(data);
+        };
+        debug!("{} 換行統一為 {}", path.display(), if self.crlf { "CRLF" } else { "LF" });
+        Ok(normalized)
+    }
+}
+
+// 統一換行格式，內容已一致時返回 None
+fn normalize_line_endings(data: &[u8], crlf: bool) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len() + if crlf { data.len() / 32 } else { 0 });
+    let mut changed = false;
+    let mut i = 0;
+    while i < data.len() {
+        let is_crlf = data[i] == b'\r' && data.get(i + 1) == Some(&b'\n');
+        if is_crlf {
+            if crlf {
+                output.extend_from_slice(b"\r\n");
+            } else {
+                output.push(b'\n');
+                changed = true;
+            }
+            i += 2;
+            continue;
+        }
+        if data[i] == b'\n' && crlf {
+            output.extend_from_slice(b"\r\n");
+            changed = true;
+        } else {
+            output.push(data[i]);
+        }
+        i += 1;
+    }
+    changed.then_some(output)
+}
@@ -0,0 +1,382 @@
+use crate::service::schema::METADATA_MARKER;
+
+// 頁面的最小化只移除註解、縮排與多餘空白，不改寫識別字或運算式，
+// 同一行內的空白保留一格，頁面腳本與 schema 讀取器以 `const NAME = ` 比對其他頁面時不受影響
+
+// 前後的空白可直接移除的區塊元素
+const BLOCK_TAGS: [&str; 36] = [
+    "html", "head", "body", "meta", "link", "title", "style", "script", "noscript", "div", "p", "ul", "ol",
+    "li", "table", "thead", "tbody", "tr", "td", "th", "h1", "h2", "h3", "h4", "h5", "h6", "section",
+    "header", "footer", "main", "nav", "form", "details", "summary", "br", "hr",
+];
+
+// 內容需原樣保留的元素
+const RAW_TAGS: [&str; 2] = ["pre", "textarea"];
+
+// 出現在這些字元之後的 `/` 為正規表示式的開頭，而非除號
+const REGEX_PRECEDERS: &[u8] = b"(,=:[!&|?{};+-*%<>~^";
+
+// 出現在這些關鍵字之後的 `/` 為正規表示式的開頭
+const REGEX_KEYWORDS: [&str; 13] = [
+    "return", "typeof", "case", "do", "else", "in", "of", "new", "delete", "void", "throw", "instanceof", "yield",
+];
+
+/// 最小化產出頁面：移除 HTML 註解（轉換資訊除外）與縮排，並最小化內嵌的樣式與腳本；
+/// 嵌入資料的 `application/octet-stream` 區塊、`pre` 與 `textarea` 內容原樣保留
+/// # 參數
+/// - html: 完整的頁面內容
+/// # 回傳
+/// - 最小化後的頁面內容
+pub fn minify_html(html: &str) -> String {
+    let bytes = html.as_bytes();
+    let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut last_tag = String::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'<' {
+            if bytes[i..].starts_with(b"<!--") {
+                let end = find(bytes, i + 4, b"-->").map(|end| end + 3).unwrap_or(bytes.len());
+                // 轉換資訊以註解嵌入，需原樣保留
+                if bytes[i..].starts_with(METADATA_MARKER.as_bytes()) {
+                    out.extend_from_slice(&bytes[i..end]);
+                }
+                i = end;
+                continue;
+            }
+            if !bytes.get(i + 1).is_some_and(|b| b.is_ascii_alphabetic() || *b == b'/' || *b == b'!') {
+                out.push(b'<');
+                i += 1;
+                continue;
+            }
+            let end = tag_end(bytes, i);
+            let tag = collapse_tag(&bytes[i..end]);
+            let name = tag_name(&tag);
+            out.extend_from_slice(&tag);
+            i = end;
+            last_tag = name.clone();
+            let opening = tag.get(1) != Some(&b'/');
+            if opening && (name == "script" || name == "style" || RAW_TAGS.contains(&name.as_str())) {
+                let close = format!("</{}", name);
+                let content_end = find_ignore_case(bytes, i, close.as_bytes()).unwrap_or(bytes.len());
+                // 內容切在 ASCII 的 `<` 與 `>` 上，必為合法的 UTF-8
+                let content = std::str::from_utf8(&bytes[i..content_end]).unwrap_or_default();
+                let minified = match name.as_str() {
+                    "script" if is_javascript(&tag) => minify_js(content),
+                    "style" => minify_css(content),
+                    _ => content.to_string(),
+                };
+                out.extend_from_slice(minified.as_bytes());
+                i = content_end;
+            }
+            continue;
+        }
+
+        // 文字節點：連續空白縮為一格，僅含空白且緊鄰區塊元素時直接移除
+        let end = bytes[i..].iter().position(|b| *b == b'<').map(|p| i + p).unwrap_or(bytes.len());
+        let text = &bytes[i..end];
+        if text.iter().all(u8::is_ascii_whitespace) {
+            let next_tag = if end < bytes.len() { tag_name(&bytes[end..tag_end(bytes, end)]) } else { String::new() };
+            if !is_block(&last_tag) && !is_block(&next_tag) && !text.is_empty() {
+                out.push(b' ');
+            }
+        } else {
+            collapse_whitespace(text, &mut out);
+        }
+        i = end;
+    }
+    String::from_utf8(out).unwrap_or_else(|_| html.to_string())
+}
+
+/// 最小化內嵌樣式：移除註解並刪除符號兩側的空白，字串內容原樣保留
+pub fn minify_css(css: &str) -> String {
+    let bytes = css.as_bytes();
+    let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut pending_space = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b.is_ascii_whitespace() {
+            pending_space = true;
+            i += 1;
+            continue;
+        }
+        if bytes[i..].starts_with(b"/*") {
+            i = find(bytes, i + 2, b"*/").map(|end| end + 2).unwrap_or(bytes.len());
+            pending_space = true;
+            continue;
+        }
+        if pending_space {
+            let prev = out.last().copied();
+            if prev.is_some_and(|p| !b"{};:,>(".contains(&p)) && !b"{};,>)".contains(&b) {
+                out.push(b' ');
+            }
+            pending_space = false;
+        }
+        if b == b'}' && out.last() == Some(&b';') {
+            out.pop();
+        }
+        if b == b'"' || b == b'\'' {
+            let end = string_end(bytes, i);
+            out.extend_from_slice(&bytes[i..end]);
+            i = end;
+            continue;
+        }
+        out.push(b);
+        i += 1;
+    }
+    String::from_utf8(out).unwrap_or_else(|_| css.to_string())
+}
+
+/// 最小化內嵌腳本：移除註解、縮排與空行，保留換行以維持自動分號插入的行為；
+/// 字串、樣板字串與正規表示式的內容原樣保留
+pub fn minify_js(js: &str) -> String {
+    let bytes = js.as_bytes();
+    let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+    js_code(bytes, 0, &mut out, false);
+    String::from_utf8(out).unwrap_or_else(|_| js.to_string())
+}
+
+// 處理一段程式碼，in_template 為 true 時處理到樣板字串 `${...}` 的結尾並返回 `}` 之後的位置
+fn js_code(bytes: &[u8], mut i: usize, out: &mut Vec<u8>, in_template: bool) -> usize {
+    let mut pending_space = false;
+    let mut pending_newline = false;
+    let mut depth = 0usize;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b'\n' | b'\r' => {
+                pending_newline = true;
+                i += 1;
+                continue;
+            }
+            b' ' | b'\t' => {
+                pending_space = true;
+                i += 1;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = bytes[i..].iter().position(|c| *c == b'\n').map(|p| i + p).unwrap_or(bytes.len());
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = find(bytes, i + 2, b"*/").map(|end| end + 2).unwrap_or(bytes.len());
+                if bytes[i..end].contains(&b'\n') {
+                    pending_newline = true;
+                } else {
+                    pending_space = true;
+                }
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+
+        if pending_newline && !out.is_empty() && !out.ends_with(b"\n") {
+            out.push(b'\n');
+        } else if pending_space && !out.is_empty() && !out.ends_with(b"\n") && !out.ends_with(b" ") {
+            out.push(b' ');
+        }
+        pending_newline = false;
+        pending_space = false;
+
+        match b {
+            b'\'' | b'"' => {
+                let end = string_end(bytes, i);
+                out.extend_from_slice(&bytes[i..end]);
+                i = end;
+            }
+            b'`' => {
+                i = js_template(bytes, i, out);
+            }
+            b'/' if starts_regex(out) => {
+                let end = regex_end(bytes, i);
+                out.extend_from_slice(&bytes[i..end]);
+                i = end;
+            }
+            b'{' => {
+                depth += 1;
+                out.push(b);
+                i += 1;
+            }
+            b'}' if in_template && depth == 0 => {
+                out.push(b);
+                return i + 1;
+            }
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                out.push(b);
+                i += 1;
+            }
+            _ => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    i
+}
+
+// 原樣複製樣板字串，`${...}` 內的程式碼同樣最小化
+fn js_template(bytes: &[u8], start: usize, out: &mut Vec<u8>) -> usize {
+    out.push(b'`');
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {
+                out.extend_from_slice(&bytes[i..(i + 2).min(bytes.len())]);
+                i += 2;
+            }
+            b'`' => {
+                out.push(b'`');
+                return i + 1;
+            }
+            b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                out.extend_from_slice(b"${");
+                i = js_code(bytes, i + 2, out, true);
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    i
+}
+
+// 依前一個輸出的符號或關鍵字判斷 `/` 是否為正規表示式
+fn starts_regex(out: &[u8]) -> bool {
+    let trimmed = out.iter().rposition(|b| !b.is_ascii_whitespace()).map(|p| &out[..=p]).unwrap_or(&[]);
+    let Some(last) = trimmed.last() else {
+        return true;
+    };
+    if REGEX_PRECEDERS.contains(last) {
+        return true;
+    }
+    let word_start = trimmed
+        .iter()
+        .rposition(|b| !(b.is_ascii_alphanumeric() || *b == b'_' || *b == b'$'))
+        .map(|p| p + 1)
+        .unwrap_or(0);
+    let word = std::str::from_utf8(&trimmed[word_start..]).unwrap_or_default();
+    REGEX_KEYWORDS.contains(&word)
+}
+
+// 正規表示式的結尾（含結尾的 `/`），字元類別中的 `/` 不視為結尾
+fn regex_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    let mut in_class = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => return i + 1,
+            b'\n' => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+// 字串常值的結尾（含結尾的引號）
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b if b == quote => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+// 標籤的結尾（含 `>`），屬性值中的 `>` 不視為結尾
+fn tag_end(bytes: &[u8], start: usize) -> usize {
+    let mut quote = None;
+    for (offset, b) in bytes[start..].iter().enumerate() {
+        match (quote, *b) {
+            (None, b'"' | b'\'') => quote = Some(*b),
+            (Some(q), b) if b == q => quote = None,
+            (None, b'>') => return start + offset + 1,
+            _ => {}
+        }
+    }
+    bytes.len()
+}
+
+// 將標籤內引號以外的連續空白縮為一格
+fn collapse_tag(tag: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(tag.len());
+    let mut quote = None;
+    for b in tag {
+        match (quote, *b) {
+            (None, b'"' | b'\'') => quote = Some(*b),
+            (Some(q), b) if b == q => quote = None,
+            (None, b) if b.is_ascii_whitespace() => {
+                if !out.ends_with(b" ") {
+                    out.push(b' ');
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if *b == b'>' && quote.is_none() && out.ends_with(b" ") {
+            out.pop();
+        }
+        out.push(*b);
+    }
+    out
+}
+
+// 標籤名稱（小寫，不含 `/`）
+fn tag_name(tag: &[u8]) -> String {
+    tag.iter()
+        .skip(1)
+        .skip_while(|b| **b == b'/')
+        .take_while(|b| b.is_ascii_alphanumeric())
+        .map(|b| b.to_ascii_lowercase() as char)
+        .collect()
+}
+
+fn is_block(name: &str) -> bool {
+    BLOCK_TAGS.contains(&name)
+}
+
+// 未指定 type 或 type 為 JavaScript 的 script 標籤才最小化，嵌入資料的區塊原樣保留
+fn is_javascript(tag: &[u8]) -> bool {
+    let tag = String::from_utf8_lossy(tag).to_ascii_lowercase();
+    match tag.find("type=") {
+        None => true,
+        Some(pos) => {
+            let value = tag[pos + 5..].trim_start_matches(['"', '\'']);
+            value.starts_with("text/javascript") || value.starts_with("module")
+        }
+    }
+}
+
+fn collapse_whitespace(text: &[u8], out: &mut Vec<u8>) {
+    for b in text {
+        if b.is_ascii_whitespace() {
+            if !out.ends_with(b" ") {
+                out.push(b' ');
+            }
+        } else {
+            out.push(*b);
+        }
+    }
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes.get(from..)?.windows(needle.len()).position(|w| w == needle).map(|p| from + p)
+}
+
+fn find_ignore_case(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes.get(from..)?
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+        .map(|p| from + p)
+}
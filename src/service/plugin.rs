@@ -0,0 +1,118 @@
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+use log::info;
+use crate::service::traits::i_plugin::{ContentFilter, InstructionContext, InstructionProvider, OutputRenderer};
+
+/// 插件註冊表，保存外部註冊的渲染器、過濾器與使用說明提供者
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    renderers: Vec<Arc<dyn OutputRenderer>>,
+    filters: Vec<Arc<dyn ContentFilter>>,
+    instruction_providers: Vec<Arc<dyn InstructionProvider>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        PluginRegistry::default()
+    }
+
+    /// 註冊輸出渲染器，同名渲染器以後註冊者為準
+    pub fn register_renderer(&mut self, renderer: Arc<dyn OutputRenderer>) {
+        self.renderers.retain(|r| r.name() != renderer.name());
+        info!("註冊輸出渲染器：{}", renderer.name());
+        self.renderers.push(renderer);
+    }
+
+    /// 註冊內容過濾器，依註冊順序套用
+    pub fn register_filter(&mut self, filter: Arc<dyn ContentFilter>) {
+        info!("註冊內容過濾器：{}", filter.name());
+        self.filters.push(filter);
+    }
+
+    /// 註冊使用說明提供者，依註冊順序查詢
+    pub fn register_instruction_provider(&mut self, provider: Arc<dyn InstructionProvider>) {
+        info!("註冊使用說明提供者：{}", provider.name());
+        self.instruction_providers.push(provider);
+    }
+
+    /// 依格式名稱查詢渲染器
+    pub fn renderer(&self, name: &str) -> Option<Arc<dyn OutputRenderer>> {
+        self.renderers.iter().find(|r| r.name() == name).cloned()
+    }
+
+    /// 已註冊的渲染器名稱
+    pub fn renderer_names(&self) -> Vec<String> {
+        self.renderers.iter().map(|r| r.name().to_string()).collect()
+    }
+
+    pub fn filters(&self) -> &[Arc<dyn ContentFilter>] {
+        &self.filters
+    }
+
+    /// 依序詢問提供者，返回第一個提供的使用說明
+    pub fn instructions(&self, context: &InstructionContext) -> Option<String> {
+        self.instruction_providers.iter().find_map(|p| p.instructions(context))
+    }
+}
+
+/// 全域插件註冊表
+pub fn global_registry() -> &'static RwLock<PluginRegistry> {
+    static REGISTRY: OnceLock<RwLock<PluginRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(PluginRegistry::new()))
+}
+
+/// 取得全域註冊表的快照，供單次轉換使用
+pub fn snapshot() -> PluginRegistry {
+    global_registry().read().unwrap().clone()
+}
+
+pub fn register_renderer(renderer: Arc<dyn OutputRenderer>) {
+    global_registry().write().unwrap().register_renderer(renderer);
+}
+
+pub fn register_filter(filter: Arc<dyn ContentFilter>) {
+    global_registry().write().unwrap().register_filter(filter);
+}
+
+pub fn register_instruction_provider(provider: Arc<dyn InstructionProvider>) {
+    global_registry().write().unwrap().register_instruction_provider(provider);
+}
+
+/// 依序套用適用的內容過濾器
+pub fn apply_filters(filters: &[Arc<dyn ContentFilter>], path: &Path, mut data: Vec<u8>) -> io::Result<Vec<u8>> {
+    for filter in filters.iter().filter(|f| f.applies_to(path)) {
+        data = filter.filter(path, data)?;
+    }
+    Ok(data)
+}
+
+/// 插件動態函式庫需匯出的註冊函式名稱
+#[cfg(feature = "dynamic-plugins")]
+pub const PLUGIN_ENTRY_SYMBOL: &[u8] = b"file_to_html_register";
+
+/// 插件動態函式庫的註冊函式型別，插件需以相同編譯器版本建置
+#[cfg(feature = "dynamic-plugins")]
+pub type PluginEntry = fn(&mut PluginRegistry);
+
+/// 載入動態插件並呼叫其註冊函式
+/// # 參數
+/// - path: 插件動態函式庫路徑（.so/.dylib/.dll）
+/// # 回傳
+/// - 成功時返回 ()，失敗時返回 IO 錯誤
+#[cfg(feature = "dynamic-plugins")]
+pub fn load_plugin(path: &Path) -> io::Result<()> {
+    // SAFETY: 插件需匯出符合 PluginEntry 簽章的 file_to_html_register，且以相同編譯器建置
+    unsafe {
+        let library = libloading::Library::new(path)
+            .map_err(|e| io::Error::other(format!("無法載入插件 {}: {}", path.display(), e)))?;
+        let entry: libloading::Symbol<PluginEntry> = library
+            .get(PLUGIN_ENTRY_SYMBOL)
+            .map_err(|e| io::Error::other(format!("插件 {} 缺少註冊函式: {}", path.display(), e)))?;
+        entry(&mut global_registry().write().unwrap());
+        // 插件註冊的物件仍引用函式庫中的程式碼，程式結束前不可卸載
+        std::mem::forget(library);
+    }
+    info!("已載入插件：{}", path.display());
+    Ok(())
+}
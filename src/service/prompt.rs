@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+use dialoguer::{Confirm, Input, Password, Select};
+use crate::service::traits::i_prompt::Prompter;
+
+/// 依 `--yes`（`--non-interactive`）選擇提示的實作
+/// # 參數
+/// - non_interactive: 是否為非互動模式
+/// # 回傳
+/// - 非互動模式時為 NonInteractivePrompter，否則為 TerminalPrompter
+pub fn prompter_for(non_interactive: bool) -> Arc<dyn Prompter> {
+    if non_interactive {
+        Arc::new(NonInteractivePrompter)
+    } else {
+        Arc::new(TerminalPrompter)
+    }
+}
+
+// 必須輸入而沒有預設值的提示
+fn unanswerable(prompt: &str, hint: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("無法詢問「{}」，{}", prompt, hint))
+}
+
+/// 終端機提示（預設），以 dialoguer 顯示
+pub struct TerminalPrompter;
+
+impl Prompter for TerminalPrompter {
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool> {
+        Confirm::new().with_prompt(prompt).default(default).interact().map_err(io::Error::other)
+    }
+
+    fn select(&self, prompt: &str, items: &[&str], default: usize) -> io::Result<usize> {
+        Select::new().with_prompt(prompt).items(items).default(default).interact().map_err(io::Error::other)
+    }
+
+    fn input(&self, prompt: &str, default: Option<&str>, validate: &dyn Fn(&str) -> Result<(), String>) -> io::Result<String> {
+        let mut input = Input::new().with_prompt(prompt).validate_with(|value: &String| validate(value));
+        if let Some(value) = default {
+            input = input.default(value.to_string());
+        }
+        input.interact_text().map_err(io::Error::other)
+    }
+
+    fn password(&self, prompt: &str) -> io::Result<String> {
+        Password::new().with_prompt(prompt).interact().map_err(io::Error::other)
+    }
+
+    fn message(&self, text: &str) {
+        eprintln!("{}", text);
+    }
+}
+
+/// 非互動模式（`--yes`）：有預設值的提示直接採用預設值並記錄，需要使用者輸入的提示返回錯誤
+pub struct NonInteractivePrompter;
+
+impl NonInteractivePrompter {
+    fn use_default<T: std::fmt::Display>(prompt: &str, value: T) -> T {
+        log::info!("非互動模式，採用預設值：{} → {}", prompt, value);
+        value
+    }
+}
+
+impl Prompter for NonInteractivePrompter {
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool> {
+        Ok(Self::use_default(prompt, default))
+    }
+
+    fn select(&self, prompt: &str, items: &[&str], default: usize) -> io::Result<usize> {
+        Self::use_default(prompt, items[default]);
+        Ok(default)
+    }
+
+    fn input(&self, prompt: &str, default: Option<&str>, _validate: &dyn Fn(&str) -> Result<(), String>) -> io::Result<String> {
+        default
+            .map(|value| Self::use_default(prompt, value.to_string()))
+            .ok_or_else(|| unanswerable(prompt, "非互動模式（--yes）下請改以命令列參數指定"))
+    }
+
+    fn password(&self, prompt: &str) -> io::Result<String> {
+        Err(unanswerable(prompt, "非互動模式（--yes）下請改用 '--password-mode random'、'timestamp' 或 'none'"))
+    }
+
+    fn message(&self, text: &str) {
+        log::info!("{}", text);
+    }
+}
+
+/// 依序使用預先提供的回答，供自動化測試與腳本執行互動流程；
+/// 空字串代表採用預設值，確認接受 `y`/`yes`/`n`/`no`，選擇接受索引或選項文字；顯示的訊息依序記錄
+pub struct ScriptedPrompter {
+    answers: Mutex<VecDeque<String>>,
+    messages: Mutex<Vec<String>>,
+}
+
+impl ScriptedPrompter {
+    pub fn new<I, S>(answers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ScriptedPrompter {
+            answers: Mutex::new(answers.into_iter().map(Into::into).collect()),
+            messages: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 目前為止顯示過的訊息
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 尚未使用的回答數
+    pub fn remaining(&self) -> usize {
+        self.answers.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    // 取出下一個回答，回答已用完時返回錯誤
+    fn next(&self, prompt: &str) -> io::Result<String> {
+        self.answers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+            .ok_or_else(|| unanswerable(prompt, "預先提供的回答已用完"))
+    }
+
+    fn invalid(prompt: &str, answer: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("「{}」的回答無效：{}", prompt, answer))
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool> {
+        let answer = self.next(prompt)?;
+        match answer.trim().to_lowercase().as_str() {
+            "" => Ok(default),
+            "y" | "yes" => Ok(true),
+            "n" | "no" => Ok(false),
+            _ => Err(Self::invalid(prompt, &answer)),
+        }
+    }
+
+    fn select(&self, prompt: &str, items: &[&str], default: usize) -> io::Result<usize> {
+        let answer = self.next(prompt)?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(default);
+        }
+        answer
+            .parse::<usize>()
+            .ok()
+            .filter(|index| *index < items.len())
+            .or_else(|| items.iter().position(|item| *item == answer))
+            .ok_or_else(|| Self::invalid(prompt, answer))
+    }
+
+    fn input(&self, prompt: &str, default: Option<&str>, validate: &dyn Fn(&str) -> Result<(), String>) -> io::Result<String> {
+        let answer = self.next(prompt)?;
+        let value = match default {
+            Some(value) if answer.is_empty() => value.to_string(),
+            _ => answer,
+        };
+        validate(&value).map_err(|message| Self::invalid(prompt, &message))?;
+        Ok(value)
+    }
+
+    fn password(&self, prompt: &str) -> io::Result<String> {
+        self.next(prompt)
+    }
+
+    fn message(&self, text: &str) {
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).push(text.to_string());
+    }
+}
@@ -0,0 +1,151 @@
+use std::io;
+use serde::Serialize;
+use crate::service::html::{encode_href, escape_html, js_string_literal};
+
+/// 離線快取的 Service Worker 檔名，同一輸出目錄的頁面共用
+pub const SERVICE_WORKER_NAME: &str = "sw.js";
+
+/// 安裝到主畫面時使用的圖示檔名，同一輸出目錄的頁面共用
+pub const ICON_NAME: &str = "icon.svg";
+
+// 頁面與 Service Worker 共用的快取名稱，快取內容改變時需一併更新
+const CACHE_NAME: &str = "file-to-html-v1";
+
+// 瀏覽器標題列與啟動畫面的顏色，與頁面標題的文字顏色相同
+const THEME_COLOR: &str = "#2c3e50";
+
+// 優先自網路取得最新內容並更新快取，離線時改由快取提供；只快取同源的 GET 請求
+const SERVICE_WORKER: &str = r#"// file_to_html 離線快取：頁面開啟時將自身與相關檔案存入快取，離線時由快取提供
+const CACHE_NAME = '__CACHE_NAME__';
+
+self.addEventListener('install', () => self.skipWaiting());
+
+self.addEventListener('activate', (event) => {
+    event.waitUntil(self.clients.claim());
+});
+
+self.addEventListener('fetch', (event) => {
+    const request = event.request;
+    if (request.method !== 'GET' || new URL(request.url).origin !== self.location.origin) {
+        return;
+    }
+    event.respondWith((async () => {
+        const cache = await caches.open(CACHE_NAME);
+        try {
+            const response = await fetch(request);
+            if (response.ok) {
+                await cache.put(request, response.clone());
+            }
+            return response;
+        } catch (error) {
+            const cached = await cache.match(request, { ignoreSearch: true });
+            if (cached) {
+                return cached;
+            }
+            throw error;
+        }
+    })());
+});
+"#;
+
+// 主畫面圖示：文件外框與向下箭頭
+const ICON_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 512 512">
+<rect width="512" height="512" rx="96" fill="#2c3e50"/>
+<path d="M160 96h136l88 88v232H160z" fill="#ffffff"/>
+<path d="M296 96v88h88" fill="#d0d7de"/>
+<path d="M272 232v96l36-36 20 20-72 72-72-72 20-20 36 36v-96z" fill="#007bff"/>
+</svg>
+"##;
+
+// Web App Manifest 的內容
+#[derive(Serialize)]
+struct WebManifest<'a> {
+    name: &'a str,
+    short_name: &'a str,
+    start_url: &'a str,
+    scope: &'a str,
+    display: &'a str,
+    lang: &'a str,
+    background_color: &'a str,
+    theme_color: &'a str,
+    icons: [ManifestIcon<'a>; 1],
+}
+
+#[derive(Serialize)]
+struct ManifestIcon<'a> {
+    src: &'a str,
+    sizes: &'a str,
+    #[serde(rename = "type")]
+    mime_type: &'a str,
+    purpose: &'a str,
+}
+
+/// 頁面對應的 Manifest 檔名
+pub fn manifest_name(file_name: &str) -> String {
+    format!("{}.webmanifest", file_name)
+}
+
+/// 產生單一頁面的 Web App Manifest，安裝後以獨立視窗開啟該頁面
+/// # 參數
+/// - name: 顯示名稱
+/// - start_url: 安裝後開啟的頁面檔名
+/// - lang: 頁面語言
+/// # 回傳
+/// - Manifest 的 JSON 文字，序列化失敗時返回 IO 錯誤
+pub fn web_manifest(name: &str, start_url: &str, lang: &str) -> io::Result<String> {
+    let short_name: String = name.chars().take(12).collect();
+    let start_url = encode_href(start_url);
+    let manifest = WebManifest {
+        name,
+        short_name: &short_name,
+        start_url: &start_url,
+        scope: "./",
+        display: "standalone",
+        lang,
+        background_color: "#ffffff",
+        theme_color: THEME_COLOR,
+        icons: [ManifestIcon { src: ICON_NAME, sizes: "any", mime_type: "image/svg+xml", purpose: "any" }],
+    };
+    serde_json::to_string_pretty(&manifest).map_err(io::Error::other)
+}
+
+/// 產生放在 `<head>` 的 Manifest 連結與註冊腳本；頁面以 HTTPS 或 localhost 開啟時註冊 Service Worker，
+/// 並將頁面本身、Manifest、圖示與外部資料檔存入快取，以 file:// 開啟時不做任何事
+/// # 參數
+/// - manifest: 頁面的 Manifest 檔名
+/// - payload_file: 外部資料檔的檔名，資料嵌入頁面時為空字串
+pub fn pwa_head(manifest: &str, payload_file: &str) -> String {
+    let mut assets = vec![js_string_literal(&encode_href(manifest)), js_string_literal(ICON_NAME)];
+    if !payload_file.is_empty() {
+        assets.push(js_string_literal(&encode_href(payload_file)));
+    }
+    format!(
+        r#"<link rel="manifest" href="{manifest}">
+<meta name="theme-color" content="{theme}">
+<link rel="apple-touch-icon" href="{icon}">
+<script>
+    if ('serviceWorker' in navigator && window.isSecureContext) {{
+        navigator.serviceWorker.register({worker})
+            .then(() => caches.open({cache}))
+            .then((cache) => cache.addAll([location.href.split('#')[0], {assets}]))
+            .catch((error) => console.warn('offline cache unavailable', error));
+    }}
+</script>"#,
+        manifest = escape_html(&encode_href(manifest)),
+        theme = THEME_COLOR,
+        icon = ICON_NAME,
+        worker = js_string_literal(SERVICE_WORKER_NAME),
+        cache = js_string_literal(CACHE_NAME),
+        assets = assets.join(", "),
+    )
+}
+
+/// 取得輸出目錄共用的 Service Worker 腳本
+pub fn service_worker() -> String {
+    SERVICE_WORKER.replace("__CACHE_NAME__", CACHE_NAME)
+}
+
+/// 取得輸出目錄共用的主畫面圖示
+pub fn icon_svg() -> &'static str {
+    ICON_SVG
+}
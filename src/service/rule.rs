@@ -0,0 +1,316 @@
+use std::io;
+use std::path::Path;
+use regex::{Regex, RegexBuilder};
+use crate::config::config::parse_byte_size;
+
+// 規則符合時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Include,
+    Exclude,
+}
+
+// 大小條件的比較方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn holds(self, actual: u64, expected: u64) -> bool {
+        match self {
+            Comparison::Greater => actual > expected,
+            Comparison::GreaterOrEqual => actual >= expected,
+            Comparison::Less => actual < expected,
+            Comparison::LessOrEqual => actual <= expected,
+            Comparison::Equal => actual == expected,
+            Comparison::NotEqual => actual != expected,
+        }
+    }
+}
+
+// 單一條件：檔案大小、副檔名、檔名或完整路徑
+#[derive(Debug, Clone)]
+enum Condition {
+    Size(Comparison, u64),
+    // 不含開頭的點並轉為小寫，可列出多個
+    Ext { extensions: Vec<String>, negate: bool },
+    // 完整符合檔名
+    Name { regex: Regex, negate: bool },
+    // 出現在完整路徑中的任一位置
+    Path { regex: Regex, negate: bool },
+}
+
+impl Condition {
+    fn holds(&self, path: &Path, size: u64) -> bool {
+        match self {
+            Condition::Size(comparison, expected) => comparison.holds(size, *expected),
+            Condition::Ext { extensions, negate } => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+                extensions.iter().any(|ext| name.ends_with(&format!(".{}", ext))) != *negate
+            }
+            Condition::Name { regex, negate } => {
+                regex.is_match(&path.file_name().unwrap_or_default().to_string_lossy()) != *negate
+            }
+            Condition::Path { regex, negate } => regex.is_match(&path.to_string_lossy()) != *negate,
+        }
+    }
+}
+
+/// 單一檔案規則，所有條件皆成立時套用
+#[derive(Debug, Clone)]
+pub struct FileRule {
+    pub action: RuleAction,
+    // 原始設定，用於記錄
+    pub spec: String,
+    // 由 `--max-size` 轉換而來時為大小限制（MB）
+    pub max_size: Option<f64>,
+    conditions: Vec<Condition>,
+}
+
+impl FileRule {
+    fn matches(&self, path: &Path, size: u64) -> bool {
+        self.conditions.iter().all(|condition| condition.holds(path, size))
+    }
+}
+
+/// `--rule` 與舊有篩選參數組成的規則集合，依序比對，第一條符合的規則決定是否保留檔案
+#[derive(Debug, Clone, Default)]
+pub struct FileRules {
+    rules: Vec<FileRule>,
+}
+
+impl FileRules {
+    /// # 參數
+    /// - specs: `--rule` 設定，例如 `exclude size>500MB and ext=iso`
+    /// - ignore_case: name 與 path 條件是否不分大小寫
+    /// # 回傳
+    /// - 成功時返回規則集合，格式錯誤時返回 IO 錯誤
+    pub fn new(specs: &[String], ignore_case: bool) -> io::Result<Self> {
+        let rules = specs.iter().map(|spec| parse_rule(spec, ignore_case)).collect::<io::Result<_>>()?;
+        Ok(FileRules { rules })
+    }
+
+    /// 將 `--max-size`（MB）轉為排在最後的 `exclude size>…` 規則，先前的 include 規則可保留超過大小的檔案
+    pub fn with_max_size(mut self, max_size: Option<f64>) -> Self {
+        if let Some(max) = max_size {
+            self.rules.push(FileRule {
+                action: RuleAction::Exclude,
+                spec: format!("exclude size>{}MB", max),
+                max_size: Some(max),
+                conditions: vec![Condition::Size(Comparison::Greater, (max * 1_048_576.0) as u64)],
+            });
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 取得第一條符合的規則
+    /// # 參數
+    /// - path: 檔案路徑
+    /// - size: 檔案大小（位元組）
+    /// # 回傳
+    /// - 符合的規則，沒有規則符合時返回 None（保留檔案）
+    pub fn evaluate(&self, path: &Path, size: u64) -> Option<&FileRule> {
+        self.rules.iter().find(|rule| rule.matches(path, size))
+    }
+}
+
+/// 解析單一 `--rule` 設定：`include|exclude 條件 [and 條件]...`，條件為
+/// `size` 搭配 `>`、`>=`、`<`、`<=`、`=`、`!=` 與帶單位的大小，或 `ext`、`name`、`path` 搭配 `=`、`!=`
+/// # 參數
+/// - spec: 規則設定，例如 `exclude size>500MB and ext=iso`
+/// - ignore_case: name 與 path 條件是否不分大小寫
+/// # 回傳
+/// - 成功時返回規則，格式錯誤時返回 IO 錯誤
+pub fn parse_rule(spec: &str, ignore_case: bool) -> io::Result<FileRule> {
+    let invalid = |reason: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無效的規則 '{}'：{}，格式為 include|exclude 條件 [and 條件]...，例如 exclude size>500MB and ext=iso", spec, reason)
+        )
+    };
+    let mut tokens = spec.split_whitespace();
+    let action = match tokens.next().map(str::to_ascii_lowercase).as_deref() {
+        Some("include") => RuleAction::Include,
+        Some("exclude") => RuleAction::Exclude,
+        Some(other) => return Err(invalid(format!("未知的動作 '{}'", other))),
+        None => return Err(invalid("規則不可為空白".to_string())),
+    };
+    let mut conditions = Vec::new();
+    let mut expect_condition = true;
+    for token in tokens {
+        if expect_condition {
+            conditions.push(parse_condition(token, ignore_case).map_err(invalid)?);
+        } else if !token.eq_ignore_ascii_case("and") {
+            return Err(invalid(format!("條件之間需以 and 連接，卻是 '{}'", token)));
+        }
+        expect_condition = !expect_condition;
+    }
+    if conditions.is_empty() || expect_condition {
+        return Err(invalid("缺少條件".to_string()));
+    }
+    Ok(FileRule { action, spec: spec.trim().to_string(), max_size: None, conditions })
+}
+
+// 解析單一條件，如 `size>500MB`、`ext=iso,img`、`name=*.bak`
+fn parse_condition(token: &str, ignore_case: bool) -> Result<Condition, String> {
+    let split = token
+        .find(['>', '<', '=', '!'])
+        .ok_or_else(|| format!("條件 '{}' 缺少比較運算子", token))?;
+    let (key, rest) = token.split_at(split);
+    let operator_len = rest.chars().take_while(|c| matches!(c, '>' | '<' | '=' | '!')).count();
+    let (operator, value) = rest.split_at(operator_len);
+    if value.is_empty() {
+        return Err(format!("條件 '{}' 缺少值", token));
+    }
+    let comparison = match operator {
+        ">" => Comparison::Greater,
+        ">=" => Comparison::GreaterOrEqual,
+        "<" => Comparison::Less,
+        "<=" => Comparison::LessOrEqual,
+        "=" | "==" => Comparison::Equal,
+        "!=" => Comparison::NotEqual,
+        _ => return Err(format!("未知的比較運算子 '{}'", operator)),
+    };
+    let key = key.to_ascii_lowercase();
+    if key == "size" {
+        let bytes = parse_byte_size(value).map_err(|e| e.to_string())?;
+        return Ok(Condition::Size(comparison, bytes as u64));
+    }
+    let negate = match comparison {
+        Comparison::Equal => false,
+        Comparison::NotEqual => true,
+        _ => return Err(format!("{} 條件只能使用 = 或 !=", key)),
+    };
+    match key.as_str() {
+        "ext" => {
+            let extensions: Vec<String> = value
+                .split(',')
+                .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+                .filter(|ext| !ext.is_empty())
+                .collect();
+            if extensions.is_empty() {
+                return Err(format!("條件 '{}' 缺少副檔名", token));
+            }
+            Ok(Condition::Ext { extensions, negate })
+        }
+        "name" => Ok(Condition::Name { regex: glob_regex(value, true, ignore_case)?, negate }),
+        "path" => Ok(Condition::Path { regex: glob_regex(value, false, ignore_case)?, negate }),
+        _ => Err(format!("未知的條件 '{}'，可用條件：size、ext、name、path", key)),
+    }
+}
+
+// 模式轉換方式與 include/exclude 相同，name 條件需完整符合檔名
+fn glob_regex(pattern: &str, anchored: bool, ignore_case: bool) -> Result<Regex, String> {
+    let regex = pattern.replace('.', "\\.").replace('*', ".*");
+    let regex = if anchored { format!("^(?:{})$", regex) } else { regex };
+    RegexBuilder::new(&regex)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| format!("無效的模式 '{}'：{}", pattern, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(spec: &str) -> FileRule {
+        parse_rule(spec, false).unwrap()
+    }
+
+    #[test]
+    fn size_comparison_operators() {
+        let cases = [
+            (">", Comparison::Greater),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            ("<=", Comparison::LessOrEqual),
+            ("=", Comparison::Equal),
+            ("==", Comparison::Equal),
+            ("!=", Comparison::NotEqual),
+        ];
+        for (operator, expected) in cases {
+            let condition = parse_condition(&format!("size{}10", operator), false).unwrap();
+            assert!(matches!(condition, Condition::Size(comparison, 10) if comparison == expected), "{}", operator);
+        }
+        let file = Path::new("a.bin");
+        assert!(rule("exclude size>10").matches(file, 11));
+        assert!(!rule("exclude size>10").matches(file, 10));
+        assert!(rule("exclude size>=10").matches(file, 10));
+        assert!(rule("exclude size<10").matches(file, 9));
+        assert!(!rule("exclude size<10").matches(file, 10));
+        assert!(rule("exclude size<=10").matches(file, 10));
+        assert!(rule("exclude size=10").matches(file, 10));
+        assert!(!rule("exclude size!=10").matches(file, 10));
+    }
+
+    #[test]
+    fn size_units() {
+        for (value, bytes) in [("512", 512), ("2KB", 2048), ("1.5K", 1536), ("3MiB", 3 << 20), ("1gb", 1 << 30)] {
+            let condition = parse_condition(&format!("size>{}", value), false).unwrap();
+            assert!(matches!(condition, Condition::Size(Comparison::Greater, actual) if actual == bytes), "{}", value);
+        }
+        assert!(parse_condition("size>5TB", false).is_err());
+    }
+
+    #[test]
+    fn conditions_chained_with_and() {
+        let rule = rule("exclude size>1MB AND ext=iso and name=disk*");
+        assert_eq!(rule.action, RuleAction::Exclude);
+        assert_eq!(rule.conditions.len(), 3);
+        assert!(rule.matches(Path::new("dir/disk1.iso"), 2 << 20));
+        assert!(!rule.matches(Path::new("dir/disk1.iso"), 1 << 20));
+        assert!(!rule.matches(Path::new("dir/disk1.img"), 2 << 20));
+        assert!(!rule.matches(Path::new("dir/cd1.iso"), 2 << 20));
+        assert!(parse_rule("exclude ext=iso or size>1MB", false).is_err());
+        assert!(parse_rule("exclude ext=iso and", false).is_err());
+    }
+
+    #[test]
+    fn extension_lists() {
+        let rule = rule("include ext=.JPG,png,gif");
+        assert_eq!(rule.action, RuleAction::Include);
+        for name in ["a.jpg", "b.PNG", "c.gif"] {
+            assert!(rule.matches(Path::new(name), 0), "{}", name);
+        }
+        assert!(!rule.matches(Path::new("d.jpeg"), 0));
+        assert!(parse_condition("ext=,", false).is_err());
+    }
+
+    #[test]
+    fn negated_conditions() {
+        let file = Path::new("logs/app.log");
+        assert!(!rule("exclude ext!=log").matches(file, 0));
+        assert!(rule("exclude ext!=txt,md").matches(file, 0));
+        assert!(!rule("exclude name!=app.*").matches(file, 0));
+        assert!(rule("exclude path!=cache").matches(file, 0));
+        assert!(parse_condition("ext>log", false).is_err());
+    }
+
+    #[test]
+    fn name_and_path_follow_ignore_case() {
+        let file = Path::new("Docs/README.md");
+        assert!(!rule("exclude name=readme.md").matches(file, 0));
+        assert!(parse_rule("exclude name=readme.md", true).unwrap().matches(file, 0));
+        assert!(parse_rule("exclude path=docs", true).unwrap().matches(file, 0));
+    }
+
+    #[test]
+    fn malformed_rules_rejected() {
+        for spec in ["", "   ", "exclude", "keep size>1MB", "exclude colour=red", "exclude size>abc", "exclude size>", "exclude size", "exclude size<>1"] {
+            let error = parse_rule(spec, false).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{:?}", spec);
+        }
+        assert!(parse_rule("", false).unwrap_err().to_string().contains("規則不可為空白"));
+        assert!(parse_rule("exclude colour=red", false).unwrap_err().to_string().contains("未知的條件 'colour'"));
+    }
+}
@@ -0,0 +1,517 @@
+use std::io;
+use base64::{engine::general_purpose, Engine};
+use serde::Deserialize;
+use crate::config::config::{FORMAT_VERSION, SUPPORTED_FORMAT_VERSIONS};
+use crate::models::html::ConversionMetadata;
+use crate::service::encoding::payload_encoder;
+use crate::service::gzip::decompress_payload;
+use crate::service::html::{sha256_hex, unescape_html, DATA_URI_PREFIX};
+
+/// 頁面中標示輸出格式版本的 meta 名稱
+pub const FORMAT_META_NAME: &str = "file-to-html-format";
+/// 頁面中轉換資訊註解的開頭標記
+pub const METADATA_MARKER: &str = "<!-- file-to-html-metadata ";
+/// MHTML 標示輸出格式版本的標頭名稱
+pub const FORMAT_HEADER_NAME: &str = "X-File-To-Html-Format";
+/// 不含腳本的頁面（`--no-js`）記錄下載檔名的 meta 名稱
+pub const DOWNLOAD_NAME_META_NAME: &str = "file-to-html-download-name";
+/// 不含腳本的頁面（`--no-js`）記錄資料 SHA-256 的 meta 名稱
+pub const PAYLOAD_SHA256_META_NAME: &str = "file-to-html-payload-sha256";
+
+// 最早的頁面直接將 Base64 與下載檔名寫在下載按鈕的呼叫參數中
+const LEGACY_DOWNLOAD_CALL: &str = "downloadFile('";
+
+/// 自產出頁面讀取的結構資訊，舊版頁面缺少的欄位以當時的預設行為補齊
+#[derive(Debug, Clone)]
+pub struct PageInfo {
+    // 產出物的輸出格式：`html`、`mhtml` 或 `json`
+    pub output_format: String,
+    pub format_version: u32,
+    // 產生頁面的工具版本，舊版頁面未記錄
+    pub generator: Option<String>,
+    pub download_zip_name: Option<String>,
+    pub payload_encoding: String,
+    pub payload_compression: String,
+    pub payload_sha256: Option<String>,
+    pub layer: Option<String>,
+    // 頁面資料是否需在瀏覽器中以密碼解密
+    pub encrypted: bool,
+    // 分塊加密的每塊大小，0 表示整體加密或未加密
+    pub stream_chunk_size: usize,
+    // 外部資料檔模式下的資料檔名稱
+    pub payload_file: Option<String>,
+    // 分段頁面的段次與總段數
+    pub part: Option<(usize, usize)>,
+    // 頁面嵌入的轉換資訊，版本 1 的頁面沒有此區塊
+    pub metadata: Option<ConversionMetadata>,
+}
+
+/// 生成標示輸出格式版本的 meta 標籤，版本 1 不標示以維持舊版輸出
+/// # 參數
+/// - format_version: `--format-version` 指定的版本
+/// # 回傳
+/// - 接在 viewport meta 之後的標籤（含前置換行），版本 1 時為空字串
+pub fn format_meta(format_version: u32) -> String {
+    if format_version < 2 {
+        return String::new();
+    }
+    format!(
+        "\n    <meta name=\"generator\" content=\"file_to_html {}\">\n    <meta name=\"{}\" content=\"{}\">",
+        env!("CARGO_PKG_VERSION"),
+        FORMAT_META_NAME,
+        format_version
+    )
+}
+
+/// 將轉換資訊包成 HTML 註解，連續的 `-` 以 JSON 跳脫表示，避免出現 `--` 或 `-->` 提前結束註解
+/// # 參數
+/// - metadata: 轉換資訊
+/// # 回傳
+/// - 接在 meta 標籤之後的註解（含前置換行），序列化失敗時返回 IO 錯誤
+pub fn metadata_comment(metadata: &ConversionMetadata) -> io::Result<String> {
+    let json = serde_json::to_string(metadata).map_err(io::Error::other)?;
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c == '-' && escaped.ends_with('-') {
+            escaped.push_str("\\u002d");
+        } else {
+            escaped.push(c);
+        }
+    }
+    Ok(format!("\n    {}{} -->", METADATA_MARKER, escaped))
+}
+
+/// 讀取頁面嵌入的轉換資訊
+/// # 回傳
+/// - 頁面沒有轉換資訊時返回 None，內容無法解析時返回 IO 錯誤
+pub fn read_metadata(html: &str) -> io::Result<Option<ConversionMetadata>> {
+    let Some(start) = html.find(METADATA_MARKER).map(|pos| pos + METADATA_MARKER.len()) else {
+        return Ok(None);
+    };
+    let end = html[start..].find(" -->").ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "頁面的轉換資訊區塊不完整")
+    })?;
+    serde_json::from_str(&html[start..start + end])
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("無法解析頁面的轉換資訊: {}", e)))
+}
+
+/// 判斷頁面的輸出格式版本，未標示版本的頁面視為版本 1
+pub fn detect_format_version(html: &str) -> u32 {
+    meta_content(html, FORMAT_META_NAME)
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(1)
+}
+
+/// 讀取產出物的結構資訊，依內容判斷為 HTML 頁面、MHTML 文件或 JSON 輸出
+/// # 參數
+/// - content: 產出物內容
+/// # 回傳
+/// - 成功時返回頁面資訊，無法解析或版本較目前工具新時返回 IO 錯誤
+pub fn read_document(content: &str) -> io::Result<PageInfo> {
+    if content.trim_start().starts_with('{') {
+        return read_json_document(content);
+    }
+    if is_mhtml(content) {
+        let page = mhtml_parts(content)?
+            .into_iter()
+            .find(|part| part.is_html())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "MHTML 文件中找不到頁面"))?;
+        let page = String::from_utf8(page.body)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "MHTML 文件中的頁面不是有效的 UTF-8"))?;
+        // 封存檔以附件提供，未經整體壓縮，頁面本身不嵌入資料
+        return Ok(PageInfo {
+            output_format: "mhtml".to_string(),
+            payload_encoding: "base64".to_string(),
+            payload_compression: "none".to_string(),
+            encrypted: false,
+            stream_chunk_size: 0,
+            payload_file: None,
+            part: None,
+            ..read_page(&page)?
+        });
+    }
+    read_page(content)
+}
+
+/// 讀取產出頁面的結構資訊，供檢查、還原與驗證已產出的頁面
+/// # 參數
+/// - html: 頁面內容
+/// # 回傳
+/// - 成功時返回頁面資訊，頁面版本較目前工具新時返回 IO 錯誤
+pub fn read_page(html: &str) -> io::Result<PageInfo> {
+    let format_version = detect_format_version(html);
+    check_format_version(format_version)?;
+
+    // 版本 1 的頁面依產生時期不同，可能缺少下列常數，缺少時以當時的行為補齊
+    let encrypted = match js_const(html, "ENCRYPTED") {
+        Some(value) => value == "true",
+        None => html.contains("id=\"password\""),
+    };
+    let part = js_const(html, "PART_INDEX")
+        .and_then(|index| index.parse().ok())
+        .zip(js_const(html, "PART_COUNT").and_then(|count| count.parse().ok()));
+    Ok(PageInfo {
+        output_format: "html".to_string(),
+        format_version,
+        generator: meta_content(html, "generator").map(str::to_string),
+        download_zip_name: js_const(html, "DOWNLOAD_ZIP_NAME")
+            .and_then(js_string)
+            .or_else(|| meta_content(html, DOWNLOAD_NAME_META_NAME).map(unescape_html))
+            .or_else(|| legacy_zip_name(html)),
+        payload_encoding: payload_attribute(html)
+            .or_else(|| js_const(html, "PAYLOAD_ENCODING").and_then(js_string))
+            .unwrap_or_else(|| "base64".to_string()),
+        payload_compression: js_const(html, "PAYLOAD_COMPRESSION")
+            .and_then(js_string)
+            .unwrap_or_else(|| "none".to_string()),
+        payload_sha256: js_const(html, "PAYLOAD_SHA256")
+            .and_then(js_string)
+            .or_else(|| meta_content(html, PAYLOAD_SHA256_META_NAME).map(str::to_string))
+            .filter(|sha| !sha.is_empty()),
+        layer: js_const(html, "ARCHIVE_LAYER").and_then(js_string),
+        encrypted,
+        stream_chunk_size: js_const(html, "STREAM_CHUNK_SIZE").and_then(|size| size.parse().ok()).unwrap_or(0),
+        payload_file: js_const(html, "PAYLOAD_FILE").and_then(js_string),
+        part,
+        metadata: read_metadata(html)?,
+    })
+}
+
+/// 取出頁面嵌入的資料並解碼
+/// # 參數
+/// - html: 產出物內容，MHTML 與 JSON 輸出為整份文件
+/// - info: read_document 取得的頁面資訊
+/// # 回傳
+/// - 成功時返回頁面中的原始資料；非分段頁面會一併還原整體壓縮並比對 SHA-256，
+///   分段頁面返回該段資料，需依段次串接後交由 finish_payload 處理
+pub fn read_payload(html: &str, info: &PageInfo) -> io::Result<Vec<u8>> {
+    match info.output_format.as_str() {
+        "mhtml" => {
+            let attachment = mhtml_parts(html)?
+                .into_iter()
+                .find(|part| !part.is_html())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "MHTML 文件中找不到封存檔附件"))?;
+            return finish_payload(info, attachment.body);
+        }
+        "json" => {
+            let document = parse_json_document(html)?;
+            let data = payload_encoder(&document.encoding)?.decode(&document.payload)?;
+            return finish_payload(info, data);
+        }
+        _ => {}
+    }
+    if let Some(payload_file) = &info.payload_file {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("頁面資料存放於外部資料檔 {}，請改為讀取該檔案", payload_file)
+        ));
+    }
+    let encoder = payload_encoder(&info.payload_encoding)?;
+    let text = payload_chunks(html)
+        .or_else(|| no_js_payload(html))
+        .or_else(|| legacy_payload(html))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "頁面中找不到嵌入的資料"))?;
+    let data = encoder.decode(&text)?;
+    if info.part.is_some() {
+        return Ok(data);
+    }
+    finish_payload(info, data)
+}
+
+/// 還原頁面資料的整體壓縮並比對 SHA-256
+/// # 參數
+/// - info: 頁面資訊，分段頁面可使用任一段的資訊
+/// - data: 解碼後的資料，分段時為依段次串接的結果
+/// # 回傳
+/// - 成功時返回 ZIP 數據，雜湊不符時返回 IO 錯誤
+pub fn finish_payload(info: &PageInfo, data: Vec<u8>) -> io::Result<Vec<u8>> {
+    let data = decompress_payload(data, &info.payload_compression)?;
+    if let Some(expected) = &info.payload_sha256 {
+        let actual = sha256_hex(&data);
+        if &actual != expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("頁面資料的 SHA-256 不符，預期 {}，實際 {}", expected, actual)
+            ));
+        }
+    }
+    Ok(data)
+}
+
+// 版本較目前工具新或無法辨識時返回 IO 錯誤
+fn check_format_version(format_version: u32) -> io::Result<()> {
+    if format_version > FORMAT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("頁面的輸出格式版本 {} 較目前支援的版本 {} 新，請更新 file_to_html", format_version, FORMAT_VERSION)
+        ));
+    }
+    if !SUPPORTED_FORMAT_VERSIONS.contains(&format_version) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("無法辨識的輸出格式版本 {}", format_version)
+        ));
+    }
+    Ok(())
+}
+
+// JsonRenderer 輸出的內容，轉換資訊於輸出格式版本 2 起加入
+#[derive(Deserialize)]
+struct JsonDocument {
+    name: String,
+    sha256: String,
+    encoding: String,
+    payload: String,
+    #[serde(default)]
+    metadata: Option<ConversionMetadata>,
+}
+
+fn parse_json_document(content: &str) -> io::Result<JsonDocument> {
+    serde_json::from_str(content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("無法解析 JSON 輸出: {}", e)))
+}
+
+// JSON 輸出的資料即為封存檔本身，不經瀏覽器端加密或整體壓縮
+fn read_json_document(content: &str) -> io::Result<PageInfo> {
+    let document = parse_json_document(content)?;
+    let format_version = document.metadata.as_ref().map_or(1, |metadata| metadata.format_version);
+    check_format_version(format_version)?;
+    Ok(PageInfo {
+        output_format: "json".to_string(),
+        format_version,
+        generator: document.metadata.as_ref().map(|metadata| format!("{} {}", metadata.tool, metadata.tool_version)),
+        download_zip_name: Some(document.name),
+        payload_encoding: document.encoding,
+        payload_compression: "none".to_string(),
+        payload_sha256: Some(document.sha256).filter(|sha| !sha.is_empty()),
+        layer: document.metadata.as_ref().map(|metadata| metadata.layer.clone()),
+        encrypted: false,
+        stream_chunk_size: 0,
+        payload_file: None,
+        part: None,
+        metadata: document.metadata,
+    })
+}
+
+// MHTML 文件中的一個部分
+struct MimePart {
+    content_type: String,
+    body: Vec<u8>,
+}
+
+impl MimePart {
+    fn is_html(&self) -> bool {
+        self.content_type.starts_with("text/html")
+    }
+}
+
+// 文件標頭宣告為 multipart/related 時視為 MHTML
+fn is_mhtml(content: &str) -> bool {
+    let content = content.replace("\r\n", "\n");
+    let headers = content.split("\n\n").next().unwrap_or_default();
+    headers.to_ascii_lowercase().contains("multipart/related")
+}
+
+// 依文件標頭的分隔字串拆出每個部分，並依 Content-Transfer-Encoding 解碼內容
+fn mhtml_parts(content: &str) -> io::Result<Vec<MimePart>> {
+    let content = content.replace("\r\n", "\n");
+    let (headers, _) = content.split_once("\n\n").unwrap_or((&content, ""));
+    let boundary = header_param(&unfold_headers(headers), "boundary")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "MHTML 文件缺少 boundary"))?;
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    for piece in content.split(&delimiter).skip(1) {
+        if piece.starts_with("--") {
+            break;
+        }
+        let piece = piece.strip_prefix('\n').unwrap_or(piece);
+        let (part_headers, body) = piece.split_once("\n\n").unwrap_or((piece, ""));
+        let part_headers = unfold_headers(part_headers);
+        let content_type = header_value(&part_headers, "content-type").unwrap_or_default().to_ascii_lowercase();
+        let body = body.strip_suffix('\n').unwrap_or(body);
+        let body = match header_value(&part_headers, "content-transfer-encoding").map(|v| v.to_ascii_lowercase()).as_deref() {
+            Some("base64") => {
+                let compact: String = body.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+                general_purpose::STANDARD
+                    .decode(compact)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("MHTML 附件的 Base64 資料無效: {}", e)))?
+            }
+            Some("quoted-printable") => decode_quoted_printable(body),
+            _ => body.as_bytes().to_vec(),
+        };
+        parts.push(MimePart { content_type, body });
+    }
+    Ok(parts)
+}
+
+// 將跨行的標頭（以空白開頭的續行）合併為單行
+fn unfold_headers(headers: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in headers.lines() {
+        match lines.last_mut() {
+            Some(last) if line.starts_with([' ', '\t']) => {
+                last.push(' ');
+                last.push_str(line.trim());
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+// 標頭名稱不分大小寫
+fn header_value(headers: &[String], name: &str) -> Option<String> {
+    headers.iter().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+    })
+}
+
+// 取得 Content-Type 標頭中的參數，如 `boundary="..."`
+fn header_param(headers: &[String], name: &str) -> Option<String> {
+    let value = header_value(headers, "content-type")?;
+    value.split(';').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+// 還原 quoted-printable 編碼，`=` 結尾的軟換行不屬於內容
+fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'=' {
+            output.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        if bytes.get(i + 1) == Some(&b'\n') {
+            i += 2;
+            continue;
+        }
+        match bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok()).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) => {
+                output.push(byte);
+                i += 3;
+            }
+            None => {
+                output.push(b'=');
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+// 取得指定名稱的 meta 標籤內容
+fn meta_content<'a>(html: &'a str, name: &str) -> Option<&'a str> {
+    let marker = format!("<meta name=\"{}\" content=\"", name);
+    let start = html.find(&marker)? + marker.len();
+    let end = html[start..].find('"')?;
+    Some(&html[start..start + end])
+}
+
+// 取得頁面腳本中 `const NAME = value;` 的原始值
+fn js_const<'a>(html: &'a str, name: &str) -> Option<&'a str> {
+    let marker = format!("const {} = ", name);
+    let start = html.find(&marker)? + marker.len();
+    let end = html[start..].find(";\n").or_else(|| html[start..].find(';'))?;
+    Some(html[start..start + end].trim())
+}
+
+// 還原 JavaScript 字串常值，`null` 視為未設定
+fn js_string(value: &str) -> Option<String> {
+    if value == "null" {
+        return None;
+    }
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Some(inner.to_string());
+    }
+    serde_json::from_str(value).ok()
+}
+
+// 資料區塊上標示的編碼
+fn payload_attribute(html: &str) -> Option<String> {
+    let marker = "<div id=\"payload\" data-encoding=\"";
+    let start = html.find(marker)? + marker.len();
+    let end = html[start..].find('"')?;
+    Some(html[start..start + end].to_string())
+}
+
+// 串接資料區塊內的所有分塊
+fn payload_chunks(html: &str) -> Option<String> {
+    let open = "class=\"payload-chunk\">";
+    let block_start = html.find("<div id=\"payload\"")?;
+    let block_end = block_start + html[block_start..].find("</div>")?;
+    let block = &html[block_start..block_end];
+    let mut text = String::with_capacity(block.len());
+    for piece in block.split(open).skip(1) {
+        text.push_str(&piece[..piece.find("</script>")?]);
+    }
+    Some(text)
+}
+
+// 不含腳本的頁面以資料連結提供下載，超過資料連結上限時改為可複製的 Base64 文字（可能已換行）
+fn no_js_payload(html: &str) -> Option<String> {
+    let link = format!("href=\"{}", DATA_URI_PREFIX);
+    if let Some(start) = html.find(&link).map(|pos| pos + link.len()) {
+        let end = html[start..].find('"')?;
+        return Some(html[start..start + end].to_string());
+    }
+    let open = "<textarea class=\"payload-text\"";
+    let start = html.find(open)?;
+    let start = start + html[start..].find('>')? + 1;
+    let end = html[start..].find("</textarea>")?;
+    Some(html[start..start + end].chars().filter(|c| !c.is_ascii_whitespace()).collect())
+}
+
+// 最早的頁面將 Base64 直接放在下載按鈕的 onclick 參數中
+fn legacy_payload(html: &str) -> Option<String> {
+    let start = html.find(LEGACY_DOWNLOAD_CALL)? + LEGACY_DOWNLOAD_CALL.len();
+    let end = html[start..].find('\'')?;
+    Some(html[start..start + end].to_string())
+}
+
+// 最早的頁面將下載檔名放在 onclick 的第二個參數
+fn legacy_zip_name(html: &str) -> Option<String> {
+    let start = html.find(LEGACY_DOWNLOAD_CALL)? + LEGACY_DOWNLOAD_CALL.len();
+    let rest = &html[start..];
+    let rest = &rest[rest.find("', '")? + 4..];
+    Some(rest[..rest.find('\'')?].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_printable_soft_breaks_and_escapes() {
+        assert_eq!(decode_quoted_printable("a=3Db=\nc=E4=B8=AD"), "a=bc中".as_bytes());
+        assert_eq!(decode_quoted_printable("50=% off"), b"50=% off");
+    }
+
+    #[test]
+    fn mhtml_with_quoted_printable_page() {
+        let document = concat!(
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/related;\r\n\tboundary=\"b\"\r\n\r\n",
+            "--b\r\n",
+            "Content-Type: text/html; charset=\"utf-8\"\r\n",
+            "Content-Transfer-Encoding: quoted-printable\r\n\r\n",
+            "<html><head><meta name=3D\"file-to-html-download-name\" content=3D\"a.txt.zip\">=\r\n",
+            "</head></html>\r\n",
+            "--b\r\n",
+            "Content-Type: application/zip\r\n",
+            "Content-Transfer-Encoding: base64\r\n\r\n",
+            "UEsF\r\nBg==\r\n",
+            "--b--\r\n",
+        );
+        let info = read_document(document).unwrap();
+        assert_eq!(info.output_format, "mhtml");
+        assert_eq!(info.download_zip_name.as_deref(), Some("a.txt.zip"));
+        assert_eq!(read_payload(document, &info).unwrap(), b"PK\x05\x06");
+    }
+}
@@ -0,0 +1,474 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use log::{info, warn};
+use crate::service::spill::SpillFile;
+use crate::service::traits::i_sink::{CollisionAction, CollisionResolver, FileAccess, OutputFile, OutputSink};
+use crate::utils::utils::is_reserved_device_name;
+
+/// 檔案輸出，寫入 `output_dir/name`（預設行為）
+pub struct FileSink;
+
+impl OutputSink for FileSink {
+    fn write(&self, output_dir: &str, name: &str, content: &[u8]) -> io::Result<String> {
+        write_file(&output_path(output_dir, name)?, content, FileAccess::Default)?;
+        Ok(format!("{}/{}", output_dir, name))
+    }
+
+    // 先以暫存檔名寫出所有檔案，全部成功後再逐一改為正式檔名；改名失敗時刪除剩餘的暫存檔與已改名的檔案。
+    // 機密檔案的暫存檔建立時即限制權限，改名後沿用，不會有短暫可被他人讀取的期間
+    fn write_batch(&self, output_dir: &str, files: &[OutputFile]) -> io::Result<Vec<String>> {
+        let dir = Path::new(output_dir);
+        for file in files {
+            output_path(output_dir, file.name)?;
+        }
+        let mut staged: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(files.len());
+        for file in files {
+            let temp = dir.join(partial_name(file.name));
+            let written = match file.source {
+                Some(source) => copy_file(&temp, source, file.access),
+                None => write_file(&temp, file.content, file.access),
+            };
+            if let Err(e) = written {
+                let _ = fs::remove_file(&temp);
+                discard_files(staged.iter().map(|(temp, _)| temp));
+                return Err(e);
+            }
+            staged.push((temp, dir.join(file.name)));
+        }
+        for (i, (temp, target)) in staged.iter().enumerate() {
+            if let Err(e) = fs::rename(temp, target) {
+                discard_files(staged[i..].iter().map(|(temp, _)| temp).chain(staged[..i].iter().map(|(_, target)| target)));
+                return Err(io::Error::new(e.kind(), format!("無法寫出 {}：{}", target.display(), e)));
+            }
+        }
+        Ok(files.iter().map(|file| format!("{}/{}", output_dir, file.name)).collect())
+    }
+
+    fn remove(&self, location: &str) -> io::Result<()> {
+        fs::remove_file(location)
+    }
+}
+
+// 產出物在輸出目錄中的路徑；檔名可包含子目錄，但不可為絕對路徑、以 `..` 跳出輸出目錄或包含 NUL，
+// Windows 上另拒絕保留的裝置名稱，避免寫入到裝置而非檔案
+fn output_path(output_dir: &str, name: &str) -> io::Result<PathBuf> {
+    let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidInput, format!("無效的輸出檔名 {:?}：{}", name, reason));
+    if name.is_empty() {
+        return Err(invalid("檔名為空"));
+    }
+    if name.contains('\0') {
+        return Err(invalid("包含 NUL 字元"));
+    }
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) if cfg!(windows) && is_reserved_device_name(&part.to_string_lossy()) => {
+                return Err(invalid("為 Windows 保留的裝置名稱"));
+            }
+            Component::Normal(_) | Component::CurDir => {}
+            Component::ParentDir => return Err(invalid("不可包含 `..`")),
+            Component::RootDir | Component::Prefix(_) => return Err(invalid("不可為絕對路徑")),
+        }
+    }
+    Ok(Path::new(output_dir).join(name))
+}
+
+// 建立並寫入檔案
+fn write_file(path: &Path, content: &[u8], access: FileAccess) -> io::Result<()> {
+    let mut writer = BufWriter::new(create_file(path, access)?);
+    writer.write_all(content)?;
+    writer.flush()
+}
+
+// 建立檔案並串流複製暫存檔的內容，不將內容讀入記憶體
+fn copy_file(path: &Path, source: &Path, access: FileAccess) -> io::Result<()> {
+    let mut writer = BufWriter::new(create_file(path, access)?);
+    io::copy(&mut fs::File::open(source)?, &mut writer)?;
+    writer.flush()
+}
+
+// 建立檔案；機密檔案以指定的權限位元建立，並明確重設一次以排除 umask 與既有檔案的影響
+#[cfg(unix)]
+fn create_file(path: &Path, access: FileAccess) -> io::Result<fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    match access {
+        FileAccess::Default => options.open(path),
+        FileAccess::Private(mode) => {
+            let file = options.mode(mode).open(path)?;
+            file.set_permissions(fs::Permissions::from_mode(mode))?;
+            Ok(file)
+        }
+    }
+}
+
+// 建立檔案；機密檔案未授予群組與其他人權限時，在寫入內容前移除繼承的權限，只保留目前使用者
+#[cfg(windows)]
+fn create_file(path: &Path, access: FileAccess) -> io::Result<fs::File> {
+    let file = fs::File::create(path)?;
+    if matches!(access, FileAccess::Private(mode) if mode & 0o077 == 0) {
+        restrict_to_owner(path)?;
+    }
+    Ok(file)
+}
+
+// 以 icacls 停用權限繼承，並只授予目前使用者完整控制
+#[cfg(windows)]
+fn restrict_to_owner(path: &Path) -> io::Result<()> {
+    let user = std::env::var("USERNAME")
+        .map_err(|_| io::Error::other(format!("無法取得目前使用者名稱，未能限制 {} 的存取權限", path.display())))?;
+    let status = std::process::Command::new("icacls")
+        .arg(path)
+        .args(["/inheritance:r", "/grant:r"])
+        .arg(format!("{}:F", user))
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("無法執行 icacls 限制 {} 的存取權限：{}", path.display(), e)))?;
+    if !status.success() {
+        return Err(io::Error::other(format!("icacls 無法限制 {} 的存取權限：{}", path.display(), status)));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn create_file(path: &Path, _access: FileAccess) -> io::Result<fs::File> {
+    fs::File::create(path)
+}
+
+// 暫存檔名：與正式檔名位於同一目錄以便直接改名，加上點號前綴與行程編號避免與其他檔案衝突
+fn partial_name(name: &str) -> String {
+    let path = Path::new(name);
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| name.to_string());
+    let temp = format!(".{}.{}.partial", file_name, std::process::id());
+    match path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        Some(parent) => parent.join(temp).to_string_lossy().to_string(),
+        None => temp,
+    }
+}
+
+// 撤回時刪除檔案，個別刪除失敗只記錄警告，不掩蓋原本的錯誤
+fn discard_files<'a, I>(paths: I)
+where
+    I: IntoIterator<Item = &'a PathBuf>,
+{
+    for path in paths {
+        if let Err(e) = fs::remove_file(path) {
+            warn!("無法刪除 {}：{}", path.display(), e);
+        }
+    }
+}
+
+/// 單一產出物的所有檔案（頁面、密碼檔、密碼單、外部資料檔等），生成期間暫存於記憶體，
+/// 全部產生成功後才交由輸出目的地一次寫出，避免生成失敗時留下沒有對應頁面的密碼檔
+pub struct OutputBatch {
+    output_dir: String,
+    files: Vec<StagedFile>,
+}
+
+// 批次中的單一檔案，內容存放於記憶體或暫存檔
+struct StagedFile {
+    name: String,
+    content: Vec<u8>,
+    spill: Option<SpillFile>,
+    access: FileAccess,
+}
+
+impl OutputBatch {
+    pub fn new(output_dir: &str) -> Self {
+        OutputBatch { output_dir: output_dir.to_string(), files: Vec::new() }
+    }
+
+    /// 加入一個檔案
+    /// # 回傳
+    /// - 檔案在批次中的序號，用於取得 commit 返回的位置
+    pub fn add(&mut self, name: &str, content: Vec<u8>) -> usize {
+        self.add_with_access(name, content, FileAccess::Default)
+    }
+
+    /// 加入含密碼的機密檔案，以指定的權限位元寫出
+    /// # 回傳
+    /// - 檔案在批次中的序號
+    pub fn add_private(&mut self, name: &str, content: Vec<u8>, mode: u32) -> usize {
+        self.add_with_access(name, content, FileAccess::Private(mode))
+    }
+
+    /// 加入內容存放於暫存檔的檔案，寫出時直接由暫存檔複製
+    /// # 回傳
+    /// - 檔案在批次中的序號
+    pub fn add_spilled(&mut self, name: &str, spill: SpillFile) -> usize {
+        self.files.push(StagedFile { name: name.to_string(), content: Vec::new(), spill: Some(spill), access: FileAccess::Default });
+        self.files.len() - 1
+    }
+
+    fn add_with_access(&mut self, name: &str, content: Vec<u8>, access: FileAccess) -> usize {
+        self.files.push(StagedFile { name: name.to_string(), content, spill: None, access });
+        self.files.len() - 1
+    }
+
+    /// 將所有檔案交由輸出目的地一次寫出
+    /// # 回傳
+    /// - 成功時依加入順序返回各檔案的位置，失敗時返回 IO 錯誤
+    pub fn commit(self, sink: &dyn OutputSink) -> io::Result<Vec<String>> {
+        let files: Vec<OutputFile> = self
+            .files
+            .iter()
+            .map(|file| OutputFile {
+                name: &file.name,
+                content: &file.content,
+                source: file.spill.as_ref().map(SpillFile::path),
+                access: file.access,
+            })
+            .collect();
+        sink.write_batch(&self.output_dir, &files)
+    }
+}
+
+// 重新命名時嘗試的最大序號
+const MAX_RENAME_ATTEMPTS: usize = 9999;
+
+/// 檔案輸出，目標檔案已存在時交由決策接口決定覆寫、另存新檔名或略過
+pub struct CollisionSink<R: CollisionResolver> {
+    resolver: R,
+    // 同一時間只處理一個衝突，避免平行轉換時同時詢問或選到相同的新檔名
+    lock: Mutex<()>,
+    // 實際寫出的檔案，移除時只處理這些檔案，略過而保留的既有檔案不受影響
+    written: Mutex<HashSet<String>>,
+}
+
+impl<R: CollisionResolver> CollisionSink<R> {
+    pub fn new(resolver: R) -> Self {
+        CollisionSink { resolver, lock: Mutex::new(()), written: Mutex::new(HashSet::new()) }
+    }
+
+    fn record_written<I: IntoIterator<Item = String>>(&self, locations: I) {
+        self.written.lock().unwrap_or_else(|e| e.into_inner()).extend(locations);
+    }
+}
+
+impl<R: CollisionResolver> CollisionSink<R> {
+    // 決定實際寫入的檔名，略過時返回 None
+    fn resolve_name(&self, output_dir: &str, name: &str) -> io::Result<Option<String>> {
+        let output_path = Path::new(output_dir).join(name);
+        if !output_path.exists() {
+            return Ok(Some(name.to_string()));
+        }
+        match self.resolver.resolve(&output_path)? {
+            CollisionAction::Overwrite => Ok(Some(name.to_string())),
+            CollisionAction::Rename => {
+                let renamed = free_name(output_dir, name)?;
+                info!("{} 已存在，改寫入 {}", output_path.display(), renamed);
+                Ok(Some(renamed))
+            }
+            CollisionAction::Skip => {
+                warn!("{} 已存在，保留既有檔案", output_path.display());
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<R: CollisionResolver> OutputSink for CollisionSink<R> {
+    fn write(&self, output_dir: &str, name: &str, content: &[u8]) -> io::Result<String> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        match self.resolve_name(output_dir, name)? {
+            Some(resolved) => {
+                let location = FileSink.write(output_dir, &resolved, content)?;
+                self.record_written([location.clone()]);
+                Ok(location)
+            }
+            None => Ok(format!("{}/{}", output_dir, name)),
+        }
+    }
+
+    // 先逐一決定所有檔案的檔名，再以 FileSink 一次寫出，略過的檔案返回既有檔案的位置
+    fn write_batch(&self, output_dir: &str, files: &[OutputFile]) -> io::Result<Vec<String>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut resolved = Vec::with_capacity(files.len());
+        for file in files {
+            resolved.push(self.resolve_name(output_dir, file.name)?);
+        }
+        let writes: Vec<OutputFile> = files
+            .iter()
+            .zip(&resolved)
+            .filter_map(|(file, name)| {
+                name.as_deref().map(|name| OutputFile { name, content: file.content, source: file.source, access: file.access })
+            })
+            .collect();
+        let written = FileSink.write_batch(output_dir, &writes)?;
+        self.record_written(written.iter().cloned());
+        let mut written = written.into_iter();
+        Ok(files
+            .iter()
+            .zip(resolved)
+            .map(|(file, resolved)| match resolved {
+                Some(_) => written.next().unwrap_or_default(),
+                None => format!("{}/{}", output_dir, file.name),
+            })
+            .collect())
+    }
+
+    fn remove(&self, location: &str) -> io::Result<()> {
+        if !self.written.lock().unwrap_or_else(|e| e.into_inner()).remove(location) {
+            return Ok(());
+        }
+        FileSink.remove(location)
+    }
+}
+
+// 在副檔名前加上序號，找出輸出目錄中尚未使用的檔名，如 `a.pdf.html` → `a.pdf-1.html`
+fn free_name(output_dir: &str, name: &str) -> io::Result<String> {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (name, String::new()),
+    };
+    (1..=MAX_RENAME_ATTEMPTS)
+        .map(|i| format!("{}-{}{}", stem, i, extension))
+        .find(|candidate| !Path::new(output_dir).join(candidate).exists())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::AlreadyExists, format!("找不到可用的檔名：{}", name)))
+}
+
+/// 記憶體中的單一產出物（檔名、內容）
+pub type MemoryOutput = (String, Vec<u8>);
+
+/// 記憶體輸出，保留所有產出物供呼叫端取用
+#[derive(Clone, Default)]
+pub struct MemorySink {
+    outputs: Arc<Mutex<Vec<MemoryOutput>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        MemorySink::default()
+    }
+
+    /// 取出目前累積的產出物並清空
+    pub fn take(&self) -> Vec<MemoryOutput> {
+        std::mem::take(&mut *self.outputs.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl OutputSink for MemorySink {
+    fn write(&self, _output_dir: &str, name: &str, content: &[u8]) -> io::Result<String> {
+        self.outputs.lock().unwrap_or_else(|e| e.into_inner()).push((name.to_string(), content.to_vec()));
+        Ok(name.to_string())
+    }
+}
+
+/// 回呼輸出，將每個產出物交給呼叫端處理（例如上傳至遠端）
+pub struct CallbackSink<F>
+where
+    F: Fn(&str, &[u8]) -> io::Result<()> + Send + Sync,
+{
+    callback: F,
+}
+
+impl<F> CallbackSink<F>
+where
+    F: Fn(&str, &[u8]) -> io::Result<()> + Send + Sync,
+{
+    pub fn new(callback: F) -> Self {
+        CallbackSink { callback }
+    }
+}
+
+impl<F> OutputSink for CallbackSink<F>
+where
+    F: Fn(&str, &[u8]) -> io::Result<()> + Send + Sync,
+{
+    fn write(&self, _output_dir: &str, name: &str, content: &[u8]) -> io::Result<String> {
+        (self.callback)(name, content)?;
+        Ok(name.to_string())
+    }
+}
+
+/// 串流輸出，將產出物直接寫入任意 Writer（例如 HTTP 回應或網路連線）
+pub struct WriterSink<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> WriterSink<W> {
+    pub fn new(writer: W) -> Self {
+        WriterSink { writer: Mutex::new(writer) }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<W: Write + Send> OutputSink for WriterSink<W> {
+    fn write(&self, _output_dir: &str, name: &str, content: &[u8]) -> io::Result<String> {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.write_all(content)?;
+        writer.flush()?;
+        Ok(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_sink_rejects_malicious_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_dir = dir.path().join("out");
+        fs::create_dir(&output_dir).unwrap();
+        let output_dir = output_dir.to_string_lossy();
+        for name in ["", "../escape.html", "a/../../escape.html", "/tmp/escape.html", "a\0b.html"] {
+            assert!(FileSink.write(&output_dir, name, b"x").is_err(), "{:?} 應被拒絕", name);
+        }
+        let batch = [OutputFile { name: "../escape.html", content: b"x", source: None, access: FileAccess::Default }];
+        assert!(FileSink.write_batch(&output_dir, &batch).is_err());
+        assert!(!dir.path().join("escape.html").exists());
+    }
+
+    // 寫入時 panic 的 Writer，用來讓 WriterSink 的鎖進入 poisoned 狀態
+    struct PanicWriter(Vec<u8>);
+
+    impl Write for PanicWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if buf == b"panic" {
+                panic!("寫入失敗");
+            }
+            self.0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn writer_sink_recovers_from_poisoned_lock() {
+        let sink = Arc::new(WriterSink::new(PanicWriter(Vec::new())));
+        let poisoning = Arc::clone(&sink);
+        assert!(std::thread::spawn(move || poisoning.write("", "a", b"panic")).join().is_err());
+        sink.write("", "b", b"ok").unwrap();
+        let sink = Arc::try_unwrap(sink).unwrap_or_else(|_| panic!("應只剩一個參照"));
+        assert_eq!(sink.into_inner().0, b"ok");
+    }
+
+    #[test]
+    fn device_names_rejected_on_windows() {
+        for name in ["CON", "nul.html", "sub/COM1.key"] {
+            assert_eq!(output_path("out", name).is_err(), cfg!(windows), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn file_sink_accepts_nested_names() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let output_dir = dir.path().to_string_lossy();
+        FileSink.write(&output_dir, "sub/a.html", b"x").unwrap();
+        FileSink.write(&output_dir, "./b.html", b"y").unwrap();
+        assert_eq!(fs::read(dir.path().join("sub/a.html")).unwrap(), b"x");
+        assert_eq!(fs::read(dir.path().join("b.html")).unwrap(), b"y");
+    }
+}
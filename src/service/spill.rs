@@ -0,0 +1,151 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use tempfile::{NamedTempFile, TempPath};
+use crate::service::traits::i_encoder::PayloadEncoder;
+
+/// 暫存於磁碟的資料（`--spill-to-disk`），最後一個複本離開作用域時刪除暫存檔
+#[derive(Debug, Clone)]
+pub struct SpillFile {
+    path: Arc<TempPath>,
+    len: u64,
+}
+
+impl SpillFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn open(&self) -> io::Result<BufReader<File>> {
+        Ok(BufReader::new(File::open(self.path())?))
+    }
+
+    /// 將整份資料讀回記憶體，供需要完整資料的功能使用
+    pub fn read_all(&self) -> io::Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(self.len as usize);
+        self.open()?.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// 依編碼器的分塊大小逐塊讀取資料，每次只保留一塊
+    /// # 參數
+    /// - chunk_size: 每塊的位元組數
+    /// - f: 處理每一塊的函數
+    /// # 回傳
+    /// - 成功時返回分塊數，讀取或處理失敗時返回 IO 錯誤
+    pub fn for_each_chunk<F>(&self, chunk_size: usize, mut f: F) -> io::Result<usize>
+    where
+        F: FnMut(&[u8]) -> io::Result<()>,
+    {
+        let mut reader = self.open()?;
+        let mut buffer = Vec::with_capacity(chunk_size);
+        let mut count = 0;
+        loop {
+            buffer.clear();
+            (&mut reader).take(chunk_size as u64).read_to_end(&mut buffer)?;
+            if buffer.is_empty() {
+                return Ok(count);
+            }
+            f(&buffer)?;
+            count += 1;
+        }
+    }
+}
+
+/// 暫存檔內容的 SHA-256 與編碼後的大小，決定頁面大小上限策略與完整性資訊
+pub struct SpillSummary {
+    pub sha256: String,
+    pub encoded_size: usize,
+    pub chunk_count: usize,
+}
+
+/// 逐塊讀取暫存檔，計算 SHA-256 與編碼後的總大小
+/// # 參數
+/// - spill: 暫存於磁碟的資料
+/// - encoder: 頁面資料的編碼器
+/// # 回傳
+/// - 成功時返回摘要，讀取失敗時返回 IO 錯誤
+pub fn summarize(spill: &SpillFile, encoder: &dyn PayloadEncoder) -> io::Result<SpillSummary> {
+    let mut hasher = Sha256::new();
+    let mut encoded_size = 0;
+    let chunk_count = spill.for_each_chunk(encoder.chunk_bytes(), |chunk| {
+        hasher.update(chunk);
+        encoded_size += encoder.encode(chunk).len();
+        Ok(())
+    })?;
+    Ok(SpillSummary {
+        sha256: hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect(),
+        encoded_size,
+        // 空資料仍輸出一個空的資料塊，與 encode_payload 相同
+        chunk_count: chunk_count.max(1),
+    })
+}
+
+/// 寫入目標：一般存放於記憶體，啟用 `--spill-to-disk` 時寫入暫存檔
+pub enum SpillBuffer {
+    Memory(Cursor<Vec<u8>>),
+    Disk(BufWriter<File>, TempPath),
+}
+
+impl SpillBuffer {
+    /// # 參數
+    /// - spill: 是否寫入暫存檔，暫存檔建立於系統暫存目錄（可由 `TMPDIR` 指定）
+    pub fn new(spill: bool) -> io::Result<Self> {
+        if !spill {
+            return Ok(SpillBuffer::Memory(Cursor::new(Vec::new())));
+        }
+        let (file, path) = NamedTempFile::new()
+            .map_err(|e| io::Error::new(e.kind(), format!("無法建立暫存檔：{}", e)))?
+            .into_parts();
+        Ok(SpillBuffer::Disk(BufWriter::new(file), path))
+    }
+
+    /// 完成寫入
+    /// # 回傳
+    /// - 存放於記憶體時返回數據與 None，寫入暫存檔時返回空的數據與暫存檔
+    pub fn finish(self) -> io::Result<(Vec<u8>, Option<SpillFile>)> {
+        match self {
+            SpillBuffer::Memory(cursor) => Ok((cursor.into_inner(), None)),
+            SpillBuffer::Disk(writer, path) => {
+                let file = writer.into_inner().map_err(io::IntoInnerError::into_error)?;
+                let len = file.metadata()?.len();
+                Ok((Vec::new(), Some(SpillFile { path: Arc::new(path), len })))
+            }
+        }
+    }
+}
+
+impl Write for SpillBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            SpillBuffer::Memory(cursor) => cursor.write(buf),
+            SpillBuffer::Disk(writer, _) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            SpillBuffer::Memory(cursor) => cursor.flush(),
+            SpillBuffer::Disk(writer, _) => writer.flush(),
+        }
+    }
+}
+
+impl Seek for SpillBuffer {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            SpillBuffer::Memory(cursor) => cursor.seek(pos),
+            SpillBuffer::Disk(writer, _) => writer.seek(pos),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use chrono::{Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::models::conversion::ConversionOutput;
+
+/// 統計檔的格式版本，結構不相容時遞增
+pub const STATS_FILE_VERSION: u32 = 1;
+
+// 統計檔保留的天數，較早的每日統計於寫入時移除
+const RETENTION_DAYS: i64 = 400;
+
+// 每日統計的日期格式，亦為統計檔中的鍵值
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// `--stats-file` 寫出的本機使用統計：依日期彙整轉換次數、資料量與耗時，不含檔名或任何檔案內容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    pub stats_version: u32,
+    // 以本機日期（YYYY-MM-DD）為鍵
+    pub days: BTreeMap<String, DailyStats>,
+}
+
+/// 單日的彙整統計
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyStats {
+    pub runs: u64,
+    pub failures: u64,
+    pub files: u64,
+    pub source_bytes: u64,
+    pub output_bytes: u64,
+    pub duration_ms: u64,
+}
+
+impl DailyStats {
+    /// 累加另一段期間的統計
+    pub fn add(&mut self, other: &DailyStats) {
+        self.runs += other.runs;
+        self.failures += other.failures;
+        self.files += other.files;
+        self.source_bytes += other.source_bytes;
+        self.output_bytes += other.output_bytes;
+        self.duration_ms += other.duration_ms;
+    }
+
+    /// 每次轉換的平均耗時（毫秒），沒有轉換時為 0
+    pub fn average_duration_ms(&self) -> u64 {
+        self.duration_ms.checked_div(self.runs).unwrap_or(0)
+    }
+
+    /// 以一次轉換的結果建立統計，失敗的轉換只計入次數與耗時
+    /// # 參數
+    /// - result: 轉換結果
+    /// - duration_ms: 轉換耗時（毫秒）
+    pub fn from_run(result: &io::Result<ConversionOutput>, duration_ms: u64) -> Self {
+        match result {
+            Ok(output) => DailyStats {
+                runs: 1,
+                failures: 0,
+                files: output.processed_files as u64,
+                source_bytes: output.artifacts.iter().map(|a| a.source_size as u64).sum(),
+                output_bytes: output.artifacts.iter().map(|a| a.html_size as u64).sum(),
+                duration_ms,
+            },
+            Err(_) => DailyStats { runs: 1, failures: 1, duration_ms, ..DailyStats::default() },
+        }
+    }
+}
+
+impl UsageStats {
+    /// 讀取統計檔
+    /// # 參數
+    /// - path: 統計檔路徑
+    /// # 回傳
+    /// - 成功時返回統計內容，讀取失敗、格式錯誤或版本不相容時返回 IO 錯誤
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("無法讀取統計檔 {}：{}", path.display(), e)))?;
+        Self::read_from(&mut file, path)
+    }
+
+    /// 指定日期範圍內的每日統計，依日期排序
+    /// # 參數
+    /// - from: 起始日期（含）
+    /// - to: 結束日期（含）
+    pub fn range(&self, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, DailyStats)> {
+        self.days
+            .iter()
+            .filter_map(|(key, stats)| NaiveDate::parse_from_str(key, DATE_FORMAT).ok().map(|date| (date, *stats)))
+            .filter(|(date, _)| *date >= from && *date <= to)
+            .collect()
+    }
+
+    /// 所有日期的合計
+    pub fn total(&self) -> DailyStats {
+        let mut total = DailyStats::default();
+        for stats in self.days.values() {
+            total.add(stats);
+        }
+        total
+    }
+
+    // 自已開啟的統計檔讀取，空檔案視為尚無統計
+    fn read_from(file: &mut File, path: &Path) -> io::Result<Self> {
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        if content.trim().is_empty() {
+            return Ok(UsageStats { stats_version: STATS_FILE_VERSION, days: BTreeMap::new() });
+        }
+        let stats: UsageStats = serde_json::from_str(&content).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("統計檔 '{}' 格式錯誤: {}", path.display(), e))
+        })?;
+        if stats.stats_version != STATS_FILE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("不支援的統計檔版本 {}（目前為 {}）：{}", stats.stats_version, STATS_FILE_VERSION, path.display()),
+            ));
+        }
+        Ok(stats)
+    }
+}
+
+/// 將一次轉換累加至統計檔的當日統計，統計檔不存在時建立；
+/// 讀寫期間鎖定統計檔，多個同時執行的轉換寫入同一統計檔時不會遺失紀錄
+/// # 參數
+/// - path: 統計檔路徑
+/// - run: 本次轉換的統計
+/// # 回傳
+/// - 成功時返回 Ok(())，讀寫或格式錯誤時返回 IO 錯誤
+pub fn record_run(path: &Path, run: &DailyStats) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("無法開啟統計檔 {}：{}", path.display(), e)))?;
+    fs4::fs_std::FileExt::lock_exclusive(&file)?;
+    let result = update_locked(&mut file, path, run);
+    let _ = fs4::fs_std::FileExt::unlock(&file);
+    result
+}
+
+// 在已鎖定的統計檔上累加當日統計並移除超過保留天數的紀錄
+fn update_locked(file: &mut File, path: &Path, run: &DailyStats) -> io::Result<()> {
+    let mut stats = UsageStats::read_from(file, path)?;
+    let today = Local::now().date_naive();
+    stats.days.entry(today.format(DATE_FORMAT).to_string()).or_default().add(run);
+    let oldest = (today - Duration::days(RETENTION_DAYS)).format(DATE_FORMAT).to_string();
+    stats.days.retain(|date, _| *date >= oldest);
+
+    let content = serde_json::to_string_pretty(&stats).map_err(io::Error::other)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.set_len(0)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()
+}
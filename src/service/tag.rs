@@ -0,0 +1,72 @@
+use std::io;
+use regex::Regex;
+use crate::config::config::{parse_tag, parse_tag_rule};
+use crate::models::conversion::Tags;
+
+/// 單次轉換套用的標籤：所有產出物共用的標籤，以及依 ZIP 項目名稱套用的規則
+#[derive(Debug, Clone, Default)]
+pub struct TagSet {
+    global: Tags,
+    rules: Vec<(Regex, String, String)>,
+}
+
+impl TagSet {
+    /// # 參數
+    /// - tags: `--tag` 設定，格式為 `鍵=值`
+    /// - tag_rules: `--tag-rule` 設定，格式為 `模式:鍵=值`
+    /// # 回傳
+    /// - 成功時返回標籤集合，格式錯誤或模式無法編譯時返回 IO 錯誤
+    pub fn new(tags: &[String], tag_rules: &[String]) -> io::Result<Self> {
+        let mut global = Tags::new();
+        for spec in tags {
+            let (key, value) = parse_tag(spec)?;
+            global.insert(key, value);
+        }
+        let mut rules = Vec::with_capacity(tag_rules.len());
+        for spec in tag_rules {
+            let rule = parse_tag_rule(spec)?;
+            // 模式轉換方式與 include/exclude 相同
+            let regex = Regex::new(&rule.pattern.replace('.', "\\.").replace('*', ".*")).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("無效的標籤規則模式 '{}': {}", rule.pattern, e))
+            })?;
+            rules.push((regex, rule.key, rule.value));
+        }
+        Ok(TagSet { global, rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.rules.is_empty()
+    }
+
+    /// 取得單一檔案的標籤，規則依指定順序套用，符合的規則覆寫共用標籤與先前規則中同名的鍵
+    /// # 參數
+    /// - entry_name: 檔案在 ZIP 中的項目名稱
+    pub fn for_entry(&self, entry_name: &str) -> Tags {
+        let mut tags = self.global.clone();
+        for (regex, key, value) in &self.rules {
+            if regex.is_match(entry_name) {
+                tags.insert(key.clone(), value.clone());
+            }
+        }
+        tags
+    }
+
+    /// 取得包含多個檔案的產出物標籤：共用標籤加上所有檔案一致的規則標籤，
+    /// 避免部分檔案的分類標示到整個壓縮檔
+    /// # 參數
+    /// - entry_names: 產出物包含的所有 ZIP 項目名稱
+    pub fn for_entries<'a, I>(&self, entry_names: I) -> Tags
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut common: Option<Tags> = None;
+        for name in entry_names {
+            let tags = self.for_entry(name);
+            common = Some(match common {
+                None => tags,
+                Some(common) => common.into_iter().filter(|(key, value)| tags.get(key) == Some(value)).collect(),
+            });
+        }
+        common.unwrap_or_else(|| self.global.clone())
+    }
+}
@@ -0,0 +1,27 @@
+use std::io;
+
+// 頁面資料的文字編碼接口，負責將二進位資料嵌入 HTML 及還原
+pub trait PayloadEncoder: Send + Sync {
+    /// 編碼名稱，記錄於頁面中供解碼時辨識
+    fn name(&self) -> &'static str;
+
+    /// 顯示名稱，用於按鈕與說明文字
+    fn label(&self) -> &'static str;
+
+    /// 另存文字檔時的副檔名（不含點）
+    fn suffix(&self) -> &'static str;
+
+    /// 每個分塊的原始位元組數，分塊需能獨立解碼
+    fn chunk_bytes(&self) -> usize;
+
+    /// 編碼後長度不超過 encoded_len 的最大原始位元組數，用於依頁面大小上限決定分段大小
+    fn raw_capacity(&self, encoded_len: usize) -> usize;
+
+    /// 將資料編碼為可直接嵌入 script 標籤的文字
+    fn encode(&self, data: &[u8]) -> String;
+
+    /// 將頁面中的文字還原為原始資料，忽略空白字元
+    /// # 回傳
+    /// - 成功時返回原始資料，內容不符合編碼格式時返回 IO 錯誤
+    fn decode(&self, text: &str) -> io::Result<Vec<u8>>;
+}
@@ -0,0 +1,77 @@
+use std::io;
+use std::path::Path;
+use crate::models::html::HtmlTemplateData;
+
+// 輸出渲染器的輸入內容
+pub struct RenderContext<'a> {
+    /// 模板佔位符對應的內容
+    pub data: &'a HtmlTemplateData<'a>,
+    /// 內建模板渲染後的 HTML 頁面
+    pub html: &'a str,
+    /// 渲染 html 所用的內建模板，可搭配調整後的 data 以 `generate_html_content` 重新渲染
+    pub template: &'a str,
+    /// 嵌入頁面的原始資料（Base64 編碼前）
+    pub payload: &'a [u8],
+}
+
+// 使用說明提供者的查詢條件
+pub struct InstructionContext<'a> {
+    pub layer: &'a str,
+    pub has_password: bool,
+    pub encryption_method: &'a str,
+    pub view: &'a str,
+    pub lang: &'a str,
+}
+
+// 輸出渲染器接口，負責將轉換結果渲染為特定輸出格式
+pub trait OutputRenderer: Send + Sync {
+    /// 格式名稱，對應 `--format` 參數
+    fn name(&self) -> &str;
+
+    /// 輸出檔案的副檔名（不含點）
+    fn extension(&self) -> &str;
+
+    /// 渲染輸出內容
+    /// # 參數
+    /// - context: 渲染所需的頁面內容與原始資料
+    /// # 回傳
+    /// - 成功時返回輸出檔案內容，失敗時返回 IO 錯誤
+    fn render(&self, context: &RenderContext) -> io::Result<Vec<u8>>;
+}
+
+// 內容過濾器接口，在檔案放入壓縮檔前轉換其內容
+pub trait ContentFilter: Send + Sync {
+    /// 過濾器名稱，用於日誌
+    fn name(&self) -> &str;
+
+    /// 是否處理此檔案，預設處理所有檔案
+    fn applies_to(&self, _path: &Path) -> bool {
+        true
+    }
+
+    /// 轉換檔案內容
+    /// # 參數
+    /// - path: 來源檔案路徑
+    /// - data: 檔案內容（可能已被前一個過濾器轉換）
+    /// # 回傳
+    /// - 成功時返回轉換後的內容，失敗時返回 IO 錯誤
+    fn filter(&self, path: &Path, data: Vec<u8>) -> io::Result<Vec<u8>>;
+
+    /// 內容經轉換後是否另外保留原始內容，返回保留時附加於 ZIP 項目名稱的後綴，預設不保留
+    fn original_suffix(&self) -> Option<&str> {
+        None
+    }
+}
+
+// 使用說明提供者接口，可取代內建的使用說明文字
+pub trait InstructionProvider: Send + Sync {
+    /// 提供者名稱，用於日誌
+    fn name(&self) -> &str;
+
+    /// 生成使用說明 HTML 片段
+    /// # 參數
+    /// - context: 層數、密碼等查詢條件
+    /// # 回傳
+    /// - 返回 None 表示不處理，交由下一個提供者或內建說明
+    fn instructions(&self, context: &InstructionContext) -> Option<String>;
+}
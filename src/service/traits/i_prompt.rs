@@ -0,0 +1,42 @@
+use std::io;
+
+// 使用者提示接口，互動模式與手動密碼經由此接口詢問，可替換為非互動、腳本化或其他前端（TUI/GUI）的實作
+pub trait Prompter: Send + Sync {
+    /// 詢問是或否
+    /// # 參數
+    /// - prompt: 提示文字
+    /// - default: 預設答案
+    /// # 回傳
+    /// - 使用者的選擇，無法取得回答時返回 IO 錯誤
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool>;
+
+    /// 自清單中選擇一項
+    /// # 參數
+    /// - prompt: 提示文字
+    /// - items: 選項
+    /// - default: 預設選項的索引
+    /// # 回傳
+    /// - 選擇的索引，無法取得回答時返回 IO 錯誤
+    fn select(&self, prompt: &str, items: &[&str], default: usize) -> io::Result<usize>;
+
+    /// 輸入一行文字
+    /// # 參數
+    /// - prompt: 提示文字
+    /// - default: 預設值，None 表示必須輸入
+    /// - validate: 驗證輸入內容的函數，失敗時返回顯示給使用者的訊息
+    /// # 回傳
+    /// - 通過驗證的文字，無法取得回答時返回 IO 錯誤
+    fn input(&self, prompt: &str, default: Option<&str>, validate: &dyn Fn(&str) -> Result<(), String>) -> io::Result<String>;
+
+    /// 輸入不顯示於畫面的密碼
+    /// # 參數
+    /// - prompt: 提示文字
+    /// # 回傳
+    /// - 輸入的密碼，無法取得回答時返回 IO 錯誤
+    fn password(&self, prompt: &str) -> io::Result<String>;
+
+    /// 顯示提示訊息（例如密碼強度與建議），不需要使用者回答
+    /// # 參數
+    /// - text: 訊息文字
+    fn message(&self, text: &str);
+}
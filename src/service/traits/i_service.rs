@@ -1,7 +1,8 @@
 use std::io;
 use crate::models::file::{FileCollectInput, FileCollectOutput};
-use crate::models::zip::{ZipCompressInput, ZipCompressOutput};
+use crate::models::zip::{ArchivePassthroughInput, ZipCompressInput, ZipCompressOutput};
 use crate::models::html::{HtmlGenerateInput, HtmlGenerateOutput};
+use crate::models::report::ConversionReport;
 
 // File 服務接口，負責檔案收集
 pub trait FileServiceTrait: Send + Sync {
@@ -21,6 +22,13 @@ pub trait ZipServiceTrait: Send + Sync {
     /// # 回傳
     /// - 成功時返回壓縮後的 ZIP 數據和總大小，失敗時返回 IO 錯誤
     fn compress_files(&self, input: ZipCompressInput) -> io::Result<ZipCompressOutput>;
+
+    /// 讀取既有的封存檔，不重新壓縮而原樣作為頁面資料
+    /// # 參數
+    /// - input: 封存檔路徑、偵測到的格式與變更偵測設定
+    /// # 回傳
+    /// - 成功時返回封存檔數據，內容與偵測到的格式不符或讀取失敗時返回 IO 錯誤
+    fn passthrough_archive(&self, input: ArchivePassthroughInput) -> io::Result<ZipCompressOutput>;
 }
 
 // HTML 服務接口，負責生成 HTML 檔案
@@ -31,4 +39,41 @@ pub trait HtmlServiceTrait: Send + Sync {
     /// # 回傳
     /// - 成功時返回生成的 HTML 檔案路徑，失敗時返回 IO 錯誤
     fn generate_html(&self, input: HtmlGenerateInput) -> io::Result<HtmlGenerateOutput>;
+
+    /// 根據轉換報告在輸出目錄生成 index.html，列出所有產出的頁面
+    /// # 參數
+    /// - report: 轉換過程中蒐集的報告
+    /// # 回傳
+    /// - 成功時返回生成的索引頁路徑，失敗時返回 IO 錯誤
+    fn generate_index(&self, report: &ConversionReport) -> io::Result<HtmlGenerateOutput>;
+
+    /// 根據轉換報告生成還原頁，記錄所有頁面的名稱、順序與 SHA-256，引導收件者驗證並合併分段
+    /// # 參數
+    /// - report: 轉換過程中蒐集的報告
+    /// # 回傳
+    /// - 成功時返回生成的還原頁路徑，失敗時返回 IO 錯誤
+    fn generate_restore(&self, report: &ConversionReport) -> io::Result<HtmlGenerateOutput>;
+
+    /// 在輸出目錄寫出離線安裝共用的 Service Worker 與主畫面圖示，各頁面的 Manifest 於生成頁面時寫出
+    /// # 參數
+    /// - output_dir: 輸出目錄
+    /// # 回傳
+    /// - 成功時返回寫出的檔案路徑，失敗時返回 IO 錯誤
+    fn generate_pwa_assets(&self, output_dir: &str) -> io::Result<Vec<String>>;
+
+    /// 移除已寫出的產出物檔案，產出物寫出後的處理失敗時呼叫；無法撤回的輸出目的地不做任何事
+    /// # 參數
+    /// - locations: 產出物檔案的位置
+    /// # 回傳
+    /// - 全部移除成功時返回 Ok，任一檔案無法移除時返回最後一個 IO 錯誤
+    fn discard_outputs(&self, locations: &[String]) -> io::Result<()>;
+
+    /// 在輸出目錄生成獨立的解碼工具頁，收件者可貼上或拖放本工具產生的編碼資料還原為 ZIP 檔案
+    /// # 參數
+    /// - output_dir: 輸出目錄
+    /// - file_name: 解碼工具頁的檔名
+    /// - lang: 頁面語言
+    /// # 回傳
+    /// - 成功時返回生成的解碼工具頁路徑，失敗時返回 IO 錯誤
+    fn generate_decoder(&self, output_dir: &str, file_name: &str, lang: &str) -> io::Result<HtmlGenerateOutput>;
 }
\ No newline at end of file
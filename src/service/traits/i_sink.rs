@@ -0,0 +1,78 @@
+use std::io;
+use std::path::Path;
+
+// 輸出目的地接口，負責寫出 HTML、密碼檔等產出物
+pub trait OutputSink: Send + Sync {
+    /// 寫出單一產出物
+    /// # 參數
+    /// - output_dir: 目標輸出目錄（非檔案型目的地可忽略）
+    /// - name: 產出物檔名
+    /// - content: 產出物內容
+    /// # 回傳
+    /// - 成功時返回產出物位置（檔案路徑或識別名稱），失敗時返回 IO 錯誤
+    fn write(&self, output_dir: &str, name: &str, content: &[u8]) -> io::Result<String>;
+
+    /// 一次寫出同一產出物的多個檔案，可撤回的目的地需在全部寫出後才讓檔案生效，任一檔案失敗時不留下其他檔案；
+    /// 預設依序寫出且不處理存取權限，內容暫存於本機檔案時先讀入記憶體，適用於無法撤回的目的地
+    /// # 參數
+    /// - output_dir: 目標輸出目錄
+    /// - files: 產出物檔名、內容與存取權限
+    /// # 回傳
+    /// - 成功時依序返回各檔案的位置，失敗時返回 IO 錯誤
+    fn write_batch(&self, output_dir: &str, files: &[OutputFile]) -> io::Result<Vec<String>> {
+        files
+            .iter()
+            .map(|file| match file.source {
+                Some(source) => self.write(output_dir, file.name, &std::fs::read(source)?),
+                None => self.write(output_dir, file.name, file.content),
+            })
+            .collect()
+    }
+
+    /// 移除已寫出的產出物，產出物後續處理失敗時呼叫；預設不做任何事，適用於無法撤回的目的地
+    /// # 參數
+    /// - location: write 返回的產出物位置
+    fn remove(&self, location: &str) -> io::Result<()> {
+        let _ = location;
+        Ok(())
+    }
+}
+
+/// 產出物檔案的存取權限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileAccess {
+    /// 依系統預設權限建立
+    Default,
+    /// 含密碼的機密檔案：Unix 以指定的權限位元建立；Windows 在未授予群組與其他人權限時只保留目前使用者的存取
+    Private(u32),
+}
+
+// 批次寫出的單一檔案
+pub struct OutputFile<'a> {
+    pub name: &'a str,
+    pub content: &'a [u8],
+    // 內容暫存於本機檔案（`--spill-to-disk`）時的路徑，此時 content 為空
+    pub source: Option<&'a Path>,
+    pub access: FileAccess,
+}
+
+/// 輸出檔案已存在時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionAction {
+    /// 覆寫既有檔案
+    Overwrite,
+    /// 改寫入未使用的檔名
+    Rename,
+    /// 保留既有檔案，不寫出此產出物
+    Skip,
+}
+
+// 檔名衝突的決策接口，由 CollisionSink 在目標檔案已存在時詢問
+pub trait CollisionResolver: Send + Sync {
+    /// 決定已存在檔案的處理方式
+    /// # 參數
+    /// - path: 已存在的目標檔案
+    /// # 回傳
+    /// - 成功時返回處理方式，無法取得決定時返回 IO 錯誤並中止轉換
+    fn resolve(&self, path: &Path) -> io::Result<CollisionAction>;
+}
@@ -0,0 +1,95 @@
+use std::io;
+use std::path::Path;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, BIG5, EUC_JP, EUC_KR, GB18030, GBK, SHIFT_JIS};
+use log::{info, warn};
+use crate::service::traits::i_plugin::ContentFilter;
+
+/// `--transcode-text` 可指定的目標編碼
+pub const TRANSCODE_TARGETS: [&str; 1] = ["utf8"];
+
+/// 保留原始內容時附加於 ZIP 項目名稱的後綴
+pub const ORIGINAL_SUFFIX: &str = ".orig";
+
+// 視為文字檔並嘗試轉碼的副檔名
+const TEXT_EXTENSIONS: [&str; 22] = [
+    "txt", "text", "md", "csv", "tsv", "log", "ini", "cfg", "conf", "json", "xml", "html", "htm",
+    "srt", "ass", "ssa", "lrc", "nfo", "yaml", "yml", "sql", "properties",
+];
+
+// 偵測編碼時最多檢查的位元組數，也用於判斷是否為二進位內容
+const SNIFF_LEN: usize = 64 * 1024;
+// 控制字元超過取樣內容的此比例時視為二進位內容
+const BINARY_CONTROL_RATIO: usize = 10;
+
+/// 將文字檔由 GBK、Big5、Shift-JIS 等舊式編碼轉為 UTF-8 的內容過濾器
+pub struct TextTranscoder {
+    keep_original: bool,
+}
+
+impl TextTranscoder {
+    /// # 參數
+    /// - keep_original: 轉碼後是否另外保留原始內容（項目名稱加上 `.orig`）
+    pub fn new(keep_original: bool) -> Self {
+        TextTranscoder { keep_original }
+    }
+}
+
+impl ContentFilter for TextTranscoder {
+    fn name(&self) -> &str {
+        "transcode-text"
+    }
+
+    fn applies_to(&self, path: &Path) -> bool {
+        path.extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext.as_str()))
+    }
+
+    fn filter(&self, path: &Path, data: Vec<u8>) -> io::Result<Vec<u8>> {
+        let Some(encoding) = detect_legacy_encoding(&data) else {
+            return Ok(data);
+        };
+        let (text, had_errors) = encoding.decode_without_bom_handling(&data);
+        if had_errors {
+            warn!("{} 疑似 {} 編碼但含有無法解碼的位元組，保留原始內容", path.display(), encoding.name());
+            return Ok(data);
+        }
+        info!("{} 由 {} 轉為 UTF-8", path.display(), encoding.name());
+        Ok(text.into_owned().into_bytes())
+    }
+
+    fn original_suffix(&self) -> Option<&str> {
+        self.keep_original.then_some(ORIGINAL_SUFFIX)
+    }
+}
+
+// 判斷內容是否為需要轉碼的舊式編碼；已是 UTF-8、純 ASCII 或疑似二進位內容時返回 None
+fn detect_legacy_encoding(data: &[u8]) -> Option<&'static Encoding> {
+    let sample = &data[..data.len().min(SNIFF_LEN)];
+    if looks_binary(data) || std::str::from_utf8(data).is_ok() {
+        return None;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(sample, sample.len() == data.len());
+    let encoding = detector.guess(None, false);
+    // 只轉換常見的東亞舊式編碼，其他偵測結果維持原樣以免誤判
+    [GBK, GB18030, BIG5, SHIFT_JIS, EUC_JP, EUC_KR].contains(&encoding).then_some(encoding)
+}
+
+/// 以開頭內容判斷是否為二進位資料：含 NUL 位元組，或換行、定位等以外的控制字元過多
+/// # 參數
+/// - data: 檔案內容
+/// # 回傳
+/// - 疑似二進位資料時返回 true，文字過濾器應維持原樣
+pub fn looks_binary(data: &[u8]) -> bool {
+    let sample = &data[..data.len().min(SNIFF_LEN)];
+    if sample.contains(&0) {
+        return true;
+    }
+    let controls = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    controls * BINARY_CONTROL_RATIO > sample.len()
+}
@@ -0,0 +1,333 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+use flate2::read::GzDecoder;
+use sevenz_rust::{Password, SevenZReader};
+use sha2::{Digest, Sha256};
+use crate::models::conversion::GeneratedArtifact;
+use crate::models::zip::{ArchiveManifest, ByteRange};
+use crate::service::crypto::{decrypt_chunked_from_browser, decrypt_from_browser};
+use crate::service::html::sha256_hex;
+use crate::service::plugin::apply_filters;
+use crate::service::schema::{finish_payload, read_document, read_payload, PageInfo};
+use crate::service::traits::i_plugin::ContentFilter;
+use crate::service::zip::{seven_z_error, MANIFEST_NAME};
+
+/// 產出物應包含的來源檔案
+pub struct VerifySource<'a> {
+    pub path: &'a Path,
+    // 檔案在 ZIP 中的項目名稱
+    pub entry_name: String,
+    // 只寫入部分內容時的位元組範圍
+    pub range: Option<ByteRange>,
+    // 以符號連結項目保存，比對的內容為連結目標
+    pub symlink: bool,
+}
+
+/// 驗證已寫出的產出物：自頁面（或外部資料檔）取出並解碼資料、以密碼解密後開啟 ZIP 或 tar，
+/// 逐一比對項目內容與來源檔案的 SHA-256；來源經內容過濾器轉換時，以相同的過濾器重新計算預期內容
+/// # 參數
+/// - artifact: 產出物，頁面需已寫入檔案系統
+/// - sources: 產出物應包含的來源檔案
+/// - passwords: 產生時使用的頁面密碼，以及雙層壓縮時內層 ZIP 的密碼
+/// - filters: 產生時套用的內容過濾器
+/// - layer: 壓縮層數，`double` 時先自外層 ZIP 取出內層 ZIP 再比對
+/// - archive_format: 封存格式，`none` 表示資料即為來源檔案本身
+/// # 回傳
+/// - 成功時返回比對的項目數，資料無法讀取、解密失敗或內容不符時返回 IO 錯誤；
+///   寫入後才被刪除的來源檔案無法比對，記錄警告後略過
+pub fn verify_artifact(
+    artifact: &GeneratedArtifact,
+    sources: &[VerifySource],
+    (password, inner_password): (Option<&str>, Option<&str>),
+    filters: &[Arc<dyn ContentFilter>],
+    layer: &str,
+    archive_format: &str,
+) -> io::Result<usize> {
+    let parts: Vec<&Path> = artifact.parts.iter().map(Path::new).collect();
+    let (_, payload) =
+        read_output_payload(Path::new(&artifact.html), &parts, artifact.payload_file.as_deref().map(Path::new), password)?;
+    let (zip_buffer, password) = if layer == "double" {
+        (unwrap_outer_layer(&payload, password)?, inner_password)
+    } else {
+        (payload, password)
+    };
+    let (expected, deleted) = expected_hashes(sources, filters)?;
+    // 不封存時資料即為唯一的來源檔案，以其項目名稱比對
+    let mut actual = match (archive_format, sources) {
+        ("none", [source]) => BTreeMap::from([(source.entry_name.clone(), sha256_hex(&zip_buffer))]),
+        _ => archive_entry_hashes(&zip_buffer, archive_format, password)?,
+    };
+    if archive_format == "zip" {
+        check_manifest(&zip_buffer, password, &mut actual)?;
+    }
+
+    let mut problems = Vec::new();
+    for (name, hash) in &expected {
+        match actual.get(name) {
+            None => problems.push(format!("缺少 {}", name)),
+            Some(actual_hash) if actual_hash != hash => problems.push(format!("{} 內容不符", name)),
+            Some(_) => {}
+        }
+    }
+    problems.extend(
+        actual
+            .keys()
+            .filter(|name| !expected.contains_key(*name) && !deleted.iter().any(|deleted| name.starts_with(deleted.as_str())))
+            .map(|name| format!("多出 {}", name)),
+    );
+    if !problems.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("產出驗證失敗：{}：{}", artifact.html, problems.join("；"))
+        ));
+    }
+    Ok(expected.len())
+}
+
+/// 自雙層壓縮的外層 ZIP 取出內層 ZIP
+/// # 參數
+/// - buffer: 外層 ZIP 數據
+/// - password: 外層 ZIP 加密時使用的密碼
+/// # 回傳
+/// - 成功時返回內層 ZIP 數據，外層不是只含一個項目的 ZIP 或解密失敗時返回 IO 錯誤
+pub fn unwrap_outer_layer(buffer: &[u8], password: Option<&str>) -> io::Result<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(buffer))?;
+    if archive.len() != 1 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("外層 ZIP 應只包含內層 ZIP，卻有 {} 個項目", archive.len())
+        ));
+    }
+    let encrypted = archive.by_index_raw(0)?.encrypted();
+    let mut entry = match password {
+        Some(pwd) if encrypted => archive.by_index_decrypt(0, pwd.as_bytes())?,
+        _ => archive.by_index(0)?,
+    };
+    let mut inner = Vec::new();
+    entry.read_to_end(&mut inner)?;
+    Ok(inner)
+}
+
+/// 計算封存檔中每個項目內容的 SHA-256
+/// # 參數
+/// - buffer: 封存檔數據
+/// - archive_format: 封存格式
+/// - password: 封存檔本身加密時使用的密碼
+/// # 回傳
+/// - 成功時返回項目名稱與 SHA-256，封存檔無法開啟或解密失敗時返回 IO 錯誤
+pub fn archive_entry_hashes(buffer: &[u8], archive_format: &str, password: Option<&str>) -> io::Result<BTreeMap<String, String>> {
+    match archive_format {
+        "tar" => tar_entry_hashes(tar::Archive::new(buffer)),
+        "tar.gz" => tar_entry_hashes(tar::Archive::new(GzDecoder::new(buffer))),
+        "7z" => seven_z_entry_hashes(buffer, password),
+        _ => zip_entry_hashes(buffer, password),
+    }
+}
+
+/// 比對 ZIP 中的 manifest.json（`--manifest`）與實際項目，比對後自項目清單中移除 manifest.json
+/// # 參數
+/// - zip_buffer: ZIP 數據
+/// - password: ZIP 本身加密時使用的密碼
+/// - actual: `archive_entry_hashes` 計算的項目名稱與 SHA-256
+/// # 回傳
+/// - 沒有清單時返回 None，清單與項目相符時返回清單記錄的項目數；清單無法解析、缺少項目或內容不符時返回 IO 錯誤
+pub fn check_manifest(zip_buffer: &[u8], password: Option<&str>, actual: &mut BTreeMap<String, String>) -> io::Result<Option<usize>> {
+    if actual.remove(MANIFEST_NAME).is_none() {
+        return Ok(None);
+    }
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(zip_buffer))?;
+    let index = archive
+        .index_for_name(MANIFEST_NAME)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("找不到 {}", MANIFEST_NAME)))?;
+    let encrypted = archive.by_index_raw(index)?.encrypted();
+    let mut entry = match password {
+        Some(pwd) if encrypted => archive.by_index_decrypt(index, pwd.as_bytes())?,
+        _ => archive.by_index(index)?,
+    };
+    let mut content = Vec::new();
+    entry.read_to_end(&mut content)?;
+    let manifest: ArchiveManifest = serde_json::from_slice(&content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{} 無法解析：{}", MANIFEST_NAME, e)))?;
+
+    let mut problems = Vec::new();
+    for entry in &manifest.entries {
+        match actual.get(&entry.path) {
+            None => problems.push(format!("缺少清單中的 {}", entry.path)),
+            Some(hash) if *hash != entry.sha256 => problems.push(format!("{} 與清單記錄的 SHA-256 不符", entry.path)),
+            Some(_) => {}
+        }
+    }
+    problems.extend(
+        actual
+            .keys()
+            .filter(|name| !manifest.entries.iter().any(|entry| entry.path == **name))
+            .map(|name| format!("{} 未列於清單", name)),
+    );
+    if !problems.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} 與封存檔不符：{}", MANIFEST_NAME, problems.join("；"))
+        ));
+    }
+    Ok(Some(manifest.entries.len()))
+}
+
+// 計算 ZIP 中每個項目內容的 SHA-256，ZIP 本身加密時以密碼解密
+fn zip_entry_hashes(zip_buffer: &[u8], password: Option<&str>) -> io::Result<BTreeMap<String, String>> {
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(zip_buffer))?;
+    let mut actual = BTreeMap::new();
+    for i in 0..archive.len() {
+        let encrypted = archive.by_index_raw(i)?.encrypted();
+        let mut entry = match password {
+            Some(pwd) if encrypted => archive.by_index_decrypt(i, pwd.as_bytes())?,
+            _ => archive.by_index(i)?,
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        actual.insert(name, hash_reader(&mut entry)?);
+    }
+    Ok(actual)
+}
+
+// 計算 tar 中每個一般檔案內容的 SHA-256，符號連結以連結目標計算，與 ZIP 和 7z 將目標存為內容一致
+fn tar_entry_hashes<R: Read>(mut archive: tar::Archive<R>) -> io::Result<BTreeMap<String, String>> {
+    let mut actual = BTreeMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_symlink() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().replace('\\', "/");
+        let hash = match entry.link_name()? {
+            Some(target) if entry_type.is_symlink() => sha256_hex(target.to_string_lossy().as_bytes()),
+            _ => hash_reader(&mut entry)?,
+        };
+        actual.insert(name, hash);
+    }
+    Ok(actual)
+}
+
+// 計算 7z 中每個檔案內容的 SHA-256，7z 本身加密時以密碼解密
+fn seven_z_entry_hashes(buffer: &[u8], password: Option<&str>) -> io::Result<BTreeMap<String, String>> {
+    let len = buffer.len() as u64;
+    let password = password.map_or_else(Password::empty, Password::from);
+    let mut archive = SevenZReader::new(io::Cursor::new(buffer), len, password).map_err(seven_z_error)?;
+    let mut actual = BTreeMap::new();
+    archive
+        .for_each_entries(|entry, reader| {
+            if !entry.is_directory() {
+                actual.insert(entry.name().to_string(), hash_reader(reader)?);
+            }
+            Ok(true)
+        })
+        .map_err(seven_z_error)?;
+    Ok(actual)
+}
+
+/// 依分段或外部資料檔的方式讀回頁面的完整資料，瀏覽器端加密時以密碼解密
+/// # 參數
+/// - html: 頁面（分段時為索引頁，僅用於錯誤訊息）
+/// - parts: 依段次排列的分段頁面，未分段時為空
+/// - payload_file: 外部資料檔模式下的資料檔
+/// - password: 產生時使用的密碼
+/// # 回傳
+/// - 成功時返回頁面資訊與封存檔數據，資料無法讀取、SHA-256 不符或解密失敗時返回 IO 錯誤
+pub fn read_output_payload(
+    html: &Path,
+    parts: &[&Path],
+    payload_file: Option<&Path>,
+    password: Option<&str>,
+) -> io::Result<(PageInfo, Vec<u8>)> {
+    let (info, data) = if let Some(payload_file) = payload_file {
+        let info = read_document(&fs::read_to_string(html)?)?;
+        let data = finish_payload(&info, fs::read(payload_file)?)?;
+        (info, data)
+    } else if parts.is_empty() {
+        let page = fs::read_to_string(html)?;
+        let info = read_document(&page)?;
+        let data = read_payload(&page, &info)?;
+        (info, data)
+    } else {
+        let mut joined = Vec::new();
+        let mut last_info: Option<PageInfo> = None;
+        for part in parts {
+            let page = fs::read_to_string(part)?;
+            let info = read_document(&page)?;
+            joined.extend(read_payload(&page, &info)?);
+            last_info = Some(info);
+        }
+        let info = last_info.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "分段產出物沒有任何分段頁面"))?;
+        let data = finish_payload(&info, joined)?;
+        (info, data)
+    };
+    if !info.encrypted {
+        return Ok((info, data));
+    }
+    let password = password.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("頁面已加密但沒有可用的密碼：{}", html.display()))
+    })?;
+    let data = if info.stream_chunk_size > 0 {
+        decrypt_chunked_from_browser(&data, password)?
+    } else {
+        decrypt_from_browser(&data, password)?
+    };
+    Ok((info, data))
+}
+
+// 計算每個 ZIP 項目預期內容的 SHA-256，過濾器要求保留原始內容且內容確實改變時，另外加入原始內容的項目；
+// 一併返回已被刪除而無法比對的項目名稱
+fn expected_hashes(
+    sources: &[VerifySource],
+    filters: &[Arc<dyn ContentFilter>],
+) -> io::Result<(BTreeMap<String, String>, Vec<String>)> {
+    let mut expected = BTreeMap::new();
+    let mut deleted = Vec::new();
+    for source in sources {
+        if source.symlink {
+            let target = fs::read_link(source.path)?;
+            expected.insert(source.entry_name.clone(), sha256_hex(target.to_string_lossy().as_bytes()));
+            continue;
+        }
+        let mut file = match fs::File::open(source.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                log::warn!("來源檔案在寫入後已被刪除，無法驗證：{}", source.path.display());
+                deleted.push(source.entry_name.clone());
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(range) = source.range {
+            file.seek(SeekFrom::Start(range.offset))?;
+        }
+        let mut reader = file.take(source.range.map_or(u64::MAX, |range| range.len));
+        let applicable: Vec<&Arc<dyn ContentFilter>> = filters.iter().filter(|filter| filter.applies_to(source.path)).collect();
+        if applicable.is_empty() {
+            expected.insert(source.entry_name.clone(), hash_reader(&mut reader)?);
+            continue;
+        }
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let original_suffix = applicable.iter().find_map(|filter| filter.original_suffix().map(str::to_string));
+        let original = original_suffix.is_some().then(|| data.clone());
+        let filtered = apply_filters(filters, source.path, data)?;
+        if let (Some(suffix), Some(original)) = (original_suffix, original.filter(|original| *original != filtered)) {
+            expected.insert(format!("{}{}", source.entry_name, suffix), sha256_hex(&original));
+        }
+        expected.insert(source.entry_name.clone(), sha256_hex(&filtered));
+    }
+    Ok((expected, deleted))
+}
+
+/// 以串流方式計算內容的 SHA-256，返回十六進位字串
+pub fn hash_reader<R: Read + ?Sized>(reader: &mut R) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(reader, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
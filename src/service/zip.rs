@@ -1,15 +1,31 @@
 use log::info;
 use pathdiff::diff_paths;
-use std::io::{self, Write};
+use std::collections::HashMap;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::fs::File;
-use tokio::io::AsyncReadExt;
-use zip::write::{SimpleFileOptions, ZipWriter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use chrono::{Datelike, Local, SecondsFormat, Timelike, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use zip::write::{FileOptions, SimpleFileOptions, ZipWriter};
+use sevenz_rust::lzma::LZMA2Options;
+use sevenz_rust::{AesEncoderOptions, Password, SevenZArchiveEntry, SevenZWriter};
 use zip::AesMode;
 use zip::CompressionMethod;
-use crate::models::zip::{ZipCompressInput, ZipCompressOutput};
+use crate::models::conversion::Tags;
+use crate::models::zip::{
+    ArchiveManifest, ArchivePassthroughInput, ByteRange, CompressionSpec, EntryMetadata, ManifestEntry, ZipCompressInput, ZipCompressOutput, ZipEntryInfo,
+};
+use crate::service::change::{check_unchanged, read_stable, FileSnapshot};
+use crate::service::gzip::compress_payload;
+use crate::service::plugin::apply_filters;
+use crate::service::spill::{SpillBuffer, SpillFile};
+use crate::service::traits::i_plugin::ContentFilter;
 use crate::service::traits::i_service::ZipServiceTrait;
+use crate::utils::log_sampler::FILE_LOG_TARGET;
 
 pub struct ZipService;
 
@@ -19,21 +35,74 @@ impl ZipService {
     }
 }
 
+impl Default for ZipService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ZipServiceTrait for ZipService {
     fn compress_files(&self, input: ZipCompressInput) -> io::Result<ZipCompressOutput> {
         // 原有的壓縮邏輯，從 create_inner_zip 改編
-        let zip_buffer = crate::service::zip::create_inner_zip(
-            &input.input_path,
-            &input.files,
-            input.options,
+        let mut compressor = ZipCompressor::new(
+            input.compression,
             input.password.as_deref(),
             input.aes_mode,
             input.no_progress,
-        )?;
-        let total_size = zip_buffer.len();
+            input.filters,
+        )
+        .with_ranges(input.ranges.clone())
+        .with_archive_format(&input.archive_format)
+        .with_change_detection(input.snapshots.clone(), &input.on_change)
+        .with_missing_policy(&input.on_missing)
+        .preserve_metadata(input.preserve_metadata)
+        .with_symlink_policy(&input.symlinks)
+        .spill_to_disk(input.spill_to_disk)
+        .with_jobs(input.jobs)
+        .with_manifest(input.manifest)
+        .with_archive_root(input.archive_root.clone())
+        .with_comment(input.comment.clone());
+        let (zip_buffer, spill, mut entries) = compressor.compress_archive(&input.files, &input.input_path)?;
+        let total_size = spill.as_ref().map_or(zip_buffer.len(), |spill| spill.len() as usize);
+        // 清單中標示只包含部分內容的項目
+        for (file_path, range) in &input.ranges {
+            let name = rooted_entry_name(file_path, &input.input_path, input.archive_root.as_deref());
+            if let Some(entry) = entries.iter_mut().find(|entry| Some(&entry.path) == name.as_ref()) {
+                entry.range = Some(*range);
+            }
+        }
         Ok(ZipCompressOutput {
             zip_buffer,
+            spill,
             total_size,
+            entries,
+            missing_files: compressor.missing_files().to_vec(),
+        })
+    }
+
+    fn passthrough_archive(&self, input: ArchivePassthroughInput) -> io::Result<ZipCompressOutput> {
+        let read = || std::fs::read(&input.path);
+        let data = match &input.snapshot {
+            Some(snapshot) if input.on_change == "reread" => read_stable(&input.path, snapshot, read)?,
+            _ => read()?,
+        };
+        if let Some(snapshot) = input.snapshot.as_ref().filter(|_| input.on_change != "reread") {
+            check_unchanged(&input.path, snapshot, &input.on_change)?;
+        }
+        // 蒐集後檔案可能已被替換，以實際讀取的內容再確認一次
+        if archive_signature(&data) != Some(input.archive_format.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} 的內容不是 {} 封存檔", input.path.display(), input.archive_format),
+            ));
+        }
+        info!(target: FILE_LOG_TARGET, "直接嵌入既有的 {} 封存檔，大小：{} 位元組", input.archive_format, data.len());
+        Ok(ZipCompressOutput {
+            total_size: data.len(),
+            zip_buffer: data,
+            spill: None,
+            entries: Vec::new(),
+            missing_files: Vec::new(),
         })
     }
 }
@@ -43,113 +112,1083 @@ pub trait Compressor {
     fn compress_files(&mut self, files: &[PathBuf], input_path: &Path) -> io::Result<Vec<u8>>;
 }
 
+/// 可指定的封存格式，`none` 表示不封存，頁面資料即為來源檔案本身
+pub const ARCHIVE_FORMATS: [&str; 5] = ["zip", "tar", "tar.gz", "7z", "none"];
+
+/// 封存格式對應的副檔名
+pub fn archive_extension(archive_format: &str) -> &'static str {
+    match archive_format {
+        "tar" => "tar",
+        "tar.gz" => "tar.gz",
+        "7z" => "7z",
+        _ => "zip",
+    }
+}
+
+// 可直接嵌入的既有封存檔：副檔名（小寫）、格式與檔頭
+const PASSTHROUGH_ARCHIVES: &[(&str, &str, &[u8])] = &[
+    (".zip", "zip", b"PK\x03\x04"),
+    (".zip", "zip", b"PK\x05\x06"),
+    (".7z", "7z", b"7z\xBC\xAF\x27\x1C"),
+    (".tar.gz", "tar.gz", b"\x1F\x8B"),
+    (".tgz", "tar.gz", b"\x1F\x8B"),
+];
+
+/// 依副檔名與檔頭判斷輸入檔案是否為可直接嵌入的既有封存檔（.zip、.7z、.tar.gz 或 .tgz）
+/// # 參數
+/// - path: 輸入檔案
+/// # 回傳
+/// - 副檔名與檔頭皆符合時返回封存格式，否則返回 None；無法讀取檔案時返回 IO 錯誤
+pub fn detect_archive_format(path: &Path) -> io::Result<Option<&'static str>> {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if !PASSTHROUGH_ARCHIVES.iter().any(|(extension, _, _)| name.ends_with(extension)) {
+        return Ok(None);
+    }
+    let mut header = Vec::with_capacity(8);
+    std::fs::File::open(path)?.take(8).read_to_end(&mut header)?;
+    Ok(PASSTHROUGH_ARCHIVES
+        .iter()
+        .find(|(extension, _, magic)| name.ends_with(extension) && header.starts_with(magic))
+        .map(|(_, format, _)| *format))
+}
+
+// 依檔頭判斷封存格式，不檢查副檔名
+fn archive_signature(data: &[u8]) -> Option<&'static str> {
+    PASSTHROUGH_ARCHIVES.iter().find(|(_, _, magic)| data.starts_with(magic)).map(|(_, format, _)| *format)
+}
+
+/// 未保留中繼資料時項目的 Unix 權限
+pub const DEFAULT_ENTRY_MODE: u32 = 0o644;
+// 未保留中繼資料時項目的修改時間：1980-01-01T00:00:00Z，ZIP 可表示的最早時間
+const FIXED_ENTRY_TIME: u64 = 315_532_800;
+// 7z 屬性中表示高 16 位元為 Unix 權限的旗標
+const UNIX_EXTENSION_ATTRIBUTE: u32 = 0x8000;
+// Unix 一般檔案的類型位元
+const REGULAR_FILE_MODE: u32 = 0o100000;
+// Unix 符號連結的類型位元與權限
+const SYMLINK_FILE_MODE: u32 = 0o120000;
+const SYMLINK_MODE: u32 = 0o777;
+/// `--manifest` 寫在 ZIP 第一個項目的清單名稱
+pub const MANIFEST_NAME: &str = "manifest.json";
+/// 清單的格式版本，結構不相容時遞增
+pub const MANIFEST_VERSION: u32 = 1;
+/// `--comment` 的長度上限（位元組），保留 ZIP 註解上限 65535 位元組中工具資訊的空間
+pub const MAX_COMMENT_LEN: usize = 65_000;
+
+/// 封存檔寫入器，依序加入項目後產生完整的封存檔數據與項目清單
+pub trait ArchiveWriter {
+    /// 加入一個項目
+    /// # 參數
+    /// - name: 項目名稱，以 `/` 分隔
+    /// - size: reader 將提供的位元組數
+    /// - metadata: 項目的修改時間與 Unix 權限
+    /// - reader: 項目內容
+    /// # 回傳
+    /// - 成功時返回寫入的位元組數，失敗時返回 IO 錯誤
+    fn add_entry(&mut self, name: &str, size: u64, metadata: &EntryMetadata, reader: &mut dyn Read) -> io::Result<u64>;
+
+    /// 加入符號連結項目，權限固定為 `777`
+    /// # 參數
+    /// - name: 項目名稱，以 `/` 分隔
+    /// - target: 連結目標，原樣保存
+    /// - metadata: 項目的修改時間
+    /// # 回傳
+    /// - 成功時返回連結目標的位元組數，失敗時返回 IO 錯誤
+    fn add_symlink(&mut self, name: &str, target: &str, metadata: &EntryMetadata) -> io::Result<u64>;
+
+    /// 完成封存檔
+    /// # 回傳
+    /// - 成功時返回封存檔數據、暫存檔與各項目的位移與大小清單；寫入暫存檔時數據為空，失敗時返回 IO 錯誤
+    fn finish(self: Box<Self>) -> io::Result<(Vec<u8>, Option<SpillFile>, Vec<ZipEntryInfo>)>;
+
+    /// 設定封存檔層級的註解，tar 與 7z 沒有對應的欄位而忽略
+    /// # 參數
+    /// - comment: 註解內容
+    fn set_comment(&mut self, _comment: &str) {}
+}
+
+/// 依封存格式建立寫入器，tar 與 tar.gz 不支援 ZIP 的項目壓縮方法與加密，7z 一律以 LZMA2 壓縮並以 AES-256 加密；
+/// 壓縮等級用於 tar.gz 的 gzip 與 7z 的 LZMA2
+/// # 參數
+/// - archive_format: 封存格式
+/// - options: ZIP 項目選項，已套用密碼
+/// - level: 壓縮等級（0–9）
+/// - password: 7z 的加密密碼
+/// - spill: 是否寫入暫存檔而非記憶體
+/// # 回傳
+/// - 對應格式的寫入器，失敗時返回 IO 錯誤
+pub fn archive_writer<'k>(
+    archive_format: &str,
+    options: FileOptions<'k, ()>,
+    level: u8,
+    password: Option<&str>,
+    spill: bool,
+) -> io::Result<Box<dyn ArchiveWriter + 'k>> {
+    Ok(match archive_format {
+        "tar" => Box::new(TarArchiveWriter::new(None, spill)?),
+        "tar.gz" => Box::new(TarArchiveWriter::new(Some(level), spill)?),
+        "7z" => Box::new(SevenZArchiveWriter::new(level, password, spill)?),
+        _ => Box::new(ZipArchiveWriter::new(options, spill)?),
+    })
+}
+
+// ZIP 寫入器，項目選項可能借用加密密碼
+struct ZipArchiveWriter<'k> {
+    zip: ZipWriter<SpillBuffer>,
+    options: FileOptions<'k, ()>,
+}
+
+impl<'k> ZipArchiveWriter<'k> {
+    fn new(options: FileOptions<'k, ()>, spill: bool) -> io::Result<Self> {
+        Ok(ZipArchiveWriter {
+            zip: ZipWriter::new(SpillBuffer::new(spill)?),
+            options,
+        })
+    }
+
+    // 完成只含單一來源檔案項目的部分封存檔，不讀取項目清單
+    fn finish_part(self) -> io::Result<(Vec<u8>, Option<SpillFile>)> {
+        self.zip.finish()?.finish()
+    }
+
+    // 將部分封存檔的項目依原樣附加於目前的封存檔之後，已壓縮與加密的內容不重新處理
+    fn merge_part(&mut self, part: (Vec<u8>, Option<SpillFile>)) -> io::Result<()> {
+        match part {
+            (_, Some(spill)) => self.zip.merge_archive(zip::ZipArchive::new(spill.open()?)?)?,
+            (buffer, None) => self.zip.merge_archive(zip::ZipArchive::new(Cursor::new(buffer))?)?,
+        }
+        Ok(())
+    }
+}
+
+impl ArchiveWriter for ZipArchiveWriter<'_> {
+    fn add_entry(&mut self, name: &str, _size: u64, metadata: &EntryMetadata, reader: &mut dyn Read) -> io::Result<u64> {
+        let options = self
+            .options
+            .last_modified_time(zip_time(metadata.modified))
+            .unix_permissions(metadata.mode.unwrap_or(DEFAULT_ENTRY_MODE));
+        self.zip.start_file(name, options)?;
+        io::copy(reader, &mut self.zip)
+    }
+
+    fn add_symlink(&mut self, name: &str, target: &str, metadata: &EntryMetadata) -> io::Result<u64> {
+        let options = self
+            .options
+            .last_modified_time(zip_time(metadata.modified))
+            .unix_permissions(SYMLINK_MODE);
+        self.zip.add_symlink(name, target, options)?;
+        Ok(target.len() as u64)
+    }
+
+    fn set_comment(&mut self, comment: &str) {
+        self.zip.set_comment(comment);
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<(Vec<u8>, Option<SpillFile>, Vec<ZipEntryInfo>)> {
+        let (zip_buffer, spill) = self.zip.finish()?.finish()?;
+        let entries = match &spill {
+            Some(spill) => read_entry_manifest_from(spill.open()?)?,
+            None => read_entry_manifest(&zip_buffer)?,
+        };
+        Ok((zip_buffer, spill, entries))
+    }
+}
+
+// tar 寫入器，tar.gz 在完成時將整個 tar 以指定等級的 gzip 壓縮
+struct TarArchiveWriter {
+    builder: tar::Builder<SpillBuffer>,
+    gzip: Option<u8>,
+    spill: bool,
+    entries: Vec<ZipEntryInfo>,
+}
+
+impl TarArchiveWriter {
+    fn new(gzip: Option<u8>, spill: bool) -> io::Result<Self> {
+        Ok(TarArchiveWriter {
+            builder: tar::Builder::new(SpillBuffer::new(spill)?),
+            gzip,
+            spill,
+            entries: Vec::new(),
+        })
+    }
+}
+
+impl ArchiveWriter for TarArchiveWriter {
+    fn add_entry(&mut self, name: &str, size: u64, metadata: &EntryMetadata, reader: &mut dyn Read) -> io::Result<u64> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(size);
+        header.set_mode(metadata.mode.unwrap_or(DEFAULT_ENTRY_MODE));
+        header.set_mtime(entry_time(metadata.modified).duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()));
+        // tar 標頭先寫入大小，內容長度不符時封存檔會損毀
+        let mut counted = CountingReader { inner: reader.take(size), count: 0 };
+        self.builder.append_data(&mut header, name, &mut counted)?;
+        if counted.count != size {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{} 的內容長度與預期不符（{} / {} 位元組），來源檔案可能在壓縮期間變更", name, counted.count, size)
+            ));
+        }
+        // 項目內容位於補齊至 512 位元組的區塊之前
+        let data_offset = self.builder.get_mut().stream_position()? - size.div_ceil(512) * 512;
+        self.entries.push(ZipEntryInfo {
+            path: name.to_string(),
+            size,
+            compressed_size: size,
+            data_offset,
+            method: if self.gzip.is_some() { "gzip".to_string() } else { "stored".to_string() },
+            encrypted: false,
+            range: None,
+            tags: Tags::new(),
+        });
+        Ok(size)
+    }
+
+    // 連結沒有內容可供位移讀取，不列入項目清單
+    fn add_symlink(&mut self, name: &str, target: &str, metadata: &EntryMetadata) -> io::Result<u64> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_mode(SYMLINK_MODE);
+        header.set_mtime(entry_time(metadata.modified).duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()));
+        self.builder.append_link(&mut header, name, target)?;
+        Ok(target.len() as u64)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<(Vec<u8>, Option<SpillFile>, Vec<ZipEntryInfo>)> {
+        let (tar_buffer, spill) = self.builder.into_inner()?.finish()?;
+        let Some(level) = self.gzip else {
+            return Ok((tar_buffer, spill, self.entries));
+        };
+        if let Some(tar_file) = spill {
+            // 暫存的 tar 逐段壓縮寫入另一個暫存檔
+            let mut encoder = GzEncoder::new(SpillBuffer::new(self.spill)?, Compression::new(level as u32));
+            io::copy(&mut tar_file.open()?, &mut encoder)?;
+            let (_, gzipped) = encoder.finish()?.finish()?;
+            let gzipped_len = gzipped.as_ref().map_or(0, SpillFile::len);
+            info!(target: FILE_LOG_TARGET, "tar 以 gzip 壓縮：{} → {} 位元組", tar_file.len(), gzipped_len);
+            return Ok((Vec::new(), gzipped, self.entries));
+        }
+        let gzipped = compress_payload(&tar_buffer, "gzip", Some(level as i32))?;
+        info!(target: FILE_LOG_TARGET, "tar 以 gzip 壓縮：{} → {} 位元組", tar_buffer.len(), gzipped.len());
+        Ok((gzipped, None, self.entries))
+    }
+}
+
+// 7z 寫入器，各項目分別以 LZMA2 壓縮，設定密碼時再以 AES-256 加密內容與檔名清單
+struct SevenZArchiveWriter {
+    writer: SevenZWriter<SpillBuffer>,
+    entries: Vec<ZipEntryInfo>,
+    encrypted: bool,
+}
+
+impl SevenZArchiveWriter {
+    fn new(level: u8, password: Option<&str>, spill: bool) -> io::Result<Self> {
+        let mut writer = SevenZWriter::new(SpillBuffer::new(spill)?).map_err(seven_z_error)?;
+        let lzma2 = LZMA2Options::with_preset(level as u32).into();
+        match password {
+            Some(pwd) => writer.set_content_methods(vec![AesEncoderOptions::new(Password::from(pwd)).into(), lzma2]),
+            None => writer.set_content_methods(vec![lzma2]),
+        };
+        Ok(SevenZArchiveWriter {
+            writer,
+            entries: Vec::new(),
+            encrypted: password.is_some(),
+        })
+    }
+}
+
+impl SevenZArchiveWriter {
+    // 寫入項目，`unix_mode` 含檔案類型位元
+    fn push_entry(&mut self, name: &str, metadata: &EntryMetadata, unix_mode: u32, reader: &mut dyn Read) -> io::Result<u64> {
+        let mut entry = SevenZArchiveEntry::new();
+        entry.name = name.to_string();
+        entry.has_stream = true;
+        if let Ok(modified) = entry_time(metadata.modified).try_into() {
+            entry.last_modified_date = modified;
+            entry.has_last_modified_date = true;
+        }
+        // 與 p7zip 相同，以 Unix 擴充屬性的高 16 位元記錄檔案類型與權限
+        entry.has_windows_attributes = true;
+        entry.windows_attributes = UNIX_EXTENSION_ATTRIBUTE | (unix_mode << 16);
+        let entry = self.writer.push_archive_entry(entry, Some(reader)).map_err(seven_z_error)?;
+        // 7z 的項目無法以位移直接讀取，清單只記錄大小
+        self.entries.push(ZipEntryInfo {
+            path: name.to_string(),
+            size: entry.size,
+            compressed_size: entry.compressed_size,
+            data_offset: 0,
+            method: "lzma2".to_string(),
+            encrypted: self.encrypted,
+            range: None,
+            tags: Tags::new(),
+        });
+        Ok(entry.size)
+    }
+}
+
+impl ArchiveWriter for SevenZArchiveWriter {
+    fn add_entry(&mut self, name: &str, _size: u64, metadata: &EntryMetadata, reader: &mut dyn Read) -> io::Result<u64> {
+        self.push_entry(name, metadata, REGULAR_FILE_MODE | metadata.mode.unwrap_or(DEFAULT_ENTRY_MODE), reader)
+    }
+
+    // 與 p7zip 相同，連結目標存為項目內容
+    fn add_symlink(&mut self, name: &str, target: &str, metadata: &EntryMetadata) -> io::Result<u64> {
+        self.push_entry(name, metadata, SYMLINK_FILE_MODE | SYMLINK_MODE, &mut target.as_bytes())
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<(Vec<u8>, Option<SpillFile>, Vec<ZipEntryInfo>)> {
+        let (buffer, spill) = self.writer.finish()?.finish()?;
+        Ok((buffer, spill, self.entries))
+    }
+}
+
+// 項目的修改時間，未保留時使用 ZIP 可表示的最早時間（1980-01-01）
+fn entry_time(modified: Option<SystemTime>) -> SystemTime {
+    modified.unwrap_or(UNIX_EPOCH + Duration::from_secs(FIXED_ENTRY_TIME))
+}
+
+// 清單中的單一項目，修改時間以 UTC 的 RFC 3339 表示
+fn manifest_entry(name: &str, size: u64, metadata: &EntryMetadata, hasher: Sha256) -> ManifestEntry {
+    ManifestEntry {
+        path: name.to_string(),
+        size,
+        modified: metadata
+            .modified
+            .map(|modified| chrono::DateTime::<Utc>::from(modified).to_rfc3339_opts(SecondsFormat::Secs, true)),
+        sha256: hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect(),
+    }
+}
+
+// 將修改時間轉為 ZIP 以本地時間記錄的日期時間，超出 1980–2107 年時使用最早時間
+fn zip_time(modified: Option<SystemTime>) -> zip::DateTime {
+    modified
+        .and_then(|modified| {
+            let local = chrono::DateTime::<Local>::from(modified);
+            zip::DateTime::from_date_and_time(
+                u16::try_from(local.year()).ok()?,
+                local.month() as u8,
+                local.day() as u8,
+                local.hour() as u8,
+                local.minute() as u8,
+                local.second() as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// 將 7z 套件的錯誤轉為 IO 錯誤
+pub fn seven_z_error(error: sevenz_rust::Error) -> io::Error {
+    match error {
+        sevenz_rust::Error::Io(e, context) if context.is_empty() => e,
+        sevenz_rust::Error::Io(e, context) => io::Error::new(e.kind(), format!("{}：{}", context, e)),
+        other => io::Error::other(format!("7z 封存檔處理失敗：{}", other)),
+    }
+}
+
+// 平行壓縮時每批處理的檔案數為執行緒數的倍數，限制同時保留的部分封存檔
+const PARALLEL_BATCH_FACTOR: usize = 4;
+
+// 計算實際讀取的位元組數
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+// 在讀取的同時計算 SHA-256，串流寫入的項目不需另外讀取一次來源
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+// 已寫入項目的統計，以及 `--manifest` 時依寫入順序記錄的清單項目
+#[derive(Default)]
+struct WrittenEntries {
+    total_size: usize,
+    processed_files: usize,
+    missing: Vec<PathBuf>,
+    manifest: Vec<ManifestEntry>,
+}
+
 pub struct ZipCompressor {
-    options: SimpleFileOptions,
+    compression: CompressionSpec,
     password: Option<String>,
     aes_mode: AesMode,
     pm: Arc<crate::utils::utils::ProgressManager>,
     no_progress: bool,
+    filters: Vec<Arc<dyn ContentFilter>>,
+    ranges: HashMap<PathBuf, ByteRange>,
+    archive_format: String,
+    // 蒐集時的檔案狀態，沒有記錄的檔案不檢查變更
+    snapshots: HashMap<PathBuf, FileSnapshot>,
+    on_change: String,
+    on_missing: String,
+    // 蒐集後已被刪除而略過的檔案
+    missing: Vec<PathBuf>,
+    preserve_metadata: bool,
+    // 符號連結以連結項目保存，否則讀取連結目標的內容
+    store_symlinks: bool,
+    // 封存檔寫入暫存檔而非記憶體
+    spill_to_disk: bool,
+    // 平行壓縮 ZIP 項目的執行緒數
+    jobs: usize,
+    // 在 ZIP 的第一個項目寫入 manifest.json
+    manifest: bool,
+    // 項目名稱的頂層資料夾，見 rooted_entry_name
+    archive_root: Option<String>,
+    // 使用者提供的封存檔註解，附加於工具資訊之後
+    comment: Option<String>,
 }
 
 impl ZipCompressor {
     pub fn new(
-        options: SimpleFileOptions,
+        compression: CompressionSpec,
         password: Option<&str>,
         aes_mode: AesMode,
         no_progress: bool,
+        filters: Vec<Arc<dyn ContentFilter>>,
     ) -> Self {
         let pm = Arc::new(crate::utils::utils::create_progress_bar(0, no_progress));
         ZipCompressor {
-            options,
+            compression,
             password: password.map(String::from),
             aes_mode,
             pm,
             no_progress,
+            filters,
+            ranges: HashMap::new(),
+            archive_format: "zip".to_string(),
+            snapshots: HashMap::new(),
+            on_change: "warn".to_string(),
+            on_missing: "skip".to_string(),
+            missing: Vec::new(),
+            preserve_metadata: true,
+            store_symlinks: false,
+            spill_to_disk: false,
+            jobs: 1,
+            manifest: false,
+            archive_root: None,
+            comment: None,
         }
     }
-}
 
-impl Compressor for ZipCompressor {
-    fn compress_files(&mut self, files: &[PathBuf], input_path: &Path) -> io::Result<Vec<u8>> {
-        let total_files = files.len() as u64;
-        self.pm = Arc::new(crate::utils::utils::create_progress_bar(
-            total_files,
-            self.no_progress,
-        ));
-        let mut zip_buffer = Vec::new();
-        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_buffer));
-        let mut total_size = 0;
-        let mut processed_files = 0;
-
-        let rt = tokio::runtime::Runtime::new()?;
-        for (file_path, relative_path) in files.iter().filter_map(|file_path| {
-            diff_paths(file_path, input_path.parent().unwrap_or(input_path)).map(|rp| {
-                (
-                    file_path.clone(),
-                    rp.to_string_lossy()
-                        .replace("\\", "/")
-                        .trim_start_matches("./")
-                        .to_string(),
-                )
-            })
-        }) {
-            let mut file = rt.block_on(File::open(&file_path))?;
+    /// 指定只寫入部分內容的檔案及其位元組範圍
+    pub fn with_ranges(mut self, ranges: HashMap<PathBuf, ByteRange>) -> Self {
+        self.ranges = ranges;
+        self
+    }
+
+    /// 指定封存格式（zip、tar 或 tar.gz），預設為 zip
+    pub fn with_archive_format(mut self, archive_format: &str) -> Self {
+        self.archive_format = archive_format.to_string();
+        self
+    }
+
+    /// 指定蒐集時的檔案狀態與來源變更的處理方式（warn、fail 或 reread），`reread` 會將檔案讀入記憶體
+    pub fn with_change_detection(mut self, snapshots: HashMap<PathBuf, FileSnapshot>, on_change: &str) -> Self {
+        self.snapshots = snapshots;
+        self.on_change = on_change.to_string();
+        self
+    }
+
+    /// 指定來源檔案在蒐集後被刪除時的處理方式：`skip` 略過並記錄，`fail` 中止壓縮
+    pub fn with_missing_policy(mut self, on_missing: &str) -> Self {
+        self.on_missing = on_missing.to_string();
+        self
+    }
+
+    /// 指定是否保留來源檔案的修改時間與 Unix 權限，不保留時使用固定值以產生可重現的封存檔
+    pub fn preserve_metadata(mut self, preserve: bool) -> Self {
+        self.preserve_metadata = preserve;
+        self
+    }
+
+    // 項目的中繼資料，優先使用蒐集時記錄的狀態
+    fn entry_metadata(&self, file_path: &Path, snapshot: Option<&FileSnapshot>) -> EntryMetadata {
+        if !self.preserve_metadata {
+            return EntryMetadata::default();
+        }
+        let captured;
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot,
+            None => match FileSnapshot::capture(file_path) {
+                Ok(found) => {
+                    captured = found;
+                    &captured
+                }
+                Err(_) => return EntryMetadata::default(),
+            },
+        };
+        EntryMetadata { modified: snapshot.modified, mode: snapshot.mode }
+    }
+
+    /// 指定符號連結的處理方式：`store` 保存為連結項目，其他方式讀取連結目標的內容
+    pub fn with_symlink_policy(mut self, symlinks: &str) -> Self {
+        self.store_symlinks = symlinks == "store";
+        self
+    }
+
+    /// 指定是否將封存檔寫入暫存檔，輸入很大時記憶體用量不隨封存檔大小增加
+    pub fn spill_to_disk(mut self, spill: bool) -> Self {
+        self.spill_to_disk = spill;
+        self
+    }
+
+    /// 指定平行壓縮 ZIP 項目的執行緒數，大於 1 時各檔案分別壓縮後再依序合併，封存檔內容與依序壓縮時相同；
+    /// tar、tar.gz 與 7z 一律依序寫入
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// 指定是否在 ZIP 的第一個項目寫入 manifest.json，列出各項目的路徑、大小、修改時間與 SHA-256；
+    /// 項目內容在寫入的同時計算雜湊，寫完後再將清單置於最前面；僅適用於 zip
+    pub fn with_manifest(mut self, manifest: bool) -> Self {
+        self.manifest = manifest;
+        self
+    }
+
+    /// 指定項目名稱的頂層資料夾：以其取代輸入目錄的名稱，空字串時去除頂層資料夾，None 時沿用輸入目錄的名稱
+    pub fn with_archive_root(mut self, archive_root: Option<String>) -> Self {
+        self.archive_root = archive_root;
+        self
+    }
+
+    /// 指定附加於 ZIP 封存檔註解的說明，工具資訊與建立時間一律寫入
+    pub fn with_comment(mut self, comment: Option<String>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    // 保存連結時返回符號連結的目標與修改時間，一般檔案返回 None；無法讀取時交由開啟來源時處理
+    fn symlink_entry(&self, file_path: &Path) -> io::Result<Option<(String, EntryMetadata)>> {
+        if !self.store_symlinks {
+            return Ok(None);
+        }
+        match std::fs::symlink_metadata(file_path) {
+            Ok(link) if link.file_type().is_symlink() => {
+                let target = std::fs::read_link(file_path)?.to_string_lossy().into_owned();
+                let modified = if self.preserve_metadata { link.modified().ok() } else { None };
+                Ok(Some((target, EntryMetadata { modified, mode: None })))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 最近一次壓縮時因已被刪除而略過的檔案
+    pub fn missing_files(&self) -> &[PathBuf] {
+        &self.missing
+    }
+
+    // 開啟項目的來源，`--on-change reread` 時讀取前後狀態一致才寫入，避免寫入讀取期間被修改的內容
+    fn open_entry_source(
+        &self,
+        file_path: &Path,
+        range: Option<&ByteRange>,
+        snapshot: Option<&FileSnapshot>,
+    ) -> io::Result<(Box<dyn Read>, u64)> {
+        match snapshot {
+            Some(snapshot) if self.on_change == "reread" => {
+                let data = read_stable(file_path, snapshot, || {
+                    let (mut source, size) = open_source(file_path, range)?;
+                    let mut data = Vec::with_capacity(size as usize);
+                    source.read_to_end(&mut data)?;
+                    Ok(data)
+                })?;
+                let size = data.len() as u64;
+                Ok((Box::new(Cursor::new(data)), size))
+            }
+            _ => {
+                let (source, size) = open_source(file_path, range)?;
+                Ok((Box::new(source), size))
+            }
+        }
+    }
+
+    // 寫入項目，`--manifest` 時一併記錄項目的大小、修改時間與內容的 SHA-256
+    fn add_entry(
+        &self,
+        writer: &mut dyn ArchiveWriter,
+        manifest: &mut Vec<ManifestEntry>,
+        name: &str,
+        size: u64,
+        metadata: &EntryMetadata,
+        reader: &mut dyn Read,
+    ) -> io::Result<u64> {
+        if !self.manifest {
+            return writer.add_entry(name, size, metadata, reader);
+        }
+        let mut hashing = HashingReader { inner: reader, hasher: Sha256::new() };
+        let written = writer.add_entry(name, size, metadata, &mut hashing)?;
+        manifest.push(manifest_entry(name, written, metadata, hashing.hasher));
+        Ok(written)
+    }
+
+    // 寫入單一來源檔案的項目，過濾器要求保留原始內容時一併寫入；來源已被刪除且設定略過時返回 None
+    fn write_file_entries(
+        &self,
+        writer: &mut dyn ArchiveWriter,
+        manifest: &mut Vec<ManifestEntry>,
+        file_path: &Path,
+        relative_path: &str,
+    ) -> io::Result<Option<u64>> {
+        if let Some((target, metadata)) = self.symlink_entry(file_path)? {
+            let written = writer.add_symlink(relative_path, &target, &metadata)?;
+            if self.manifest {
+                manifest.push(manifest_entry(relative_path, written, &metadata, Sha256::new_with_prefix(target.as_bytes())));
+            }
+            return Ok(Some(written));
+        }
+        let range = self.ranges.get(file_path);
+        let snapshot = self.snapshots.get(file_path);
+        let metadata = self.entry_metadata(file_path, snapshot);
+        let (mut source, size) = match self.open_entry_source(file_path, range, snapshot) {
+            Ok(opened) => opened,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if self.on_missing != "skip" {
+                    return Err(io::Error::new(e.kind(), format!("來源檔案在蒐集後已被刪除：{}", file_path.display())));
+                }
+                log::warn!("來源檔案在蒐集後已被刪除，已略過：{}", file_path.display());
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let mut written = 0;
+        if self.filters.iter().any(|filter| filter.applies_to(file_path)) {
             let mut data = Vec::new();
-            rt.block_on(file.read_to_end(&mut data))?;
-
-            if let Some(pwd) = &self.password {
-                let encrypt_options = SimpleFileOptions::default()
-                    .compression_method(CompressionMethod::DEFLATE)
-                    .compression_level(Some(5))
-                    .with_aes_encryption(self.aes_mode, pwd);
-                zip.start_file(&relative_path, encrypt_options)?;
-            } else {
-                zip.start_file(&relative_path, self.options)?;
+            source.read_to_end(&mut data)?;
+            // 過濾器要求保留原始內容時先留存一份，內容確實改變才另外寫入
+            let original = self
+                .filters
+                .iter()
+                .filter(|filter| filter.applies_to(file_path))
+                .find_map(|filter| filter.original_suffix().map(str::to_string))
+                .map(|suffix| (suffix, data.clone()));
+            let data = apply_filters(&self.filters, file_path, data)?;
+            written += self.add_entry(writer, manifest, relative_path, data.len() as u64, &metadata, &mut data.as_slice())?;
+            if let Some((suffix, original)) = original.filter(|(_, original)| *original != data) {
+                let name = format!("{}{}", relative_path, suffix);
+                written += self.add_entry(writer, manifest, &name, original.len() as u64, &metadata, &mut original.as_slice())?;
+            }
+        } else {
+            // 沒有內容過濾器時直接串流寫入封存檔，不將整個檔案讀入記憶體
+            written += self.add_entry(writer, manifest, relative_path, size, &metadata, &mut source)?;
+        }
+        if let Some(snapshot) = snapshot.filter(|_| self.on_change != "reread") {
+            check_unchanged(file_path, snapshot, &self.on_change)?;
+        }
+        Ok(Some(written))
+    }
+
+    // 依序將各檔案寫入封存檔
+    fn write_sequential(&self, writer: &mut dyn ArchiveWriter, named: &[(PathBuf, String)]) -> io::Result<WrittenEntries> {
+        let mut written_entries = WrittenEntries::default();
+        for (file_path, relative_path) in named {
+            match self.write_file_entries(writer, &mut written_entries.manifest, file_path, relative_path)? {
+                Some(written) => written_entries.total_size += written as usize,
+                None => {
+                    written_entries.missing.push(file_path.clone());
+                    continue;
+                }
             }
-            zip.write_all(&data)?;
-            total_size += data.len();
-            processed_files += 1;
+            written_entries.processed_files += 1;
 
             // 每 100 個檔案更新進度條
             if !self.no_progress {
                 self.pm
-                    .update(processed_files as u64, Some(total_size), "壓縮檔案");
+                    .update(written_entries.processed_files as u64, Some(written_entries.total_size), "壓縮檔案");
+            }
+        }
+        Ok(written_entries)
+    }
+
+    // 以多個執行緒分別將各檔案壓縮為部分封存檔，再依原順序合併；每批只保留執行緒數數倍的部分封存檔，
+    // 啟用 `--spill-to-disk` 時部分封存檔也寫入暫存檔
+    fn write_parallel<'k>(
+        &self,
+        named: &[(PathBuf, String)],
+        options: FileOptions<'k, ()>,
+        workers: usize,
+    ) -> io::Result<(ZipArchiveWriter<'k>, WrittenEntries)> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(workers)
+            .build()
+            .map_err(|e| io::Error::other(format!("無法建立壓縮執行緒：{}", e)))?;
+        let mut writer = ZipArchiveWriter::new(options, self.spill_to_disk)?;
+        let mut written_entries = WrittenEntries::default();
+        for batch in named.chunks(workers * PARALLEL_BATCH_FACTOR) {
+            let parts = pool.install(|| {
+                batch
+                    .par_iter()
+                    .map(|(file_path, relative_path)| {
+                        let mut part = ZipArchiveWriter::new(options, self.spill_to_disk)?;
+                        let mut manifest = Vec::new();
+                        let written = self.write_file_entries(&mut part, &mut manifest, file_path, relative_path)?;
+                        Ok((written, part.finish_part()?, manifest))
+                    })
+                    .collect::<io::Result<Vec<_>>>()
+            })?;
+            for ((file_path, _), (written, part, manifest)) in batch.iter().zip(parts) {
+                let Some(written) = written else {
+                    written_entries.missing.push(file_path.clone());
+                    continue;
+                };
+                writer.merge_part(part)?;
+                written_entries.manifest.extend(manifest);
+                written_entries.total_size += written as usize;
+                written_entries.processed_files += 1;
+                if !self.no_progress {
+                    self.pm
+                        .update(written_entries.processed_files as u64, Some(written_entries.total_size), "壓縮檔案");
+                }
             }
         }
+        Ok((writer, written_entries))
+    }
+
+    // 建立以 manifest.json 為第一個項目的封存檔，再依原樣附加已寫入的項目；清單以相同的壓縮與加密選項寫入
+    fn prepend_manifest<'k>(
+        &self,
+        body: ZipArchiveWriter<'k>,
+        options: FileOptions<'k, ()>,
+        entries: Vec<ManifestEntry>,
+    ) -> io::Result<ZipArchiveWriter<'k>> {
+        let manifest = ArchiveManifest {
+            manifest_version: MANIFEST_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            entries,
+        };
+        let content = serde_json::to_vec_pretty(&manifest).map_err(io::Error::other)?;
+        let mut writer = ZipArchiveWriter::new(options, self.spill_to_disk)?;
+        writer.add_entry(MANIFEST_NAME, content.len() as u64, &EntryMetadata::default(), &mut content.as_slice())?;
+        writer.merge_part(body.finish_part()?)?;
+        info!(target: FILE_LOG_TARGET, "已寫入 {}，記錄 {} 個項目", MANIFEST_NAME, manifest.entries.len());
+        Ok(writer)
+    }
+
+    /// 壓縮檔案並返回封存檔數據與各項目的清單
+    /// # 參數
+    /// - files: 要壓縮的檔案
+    /// - input_path: 輸入路徑，項目名稱以其上層目錄為基準
+    /// # 回傳
+    /// - 成功時返回（封存檔數據，項目清單），失敗時返回 IO 錯誤
+    pub fn compress_archive(
+        &mut self,
+        files: &[PathBuf],
+        input_path: &Path,
+    ) -> io::Result<(Vec<u8>, Option<SpillFile>, Vec<ZipEntryInfo>)> {
+        let total_files = files.len() as u64;
+        self.missing.clear();
+        self.pm = Arc::new(crate::utils::utils::create_progress_bar(
+            total_files,
+            self.no_progress,
+        ));
+        let options = file_options(&self.compression);
+        let options = match &self.password {
+            Some(pwd) => options.with_aes_encryption(self.aes_mode, pwd),
+            None => options,
+        };
+        let named: Vec<(PathBuf, String)> = files
+            .iter()
+            .filter_map(|file_path| {
+                rooted_entry_name(file_path, input_path, self.archive_root.as_deref()).map(|name| (file_path.clone(), name))
+            })
+            .collect();
+        if self.manifest && named.iter().any(|(_, name)| name == MANIFEST_NAME) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("輸入中有與清單同名的項目 {}，無法使用 --manifest", MANIFEST_NAME),
+            ));
+        }
+        let workers = self.jobs.min(named.len()).max(1);
+        let (mut writer, written_entries) = if self.archive_format == "zip" && (workers > 1 || self.manifest) {
+            let (body, mut written_entries) = if workers > 1 {
+                info!(target: FILE_LOG_TARGET, "以 {} 個執行緒平行壓縮 {} 個檔案", workers, named.len());
+                self.write_parallel(&named, options, workers)?
+            } else {
+                let mut body = ZipArchiveWriter::new(options, self.spill_to_disk)?;
+                let written_entries = self.write_sequential(&mut body, &named)?;
+                (body, written_entries)
+            };
+            let writer = if self.manifest {
+                self.prepend_manifest(body, options, std::mem::take(&mut written_entries.manifest))?
+            } else {
+                body
+            };
+            (Box::new(writer) as Box<dyn ArchiveWriter>, written_entries)
+        } else {
+            let mut writer = archive_writer(
+                &self.archive_format,
+                options,
+                self.compression.level,
+                self.password.as_deref(),
+                self.spill_to_disk,
+            )?;
+            let written_entries = self.write_sequential(writer.as_mut(), &named)?;
+            (writer, written_entries)
+        };
+        let created = self.preserve_metadata.then(SystemTime::now);
+        writer.set_comment(&archive_comment(self.comment.as_deref(), created));
+        let WrittenEntries { total_size, processed_files, missing, .. } = written_entries;
+        self.missing = missing;
 
         if !self.no_progress {
             self.pm
                 .update(processed_files as u64, Some(total_size), "壓縮檔案");
         }
         self.pm.finish(processed_files as u64, Some(total_size), 0);
-        info!("內層 ZIP 壓縮完成，大小：{} 位元組", total_size);
-        zip.finish()?;
-        Ok(zip_buffer)
+        info!(target: FILE_LOG_TARGET, "內層 {} 壓縮完成，大小：{} 位元組", self.archive_format, total_size);
+        writer.finish()
     }
 }
 
+impl Compressor for ZipCompressor {
+    fn compress_files(&mut self, files: &[PathBuf], input_path: &Path) -> io::Result<Vec<u8>> {
+        match self.compress_archive(files, input_path)? {
+            (_, Some(spill), _) => spill.read_all(),
+            (buffer, None, _) => Ok(buffer),
+        }
+    }
+}
+
+/// 檔案在 ZIP 中的項目名稱，以輸入路徑的上層目錄為基準並統一使用 `/` 分隔
+pub fn entry_name(file_path: &Path, input_path: &Path) -> Option<String> {
+    diff_paths(file_path, input_path.parent().unwrap_or(input_path)).map(|rp| {
+        rp.to_string_lossy()
+            .replace("\\", "/")
+            .trim_start_matches("./")
+            .to_string()
+    })
+}
+
+/// 依 `--archive-root` 或 `--strip-prefix` 計算檔案在封存檔中的項目名稱
+/// # 參數
+/// - file_path: 來源檔案
+/// - input_path: 輸入路徑
+/// - archive_root: 頂層資料夾，取代輸入目錄的名稱；空字串時去除頂層資料夾，項目直接位於封存檔的最上層；None 時同 `entry_name`
+/// # 回傳
+/// - 以 `/` 分隔的項目名稱，無法計算相對路徑時返回 None
+pub fn rooted_entry_name(file_path: &Path, input_path: &Path, archive_root: Option<&str>) -> Option<String> {
+    let Some(root) = archive_root else {
+        return entry_name(file_path, input_path);
+    };
+    // 輸入為單一檔案時相對路徑即為檔名，輸入為目錄時以目錄本身為基準
+    let relative = if file_path == input_path {
+        entry_name(file_path, input_path)?
+    } else {
+        diff_paths(file_path, input_path)?.to_string_lossy().replace("\\", "/").trim_start_matches("./").to_string()
+    };
+    match root.trim_matches('/') {
+        "" => Some(relative),
+        root => Some(format!("{}/{}", root, relative)),
+    }
+}
+
+/// 檢查 `--archive-root` 是否為相對路徑，且不含 `.`、`..` 或空的路徑片段
+/// # 參數
+/// - archive_root: 指定的頂層資料夾
+/// # 回傳
+/// - 無效時返回 IO 錯誤
+pub fn validate_archive_root(archive_root: &str) -> io::Result<()> {
+    let trimmed = archive_root.trim_end_matches('/');
+    let valid = !trimmed.is_empty()
+        && !trimmed.starts_with('/')
+        && !trimmed.contains('\\')
+        && trimmed.split('/').all(|part| !matches!(part, "" | "." | ".."));
+    if !valid {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無效的封存根目錄 '{}'，需為相對路徑（例如 myproject/），且不含 '.'、'..' 或空的路徑片段", archive_root),
+        ));
+    }
+    Ok(())
+}
+
+/// 寫入 ZIP 結尾記錄的封存檔註解：工具名稱與版本、建立時間及使用者以 `--comment` 提供的說明，
+/// 讓多年後解開的封存檔仍可辨識來源；工具資訊以 ASCII 書寫，不支援 UTF-8 註解的解壓縮工具也能正確顯示
+/// # 參數
+/// - comment: 使用者提供的說明
+/// - created: 建立時間，None 時不記錄（未保留中繼資料時，維持相同內容產生相同的封存檔）
+pub fn archive_comment(comment: Option<&str>, created: Option<SystemTime>) -> String {
+    let mut lines = vec![format!("Created by file_to_html {}", env!("CARGO_PKG_VERSION"))];
+    if let Some(created) = created {
+        lines.push(format!(
+            "Created at {}",
+            chrono::DateTime::<Utc>::from(created).to_rfc3339_opts(SecondsFormat::Secs, true)
+        ));
+    }
+    if let Some(comment) = comment {
+        lines.push(comment.to_string());
+    }
+    lines.join("\n")
+}
+
+/// 檢查 `--comment` 的長度，ZIP 註解連同工具資訊不可超過 65535 位元組
+pub fn validate_archive_comment(comment: &str) -> io::Result<()> {
+    if comment.len() > MAX_COMMENT_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("封存檔註解過長（{} 位元組），上限為 {} 位元組", comment.len(), MAX_COMMENT_LEN),
+        ));
+    }
+    Ok(())
+}
+
+// 開啟來源檔案，指定範圍時只讀取該範圍的內容，一併返回將讀取的位元組數
+fn open_source(file_path: &Path, range: Option<&ByteRange>) -> io::Result<(io::Take<std::fs::File>, u64)> {
+    let mut file = std::fs::File::open(file_path)?;
+    let size = match range {
+        Some(range) => {
+            file.seek(SeekFrom::Start(range.offset))?;
+            range.len
+        }
+        None => file.metadata()?.len(),
+    };
+    Ok((file.take(size), size))
+}
+
+/// 可指定的 ZIP 項目壓縮方法
+pub const COMPRESSION_METHODS: [&str; 5] = ["deflate", "deflate64", "bzip2", "zstd", "stored"];
+
+/// 判斷壓縮方法是否僅有部分解壓工具支援（Windows 檔案總管、macOS 封存工具程式與樹狀檢視皆無法解開）
+pub fn is_limited_compression_method(method: &str) -> bool {
+    matches!(method, "bzip2" | "zstd")
+}
+
+/// 依指定的壓縮方法與等級決定實際使用的壓縮設定，無法寫入或相容性不足的方法降級為 Deflate 並記錄警告
+/// # 參數
+/// - method: 壓縮方法名稱
+/// - level: 壓縮等級（0–9）
+/// - force: 是否保留相容性不足的方法，不自動降級
+/// # 回傳
+/// - 實際使用的壓縮設定
+pub fn resolve_compression(method: &str, level: u8, force: bool) -> CompressionSpec {
+    let deflate = CompressionSpec { method: "deflate".to_string(), level };
+    match method {
+        "deflate64" => {
+            log::warn!("zip 套件僅支援讀取 Deflate64，無法寫入，改用 deflate 壓縮");
+            deflate
+        }
+        "bzip2" | "zstd" if !force => {
+            log::warn!(
+                "{} 無法以 Windows 檔案總管或 macOS 封存工具程式解壓，已改用 deflate；如確定收件者使用 7-Zip 等工具，可加上 --force-method",
+                method
+            );
+            deflate
+        }
+        "bzip2" | "zstd" | "stored" => CompressionSpec { method: method.to_string(), level },
+        _ => deflate,
+    }
+}
+
+/// 不壓縮的設定，用於頁面資料另外整體壓縮時
+pub fn stored_compression() -> CompressionSpec {
+    CompressionSpec { method: "stored".to_string(), level: 0 }
+}
+
+/// 將壓縮設定轉為 ZIP 項目選項；等級 0 的項目不壓縮（Stored），stored 忽略等級
+/// # 參數
+/// - compression: 壓縮方法與等級
+/// # 回傳
+/// - ZIP 項目選項
+pub fn file_options(compression: &CompressionSpec) -> SimpleFileOptions {
+    let level = compression.level as i64;
+    match compression.method.as_str() {
+        _ if level == 0 => SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+        "stored" => SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+        "bzip2" => SimpleFileOptions::default()
+            .compression_method(CompressionMethod::BZIP2)
+            .compression_level(Some(level)),
+        "zstd" => SimpleFileOptions::default()
+            .compression_method(CompressionMethod::ZSTD)
+            .compression_level(Some(level)),
+        _ => SimpleFileOptions::default()
+            .compression_method(CompressionMethod::DEFLATE)
+            .compression_level(Some(level)),
+    }
+}
+
+// 讀回已完成的 ZIP，產生各項目的位移與大小清單
+pub fn read_entry_manifest(zip_buffer: &[u8]) -> io::Result<Vec<ZipEntryInfo>> {
+    read_entry_manifest_from(Cursor::new(zip_buffer))
+}
+
+// 自任意可定位的來源讀取 ZIP 項目清單，暫存於磁碟的封存檔不需讀回記憶體
+fn read_entry_manifest_from<R: Read + Seek>(reader: R) -> io::Result<Vec<ZipEntryInfo>> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut entries = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let method = match entry.compression() {
+            CompressionMethod::Stored => "stored".to_string(),
+            CompressionMethod::Deflated => "deflated".to_string(),
+            other => format!("{:?}", other).to_lowercase(),
+        };
+        entries.push(ZipEntryInfo {
+            path: entry.name().to_string(),
+            size: entry.size(),
+            compressed_size: entry.compressed_size(),
+            data_offset: entry.data_start(),
+            method,
+            encrypted: entry.encrypted(),
+            range: None,
+            tags: Tags::new(),
+        });
+    }
+    Ok(entries)
+}
+
+// 將壓縮後的數據依固定大小切成多個分段，依序串接即可還原
+pub fn split_payload(zip_buffer: &[u8], part_size: usize) -> io::Result<Vec<&[u8]>> {
+    if part_size == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "分段大小必須大於 0"));
+    }
+    Ok(zip_buffer.chunks(part_size).collect())
+}
+
 pub fn create_inner_zip(
     input_path: &Path,
     files: &[PathBuf],
-    options: SimpleFileOptions,
+    compression: CompressionSpec,
     password: Option<&str>,
     aes_mode: AesMode,
     no_progress: bool,
+    filters: Vec<Arc<dyn ContentFilter>>,
 ) -> io::Result<Vec<u8>> {
-    let mut compressor = ZipCompressor::new(options, password, aes_mode, no_progress);
+    let mut compressor = ZipCompressor::new(compression, password, aes_mode, no_progress, filters);
     compressor.compress_files(files, input_path)
 }
 
-// 更新其他函數以使用 Stored 模式
 pub fn create_zip_buffer(
     file_name: &str,
     data: &[u8],
-    options: SimpleFileOptions,
+    compression: &CompressionSpec,
 ) -> io::Result<Vec<u8>> {
     let mut zip_buffer = Vec::new();
     let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_buffer));
-    zip.start_file(file_name, options)?;
+    zip.start_file(file_name, file_options(compression))?;
     zip.write_all(data)?;
+    zip.set_comment(archive_comment(None, Some(SystemTime::now())));
     zip.finish()?;
     Ok(zip_buffer)
 }
@@ -157,91 +1196,63 @@ pub fn create_zip_buffer(
 pub fn compress_file_content(
     data: &[u8],
     file_name: &str,
+    compression: &CompressionSpec,
     password: Option<&str>,
     aes_mode: AesMode,
 ) -> io::Result<Vec<u8>> {
     let mut zip_buffer = Vec::new();
     let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_buffer));
     if let Some(pwd) = password {
-        let options = SimpleFileOptions::default()
-            .compression_method(CompressionMethod::DEFLATE)
-            .compression_level(Some(5))
+        let options = file_options(compression)
             .with_aes_encryption(aes_mode, pwd);
         zip.start_file(file_name.to_string(), options)?;
     } else {
-        let options = SimpleFileOptions::default()
-            .compression_method(CompressionMethod::DEFLATE)
-            .compression_level(Some(5));
+        let options = file_options(compression);
         zip.start_file(file_name.to_string(), options)?;
     }
     zip.write_all(data)?;
+    zip.set_comment(archive_comment(None, Some(SystemTime::now())));
     zip.finish()?;
     Ok(zip_buffer)
 }
 
+/// 將已產生的封存檔以 `{file_name}.zip` 為唯一項目包裝為外層 ZIP，`none` 時原樣返回；
+/// 雙層壓縮以此包裝內層 ZIP，外層與內層各自以不同的密碼加密
+/// # 參數
+/// - data: 內層封存檔數據
+/// - file_name: 原始檔案名稱（不含副檔名）
+/// - layer: 壓縮層數
+/// - compression: 外層項目的壓縮方式
+/// - password: 外層 ZIP 的密碼，None 時不加密
+/// - aes_mode: 外層 ZIP 的 AES 強度
+/// # 回傳
+/// - 成功時返回外層 ZIP 數據，寫入失敗時返回 IO 錯誤
 pub fn create_zip(
     data: &[u8],
     file_name: &str,
     layer: &str,
+    compression: &CompressionSpec,
     password: Option<&str>,
     aes_mode: AesMode,
 ) -> io::Result<Vec<u8>> {
-    if layer == "double" {
-        let mut outer_zip_buffer = Vec::new();
-        let mut outer_zip = ZipWriter::new(std::io::Cursor::new(&mut outer_zip_buffer));
-        if let Some(pwd) = password {
-            let outer_options = SimpleFileOptions::default()
-                .compression_method(CompressionMethod::DEFLATE)
-                .compression_level(Some(5))
-                .with_aes_encryption(aes_mode, pwd);
-            outer_zip.start_file(format!("{}_outer.zip", file_name), outer_options)?;
-            outer_zip.write_all(data)?;
-            outer_zip.finish()?;
-            info!(
-                "生成外層加密 ZIP，密碼：{}，大小：{} 位元組",
-                pwd,
-                outer_zip_buffer.len()
-            );
-        } else {
-            let outer_options = SimpleFileOptions::default()
-                .compression_method(CompressionMethod::DEFLATE)
-                .compression_level(Some(5));
-            outer_zip.start_file(format!("{}_outer.zip", file_name), outer_options)?;
-            outer_zip.write_all(data)?;
-            outer_zip.finish()?;
-            info!(
-                "生成外層無密碼 ZIP，大小：{} 位元組",
-                outer_zip_buffer.len()
-            );
-        }
-        Ok(outer_zip_buffer)
-    } else if layer == "single" {
-        let mut zip_buffer = Vec::new();
-        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_buffer));
-        if let Some(pwd) = password {
-            let options = SimpleFileOptions::default()
-                .compression_method(CompressionMethod::DEFLATE)
-                .compression_level(Some(5))
-                .with_aes_encryption(aes_mode, pwd);
-            zip.start_file(format!("{}.zip", file_name), options)?;
-            zip.write_all(data)?;
-            zip.finish()?;
-            info!(
-                "生成單層加密 ZIP，密碼：{}，大小：{} 位元組",
-                pwd,
-                zip_buffer.len()
-            );
-        } else {
-            let options = SimpleFileOptions::default()
-                .compression_method(CompressionMethod::DEFLATE)
-                .compression_level(Some(5));
-            zip.start_file(format!("{}.zip", file_name), options)?;
-            zip.write_all(data)?;
-            zip.finish()?;
-            info!("生成單層無密碼 ZIP，大小：{} 位元組", zip_buffer.len());
-        }
-        Ok(zip_buffer)
-    } else {
-        Ok(data.to_vec())
+    if layer == "none" {
+        return Ok(data.to_vec());
     }
+    let options = match password {
+        Some(pwd) => file_options(compression).with_aes_encryption(aes_mode, pwd),
+        None => file_options(compression),
+    };
+    let mut zip_buffer = Vec::new();
+    let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_buffer));
+    zip.start_file(format!("{}.zip", file_name), options)?;
+    zip.write_all(data)?;
+    zip.set_comment(archive_comment(None, Some(SystemTime::now())));
+    zip.finish()?;
+    info!(
+        target: FILE_LOG_TARGET,
+        "生成外層{} ZIP，大小：{} 位元組",
+        if password.is_some() { "加密" } else { "無密碼" },
+        zip_buffer.len()
+    );
+    Ok(zip_buffer)
 }
@@ -0,0 +1,50 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// 程序 ID 檔，建立時寫入目前程序 ID，結束時自動移除
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// 寫入程序 ID 檔
+    /// # 參數
+    /// - path: 程序 ID 檔路徑
+    /// # 回傳
+    /// - 成功時返回 PidFile，寫入失敗時返回 IO 錯誤
+    pub fn create(path: &str) -> io::Result<Self> {
+        let path = PathBuf::from(path);
+        fs::write(&path, format!("{}\n", std::process::id()))
+            .map_err(|e| io::Error::new(e.kind(), format!("無法寫入程序 ID 檔 {}: {}", path.display(), e)))?;
+        log::info!("程序 ID 已寫入：{}", path.display());
+        Ok(PidFile { path })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            log::warn!("無法移除程序 ID 檔 {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// 註冊 SIGTERM 與 Ctrl+C 的處理，收到訊號時將回傳的旗標設為 true
+/// # 回傳
+/// - 成功時返回關閉旗標，處理函式已註冊過時返回 IO 錯誤
+pub fn install_shutdown_handler() -> io::Result<Arc<AtomicBool>> {
+    let flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&flag);
+    ctrlc::set_handler(move || {
+        if handler_flag.swap(true, Ordering::SeqCst) {
+            log::warn!("再次收到結束訊號，立即結束");
+            std::process::exit(130);
+        }
+        log::info!("收到結束訊號，等待進行中的轉換完成後結束");
+    })
+    .map_err(|e| io::Error::other(format!("無法註冊結束訊號處理: {}", e)))?;
+    Ok(flag)
+}
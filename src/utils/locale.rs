@@ -0,0 +1,103 @@
+use chrono::{DateTime, Local};
+use crate::service::i18n::DEFAULT_LANG;
+
+/// 依語系格式化數字、檔案大小與日期時間：頁面、索引頁與密碼單依 `--lang`，
+/// 進度與主控台訊息依系統語系；JSON 報告與頁面中的轉換資訊維持不受語系影響的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleFormat {
+    // 千分位分隔符號
+    pub thousands_separator: char,
+    // 小數點
+    pub decimal_separator: char,
+    // chrono 的日期時間格式
+    pub datetime_format: &'static str,
+}
+
+// 語系與對應的格式，先比對完整語系代碼再比對語言代碼；`fr` 與 `ru` 以不換行空白分隔千分位
+const LOCALE_FORMATS: [(&str, LocaleFormat); 14] = [
+    ("zh-TW", LocaleFormat::new(',', '.', "%Y/%m/%d %H:%M:%S")),
+    ("zh-HK", LocaleFormat::new(',', '.', "%Y/%m/%d %H:%M:%S")),
+    ("zh", LocaleFormat::new(',', '.', "%Y-%m-%d %H:%M:%S")),
+    ("ja", LocaleFormat::new(',', '.', "%Y/%m/%d %H:%M:%S")),
+    ("ko", LocaleFormat::new(',', '.', "%Y. %m. %d. %H:%M:%S")),
+    ("en-GB", LocaleFormat::new(',', '.', "%d/%m/%Y %H:%M:%S")),
+    ("en", LocaleFormat::new(',', '.', "%m/%d/%Y %I:%M:%S %p")),
+    ("de", LocaleFormat::new('.', ',', "%d.%m.%Y %H:%M:%S")),
+    ("fr", LocaleFormat::new('\u{202f}', ',', "%d/%m/%Y %H:%M:%S")),
+    ("es", LocaleFormat::new('.', ',', "%d/%m/%Y %H:%M:%S")),
+    ("it", LocaleFormat::new('.', ',', "%d/%m/%Y %H:%M:%S")),
+    ("pt", LocaleFormat::new('.', ',', "%d/%m/%Y %H:%M:%S")),
+    ("nl", LocaleFormat::new('.', ',', "%d-%m-%Y %H:%M:%S")),
+    ("ru", LocaleFormat::new('\u{a0}', ',', "%d.%m.%Y %H:%M:%S")),
+];
+
+// 無法識別的語系使用 ISO 8601 日期
+const FALLBACK_FORMAT: LocaleFormat = LocaleFormat::new(',', '.', "%Y-%m-%d %H:%M:%S");
+
+impl LocaleFormat {
+    const fn new(thousands_separator: char, decimal_separator: char, datetime_format: &'static str) -> Self {
+        LocaleFormat { thousands_separator, decimal_separator, datetime_format }
+    }
+
+    /// 取得語系的格式
+    /// # 參數
+    /// - locale: 語系代碼，接受 `zh-TW`、`zh_TW.UTF-8` 等形式
+    /// # 回傳
+    /// - 語系的格式，無法識別時使用 ISO 8601 日期與 `,`、`.` 分隔
+    pub fn for_locale(locale: &str) -> Self {
+        let normalized = locale.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+        let language = normalized.split('-').next().unwrap_or_default();
+        LOCALE_FORMATS
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(&normalized))
+            .or_else(|| LOCALE_FORMATS.iter().find(|(code, _)| code.eq_ignore_ascii_case(language)))
+            .map_or(FALLBACK_FORMAT, |(_, format)| *format)
+    }
+
+    /// 取得系統語系的格式，無法取得系統語系時使用預設語言
+    pub fn system() -> Self {
+        Self::for_locale(&sys_locale::get_locale().unwrap_or_else(|| DEFAULT_LANG.to_string()))
+    }
+
+    /// 以千分位分隔整數，如 `1234567` → `1,234,567`
+    pub fn format_number(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let mut output = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                output.push(self.thousands_separator);
+            }
+            output.push(digit);
+        }
+        output
+    }
+
+    /// 格式化小數，整數部分加上千分位
+    /// # 參數
+    /// - value: 數值，負數與非有限值視為 0
+    /// - precision: 小數位數
+    pub fn format_decimal(&self, value: f64, precision: usize) -> String {
+        let value = if value.is_finite() { value.max(0.0) } else { 0.0 };
+        let fixed = format!("{:.*}", precision, value);
+        let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+        let integer = self.format_number(integer.parse().unwrap_or(0));
+        if fraction.is_empty() {
+            integer
+        } else {
+            format!("{}{}{}", integer, self.decimal_separator, fraction)
+        }
+    }
+
+    /// 以 KB 或 MB 表示檔案大小，保留兩位小數
+    pub fn format_size(&self, size: usize) -> String {
+        if size < 1024 * 1024 {
+            format!("{} KB", self.format_decimal(size as f64 / 1024.0, 2))
+        } else {
+            format!("{} MB", self.format_decimal(size as f64 / (1024.0 * 1024.0), 2))
+        }
+    }
+
+    pub fn format_datetime(&self, time: &DateTime<Local>) -> String {
+        time.format(self.datetime_format).to_string()
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use log::{info, LevelFilter, Log, Metadata, Record};
+
+/// 逐檔詳細記錄使用的日誌目標，超過取樣門檻後只在 debug 等級輸出
+pub const FILE_LOG_TARGET: &str = "file_to_html::file";
+
+/// `--log-sample` 的預設值
+pub const DEFAULT_LOG_SAMPLE: u64 = 1000;
+
+// 每幾個檔案輸出一行摘要，0 表示不取樣
+static SAMPLE: AtomicU64 = AtomicU64::new(0);
+// 程式執行期間已轉換的檔案數，伺服器與監看模式跨多次轉換累計
+static CONVERTED_FILES: AtomicU64 = AtomicU64::new(0);
+// 日誌等級為 debug 時一律輸出逐檔詳細記錄
+static DETAIL: AtomicBool = AtomicBool::new(true);
+
+/// 包裝 env_logger 的日誌器：前 N 個檔案照常輸出逐檔詳細記錄，之後改為每 N 個檔案輸出一行摘要，
+/// 避免大量檔案的轉換產生數百萬行日誌；其他目標的記錄不受影響
+pub struct SampledLogger {
+    inner: env_logger::Logger,
+}
+
+impl SampledLogger {
+    /// # 參數
+    /// - inner: 實際輸出記錄的 env_logger
+    /// - sample: 每幾個檔案輸出一行摘要，0 表示不取樣
+    pub fn new(inner: env_logger::Logger, sample: u64) -> Self {
+        SAMPLE.store(sample, Ordering::Relaxed);
+        DETAIL.store(sample == 0 || inner.filter() >= LevelFilter::Debug, Ordering::Relaxed);
+        SampledLogger { inner }
+    }
+
+    pub fn filter(&self) -> LevelFilter {
+        self.inner.filter()
+    }
+}
+
+impl Log for SampledLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if record.target() == FILE_LOG_TARGET
+            && !DETAIL.load(Ordering::Relaxed)
+            && CONVERTED_FILES.load(Ordering::Relaxed) >= SAMPLE.load(Ordering::Relaxed)
+        {
+            return;
+        }
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// 記錄一個檔案轉換完成，達到取樣門檻時輸出摘要
+pub fn file_converted() {
+    let count = CONVERTED_FILES.fetch_add(1, Ordering::Relaxed) + 1;
+    let sample = SAMPLE.load(Ordering::Relaxed);
+    if DETAIL.load(Ordering::Relaxed) || sample == 0 || !count.is_multiple_of(sample) {
+        return;
+    }
+    if count == sample {
+        info!(
+            "已轉換 {} 個檔案，之後的逐檔記錄改為每 {} 個檔案輸出一行摘要（--log-level debug 顯示全部，逐檔結果見 --report）",
+            count, sample
+        );
+    } else {
+        info!("已轉換 {} 個檔案", count);
+    }
+}
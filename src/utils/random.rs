@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use sha2::{Digest, Sha256};
+
+// `--seed` 指定的種子，未指定時使用系統亂數
+static SEED: AtomicU64 = AtomicU64::new(0);
+static SEEDED: AtomicBool = AtomicBool::new(false);
+
+/// 設定整個程式使用的亂數種子，None 表示使用系統亂數
+pub fn set_seed(seed: Option<u64>) {
+    SEED.store(seed.unwrap_or(0), Ordering::Relaxed);
+    SEEDED.store(seed.is_some(), Ordering::Relaxed);
+}
+
+/// 目前的亂數種子，未指定時返回 None
+pub fn seed() -> Option<u64> {
+    SEEDED.load(Ordering::Relaxed).then(|| SEED.load(Ordering::Relaxed))
+}
+
+/// 取得指定用途的亂數產生器；指定種子時由種子、用途與內容推導，
+/// 結果與平行處理的先後順序無關，相同輸入會得到相同輸出
+/// # 參數
+/// - purpose: 用途名稱，例如 `password`、`webcrypto`
+/// - context: 區分同一用途下不同呼叫的內容，例如待加密的資料
+/// # 回傳
+/// - 亂數產生器，未指定種子時為系統亂數
+pub fn rng_for(purpose: &str, context: &[&[u8]]) -> Box<dyn RngCore> {
+    let Some(seed) = seed() else {
+        return Box::new(rand::rng());
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_le_bytes());
+    hasher.update((purpose.len() as u64).to_le_bytes());
+    hasher.update(purpose.as_bytes());
+    for part in context {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    Box::new(StdRng::from_seed(hasher.finalize().into()))
+}
+
+/// 以系統的密碼學安全亂數產生十六進位識別碼，不受 `--seed` 影響，無法由時間或序號推測
+/// # 參數
+/// - bytes: 亂數的位元組數，識別碼長度為其兩倍
+pub fn random_id(bytes: usize) -> String {
+    let mut buffer = vec![0u8; bytes];
+    rand::rng().fill_bytes(&mut buffer);
+    buffer.iter().map(|b| format!("{:02x}", b)).collect()
+}
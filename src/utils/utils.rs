@@ -1,27 +1,37 @@
 use std::io::{self, Write};
-use regex::RegexSet;
+use regex::{RegexSet, RegexSetBuilder};
 use rand::{Rng};
-use chrono::Local;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
 use indicatif::{ProgressBar, ProgressStyle};
 use log;
 use std::time::Instant;
 use std::sync::{Arc, Mutex};
 use crate::config::config::PasswordMode;
+use crate::service::traits::i_prompt::Prompter;
+use crate::service::zip::archive_extension;
+use crate::utils::locale::LocaleFormat;
+use crate::utils::log_sampler::SampledLogger;
+use zxcvbn::feedback::{Suggestion, Warning};
 use std::path::Path;
+use std::process::{Command, Stdio};
 use std::fs::File;
 use rand::distr::Alphanumeric;
 
-pub fn setup_logging(log_level: &str) -> io::Result<()> {
+/// 初始化日誌，逐檔詳細記錄依取樣設定彙整為摘要
+/// # 參數
+/// - log_level: 日誌等級（`debug`、`info`、`warn`、`error`）
+/// - log_sample: 超過此檔案數後每幾個檔案輸出一行摘要，0 表示輸出所有逐檔記錄
+pub fn setup_logging(log_level: &str, log_sample: u64) -> io::Result<()> {
     let log_level_filter = match log_level {
+        "debug" => log::LevelFilter::Debug,
         "info" => log::LevelFilter::Info,
         "warn" => log::LevelFilter::Warn,
         "error" => log::LevelFilter::Error,
         _ => log::LevelFilter::Info,
     };
-    env_logger::Builder::new()
-        .filter_level(log_level_filter)
-        .init();
-    Ok(())
+    let logger = SampledLogger::new(env_logger::Builder::new().filter_level(log_level_filter).build(), log_sample);
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(logger)).map_err(|e| io::Error::other(format!("無法初始化日誌：{}", e)))
 }
 
 pub struct ProgressManager {
@@ -30,6 +40,8 @@ pub struct ProgressManager {
     start: Instant,
     last_update: Instant,
     update_interval: f64,
+    // 進度訊息依系統語系格式化數字
+    locale: LocaleFormat,
 }
 
 impl ProgressManager {
@@ -60,6 +72,7 @@ impl ProgressManager {
             start: Instant::now(),
             last_update: Instant::now(),
             update_interval: 1.0,
+            locale: LocaleFormat::system(),
         }
     }
 
@@ -73,20 +86,23 @@ impl ProgressManager {
             let speed = if elapsed > 0.0 { count as f64 / elapsed } else { 0.0 };
             let msg = match total_size {
                 Some(size) => format!(
-                    "{}：{} 檔案，{:.2} MB，速度：{:.0} 檔案/秒",
-                    action, count, size as f64 / 1_048_576.0, speed
+                    "{}：{} 檔案，{} MB，速度：{} 檔案/秒",
+                    action,
+                    self.locale.format_number(count),
+                    self.locale.format_decimal(size as f64 / 1_048_576.0, 2),
+                    self.locale.format_decimal(speed, 0)
                 ),
                 None => format!(
-                    "{}：{} 檔案，速度：{:.0} 檔案/秒",
-                    action, count, speed
+                    "{}：{} 檔案，速度：{} 檔案/秒",
+                    action,
+                    self.locale.format_number(count),
+                    self.locale.format_decimal(speed, 0)
                 ),
             };
             let pb = self.pb.lock().unwrap();
             pb.set_message(msg);
             pb.set_position(count);
             drop(pb);
-            // 由於 last_update 僅在單執行緒中使用，無需同步
-            self.last_update;
         }
     }
 
@@ -96,15 +112,15 @@ impl ProgressManager {
         }
         let msg = match total_size {
             Some(size) => format!(
-                "完成，共 {} 個檔案，總大小：{:.2} MB，跳過 {} 個目錄",
-                file_count,
-                size as f64 / 1_048_576.0,
-                skipped_dirs
+                "完成，共 {} 個檔案，總大小：{} MB，跳過 {} 個目錄",
+                self.locale.format_number(file_count),
+                self.locale.format_decimal(size as f64 / 1_048_576.0, 2),
+                self.locale.format_number(skipped_dirs)
             ),
             None => format!(
                 "完成，共 {} 個檔案，跳過 {} 個目錄",
-                file_count,
-                skipped_dirs
+                self.locale.format_number(file_count),
+                self.locale.format_number(skipped_dirs)
             ),
         };
         let pb = self.pb.lock().unwrap();
@@ -131,17 +147,152 @@ pub fn manage_progress(
     pm.update(count, total_size, action);
 }
 
-pub fn get_file_name(path: &Path, layer: &str) -> (String, String) {
+/// 取得頁面名稱與下載檔名，下載檔名經 sanitize_download_name 整理
+/// # 參數
+/// - path: 來源檔案或目錄
+/// - layer: 壓縮層級，決定下載檔名的後綴
+/// - archive_format: 封存格式，tar 與 tar.gz 只有單層，以對應的副檔名命名；`none` 與不分層相同，沿用來源檔名
+/// - ascii_names: 下載檔名是否只使用 ASCII 字元
+/// # 回傳
+/// - （頁面名稱，下載檔名）
+pub fn get_file_name(path: &Path, layer: &str, archive_format: &str, ascii_names: bool) -> (String, String) {
     let file_name = path.file_name()
         .unwrap_or(std::ffi::OsStr::new("archive"))
         .to_string_lossy()
         .to_string();
     let download_zip_name = match layer {
-        "none" => file_name.clone(),
+        _ if layer == "none" || archive_format == "none" => file_name.clone(),
+        _ if archive_format != "zip" => format!("{}.{}", file_name, archive_extension(archive_format)),
         "single" => format!("{}.zip", file_name),
         _ => format!("{}_outer.zip", file_name),
     };
-    (file_name, download_zip_name)
+    (file_name, sanitize_download_name(&download_zip_name, ascii_names))
+}
+
+/// 頁面下載資料時使用的 MIME 類型：不分層時資料即為來源檔案，依副檔名判斷，其餘依封存格式
+/// # 參數
+/// - layer: 壓縮層級
+/// - archive_format: 封存格式
+/// - download_name: 下載檔名
+/// # 回傳
+/// - MIME 類型，無法判斷時為 `application/octet-stream`
+pub fn download_mime_type(layer: &str, archive_format: &str, download_name: &str) -> &'static str {
+    if layer != "none" && archive_format != "none" {
+        return match archive_format {
+            "tar" => "application/x-tar",
+            "tar.gz" => "application/gzip",
+            "7z" => "application/x-7z-compressed",
+            _ => "application/zip",
+        };
+    }
+    let extension = Path::new(download_name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "txt" | "log" | "md" => "text/plain",
+        "csv" => "text/csv",
+        "htm" | "html" => "text/html",
+        "css" => "text/css",
+        "js" => "text/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "7z" => "application/x-7z-compressed",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "doc" => "application/msword",
+        "xls" => "application/vnd.ms-excel",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        _ => "application/octet-stream",
+    }
+}
+
+// Windows 保留的裝置名稱，不分大小寫且加上副檔名同樣無法使用
+const RESERVED_DEVICE_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 是否為 Windows 保留的裝置名稱，如 `CON`、`nul.txt`、`COM1.tar.gz`
+pub fn is_reserved_device_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    RESERVED_DEVICE_NAMES.iter().any(|device| device.eq_ignore_ascii_case(stem))
+}
+
+/// 整理 `download` 屬性使用的檔名：檔案系統不允許的字元、控制字元，以及各瀏覽器處理方式不一的 `#`、`%`
+/// 與對應的全形符號改為 `_`，去除結尾的點與空白，並避開 Windows 保留的裝置名稱
+/// # 參數
+/// - name: 原始檔名
+/// - ascii_only: 是否將非 ASCII 字元改以 UTF-8 百分比編碼表示（`%` 之後不會再被替換）
+/// # 回傳
+/// - 整理後的檔名，整理後為空時返回 `download`
+pub fn sanitize_download_name(name: &str, ascii_only: bool) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' | '#' | '%' => '_',
+            '＜' | '＞' | '：' | '＂' | '／' | '＼' | '｜' | '？' | '＊' | '＃' | '％' => '_',
+            '\u{3000}' => ' ',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    sanitized.truncate(sanitized.trim_end_matches(['.', ' ']).len());
+    if ascii_only {
+        sanitized = sanitized
+            .chars()
+            .map(|c| if c.is_ascii() { c.to_string() } else { urlencoding::encode(c.encode_utf8(&mut [0; 4])).into_owned() })
+            .collect();
+    }
+    if is_reserved_device_name(&sanitized) {
+        sanitized.insert(0, '_');
+    }
+    if sanitized.is_empty() {
+        return "download".to_string();
+    }
+    sanitized
+}
+
+/// 依命名樣式產生檔名，`{name}` 替換為指定名稱，`{ext}` 替換為頁面副檔名
+pub fn apply_name_pattern(pattern: &str, name: &str, extension: &str) -> String {
+    pattern.replace("{name}", name).replace("{ext}", extension)
+}
+
+/// 解析到期時間，接受 `YYYY-MM-DD`（當日結束時到期）、`YYYY-MM-DD HH:MM` 與 RFC 3339 格式，未指定時區時使用本機時區
+/// # 回傳
+/// - 成功時返回到期時間，格式無法辨識時返回 IO 錯誤
+pub fn parse_expires(value: &str) -> io::Result<DateTime<Local>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Local));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(23, 59, 59)));
+    naive
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .ok_or_else(|| io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("無法解析到期時間 '{}'，請使用 YYYY-MM-DD、YYYY-MM-DD HH:MM 或 RFC 3339 格式", value)
+        ))
 }
 
 pub fn copy_file_content<W: Write>(file_path: &Path, writer: &mut W) -> io::Result<usize> {
@@ -154,14 +305,125 @@ pub fn copy_file_content<W: Write>(file_path: &Path, writer: &mut W) -> io::Resu
 }
 
 pub fn generate_random_password(length: usize) -> String {
-    rand::rng()
+    crate::utils::random::rng_for("password", &[&(length as u64).to_le_bytes()])
         .sample_iter(&Alphanumeric)
         .take(length)
         .map(char::from)
         .collect()
 }
 
-pub fn generate_password(password_mode: &PasswordMode, preset_password: Option<String>) -> io::Result<Option<String>> {
+/// 手動輸入密碼的最低 zxcvbn 分數（0–4）
+pub const MIN_PASSWORD_SCORE: u8 = 3;
+
+/// 以 zxcvbn 評估手動輸入的密碼，經由 prompter 顯示強度與建議
+/// # 參數
+/// - password: 使用者輸入的密碼
+/// - allow_weak: 分數低於 `MIN_PASSWORD_SCORE` 時是否仍允許使用
+/// - prompter: 顯示強度與建議的實作
+/// # 回傳
+/// - 強度足夠或允許弱密碼時返回 true，否則返回 false
+pub fn check_password_strength(password: &str, allow_weak: bool, prompter: &dyn Prompter) -> bool {
+    let entropy = zxcvbn::zxcvbn(password, &[]);
+    let score = u8::from(entropy.score());
+    let label = ["極弱", "弱", "普通", "強", "極強"][score as usize];
+    prompter.message(&format!("密碼強度：[{}{}] {}/4（{}）", "■".repeat(score as usize), "□".repeat(4 - score as usize), score, label));
+    if let Some(feedback) = entropy.feedback() {
+        if let Some(warning) = feedback.warning() {
+            prompter.message(&format!("  警告：{}", password_warning_text(warning)));
+        }
+        for suggestion in feedback.suggestions() {
+            prompter.message(&format!("  建議：{}", password_suggestion_text(*suggestion)));
+        }
+    }
+    if score >= MIN_PASSWORD_SCORE {
+        return true;
+    }
+    if allow_weak {
+        log::warn!("密碼強度不足（{}/4），因 --allow-weak-password 仍使用此密碼", score);
+        return true;
+    }
+    prompter.message(&format!(
+        "密碼強度不足（{}/4，至少需 {}），請改用更長或更不易猜測的密碼，或加上 --allow-weak-password",
+        score, MIN_PASSWORD_SCORE
+    ));
+    false
+}
+
+// zxcvbn 警告的中文說明
+fn password_warning_text(warning: Warning) -> &'static str {
+    match warning {
+        Warning::StraightRowsOfKeysAreEasyToGuess => "鍵盤上同一排相鄰的按鍵很容易被猜到",
+        Warning::ShortKeyboardPatternsAreEasyToGuess => "簡短的鍵盤圖樣很容易被猜到",
+        Warning::RepeatsLikeAaaAreEasyToGuess => "像 aaa 這樣重複的字元很容易被猜到",
+        Warning::RepeatsLikeAbcAbcAreOnlySlightlyHarderToGuess => "像 abcabc 這樣的重複只比 abc 稍微難猜",
+        Warning::ThisIsATop10Password => "這是最常見的前 10 名密碼之一",
+        Warning::ThisIsATop100Password => "這是最常見的前 100 名密碼之一",
+        Warning::ThisIsACommonPassword => "這是常見的密碼",
+        Warning::ThisIsSimilarToACommonlyUsedPassword => "這與常用的密碼相似",
+        Warning::SequencesLikeAbcAreEasyToGuess => "像 abc 或 6543 這樣的連續字元很容易被猜到",
+        Warning::RecentYearsAreEasyToGuess => "近年的年份很容易被猜到",
+        Warning::AWordByItselfIsEasyToGuess => "單獨一個單字很容易被猜到",
+        Warning::DatesAreOftenEasyToGuess => "日期通常很容易被猜到",
+        Warning::NamesAndSurnamesByThemselvesAreEasyToGuess => "單獨的名字或姓氏很容易被猜到",
+        Warning::CommonNamesAndSurnamesAreEasyToGuess => "常見的名字和姓氏很容易被猜到",
+    }
+}
+
+// zxcvbn 建議的中文說明
+fn password_suggestion_text(suggestion: Suggestion) -> &'static str {
+    match suggestion {
+        Suggestion::UseAFewWordsAvoidCommonPhrases => "使用幾個單字組合，避免常見的片語",
+        Suggestion::NoNeedForSymbolsDigitsOrUppercaseLetters => "不一定需要符號、數字或大寫字母",
+        Suggestion::AddAnotherWordOrTwo => "再加上一兩個不常見的單字",
+        Suggestion::CapitalizationDoesntHelpVeryMuch => "大寫字母的幫助不大",
+        Suggestion::AllUppercaseIsAlmostAsEasyToGuessAsAllLowercase => "全部大寫幾乎和全部小寫一樣容易猜到",
+        Suggestion::ReversedWordsArentMuchHarderToGuess => "反過來拼寫的單字並不會比較難猜",
+        Suggestion::PredictableSubstitutionsDontHelpVeryMuch => "可預期的替換（例如以 @ 代替 a）幫助不大",
+        Suggestion::UseALongerKeyboardPatternWithMoreTurns => "改用更長、轉折更多的鍵盤圖樣",
+        Suggestion::AvoidRepeatedWordsAndCharacters => "避免重複的單字與字元",
+        Suggestion::AvoidSequences => "避免連續的字元",
+        Suggestion::AvoidRecentYears => "避免使用近年的年份",
+        Suggestion::AvoidYearsThatAreAssociatedWithYou => "避免使用與自己有關的年份",
+        Suggestion::AvoidDatesAndYearsThatAreAssociatedWithYou => "避免使用與自己有關的日期與年份",
+    }
+}
+
+/// 詢問手動輸入的密碼並要求再次輸入確認，強度不足時重新詢問，直到通過或允許弱密碼
+/// # 參數
+/// - prompter: 詢問密碼與顯示強度的實作
+/// - allow_weak_password: 密碼強度不足時是否仍使用
+/// # 回傳
+/// - 成功時返回密碼，詢問失敗或兩次輸入不一致時返回 IO 錯誤
+pub fn prompt_manual_password(prompter: &dyn Prompter, allow_weak_password: bool) -> io::Result<String> {
+    let pwd = loop {
+        let pwd = prompter.password("請輸入 ZIP 加密密碼")
+            .map_err(|e| io::Error::other(format!("密碼輸入失敗: {}", e)))?;
+        if check_password_strength(&pwd, allow_weak_password, prompter) {
+            break pwd;
+        }
+    };
+    let confirm_pwd = prompter.password("請再次輸入密碼以確認")
+        .map_err(|e| io::Error::other(format!("密碼確認失敗: {}", e)))?;
+    if pwd != confirm_pwd {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "密碼不匹配"));
+    }
+    Ok(pwd)
+}
+
+/// 依密碼模式產生 ZIP 密碼
+/// # 參數
+/// - password_mode: 密碼模式
+/// - preset_password: 手動模式下已取得的密碼，None 時經由 prompter 詢問
+/// - allow_weak_password: 手動輸入的密碼強度不足時是否仍使用
+/// - prompter: 詢問手動密碼的實作
+/// # 回傳
+/// - 成功時返回密碼，不加密時返回 None；詢問失敗或密碼不匹配時返回 IO 錯誤
+pub fn generate_password(
+    password_mode: &PasswordMode,
+    preset_password: Option<String>,
+    allow_weak_password: bool,
+    prompter: &dyn Prompter,
+) -> io::Result<Option<String>> {
     match password_mode {
         PasswordMode::Random => {
             let pwd = generate_random_password(16);
@@ -173,20 +435,9 @@ pub fn generate_password(password_mode: &PasswordMode, preset_password: Option<S
                 log::info!("使用預設手動輸入密碼");
                 Ok(Some(pwd))
             } else {
-                let pwd = dialoguer::Password::new()
-                    .with_prompt("請輸入 ZIP 加密密碼")
-                    .interact()
-                    .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("密碼輸入失敗: {}", e)))?;
-                let confirm_pwd = dialoguer::Password::new()
-                    .with_prompt("請再次輸入密碼以確認")
-                    .interact()
-                    .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("密碼確認失敗: {}", e)))?;
-                if pwd != confirm_pwd {
-                    Err(io::Error::new(io::ErrorKind::InvalidInput, "密碼不匹配"))
-                } else {
-                    log::info!("使用手動輸入密碼");
-                    Ok(Some(pwd))
-                }
+                let pwd = prompt_manual_password(prompter, allow_weak_password)?;
+                log::info!("使用手動輸入密碼");
+                Ok(Some(pwd))
             }
         }
         PasswordMode::Timestamp => {
@@ -201,33 +452,108 @@ pub fn generate_password(password_mode: &PasswordMode, preset_password: Option<S
     }
 }
 
-pub fn format_file_size(size: usize) -> String {
-    if size < 1024 * 1024 {
-        format!("{:.2} KB", size as f64 / 1024.0)
-    } else {
-        format!("{:.2} MB", size as f64 / (1024.0 * 1024.0))
-    }
-}
-
-pub fn create_regex_sets(include: &[String], exclude: &[String]) -> (RegexSet, RegexSet) {
-    let include_patterns: Vec<_> = include.iter()
-        .map(|p| p.replace(".", "\\.").replace("*", ".*"))
-        .collect();
-    let exclude_patterns: Vec<_> = exclude.iter()
-        .map(|p| p.replace(".", "\\.").replace("*", ".*"))
-        .collect();
+/// 將 include 與 exclude 模式轉為正則表達式集合
+/// # 參數
+/// - include: 包含模式，`*` 代表任意字元
+/// - exclude: 排除模式
+/// - ignore_case: 是否不分大小寫
+/// - anchored: 模式是否需完整符合比對對象（檔名），否則只需出現在比對對象（完整路徑）中的任一位置
+/// # 回傳
+/// - （包含模式集合，排除模式集合），模式無效時改用空集合
+pub fn create_regex_sets(include: &[String], exclude: &[String], ignore_case: bool, anchored: bool) -> (RegexSet, RegexSet) {
+    let to_regex = |p: &String| {
+        let regex = p.replace(".", "\\.").replace("*", ".*");
+        if anchored { format!("^(?:{})$", regex) } else { regex }
+    };
+    let include_patterns: Vec<_> = include.iter().map(to_regex).collect();
+    let exclude_patterns: Vec<_> = exclude.iter().map(to_regex).collect();
 
-    let include_set = RegexSet::new(&include_patterns)
+    let include_set = RegexSetBuilder::new(&include_patterns)
+        .case_insensitive(ignore_case)
+        .build()
         .unwrap_or_else(|e| {
             log::warn!("無效的包含模式: {}，使用空集作為回退", e);
             RegexSet::empty()
         });
 
-    let exclude_set = RegexSet::new(&exclude_patterns)
+    let exclude_set = RegexSetBuilder::new(&exclude_patterns)
+        .case_insensitive(ignore_case)
+        .build()
         .unwrap_or_else(|e| {
             log::warn!("無效的排除模式: {}，使用空集作為回退", e);
             RegexSet::empty()
         });
 
     (include_set, exclude_set)
-}
\ No newline at end of file
+}
+/// 以系統預設瀏覽器開啟頁面（Windows 使用 explorer、macOS 使用 open、其他平台使用 xdg-open）
+/// # 參數
+/// - path: 頁面路徑
+/// # 回傳
+/// - 成功時返回 ()，無法啟動瀏覽器時返回 IO 錯誤
+pub fn open_in_browser(path: &Path) -> io::Result<()> {
+    let path = std::path::absolute(path)?;
+    let launcher = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let status = Command::new(launcher)
+        .arg(&path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("無法執行 {} 開啟 {}：{}", launcher, path.display(), e)))?;
+    // explorer 即使成功開啟也會返回非零結束碼，只檢查其他平台
+    if !status.success() && !cfg!(target_os = "windows") {
+        return Err(io::Error::other(format!("{} 無法開啟 {}：{}", launcher, path.display(), status)));
+    }
+    log::info!("已以預設瀏覽器開啟：{}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::prompt::ScriptedPrompter;
+
+    const STRONG_PASSWORD: &str = "correct-horse-battery-staple-42";
+
+    #[test]
+    fn manual_password_requires_matching_confirmation() {
+        let prompter = ScriptedPrompter::new([STRONG_PASSWORD, STRONG_PASSWORD]);
+        assert_eq!(prompt_manual_password(&prompter, false).unwrap(), STRONG_PASSWORD);
+
+        let prompter = ScriptedPrompter::new([STRONG_PASSWORD, "correct-horse-battery-staple-43"]);
+        let error = prompt_manual_password(&prompter, false).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "密碼不匹配");
+    }
+
+    #[test]
+    fn weak_password_is_asked_again() {
+        let prompter = ScriptedPrompter::new(["password", STRONG_PASSWORD, STRONG_PASSWORD]);
+        assert_eq!(prompt_manual_password(&prompter, false).unwrap(), STRONG_PASSWORD);
+        assert_eq!(prompter.remaining(), 0);
+        let messages = prompter.messages();
+        assert!(messages.iter().any(|m| m.starts_with("密碼強度：[□□□□] 0/4")));
+        assert!(messages.iter().any(|m| m.starts_with("  警告：") && !m.is_ascii()));
+        assert!(messages.iter().any(|m| m.starts_with("密碼強度不足")));
+    }
+
+    #[test]
+    fn weak_password_allowed_with_flag() {
+        let prompter = ScriptedPrompter::new(["password", "password"]);
+        assert_eq!(prompt_manual_password(&prompter, true).unwrap(), "password");
+        assert!(!prompter.messages().iter().any(|m| m.starts_with("密碼強度不足")));
+    }
+
+    #[test]
+    fn weak_password_without_more_answers_fails() {
+        let prompter = ScriptedPrompter::new(["password"]);
+        assert!(prompt_manual_password(&prompter, false).is_err());
+    }
+}
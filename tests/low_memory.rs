@@ -0,0 +1,77 @@
+// 低記憶體模式的記憶體上限測試：轉換遠大於上限的檔案，確認行程的記憶體峰值不隨檔案大小成長
+// 以 /proc/self/status 的 VmHWM 量測，測試檔只放一個測試以免其他測試的配置影響峰值
+
+#![cfg(target_os = "linux")]
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+use file_to_html::config::config::PasswordMode;
+use file_to_html::config::ports::AppConfig;
+use file_to_html::facade::conversion_facade::ConversionFacade;
+use file_to_html::facade::traits::i_conversion::ConversionFacadeTrait;
+use file_to_html::models::conversion::ConversionInput;
+use file_to_html::service::config_service::StaticConfigAdapter;
+use file_to_html::service::file::FileService;
+use file_to_html::service::html::HtmlService;
+use file_to_html::service::zip::ZipService;
+
+const INPUT_SIZE: usize = 32 * 1024 * 1024;
+// 轉換期間允許增加的記憶體，為輸入大小的一半
+const MEMORY_BUDGET: usize = INPUT_SIZE / 2;
+
+// 行程目前為止的常駐記憶體峰值（位元組）
+fn peak_rss() -> usize {
+    let status = fs::read_to_string("/proc/self/status").unwrap();
+    let line = status.lines().find(|line| line.starts_with("VmHWM:")).unwrap();
+    line.split_whitespace().nth(1).unwrap().parse::<usize>().unwrap() * 1024
+}
+
+#[test]
+fn low_memory_conversion_stays_within_budget() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("big.bin");
+    // 以 xorshift 產生無法壓縮的內容，逐塊寫入以免測試本身佔用記憶體
+    let mut writer = BufWriter::new(File::create(&input).unwrap());
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut block = vec![0u8; 1024 * 1024];
+    for _ in 0..INPUT_SIZE / block.len() {
+        for byte in block.iter_mut() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *byte = state as u8;
+        }
+        writer.write_all(&block).unwrap();
+    }
+    writer.flush().unwrap();
+    drop(block);
+
+    let config = AppConfig {
+        input: input.display().to_string(),
+        output: dir.path().join("out").display().to_string(),
+        password_mode: PasswordMode::None,
+        no_progress: true,
+        low_memory: true,
+        compression_method: "stored".into(),
+        ..AppConfig::default()
+    };
+    let facade = ConversionFacade::new(
+        Box::new(StaticConfigAdapter::new(config.clone())),
+        Box::new(FileService::new()),
+        Box::new(ZipService::new()),
+        Box::new(HtmlService::new()),
+    );
+    let before = peak_rss();
+    let output = facade.execute_conversion(ConversionInput::from(&config)).expect("低記憶體模式轉換應成功");
+    let growth = peak_rss().saturating_sub(before);
+
+    let page_size = fs::metadata(&output.artifacts[0].html).unwrap().len() as usize;
+    assert!(page_size > INPUT_SIZE, "頁面應嵌入完整的資料");
+    assert!(
+        growth < MEMORY_BUDGET,
+        "低記憶體模式的記憶體峰值增加 {} 位元組，超過上限 {} 位元組",
+        growth,
+        MEMORY_BUDGET
+    );
+}
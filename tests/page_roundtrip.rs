@@ -0,0 +1,188 @@
+// 產出頁面的往返測試：轉換後以 verify 與 extract 使用的讀取流程取回封存檔並比對來源內容
+
+use std::fs;
+use std::path::Path;
+
+use file_to_html::config::config::PasswordMode;
+use file_to_html::config::ports::AppConfig;
+use file_to_html::facade::conversion_facade::ConversionFacade;
+use file_to_html::facade::traits::i_conversion::ConversionFacadeTrait;
+use file_to_html::models::conversion::{ConversionInput, ConversionOutput};
+use file_to_html::service::config_service::StaticConfigAdapter;
+use file_to_html::service::file::FileService;
+use file_to_html::service::html::{sha256_hex, HtmlService};
+use file_to_html::service::schema::{read_document, read_page};
+use file_to_html::service::verify::{archive_entry_hashes, read_output_payload, unwrap_outer_layer};
+use file_to_html::service::zip::ZipService;
+
+const CONTENT: &[u8] = b"file_to_html round trip\n";
+
+fn convert(input: &Path, output: &Path, config: AppConfig) -> ConversionOutput {
+    let config = AppConfig {
+        input: input.display().to_string(),
+        output: output.display().to_string(),
+        password_mode: PasswordMode::None,
+        no_progress: true,
+        verify_output: true,
+        ..config
+    };
+    let facade = ConversionFacade::new(
+        Box::new(StaticConfigAdapter::new(config.clone())),
+        Box::new(FileService::new()),
+        Box::new(ZipService::new()),
+        Box::new(HtmlService::new()),
+    );
+    facade.execute_conversion(ConversionInput::from(&config)).expect("轉換與產出驗證應成功")
+}
+
+// 以 extract 的讀取流程取回封存檔，並確認項目內容與來源相同
+fn assert_round_trip(output: &ConversionOutput) {
+    let artifact = &output.artifacts[0];
+    let page = Path::new(&artifact.html);
+    let (info, archive) = read_output_payload(page, &[], None, None).expect("應可取回頁面資料");
+    assert_eq!(info.payload_sha256.as_deref(), Some(sha256_hex(&archive).as_str()));
+    let entries = archive_entry_hashes(&archive, "zip", None).expect("應可開啟封存檔");
+    assert_eq!(entries.get("a.txt"), Some(&sha256_hex(CONTENT)));
+}
+
+#[test]
+fn no_js_page_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("a.txt");
+    fs::write(&input, CONTENT).unwrap();
+    let output = convert(&input, &dir.path().join("out"), AppConfig { no_js: true, ..AppConfig::default() });
+    assert_round_trip(&output);
+
+    let html = fs::read_to_string(&output.artifacts[0].html).unwrap();
+    assert!(!html.contains("<script"), "--no-js 頁面不應包含腳本");
+    assert!(read_page(&html).unwrap().download_zip_name.is_some());
+}
+
+#[test]
+fn no_js_text_payload_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("a.txt");
+    fs::write(&input, CONTENT).unwrap();
+    // 超過資料連結上限需 2 MiB 以上的資料，改將資料連結替換為換行的 Base64 文字，確認讀取時忽略空白
+    let page = dir.path().join("page.html");
+    let output = convert(&input, &dir.path().join("out"), AppConfig { no_js: true, ..AppConfig::default() });
+    let html = fs::read_to_string(&output.artifacts[0].html).unwrap();
+    let link_start = html.find("href=\"data:").unwrap();
+    let data_start = html[link_start..].find(',').unwrap() + link_start + 1;
+    let data_end = html[data_start..].find('"').unwrap() + data_start;
+    let wrapped: String = html.as_bytes()[data_start..data_end]
+        .chunks(8)
+        .map(|line| std::str::from_utf8(line).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let link_end = html[link_start..].find("</a>").unwrap() + link_start + "</a>".len();
+    let anchor_start = html[..link_start].rfind("<a ").unwrap();
+    let textarea = format!("<textarea class=\"payload-text\" readonly aria-label=\"Base64\">{}</textarea>", wrapped);
+    fs::write(&page, format!("{}{}{}", &html[..anchor_start], textarea, &html[link_end..])).unwrap();
+
+    let (_, archive) = read_output_payload(&page, &[], None, None).expect("應可取回換行的 Base64 文字");
+    let entries = archive_entry_hashes(&archive, "zip", None).unwrap();
+    assert_eq!(entries.get("a.txt"), Some(&sha256_hex(CONTENT)));
+}
+
+#[test]
+fn mhtml_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("a.txt");
+    fs::write(&input, CONTENT).unwrap();
+    let output = convert(&input, &dir.path().join("out"), AppConfig { format: "mhtml".into(), ..AppConfig::default() });
+    assert!(output.artifacts[0].html.ends_with(".mht"));
+    assert_round_trip(&output);
+
+    let content = fs::read_to_string(&output.artifacts[0].html).unwrap();
+    let info = read_document(&content).unwrap();
+    assert_eq!(info.output_format, "mhtml");
+    assert_eq!(info.download_zip_name.as_deref(), Some("a.txt.zip"));
+}
+
+#[test]
+fn json_round_trip() {
+    for encoding in ["base64", "base85"] {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("a.txt");
+        fs::write(&input, CONTENT).unwrap();
+        let config = AppConfig { format: "json".into(), payload_encoding: encoding.into(), ..AppConfig::default() };
+        let output = convert(&input, &dir.path().join("out"), config);
+        assert_round_trip(&output);
+
+        let content = fs::read_to_string(&output.artifacts[0].html).unwrap();
+        let info = read_document(&content).unwrap();
+        assert_eq!(info.output_format, "json");
+        assert_eq!(info.payload_encoding, encoding);
+        assert!(info.metadata.is_some(), "JSON 輸出應記錄轉換資訊");
+    }
+}
+
+#[test]
+fn double_layer_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("a.txt");
+    fs::write(&input, CONTENT).unwrap();
+    let config = AppConfig {
+        layer: "double".into(),
+        inner_password_mode: Some(PasswordMode::Random),
+        outer_password_mode: Some(PasswordMode::Random),
+        display_password: false,
+        ..AppConfig::default()
+    };
+    let output = convert(&input, &dir.path().join("out"), config);
+    let artifact = &output.artifacts[0];
+
+    // 密碼檔每行一個標示層別的密碼
+    let key_file = fs::read_to_string(artifact.key.as_ref().expect("應寫出密碼檔")).unwrap();
+    let password = |label: &str| key_file.lines().find_map(|line| line.strip_prefix(label)).unwrap().to_string();
+    let (outer, inner) = (password("外層密碼："), password("內層密碼："));
+    assert_ne!(outer, inner, "內外層密碼應各自產生");
+
+    let (info, archive) = read_output_payload(Path::new(&artifact.html), &[], None, None).unwrap();
+    assert_eq!(info.download_zip_name.as_deref(), Some("a.txt_outer.zip"));
+    let inner_zip = unwrap_outer_layer(&archive, Some(&outer)).expect("應可以外層密碼取出內層 ZIP");
+    assert!(archive_entry_hashes(&inner_zip, "zip", Some(&outer)).is_err(), "內層 ZIP 不應能以外層密碼開啟");
+    let entries = archive_entry_hashes(&inner_zip, "zip", Some(&inner)).unwrap();
+    assert_eq!(entries.get("a.txt"), Some(&sha256_hex(CONTENT)));
+}
+
+#[test]
+fn group_by_dir_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in");
+    for group in ["alpha", "beta"] {
+        fs::create_dir_all(input.join(group)).unwrap();
+        fs::write(input.join(group).join("a.txt"), CONTENT).unwrap();
+    }
+    fs::write(input.join("loose.txt"), CONTENT).unwrap();
+    let report = dir.path().join("report.json");
+    let output = convert(
+        &input,
+        &dir.path().join("out"),
+        AppConfig {
+            group_by: Some("dir".into()),
+            jobs: 2,
+            report: Some(report.display().to_string()),
+            ..AppConfig::default()
+        },
+    );
+
+    // 每個子目錄各一個頁面，依目錄名稱排序，根目錄下的檔案不屬於任何群組
+    let pages: Vec<String> = output
+        .artifacts
+        .iter()
+        .map(|artifact| Path::new(&artifact.html).file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(pages, ["alpha.html", "beta.html"]);
+    assert_eq!(output.processed_files, 2);
+    for artifact in &output.artifacts {
+        let (_, archive) = read_output_payload(Path::new(&artifact.html), &[], None, None).expect("應可取回頁面資料");
+        let entries = archive_entry_hashes(&archive, "zip", None).expect("應可開啟封存檔");
+        assert!(entries.values().all(|hash| *hash == sha256_hex(CONTENT)));
+        assert_eq!(entries.len(), 1);
+    }
+    let report = fs::read_to_string(report).unwrap();
+    assert!(report.contains("\"artifact_count\": 2"));
+    assert!(!report.contains("failed_groups"));
+}
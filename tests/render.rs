@@ -0,0 +1,94 @@
+// HtmlService::render 的測試：將呼叫端建立的封存檔嵌入頁面，並以 verify 的讀取流程取回
+
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+use file_to_html::models::html::{ArchiveMeta, RenderOptions};
+use file_to_html::service::html::{sha256_hex, HtmlService};
+use file_to_html::service::sink::MemorySink;
+use file_to_html::service::verify::{archive_entry_hashes, read_output_payload};
+use zip::write::SimpleFileOptions;
+use zip::{AesMode, ZipWriter};
+
+const CONTENT: &[u8] = b"pre-built archive\n";
+const PASSWORD: &str = "correct horse battery staple";
+
+fn build_zip(aes: Option<AesMode>) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = match aes {
+        Some(mode) => SimpleFileOptions::default().with_aes_encryption(mode, PASSWORD),
+        None => SimpleFileOptions::default(),
+    };
+    writer.start_file("a.txt", options).unwrap();
+    writer.write_all(CONTENT).unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+fn render_to(dir: &Path, archive: Vec<u8>, options: RenderOptions) -> std::io::Result<String> {
+    let options = RenderOptions { output_dir: dir.display().to_string(), ..options };
+    HtmlService::new()
+        .render(archive, ArchiveMeta::new("report", "zip", CONTENT.len()), options)
+        .map(|output| output.html_file_path)
+}
+
+#[test]
+fn renders_pre_built_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = build_zip(None);
+    let page = render_to(dir.path(), archive.clone(), RenderOptions::default()).unwrap();
+
+    let (info, payload) = read_output_payload(Path::new(&page), &[], None, None).unwrap();
+    assert_eq!(payload, archive);
+    assert_eq!(info.download_zip_name.as_deref(), Some("report.zip"));
+    let entries = archive_entry_hashes(&payload, "zip", None).unwrap();
+    assert_eq!(entries.get("a.txt"), Some(&sha256_hex(CONTENT)));
+}
+
+#[test]
+fn renders_to_memory_sink() {
+    let sink = MemorySink::new();
+    let service = HtmlService::with_sink(Box::new(sink.clone()));
+    service.render(build_zip(None), ArchiveMeta::new("report", "zip", CONTENT.len()), RenderOptions::default()).unwrap();
+    let outputs = sink.take();
+    assert_eq!(outputs.len(), 1);
+    assert!(String::from_utf8_lossy(&outputs[0].1).contains("report.zip"));
+}
+
+#[test]
+fn webcrypto_encrypts_the_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = build_zip(None);
+    let options = RenderOptions {
+        password: Some(PASSWORD.to_string()),
+        encryption_method: "webcrypto".to_string(),
+        ..RenderOptions::default()
+    };
+    let page = render_to(dir.path(), archive.clone(), options).unwrap();
+    let (info, payload) = read_output_payload(Path::new(&page), &[], None, Some(PASSWORD)).unwrap();
+    assert!(info.encrypted);
+    assert_eq!(payload, archive);
+}
+
+#[test]
+fn aes_requires_pre_encrypted_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = |method: &str, password: &str| RenderOptions {
+        password: Some(password.to_string()),
+        encryption_method: method.to_string(),
+        ..RenderOptions::default()
+    };
+
+    let plain = render_to(dir.path(), build_zip(None), options("aes256", PASSWORD));
+    assert!(plain.is_err(), "未加密的封存檔不應以 aes256 嵌入");
+    let weaker = render_to(dir.path(), build_zip(Some(AesMode::Aes128)), options("aes256", PASSWORD));
+    assert!(weaker.is_err(), "加密強度與 encryption_method 不符時應返回錯誤");
+    let wrong = render_to(dir.path(), build_zip(Some(AesMode::Aes256)), options("aes256", "wrong password"));
+    assert!(wrong.is_err(), "密碼不符時應返回錯誤");
+
+    let archive = build_zip(Some(AesMode::Aes256));
+    let page = render_to(dir.path(), archive.clone(), options("aes256", PASSWORD)).unwrap();
+    let (_, payload) = read_output_payload(Path::new(&page), &[], None, Some(PASSWORD)).unwrap();
+    assert_eq!(payload, archive);
+    let entries = archive_entry_hashes(&payload, "zip", Some(PASSWORD)).unwrap();
+    assert_eq!(entries.get("a.txt"), Some(&sha256_hex(CONTENT)));
+}